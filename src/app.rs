@@ -1,21 +1,36 @@
 #![cfg(feature = "ssr")]
 
+use crate::db::DbPools;
 use crate::routes::config;
 use actix_files::NamedFile;
-use actix_web::middleware::NormalizePath;
+use actix_web::body::{EitherBody, MessageBody};
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::middleware::{Next, NormalizePath};
 use actix_web::web::Data;
 use actix_web::{get, Result};
 use leptos::config::LeptosOptions;
-use leptos_actix::handle_server_fns;
-use sqlx::PgPool;
+use leptos_actix::handle_server_fns_with_context;
+use std::time::Duration;
 
-pub async fn run(pool: PgPool) -> std::io::Result<()> {
+/// Cookie set on a response to a request that wrote; while it lasts, the
+/// client's reads go to the primary instead of the read replica.
+const READ_PRIMARY_COOKIE: &str = "lazycoder.rw";
+
+/// Paths never redirected by `redirect_moved`: their 404s are answers, not
+/// missing pages.
+const UNREDIRECTED_PATHS: [&str; 4] = ["/api/", "/load/", "/pkg/", "/assets/"];
+
+/// How long `READ_PRIMARY_COOKIE` lasts.
+#[derive(Clone, Copy)]
+struct ReadYourWrites(Duration);
+
+pub async fn run(pools: DbPools, read_your_writes: Duration) -> std::io::Result<()> {
     use crate::pages::app::App;
     use crate::state::new_app_state;
     use actix_files::Files;
     use actix_session::storage::CookieSessionStore;
     use actix_web::cookie::Key;
-    use actix_web::middleware::Logger;
+    use actix_web::middleware::{from_fn, Condition, Logger};
     use actix_web::{App, HttpServer};
     use leptos::config::get_configuration;
     use leptos::prelude::*;
@@ -30,7 +45,10 @@ pub async fn run(pool: PgPool) -> std::io::Result<()> {
             .expect("SECRET_KEY must be set")
             .as_bytes(),
     );
-    let state = new_app_state(pool.clone()).await;
+    let route_reads_enabled = pools.read.is_some();
+    let primary = pools.primary.clone();
+    let state = new_app_state(pools).await;
+    crate::jobs::start(primary, state.clone(), &crate::jobs::JobConfig::from_env());
     HttpServer::new(move || {
         let routes = generate_route_list(App);
         let leptos_options = &conf.leptos_options;
@@ -40,6 +58,11 @@ pub async fn run(pool: PgPool) -> std::io::Result<()> {
 
         App::new()
             .app_data(state.clone())
+            .app_data(Data::new(ReadYourWrites(read_your_writes)))
+            // Innermost, so that the session is already loaded
+            .wrap(from_fn(with_session_actor))
+            .wrap(from_fn(redirect_moved))
+            .wrap(Condition::new(route_reads_enabled, from_fn(route_reads)))
             .wrap(Logger::default())
             .wrap({
                 use actix_session::SessionMiddleware;
@@ -60,9 +83,9 @@ pub async fn run(pool: PgPool) -> std::io::Result<()> {
             .service(Files::new("/pkg", format!("{site_root}/pkg")))
             .service(Files::new("/assets", &site_root))
             .service(favicon)
-            .route("/load/{tail:.*}", handle_server_fns())
+            .route("/load/{tail:.*}", handle_server_fns_with_context(provide_read_routing))
             .configure(config)
-            .leptos_routes(routes, {
+            .leptos_routes_with_context(routes, provide_read_routing, {
                 let leptos_options = leptos_options.clone();
                 move || {
                     view! {
@@ -90,9 +113,112 @@ pub async fn run(pool: PgPool) -> std::io::Result<()> {
         .await
 }
 
+/// Makes the signed-in user the actor of every write audited while handling
+/// the request.
+async fn with_session_actor(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, actix_web::Error> {
+    use crate::common::audit::with_actor;
+    use actix_session::SessionExt as _;
+
+    // Only the id of the user stored at login is needed
+    #[derive(serde::Deserialize)]
+    struct SessionUser {
+        id: i32,
+    }
+
+    let user_id = req
+        .get_session()
+        .get::<SessionUser>("user")
+        .ok()
+        .flatten()
+        .map(|user| user.id);
+    with_actor(user_id, next.call(req)).await
+}
+
+/// Answers a page that is not found, but has moved or was redirected by
+/// hand, with a 301 to where it is now. The query string is passed on.
+async fn redirect_moved(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<EitherBody<impl MessageBody>>, actix_web::Error> {
+    use crate::state::AppState;
+    use actix_web::http::{header, Method, StatusCode};
+    use actix_web::HttpResponse;
+
+    let response = next.call(req).await?;
+    let request = response.request();
+    let path = request.path();
+    if response.status() != StatusCode::NOT_FOUND
+        || !matches!(*request.method(), Method::GET | Method::HEAD)
+        || UNREDIRECTED_PATHS.iter().any(|prefix| path.starts_with(prefix))
+    {
+        return Ok(response.map_into_left_body());
+    }
+    let Some(state) = request.app_data::<Data<AppState>>().cloned() else {
+        return Ok(response.map_into_left_body());
+    };
+    let (path, query) = (path.to_string(), request.query_string().to_string());
+    let location = match state.slug_redirect_service.resolve(&path).await {
+        Ok(Some(location)) if query.is_empty() || location.contains('?') => location,
+        Ok(Some(location)) => format!("{location}?{query}"),
+        Ok(None) => return Ok(response.map_into_left_body()),
+        Err(e) => {
+            log::error!("could not look up redirects of {path}: {e:?}");
+            return Ok(response.map_into_left_body());
+        }
+    };
+    let moved = HttpResponse::MovedPermanently()
+        .insert_header((header::LOCATION, location))
+        .finish();
+    Ok(response.into_response(moved).map_into_right_body())
+}
+
 #[get("favicon.ico")]
 async fn favicon(leptos_options: Data<LeptosOptions>) -> Result<NamedFile> {
     let leptos_options = leptos_options.into_inner();
     let site_root = &leptos_options.site_root;
     Ok(NamedFile::open(format!("{site_root}/favicon.ico"))?)
 }
+
+/// Sends the request's reads to the primary if the client wrote recently, and
+/// marks clients that write for `ReadYourWrites`.
+async fn route_reads(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, actix_web::Error> {
+    use crate::infras::sqlx_transaction::{with_read_routing, ReadRouting};
+    use actix_web::cookie::{time, Cookie};
+    use actix_web::HttpMessage as _;
+
+    let routing = ReadRouting::new(req.cookie(READ_PRIMARY_COOKIE).is_some());
+    let window = req.app_data::<Data<ReadYourWrites>>().map(|w| w.0);
+    req.extensions_mut().insert(routing.clone());
+    let response = with_read_routing(&routing, next.call(req)).await;
+    let wrote = routing.wrote();
+    let mut response = response?;
+    if let (true, Some(window)) = (wrote, window) {
+        let cookie = Cookie::build(READ_PRIMARY_COOKIE, "1")
+            .path("/")
+            .http_only(true)
+            .max_age(time::Duration::seconds(window.as_secs() as i64))
+            .finish();
+        response.response_mut().add_cookie(&cookie)?;
+    }
+    Ok(response)
+}
+
+/// Hands the read routing of `route_reads` to Leptos, whose resources run on
+/// spawned tasks and so outside its scope.
+fn provide_read_routing() {
+    use crate::infras::sqlx_transaction::ReadRouting;
+    use actix_web::HttpMessage as _;
+    use leptos::prelude::{provide_context, use_context};
+
+    let routing = use_context::<leptos_actix::Request>()
+        .and_then(|req| req.extensions().get::<ReadRouting>().cloned());
+    if let Some(routing) = routing {
+        provide_context(routing);
+    }
+}
@@ -1,7 +1,134 @@
-use crate::common::repository::{Repository, ViewRepository};
+use crate::common::error::CoreError;
+use crate::common::filter::ScalarValue;
+use crate::common::repository::{Repository, Transactional, ViewRepository};
 use crate::common::service::{Service, ViewService};
 use crate::{define_readonly_struct_with_common_fields, define_struct_with_common_fields};
+use regex::Regex;
+use std::collections::HashMap;
+use std::future::Future;
+use std::str::FromStr;
 use std::sync::Arc;
+use time::format_description::well_known::Rfc3339;
+use time::macros::format_description;
+
+/// What an attribute's values hold. Each data type is stored in exactly one
+/// value column of `attribute_values`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttributeDataType {
+    Int,
+    Float,
+    String,
+    Boolean,
+    Date,
+    DateTime,
+    Time,
+}
+
+impl AttributeDataType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AttributeDataType::Int => "int",
+            AttributeDataType::Float => "float",
+            AttributeDataType::String => "string",
+            AttributeDataType::Boolean => "boolean",
+            AttributeDataType::Date => "date",
+            AttributeDataType::DateTime => "datetime",
+            AttributeDataType::Time => "time",
+        }
+    }
+
+    /// The `attribute_values` column holding values of this type.
+    pub fn value_column(&self) -> &'static str {
+        match self {
+            AttributeDataType::Int => "int_value",
+            AttributeDataType::Float => "double_value",
+            AttributeDataType::String => "string_value",
+            AttributeDataType::Boolean => "boolean_value",
+            AttributeDataType::Date => "date_value",
+            AttributeDataType::DateTime => "datetime_value",
+            AttributeDataType::Time => "time_value",
+        }
+    }
+
+    /// Placeholder value of this type.
+    pub fn prototype(&self) -> ScalarValue {
+        match self {
+            AttributeDataType::Int => ScalarValue::Int(0),
+            AttributeDataType::Float => ScalarValue::Float(0.0),
+            AttributeDataType::String => ScalarValue::String(String::new()),
+            AttributeDataType::Boolean => ScalarValue::Bool(false),
+            AttributeDataType::Date => ScalarValue::Date(
+                time::Date::from_calendar_date(1970, time::Month::January, 1).unwrap(),
+            ),
+            AttributeDataType::DateTime => ScalarValue::DateTime(time::OffsetDateTime::UNIX_EPOCH),
+            AttributeDataType::Time => ScalarValue::Time(time::Time::MIDNIGHT),
+        }
+    }
+
+    /// Reads a value of this type from the text form used for defaults and
+    /// allowed values: ISO dates, RFC 3339 date-times and `hh:mm:ss` times.
+    pub fn parse_value(&self, raw: &str) -> Option<ScalarValue> {
+        match self {
+            AttributeDataType::Int => raw.trim().parse().ok().map(ScalarValue::Int),
+            AttributeDataType::Float => raw.trim().parse().ok().map(ScalarValue::Float),
+            AttributeDataType::String => Some(ScalarValue::String(raw.to_string())),
+            AttributeDataType::Boolean => raw.trim().parse().ok().map(ScalarValue::Bool),
+            AttributeDataType::Date => {
+                time::Date::parse(raw.trim(), format_description!("[year]-[month]-[day]"))
+                    .ok()
+                    .map(ScalarValue::Date)
+            }
+            AttributeDataType::DateTime => time::OffsetDateTime::parse(raw.trim(), &Rfc3339)
+                .ok()
+                .map(ScalarValue::DateTime),
+            AttributeDataType::Time => time::Time::parse(
+                raw.trim(),
+                format_description!("[hour]:[minute]:[second][optional [.[subsecond]]]"),
+            )
+            .ok()
+            .map(ScalarValue::Time),
+        }
+    }
+
+    /// Text form of `value`, read back by `parse_value`.
+    pub fn format_value(value: &ScalarValue) -> String {
+        match value {
+            ScalarValue::Int(v) => v.to_string(),
+            ScalarValue::Float(v) => v.to_string(),
+            ScalarValue::String(v) => v.clone(),
+            ScalarValue::Bool(v) => v.to_string(),
+            ScalarValue::Date(v) => v
+                .format(format_description!("[year]-[month]-[day]"))
+                .unwrap_or_default(),
+            ScalarValue::DateTime(v) => v.format(&Rfc3339).unwrap_or_default(),
+            ScalarValue::Time(v) => v
+                .format(format_description!("[hour]:[minute]:[second]"))
+                .unwrap_or_default(),
+        }
+    }
+}
+
+impl FromStr for AttributeDataType {
+    type Err = ();
+    /// Accepts the canonical names and the SQL type names older rows may use.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "int" | "integer" => Ok(AttributeDataType::Int),
+            "float" | "double" | "double precision" | "real" | "numeric" => {
+                Ok(AttributeDataType::Float)
+            }
+            "string" | "text" | "character varying" | "character" => Ok(AttributeDataType::String),
+            "boolean" | "bool" => Ok(AttributeDataType::Boolean),
+            "date" => Ok(AttributeDataType::Date),
+            "datetime"
+            | "timestamp"
+            | "timestamp with time zone"
+            | "timestamp without time zone" => Ok(AttributeDataType::DateTime),
+            "time" => Ok(AttributeDataType::Time),
+            _ => Err(()),
+        }
+    }
+}
 
 define_struct_with_common_fields!(Attribute {
     req {
@@ -9,7 +136,14 @@ define_struct_with_common_fields!(Attribute {
         pub entity_type: String,
         pub data_type: String,
     }
-    opt {}
+    opt {
+        pub required: bool,
+        pub min_value: Option<f64>,
+        pub max_value: Option<f64>,
+        pub pattern: Option<String>,
+        pub allowed_values: Vec<String>,
+        pub default_value: Option<String>,
+    }
 });
 define_readonly_struct_with_common_fields!(AttributeInfo {
     pub name: String,
@@ -17,7 +151,155 @@ define_readonly_struct_with_common_fields!(AttributeInfo {
     pub data_type: String,
 });
 
-pub trait AttributeRepository: Repository<Attribute, AttributeCreate> + Send + Sync {}
+impl Attribute {
+    pub fn parsed_data_type(&self) -> Result<AttributeDataType, CoreError> {
+        parse_data_type(&self.data_type)
+    }
+
+    /// The default value, if one is set, as a value of the attribute's type.
+    pub fn default_scalar(&self) -> Result<Option<ScalarValue>, CoreError> {
+        let data_type = self.parsed_data_type()?;
+        self.default_value
+            .as_deref()
+            .map(|raw| {
+                data_type.parse_value(raw).ok_or_else(|| {
+                    invalid_field(
+                        "error.attribute.invalid.default_value",
+                        "default_value",
+                        [("value", raw.to_string())],
+                    )
+                })
+            })
+            .transpose()
+    }
+
+    /// Checks a value against the attribute's constraints. `field` names the
+    /// value column reported in errors. Bounds apply to numbers, and to the
+    /// length of strings; patterns must match a whole string.
+    pub fn check_value(&self, field: &str, value: &ScalarValue) -> Result<(), CoreError> {
+        let violation = |message: &'static str, (key, detail): (&str, String)| {
+            CoreError::UnprocessableEntity(
+                message,
+                HashMap::from([
+                    ("field".to_string(), field.to_string()),
+                    ("attribute".to_string(), self.name.clone()),
+                    (key.to_string(), detail),
+                ]),
+            )
+        };
+        let magnitude = match value {
+            ScalarValue::Int(v) => Some(*v as f64),
+            ScalarValue::Float(v) => Some(*v),
+            ScalarValue::String(v) => Some(v.chars().count() as f64),
+            _ => None,
+        };
+        if let Some(magnitude) = magnitude {
+            if let Some(min) = self.min_value.filter(|min| magnitude < *min) {
+                return Err(violation(
+                    "error.attribute_value.too_small",
+                    ("min", min.to_string()),
+                ));
+            }
+            if let Some(max) = self.max_value.filter(|max| magnitude > *max) {
+                return Err(violation(
+                    "error.attribute_value.too_large",
+                    ("max", max.to_string()),
+                ));
+            }
+        }
+        if let (Some(pattern), ScalarValue::String(v)) = (&self.pattern, value) {
+            if !self.compiled_pattern(pattern)?.is_match(v) {
+                return Err(violation(
+                    "error.attribute_value.pattern",
+                    ("pattern", pattern.clone()),
+                ));
+            }
+        }
+        if !self.allowed_values.is_empty() {
+            let data_type = self.parsed_data_type()?;
+            let allowed = self
+                .allowed_values
+                .iter()
+                .any(|raw| data_type.parse_value(raw).as_ref() == Some(value));
+            if !allowed {
+                return Err(violation(
+                    "error.attribute_value.not_allowed",
+                    ("allowed", self.allowed_values.join(", ")),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks that the constraints themselves make sense for the data type,
+    /// and normalizes `data_type` to its canonical name.
+    pub fn validate(&mut self) -> Result<(), CoreError> {
+        let data_type = self.parsed_data_type()?;
+        self.data_type = data_type.as_str().to_string();
+        if let (Some(min), Some(max)) = (self.min_value, self.max_value) {
+            if min > max {
+                return Err(invalid_field(
+                    "error.attribute.invalid.range",
+                    "min_value",
+                    [("min", min.to_string()), ("max", max.to_string())],
+                ));
+            }
+        }
+        if let Some(pattern) = &self.pattern {
+            self.compiled_pattern(pattern)?;
+        }
+        if let Some(raw) = self
+            .allowed_values
+            .iter()
+            .find(|raw| data_type.parse_value(raw).is_none())
+        {
+            return Err(invalid_field(
+                "error.attribute.invalid.allowed_value",
+                "allowed_values",
+                [("value", raw.clone())],
+            ));
+        }
+        if let Some(default) = self.default_scalar()? {
+            self.check_value("default_value", &default)?;
+        }
+        Ok(())
+    }
+
+    fn compiled_pattern(&self, pattern: &str) -> Result<Regex, CoreError> {
+        Regex::new(&format!("^(?:{pattern})$")).map_err(|_| {
+            invalid_field(
+                "error.attribute.invalid.pattern",
+                "pattern",
+                [("pattern", pattern.to_string())],
+            )
+        })
+    }
+}
+
+fn parse_data_type(raw: &str) -> Result<AttributeDataType, CoreError> {
+    raw.parse().map_err(|_| {
+        invalid_field(
+            "error.attribute.invalid.data_type",
+            "data_type",
+            [("data_type", raw.to_string())],
+        )
+    })
+}
+
+fn invalid_field<const N: usize>(
+    message: &'static str,
+    field: &str,
+    details: [(&str, String); N],
+) -> CoreError {
+    let mut map: HashMap<String, String> = details
+        .into_iter()
+        .map(|(key, value)| (key.to_string(), value))
+        .collect();
+    map.insert("field".to_string(), field.to_string());
+    CoreError::UnprocessableEntity(message, map)
+}
+
+pub trait AttributeRepository: Repository<Attribute, AttributeCreate> + Transactional + Send + Sync {}
 pub trait AttributeInfoRepository: ViewRepository<AttributeInfo> + Send + Sync {}
 
 #[derive(Clone)]
@@ -41,6 +323,30 @@ impl<R: AttributeRepository> ViewService for AttributeService<R> {
 
 impl<R: AttributeRepository> Service for AttributeService<R> {
     type Create = AttributeCreate;
+
+    fn prepare_create(
+        &self,
+        _repo: &R,
+        create: &AttributeCreate,
+    ) -> impl Future<Output = Result<AttributeCreate, CoreError>> {
+        let mut create = create.clone();
+        async move {
+            create.data_type = parse_data_type(&create.data_type)?.as_str().to_string();
+            Ok(create)
+        }
+    }
+
+    fn prepare_update(
+        &self,
+        _repo: &R,
+        entity: &Attribute,
+    ) -> impl Future<Output = Result<Attribute, CoreError>> {
+        let mut entity = entity.clone();
+        async move {
+            entity.validate()?;
+            Ok(entity)
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -61,3 +367,143 @@ impl<R: AttributeInfoRepository> ViewService for AttributeInfoService<R> {
         &self.repository
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attribute(data_type: &str) -> Attribute {
+        let now = time::OffsetDateTime::now_utc();
+        Attribute {
+            id: 1,
+            uid: String::new(),
+            version: 0,
+            created_at: now,
+            updated_at: now,
+            name: "rating".into(),
+            entity_type: "posts".into(),
+            data_type: data_type.into(),
+            required: false,
+            min_value: None,
+            max_value: None,
+            pattern: None,
+            allowed_values: vec![],
+            default_value: None,
+        }
+    }
+
+    fn message(result: Result<(), CoreError>) -> &'static str {
+        match result {
+            Err(CoreError::UnprocessableEntity(message, _)) => message,
+            other => panic!("expected an unprocessable entity, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_data_type_aliases_parse_to_canonical_names() {
+        let parsed: AttributeDataType = "Double Precision".parse().unwrap();
+        assert_eq!(parsed.as_str(), "float");
+        assert_eq!(parsed.value_column(), "double_value");
+        assert_eq!("timestamp".parse(), Ok(AttributeDataType::DateTime));
+        assert!("json".parse::<AttributeDataType>().is_err());
+    }
+
+    #[test]
+    fn test_check_value_applies_bounds_to_numbers_and_string_lengths() {
+        let mut rating = attribute("int");
+        rating.min_value = Some(1.0);
+        rating.max_value = Some(5.0);
+        assert!(rating
+            .check_value("int_value", &ScalarValue::Int(3))
+            .is_ok());
+        assert_eq!(
+            message(rating.check_value("int_value", &ScalarValue::Int(0))),
+            "error.attribute_value.too_small"
+        );
+        assert_eq!(
+            message(rating.check_value("int_value", &ScalarValue::Int(6))),
+            "error.attribute_value.too_large"
+        );
+
+        let mut code = attribute("string");
+        code.max_value = Some(3.0);
+        assert!(code
+            .check_value("string_value", &ScalarValue::String("été".into()))
+            .is_ok());
+        assert_eq!(
+            message(code.check_value("string_value", &ScalarValue::String("abcd".into()))),
+            "error.attribute_value.too_large"
+        );
+    }
+
+    #[test]
+    fn test_check_value_matches_whole_pattern_and_allowed_values() {
+        let mut code = attribute("string");
+        code.pattern = Some("[A-Z]{2}".into());
+        assert!(code
+            .check_value("string_value", &ScalarValue::String("FR".into()))
+            .is_ok());
+        let err = code
+            .check_value("string_value", &ScalarValue::String("FRA".into()))
+            .unwrap_err();
+        match err {
+            CoreError::UnprocessableEntity(message, details) => {
+                assert_eq!(message, "error.attribute_value.pattern");
+                assert_eq!(details["field"], "string_value");
+                assert_eq!(details["attribute"], "rating");
+            }
+            other => panic!("unexpected error {other:?}"),
+        }
+
+        let mut weight = attribute("float");
+        weight.allowed_values = vec!["0.5".into(), "1".into()];
+        assert!(weight
+            .check_value("double_value", &ScalarValue::Float(1.0))
+            .is_ok());
+        assert_eq!(
+            message(weight.check_value("double_value", &ScalarValue::Float(2.0))),
+            "error.attribute_value.not_allowed"
+        );
+    }
+
+    #[test]
+    fn test_validate_normalizes_data_type_and_rejects_bad_constraints() {
+        let mut rating = attribute("integer");
+        rating.default_value = Some("3".into());
+        assert!(rating.validate().is_ok());
+        assert_eq!(rating.data_type, "int");
+
+        let mut unknown = attribute("json");
+        assert_eq!(
+            message(unknown.validate()),
+            "error.attribute.invalid.data_type"
+        );
+
+        let mut range = attribute("int");
+        range.min_value = Some(5.0);
+        range.max_value = Some(1.0);
+        assert_eq!(message(range.validate()), "error.attribute.invalid.range");
+
+        let mut pattern = attribute("string");
+        pattern.pattern = Some("(".into());
+        assert_eq!(
+            message(pattern.validate()),
+            "error.attribute.invalid.pattern"
+        );
+
+        let mut allowed = attribute("date");
+        allowed.allowed_values = vec!["2024-02-30".into()];
+        assert_eq!(
+            message(allowed.validate()),
+            "error.attribute.invalid.allowed_value"
+        );
+
+        let mut default = attribute("int");
+        default.max_value = Some(5.0);
+        default.default_value = Some("9".into());
+        assert_eq!(
+            message(default.validate()),
+            "error.attribute_value.too_large"
+        );
+    }
+}
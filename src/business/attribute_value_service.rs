@@ -1,16 +1,21 @@
-use crate::common::repository::{Repository, ViewRepository};
-use crate::common::service::{Service, ViewService};
+use crate::business::attribute_service::{Attribute, AttributeRepository};
+use crate::common::audit::{AuditEntry, AuditOperation};
+use crate::common::error::CoreError;
+use crate::common::filter::{Filter, FilterOperator, FilterValue, ScalarValue, SoftDeleteScope};
+use crate::common::repository::{Repository, Transactional, ViewRepository};
+use crate::common::service::{upsert_with_audit, Service, ViewService};
 use crate::{define_readonly_struct_with_common_fields, define_struct_with_common_fields};
+use std::collections::HashMap;
+use std::future::Future;
 use std::sync::Arc;
 
-// Table entity: attribute_values
+// Table entity: attribute_values. Exactly the value column matching the
+// attribute's data type is set.
 define_struct_with_common_fields!(AttributeValue {
     req {
         pub attribute_id: i32,
         pub entity_id: i32,
         pub entity_type: String,
-    }
-    opt {
         pub int_value: Option<i32>,
         pub double_value: Option<f64>,
         pub string_value: Option<String>,
@@ -19,8 +24,50 @@ define_struct_with_common_fields!(AttributeValue {
         pub datetime_value: Option<time::OffsetDateTime>,
         pub time_value: Option<time::Time>,
     }
+    opt {}
 });
 
+/// Typed access to the value columns shared by `AttributeValue` and
+/// `AttributeValueCreate`.
+trait ValueColumns {
+    fn attribute_id(&self) -> i32;
+    fn values(&self) -> [(&'static str, Option<ScalarValue>); 7];
+    fn set_value(&mut self, value: ScalarValue);
+}
+
+macro_rules! impl_value_columns {
+    ($($name:ident),*) => {$(
+        impl ValueColumns for $name {
+            fn attribute_id(&self) -> i32 {
+                self.attribute_id
+            }
+            fn values(&self) -> [(&'static str, Option<ScalarValue>); 7] {
+                [
+                    ("int_value", self.int_value.map(ScalarValue::Int)),
+                    ("double_value", self.double_value.map(ScalarValue::Float)),
+                    ("string_value", self.string_value.clone().map(ScalarValue::String)),
+                    ("boolean_value", self.boolean_value.map(ScalarValue::Bool)),
+                    ("date_value", self.date_value.map(ScalarValue::Date)),
+                    ("datetime_value", self.datetime_value.map(ScalarValue::DateTime)),
+                    ("time_value", self.time_value.map(ScalarValue::Time)),
+                ]
+            }
+            fn set_value(&mut self, value: ScalarValue) {
+                match value {
+                    ScalarValue::Int(v) => self.int_value = Some(v),
+                    ScalarValue::Float(v) => self.double_value = Some(v),
+                    ScalarValue::String(v) => self.string_value = Some(v),
+                    ScalarValue::Bool(v) => self.boolean_value = Some(v),
+                    ScalarValue::Date(v) => self.date_value = Some(v),
+                    ScalarValue::DateTime(v) => self.datetime_value = Some(v),
+                    ScalarValue::Time(v) => self.time_value = Some(v),
+                }
+            }
+        }
+    )*};
+}
+impl_value_columns!(AttributeValue, AttributeValueCreate);
+
 // View entity: attribute_values with joined attribute metadata
 define_readonly_struct_with_common_fields!(AttributeValueInfo {
     pub int_value: Option<i32>,
@@ -40,24 +87,248 @@ define_readonly_struct_with_common_fields!(AttributeValueInfo {
 
 // Repositories
 pub trait AttributeValueRepository:
-    Repository<AttributeValue, AttributeValueCreate> + Send + Sync
+    Repository<AttributeValue, AttributeValueCreate> + Transactional + Send + Sync
 {
 }
 pub trait AttributeValueInfoRepository: ViewRepository<AttributeValueInfo> + Send + Sync {}
 
-// Service for table (CRUD)
+// Service for table (CRUD). Writes are checked against the attribute each
+// value belongs to, looked up through `attributes`.
 #[derive(Clone)]
-pub struct AttributeValueService<R: AttributeValueRepository> {
+pub struct AttributeValueService<R: AttributeValueRepository, A: AttributeRepository> {
     repository: Arc<R>,
+    attributes: Arc<A>,
 }
 
-impl<R: AttributeValueRepository> AttributeValueService<R> {
-    pub fn new(repository: Arc<R>) -> Self {
-        Self { repository }
+impl<R: AttributeValueRepository, A: AttributeRepository> AttributeValueService<R, A> {
+    pub fn new(repository: Arc<R>, attributes: Arc<A>) -> Self {
+        Self {
+            repository,
+            attributes,
+        }
     }
+
+    /// Attributes defined for `entity_type`, by name.
+    pub async fn get_definitions(&self, entity_type: &str) -> Result<Vec<Attribute>, CoreError> {
+        let mut definitions = self
+            .attributes
+            .find_all(vec![equals(
+                "entity_type",
+                ScalarValue::String(entity_type.into()),
+            )])
+            .await?;
+        definitions.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(definitions)
+    }
+
+    /// Custom field values of one entity, keyed by attribute name.
+    pub async fn get_attributes(
+        &self,
+        entity_type: &str,
+        entity_id: i32,
+    ) -> Result<HashMap<String, ScalarValue>, CoreError> {
+        let definitions = self.get_definitions(entity_type).await?;
+        let rows = self
+            .repository
+            .find_all(entity_filters(entity_type, entity_id))
+            .await?;
+        let mut values = HashMap::new();
+        for row in rows {
+            let Some(attribute) = definitions.iter().find(|a| a.id == row.attribute_id) else {
+                continue;
+            };
+            let column = attribute.parsed_data_type()?.value_column();
+            if let Some((_, Some(value))) = row.values().into_iter().find(|(c, _)| *c == column) {
+                values.insert(attribute.name.clone(), value);
+            }
+        }
+        Ok(values)
+    }
+
+    /// Writes custom field values of one entity by attribute name in a single
+    /// transaction. `None` clears a field, unless the attribute has a default
+    /// to fall back on. Fields not named are left as they are. Returns every
+    /// value the entity has afterwards.
+    pub async fn set_attributes(
+        &self,
+        entity_type: &str,
+        entity_id: i32,
+        values: HashMap<String, Option<ScalarValue>>,
+    ) -> Result<HashMap<String, ScalarValue>, CoreError> {
+        let definitions = self.get_definitions(entity_type).await?;
+        let mut upserts = Vec::new();
+        let mut cleared = Vec::new();
+        for (name, value) in values {
+            let attribute = definitions.iter().find(|a| a.name == name).ok_or_else(|| {
+                CoreError::UnprocessableEntity(
+                    "error.attribute.unknown",
+                    HashMap::from([
+                        ("field".to_string(), name.clone()),
+                        ("attribute".to_string(), name.clone()),
+                    ]),
+                )
+            })?;
+            let data_type = attribute.parsed_data_type()?;
+            let mut row = empty_row(attribute.id, entity_type, entity_id);
+            match (value, data_type.prototype()) {
+                (None, _) => {}
+                (Some(ScalarValue::Int(v)), ScalarValue::Float(_)) => {
+                    row.set_value(ScalarValue::Float(v.into()))
+                }
+                (Some(v), prototype)
+                    if std::mem::discriminant(&v) == std::mem::discriminant(&prototype) =>
+                {
+                    row.set_value(v)
+                }
+                (Some(_), _) => {
+                    return Err(CoreError::UnprocessableEntity(
+                        "error.attribute_value.invalid.type",
+                        HashMap::from([
+                            ("field".to_string(), data_type.value_column().to_string()),
+                            ("attribute".to_string(), attribute.name.clone()),
+                            ("data_type".to_string(), data_type.as_str().to_string()),
+                        ]),
+                    ))
+                }
+            }
+            let row = check_with(attribute, row)?;
+            if row.values().iter().all(|(_, v)| v.is_none()) {
+                cleared.push(attribute.id);
+            } else {
+                upserts.push(row);
+            }
+        }
+
+        let filters = entity_filters(entity_type, entity_id);
+        self.with_transaction(move |repo, _| async move {
+            upsert_with_audit(
+                &repo,
+                &["entity_type", "entity_id", "attribute_id"],
+                &upserts,
+            )
+            .await?;
+            let ids: Vec<i32> = repo
+                .find_all(filters)
+                .await?
+                .into_iter()
+                .filter(|row| cleared.contains(&row.attribute_id))
+                .map(|row| row.id)
+                .collect();
+            if !ids.is_empty() {
+                let before = repo
+                    .snapshot_by_ids(ids.clone(), SoftDeleteScope::Exclude)
+                    .await?;
+                repo.delete_by_ids(ids).await?;
+                repo.record_audit(AuditEntry::from_snapshots(
+                    AuditOperation::Delete,
+                    before,
+                    vec![],
+                ))
+                .await?;
+            }
+            Ok(())
+        })
+        .await?;
+        self.get_attributes(entity_type, entity_id).await
+    }
+
+    /// Looks up the attribute `value` belongs to and checks it with `check_with`.
+    async fn check<V: ValueColumns>(&self, value: V) -> Result<V, CoreError> {
+        let attribute_id = value.attribute_id();
+        let attribute = self
+            .attributes
+            .find_by_id(attribute_id)
+            .await?
+            .ok_or_else(|| {
+                CoreError::UnprocessableEntity(
+                    "error.attribute_value.unknown.attribute",
+                    HashMap::from([
+                        ("field".to_string(), "attribute_id".to_string()),
+                        ("attribute_id".to_string(), attribute_id.to_string()),
+                    ]),
+                )
+            })?;
+        check_with(&attribute, value)
+    }
+}
+
+/// Checks that only the column matching the attribute's data type is set and
+/// that its value meets the attribute's constraints. An empty value takes the
+/// attribute's default.
+fn check_with<V: ValueColumns>(attribute: &Attribute, mut value: V) -> Result<V, CoreError> {
+    let data_type = attribute.parsed_data_type()?;
+    let column = data_type.value_column();
+    let field_error = |message: &'static str, field: &str| {
+        CoreError::UnprocessableEntity(
+            message,
+            HashMap::from([
+                ("field".to_string(), field.to_string()),
+                ("attribute".to_string(), attribute.name.clone()),
+                ("data_type".to_string(), data_type.as_str().to_string()),
+            ]),
+        )
+    };
+    let mut current = None;
+    for (field, v) in value.values() {
+        match v {
+            Some(v) if field == column => current = Some(v),
+            Some(_) => return Err(field_error("error.attribute_value.invalid.column", field)),
+            None => {}
+        }
+    }
+    match current {
+        Some(v) => attribute.check_value(column, &v)?,
+        None => match attribute.default_scalar()? {
+            Some(default) => value.set_value(default),
+            None if attribute.required => {
+                return Err(field_error("error.attribute_value.required", column))
+            }
+            None => {}
+        },
+    }
+    Ok(value)
+}
+
+fn equals(property_name: &str, value: ScalarValue) -> Filter {
+    Filter::Property {
+        property_name: property_name.to_string(),
+        operator: FilterOperator::Equal,
+        value: FilterValue::Single(value),
+    }
+}
+
+fn entity_filters(entity_type: &str, entity_id: i32) -> Vec<Filter> {
+    vec![
+        equals("entity_type", ScalarValue::String(entity_type.to_string())),
+        equals("entity_id", ScalarValue::Int(entity_id)),
+    ]
 }
 
-impl<R: AttributeValueRepository> ViewService for AttributeValueService<R> {
+/// A value row without any value, for `set_attributes` to fill in and upsert.
+fn empty_row(attribute_id: i32, entity_type: &str, entity_id: i32) -> AttributeValue {
+    let now = time::OffsetDateTime::now_utc();
+    AttributeValue {
+        id: 0,
+        uid: String::new(),
+        version: 0,
+        created_at: now,
+        updated_at: now,
+        attribute_id,
+        entity_id,
+        entity_type: entity_type.to_string(),
+        int_value: None,
+        double_value: None,
+        string_value: None,
+        boolean_value: None,
+        date_value: None,
+        datetime_value: None,
+        time_value: None,
+    }
+}
+
+impl<R: AttributeValueRepository, A: AttributeRepository> ViewService
+    for AttributeValueService<R, A>
+{
     type Entity = AttributeValue;
     type Repo = R;
     fn get_repository(&self) -> &Self::Repo {
@@ -65,8 +336,24 @@ impl<R: AttributeValueRepository> ViewService for AttributeValueService<R> {
     }
 }
 
-impl<R: AttributeValueRepository> Service for AttributeValueService<R> {
+impl<R: AttributeValueRepository, A: AttributeRepository> Service for AttributeValueService<R, A> {
     type Create = AttributeValueCreate;
+
+    fn prepare_create(
+        &self,
+        _repo: &R,
+        create: &AttributeValueCreate,
+    ) -> impl Future<Output = Result<AttributeValueCreate, CoreError>> {
+        self.check(create.clone())
+    }
+
+    fn prepare_update(
+        &self,
+        _repo: &R,
+        entity: &AttributeValue,
+    ) -> impl Future<Output = Result<AttributeValue, CoreError>> {
+        self.check(entity.clone())
+    }
 }
 
 // Service for view (read-only)
@@ -88,3 +375,177 @@ impl<R: AttributeValueInfoRepository> ViewService for AttributeValueInfoService<
         &self.repository
     }
 }
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use super::*;
+    use crate::business::attribute_service::{Attribute, AttributeCreate, AttributeService};
+    use crate::infras::in_memory_repositories::{
+        AttributeInMemoryRepository, AttributeValueInMemoryRepository,
+    };
+    use crate::infras::in_memory_repository::InMemoryDatabase;
+
+    type Values =
+        AttributeValueService<AttributeValueInMemoryRepository, AttributeInMemoryRepository>;
+
+    async fn setup(configure: impl FnOnce(&mut Attribute)) -> (Values, i32) {
+        let database = InMemoryDatabase::new();
+        let attributes = Arc::new(AttributeInMemoryRepository::new(database.clone()));
+        let attribute_service = AttributeService::new(attributes.clone());
+        let mut attribute = attribute_service
+            .create(&AttributeCreate {
+                name: "rating".into(),
+                entity_type: "posts".into(),
+                data_type: "integer".into(),
+            })
+            .await
+            .unwrap();
+        configure(&mut attribute);
+        let attribute = attribute_service.update(&attribute).await.unwrap();
+        assert_eq!(attribute.data_type, "int");
+        let values = AttributeValueService::new(
+            Arc::new(AttributeValueInMemoryRepository::new(database)),
+            attributes,
+        );
+        (values, attribute.id)
+    }
+
+    fn value(attribute_id: i32, entity_id: i32) -> AttributeValueCreate {
+        AttributeValueCreate {
+            attribute_id,
+            entity_id,
+            entity_type: "posts".into(),
+            int_value: None,
+            double_value: None,
+            string_value: None,
+            boolean_value: None,
+            date_value: None,
+            datetime_value: None,
+            time_value: None,
+        }
+    }
+
+    fn rejected(result: Result<AttributeValue, CoreError>) -> (&'static str, String) {
+        match result {
+            Err(CoreError::UnprocessableEntity(message, details)) => {
+                (message, details["field"].clone())
+            }
+            other => panic!("expected an unprocessable entity, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_values_must_use_the_column_of_their_data_type() {
+        let (values, rating) = setup(|a| a.max_value = Some(5.0)).await;
+
+        let created = values
+            .create(&AttributeValueCreate {
+                int_value: Some(4),
+                ..value(rating, 1)
+            })
+            .await
+            .unwrap();
+        assert_eq!(created.int_value, Some(4));
+
+        let wrong_column = AttributeValueCreate {
+            string_value: Some("4".into()),
+            ..value(rating, 2)
+        };
+        assert_eq!(
+            rejected(values.create(&wrong_column).await),
+            (
+                "error.attribute_value.invalid.column",
+                "string_value".into()
+            )
+        );
+
+        let mut too_large = created.clone();
+        too_large.int_value = Some(6);
+        assert_eq!(
+            rejected(values.update(&too_large).await),
+            ("error.attribute_value.too_large", "int_value".into())
+        );
+
+        assert_eq!(
+            rejected(values.create(&value(rating + 100, 3)).await),
+            (
+                "error.attribute_value.unknown.attribute",
+                "attribute_id".into()
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn test_empty_values_take_the_default_or_fail_when_required() {
+        let (values, rating) = setup(|a| a.default_value = Some("3".into())).await;
+        let created = values.create(&value(rating, 1)).await.unwrap();
+        assert_eq!(created.int_value, Some(3));
+
+        let (values, rating) = setup(|a| a.required = true).await;
+        let results = values.create_many(&[value(rating, 1)]).await;
+        assert_eq!(
+            rejected(results.into_iter().next().unwrap()),
+            ("error.attribute_value.required", "int_value".into())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_set_attributes_writes_clears_and_reads_by_name() {
+        let (values, _) = setup(|a| a.max_value = Some(5.0)).await;
+
+        let written = values
+            .set_attributes(
+                "posts",
+                7,
+                HashMap::from([("rating".to_string(), Some(ScalarValue::Int(4)))]),
+            )
+            .await
+            .unwrap();
+        assert_eq!(written.get("rating"), Some(&ScalarValue::Int(4)));
+
+        let updated = values
+            .set_attributes(
+                "posts",
+                7,
+                HashMap::from([("rating".to_string(), Some(ScalarValue::Int(5)))]),
+            )
+            .await
+            .unwrap();
+        assert_eq!(updated.get("rating"), Some(&ScalarValue::Int(5)));
+        assert_eq!(values.count(vec![]).await.unwrap(), 1);
+
+        let unknown = values
+            .set_attributes(
+                "posts",
+                7,
+                HashMap::from([("colour".to_string(), Some(ScalarValue::Int(1)))]),
+            )
+            .await;
+        assert!(matches!(
+            unknown,
+            Err(CoreError::UnprocessableEntity("error.attribute.unknown", _))
+        ));
+
+        let wrong_type = values
+            .set_attributes(
+                "posts",
+                7,
+                HashMap::from([("rating".to_string(), Some(ScalarValue::Bool(true)))]),
+            )
+            .await;
+        assert!(matches!(
+            wrong_type,
+            Err(CoreError::UnprocessableEntity(
+                "error.attribute_value.invalid.type",
+                _
+            ))
+        ));
+
+        let cleared = values
+            .set_attributes("posts", 7, HashMap::from([("rating".to_string(), None)]))
+            .await
+            .unwrap();
+        assert!(cleared.is_empty());
+        assert!(values.get_attributes("posts", 8).await.unwrap().is_empty());
+    }
+}
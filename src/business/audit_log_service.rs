@@ -0,0 +1,39 @@
+use crate::common::repository::ViewRepository;
+use crate::common::service::ViewService;
+use crate::define_readonly_struct_with_common_fields;
+use std::sync::Arc;
+
+// View entity: audit log entries with the acting user's name. Entries are
+// written by the services themselves, so there is no table entity.
+define_readonly_struct_with_common_fields!(AuditLogInfo {
+    pub entity_type: String,
+    pub entity_id: i32,
+    pub entity_uid: String,
+    pub operation: String,
+    pub user_id: Option<i32>,
+    pub username: Option<String>,
+    pub changes: String,
+});
+
+// Repositories
+pub trait AuditLogInfoRepository: ViewRepository<AuditLogInfo> + Send + Sync {}
+
+// Service for view (read-only)
+#[derive(Clone)]
+pub struct AuditLogInfoService<R: AuditLogInfoRepository> {
+    repository: Arc<R>,
+}
+
+impl<R: AuditLogInfoRepository> AuditLogInfoService<R> {
+    pub fn new(repository: Arc<R>) -> Self {
+        Self { repository }
+    }
+}
+
+impl<R: AuditLogInfoRepository> ViewService for AuditLogInfoService<R> {
+    type Entity = AuditLogInfo;
+    type Repo = R;
+    fn get_repository(&self) -> &Self::Repo {
+        &self.repository
+    }
+}
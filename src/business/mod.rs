@@ -1,7 +1,10 @@
 pub mod macros;
 
+pub mod audit_log_service;
 pub mod auth_service;
+pub mod post_revision_service;
 pub mod post_service;
+pub mod post_term_service;
 pub mod post_taxonomy_service;
 pub mod user_service;
 
@@ -9,4 +12,6 @@ pub mod attribute_service;
 pub mod attribute_value_service;
 pub mod post_collection_service;
 pub mod post_type_service;
+pub mod slug_redirect_service;
+pub mod slug_service;
 pub mod term_service;
@@ -1,11 +1,19 @@
-use crate::common::repository::{Repository, ViewRepository};
+use crate::business::slug_redirect_service::{SlugRedirectCreate, SlugRedirectRecorder};
+use crate::business::slug_service::{SlugScope, SlugService};
+use crate::common::error::CoreError;
+use crate::common::repository::{Repository, Transactional, ViewRepository};
 use crate::common::service::{Service, ViewService};
 use crate::{define_readonly_struct_with_common_fields, define_struct_with_common_fields};
+use std::future::Future;
 use std::sync::Arc;
 
+/// Table of collections, as the `entity_type` of their old slugs.
+pub const POST_COLLECTIONS: &str = "post_collections";
+
 // Table entity: post_collections
 define_struct_with_common_fields!(PostCollection {
     req {
+        /// Made from the title when left empty.
         pub slug: String,
         pub title: String,
         pub visibility: String,
@@ -23,7 +31,11 @@ define_readonly_struct_with_common_fields!(PostCollectionInfo {
 });
 
 pub trait PostCollectionRepository:
-    Repository<PostCollection, PostCollectionCreate> + Send + Sync
+    Repository<PostCollection, PostCollectionCreate>
+    + Transactional
+    + SlugRedirectRecorder
+    + Send
+    + Sync
 {
 }
 pub trait PostCollectionInfoRepository: ViewRepository<PostCollectionInfo> + Send + Sync {}
@@ -48,8 +60,63 @@ impl<R: PostCollectionRepository> ViewService for PostCollectionService<R> {
     }
 }
 
+/// Collection slugs are unique across all collections.
+fn slug_scope() -> SlugScope<PostCollection> {
+    SlugScope {
+        column: "slug",
+        filters: vec![],
+        fallback: "collection",
+        underscores: false,
+        slug_of: |collection| &collection.slug,
+    }
+}
+
 impl<R: PostCollectionRepository> Service for PostCollectionService<R> {
     type Create = PostCollectionCreate;
+
+    fn prepare_create(
+        &self,
+        repo: &R,
+        create: &PostCollectionCreate,
+    ) -> impl Future<Output = Result<PostCollectionCreate, CoreError>> {
+        let mut create = create.clone();
+        async move {
+            let wanted = if create.slug.trim().is_empty() { &create.title } else { &create.slug };
+            create.slug =
+                SlugService::unique_slug(repo, &slug_scope(), wanted, None).await?;
+            Ok(create)
+        }
+    }
+
+    /// A changed slug is made unique, and the old one keeps leading to the
+    /// collection.
+    fn prepare_update(
+        &self,
+        repo: &R,
+        collection: &PostCollection,
+    ) -> impl Future<Output = Result<PostCollection, CoreError>> {
+        let mut collection = collection.clone();
+        async move {
+            let stored = repo.find_by_id(collection.id).await?;
+            collection.slug = SlugService::updated_slug(
+                repo,
+                &slug_scope(),
+                &collection,
+                stored.as_ref(),
+            )
+            .await?;
+            if let Some(stored) = stored.filter(|stored| stored.slug != collection.slug) {
+                repo.record_slug_redirect(&SlugRedirectCreate::renamed(
+                    POST_COLLECTIONS,
+                    0,
+                    stored.slug,
+                    collection.id,
+                ))
+                .await?;
+            }
+            Ok(collection)
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -0,0 +1,312 @@
+use crate::business::post_service::{Post, PostRepository, PostService, PostStatus, POSTS};
+use crate::common::audit::current_actor;
+use crate::common::cache::CACHE;
+use crate::common::error::CoreError;
+use crate::common::repository::{run_in_transaction, Repository, Transactional, ViewRepository};
+use crate::common::service::{Service, ViewService};
+use crate::{define_readonly_struct_with_common_fields, define_struct_with_common_fields};
+use std::future::Future;
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// Why a revision was taken. Autosaves follow every change to a post and are
+/// pruned first; checkpoints are saved on purpose and kept apart from them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RevisionKind {
+    AUTOSAVE,
+    CHECKPOINT,
+}
+
+impl RevisionKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RevisionKind::AUTOSAVE => "AUTOSAVE",
+            RevisionKind::CHECKPOINT => "CHECKPOINT",
+        }
+    }
+}
+
+impl FromStr for RevisionKind {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "AUTOSAVE" => Ok(RevisionKind::AUTOSAVE),
+            "CHECKPOINT" => Ok(RevisionKind::CHECKPOINT),
+            _ => Err(()),
+        }
+    }
+}
+
+// Table entity: a copy of the editable fields of a post
+define_struct_with_common_fields!(PostRevision {
+    req {
+        pub post_id: i32,
+        pub kind: RevisionKind,
+        pub title: String,
+        pub summary: String,
+        pub content: String,
+        pub status: PostStatus,
+        pub user_id: Option<i32>,
+    }
+    opt {}
+});
+
+// View entity: revisions with the name of the user who made them
+define_readonly_struct_with_common_fields!(PostRevisionInfo {
+    pub post_id: i32,
+    pub kind: RevisionKind,
+    pub title: String,
+    pub summary: String,
+    pub content: String,
+    pub status: PostStatus,
+    pub user_id: Option<i32>,
+    pub username: Option<String>,
+});
+
+impl PostRevision {
+    /// True when `post` has the same title, summary, content and status.
+    pub fn matches(&self, post: &Post) -> bool {
+        self.title == post.title
+            && self.summary == post.summary
+            && self.content == post.content
+            && self.status == post.status
+    }
+
+    /// Copies the revised fields back onto `post`. The status is only
+    /// recorded: it changes through the workflow, not by restoring.
+    pub fn apply_to(&self, post: &mut Post) {
+        post.title = self.title.clone();
+        post.summary = self.summary.clone();
+        post.content = self.content.clone();
+    }
+}
+
+/// How many revisions of each kind are kept per post; `None` keeps them all.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RevisionRetention {
+    pub max_autosaves: Option<usize>,
+    pub max_checkpoints: Option<usize>,
+}
+
+impl Default for RevisionRetention {
+    fn default() -> Self {
+        Self {
+            max_autosaves: Some(50),
+            max_checkpoints: None,
+        }
+    }
+}
+
+impl RevisionRetention {
+    pub fn max_for(&self, kind: RevisionKind) -> Option<usize> {
+        match kind {
+            RevisionKind::AUTOSAVE => self.max_autosaves,
+            RevisionKind::CHECKPOINT => self.max_checkpoints,
+        }
+    }
+}
+
+// Repositories
+pub trait PostRevisionRepository:
+    Repository<PostRevision, PostRevisionCreate> + Transactional + Send + Sync
+{
+    /// Most recent revision of a post, of either kind.
+    fn find_latest(
+        &self,
+        post_id: i32,
+    ) -> impl Future<Output = Result<Option<PostRevision>, CoreError>>;
+    /// Deletes all but the `keep` most recent revisions of `kind` of a post.
+    fn delete_oldest(
+        &self,
+        post_id: i32,
+        kind: RevisionKind,
+        keep: usize,
+    ) -> impl Future<Output = Result<u64, CoreError>>;
+}
+
+pub trait PostRevisionInfoRepository: ViewRepository<PostRevisionInfo> + Send + Sync {}
+
+// Services
+#[derive(Clone)]
+pub struct PostRevisionService<R: PostRevisionRepository> {
+    repository: Arc<R>,
+    retention: RevisionRetention,
+}
+
+impl<R: PostRevisionRepository> PostRevisionService<R> {
+    pub fn new(repository: Arc<R>, retention: RevisionRetention) -> Self {
+        Self {
+            repository,
+            retention,
+        }
+    }
+
+    /// Saves the current state of `post` as a revision by the current actor,
+    /// then prunes that kind down to the retention limit. Autosaves identical
+    /// to the latest revision are skipped and return `None`.
+    pub async fn record(
+        &self,
+        post: &Post,
+        kind: RevisionKind,
+    ) -> Result<Option<PostRevision>, CoreError> {
+        let retention = self.retention;
+        let tx = self.repository.begin().await?;
+        let repo = self.repository.in_transaction(&tx);
+        run_in_transaction(tx, move |_| async move {
+            if kind == RevisionKind::AUTOSAVE {
+                if let Some(latest) = repo.find_latest(post.id).await? {
+                    if latest.matches(post) {
+                        return Ok(None);
+                    }
+                }
+            }
+            let revision = repo
+                .create(&PostRevisionCreate {
+                    post_id: post.id,
+                    kind,
+                    title: post.title.clone(),
+                    summary: post.summary.clone(),
+                    content: post.content.clone(),
+                    status: post.status,
+                    user_id: current_actor(),
+                })
+                .await?;
+            if let Some(keep) = retention.max_for(kind) {
+                repo.delete_oldest(post.id, kind, keep).await?;
+            }
+            Ok(Some(revision))
+        })
+        .await
+    }
+
+    /// Updates a post through `posts` and autosaves the result, in one
+    /// transaction.
+    pub async fn update_post<P: PostRepository + Transactional<Tx = R::Tx>>(
+        &self,
+        posts: &PostService<P>,
+        post: &Post,
+    ) -> Result<Post, CoreError> {
+        self.write_post(posts, RevisionKind::AUTOSAVE, |posts| async move {
+            posts.update(post).await
+        })
+        .await
+    }
+
+    /// Batch counterpart of `update_post`: one result per post, in order.
+    /// Each post is written with its revision in its own transaction.
+    pub async fn update_posts<P: PostRepository + Transactional<Tx = R::Tx>>(
+        &self,
+        posts: &PostService<P>,
+        batch: &[Post],
+    ) -> Vec<Result<Post, CoreError>> {
+        let mut results = Vec::with_capacity(batch.len());
+        for post in batch {
+            results.push(self.update_post(posts, post).await);
+        }
+        results
+    }
+
+    /// Upserts posts through `posts` by `key` and autosaves the results, each
+    /// post in its own transaction. One result per post, in order.
+    pub async fn upsert_posts<P: PostRepository + Transactional<Tx = R::Tx>>(
+        &self,
+        posts: &PostService<P>,
+        key: &[&str],
+        batch: &[Post],
+    ) -> Vec<Result<Post, CoreError>> {
+        let mut results = Vec::with_capacity(batch.len());
+        for post in batch {
+            results.push(
+                self.write_post(posts, RevisionKind::AUTOSAVE, |posts| async move {
+                    posts
+                        .upsert_by(key, std::slice::from_ref(post))
+                        .await
+                        .remove(0)
+                })
+                .await,
+            );
+        }
+        results
+    }
+
+    /// Puts a post back the way it was at `revision_id`. The restored state is
+    /// saved as a new checkpoint, so a restore can itself be undone.
+    pub async fn restore<P: PostRepository + Transactional<Tx = R::Tx>>(
+        &self,
+        posts: &PostService<P>,
+        revision_id: i32,
+    ) -> Result<Post, CoreError> {
+        let revision = self
+            .repository
+            .find_by_id(revision_id)
+            .await?
+            .ok_or(CoreError::not_found("error.not_found"))?;
+        let mut post = posts
+            .get_by_id(revision.post_id)
+            .await?
+            .ok_or(CoreError::not_found("error.not_found"))?;
+        revision.apply_to(&mut post);
+        self.write_post(posts, RevisionKind::CHECKPOINT, |posts| async move {
+            posts.update(&post).await
+        })
+        .await
+    }
+
+    /// Runs `write` with `posts` joined to a new transaction and records the
+    /// post it returns as a `kind` revision in that same transaction, so a
+    /// post never changes without its revision.
+    async fn write_post<P, F, Fut>(
+        &self,
+        posts: &PostService<P>,
+        kind: RevisionKind,
+        write: F,
+    ) -> Result<Post, CoreError>
+    where
+        P: PostRepository + Transactional<Tx = R::Tx>,
+        F: FnOnce(PostService<P>) -> Fut,
+        Fut: Future<Output = Result<Post, CoreError>>,
+    {
+        let tx = self.repository.begin().await?;
+        let posts = posts.in_transaction(&tx);
+        let revisions = Self {
+            repository: Arc::new(self.repository.in_transaction(&tx)),
+            retention: self.retention,
+        };
+        let written = run_in_transaction(tx, move |_| async move {
+            let post = write(posts).await?;
+            revisions.record(&post, kind).await?;
+            Ok(post)
+        })
+        .await?;
+        // The update's own invalidation ran before the commit
+        CACHE.notify_write(POSTS);
+        Ok(written)
+    }
+}
+
+impl<R: PostRevisionRepository> ViewService for PostRevisionService<R> {
+    type Entity = PostRevision;
+    type Repo = R;
+    fn get_repository(&self) -> &Self::Repo {
+        &self.repository
+    }
+}
+
+#[derive(Clone)]
+pub struct PostRevisionInfoService<R: PostRevisionInfoRepository> {
+    repository: Arc<R>,
+}
+
+impl<R: PostRevisionInfoRepository> PostRevisionInfoService<R> {
+    pub fn new(repository: Arc<R>) -> Self {
+        Self { repository }
+    }
+}
+
+impl<R: PostRevisionInfoRepository> ViewService for PostRevisionInfoService<R> {
+    type Entity = PostRevisionInfo;
+    type Repo = R;
+    fn get_repository(&self) -> &Self::Repo {
+        &self.repository
+    }
+}
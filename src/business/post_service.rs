@@ -1,10 +1,27 @@
+use crate::business::slug_redirect_service::{SlugRedirectCreate, SlugRedirectRecorder};
+use crate::business::slug_service::{SlugScope, SlugService};
+use crate::business::user_service::UserRole;
+use crate::common::audit::{AuditEntry, AuditOperation};
+use crate::common::cache::{CacheConfig, CACHE};
 use crate::common::error::CoreError;
-use crate::common::repository::{Repository, ViewRepository};
+use crate::common::filter::{Filter, FilterOperator, FilterValue, ScalarValue, SoftDeleteScope};
+use crate::common::repository::{Repository, Transactional, ViewRepository};
 use crate::common::service::{Service, ViewService};
 use crate::{define_readonly_struct_with_common_fields, define_struct_with_common_fields};
+use std::collections::HashMap;
 use std::future::Future;
 use std::str::FromStr;
 use std::sync::Arc;
+use std::time::Duration;
+
+/// Cache namespace of rendered post bodies, keyed by post id and version.
+pub const POST_HTML: &str = "POST_HTML";
+
+/// Table of posts, as the `entity_type` of their old slugs.
+pub const POSTS: &str = "posts";
+
+/// Posts are unique by slug within their type; upserts match on these.
+pub const UPSERT_KEY: [&str; 2] = ["type_id", "slug"];
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 #[repr(i32)]
@@ -62,17 +79,116 @@ impl FromStr for PostStatus {
 define_struct_with_common_fields!(Post {
     req {
         pub title: String,
+        /// Made from the title when left empty.
+        pub slug: String,
         pub user_id: i32,
         pub type_id: i32,
     }
     opt {
-        pub slug: String,
         pub summary: String,
         pub content: String,
         pub status: PostStatus,
+        /// Set the first time the post is published, and kept from then on.
+        pub published_at: Option<time::OffsetDateTime>,
+        /// When a post in review is published by the scheduler.
+        pub scheduled_at: Option<time::OffsetDateTime>,
+        /// When a published post is archived by the scheduler.
+        pub expires_at: Option<time::OffsetDateTime>,
+    }
+});
+
+// Table entity: post_transitions, the history of a post's workflow.
+define_struct_with_common_fields!(PostTransition {
+    req {
+        pub post_id: i32,
+        pub from_status: PostStatus,
+        pub to_status: PostStatus,
+        pub user_id: Option<i32>,
+        pub comment: Option<String>,
     }
+    opt {}
 });
 
+/// A status change of the editorial workflow.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WorkflowRule {
+    /// What the change is called in the admin UI.
+    pub action: &'static str,
+    pub from: PostStatus,
+    pub to: PostStatus,
+    /// Only editors and admins may make it; otherwise the author may too.
+    pub editor_only: bool,
+}
+
+const fn rule(
+    action: &'static str,
+    from: PostStatus,
+    to: PostStatus,
+    editor_only: bool,
+) -> WorkflowRule {
+    WorkflowRule {
+        action,
+        from,
+        to,
+        editor_only,
+    }
+}
+
+/// Every status change a post can go through. Authors move their drafts in
+/// and out of review; editors decide what gets published and archived.
+/// Trashing is not a status change: it sets `deleted_at` like any other row.
+pub const WORKFLOW: &[WorkflowRule] = &[
+    rule(
+        "Submit for review",
+        PostStatus::DRAFT,
+        PostStatus::REVIEW,
+        false,
+    ),
+    rule(
+        "Back to draft",
+        PostStatus::REVIEW,
+        PostStatus::DRAFT,
+        false,
+    ),
+    rule("Publish", PostStatus::REVIEW, PostStatus::PUBLISHED, true),
+    rule("Publish", PostStatus::DRAFT, PostStatus::PUBLISHED, true),
+    rule("Unpublish", PostStatus::PUBLISHED, PostStatus::DRAFT, true),
+    rule("Archive", PostStatus::PUBLISHED, PostStatus::ARCHIVED, true),
+    rule("Reopen", PostStatus::ARCHIVED, PostStatus::DRAFT, true),
+];
+
+/// Who asks for a status change.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WorkflowActor {
+    pub user_id: Option<i32>,
+    pub role: UserRole,
+}
+
+impl WorkflowActor {
+    /// The scheduler, which acts for no user and may make any change.
+    pub const SYSTEM: WorkflowActor = WorkflowActor {
+        user_id: None,
+        role: UserRole::ADMIN,
+    };
+
+    pub fn is_editor(&self) -> bool {
+        matches!(self.role, UserRole::EDITOR | UserRole::ADMIN)
+    }
+
+    pub fn may(&self, rule: &WorkflowRule, post: &Post) -> bool {
+        self.is_editor() || (!rule.editor_only && self.user_id == Some(post.user_id))
+    }
+}
+
+/// What one pass of the publishing schedule did.
+#[derive(Debug, Default)]
+pub struct ScheduleRun {
+    pub published: Vec<i32>,
+    pub archived: Vec<i32>,
+    /// Posts that were due but could not be moved; the next pass retries them.
+    pub failed: Vec<(i32, CoreError)>,
+}
+
 define_readonly_struct_with_common_fields!(PostInfo {
     pub slug: String,
     pub title: String,
@@ -84,9 +200,36 @@ define_readonly_struct_with_common_fields!(PostInfo {
     pub email: String,
 });
 
-pub trait PostRepository: Repository<Post, PostCreate> + Send + Sync {
+pub trait PostRepository:
+    Repository<Post, PostCreate> + Transactional + SlugRedirectRecorder + Send + Sync
+{
     fn find_by_slug(&self, slug: &str) -> impl Future<Output = Result<Option<Post>, CoreError>>;
     fn find_by_author(&self, user_id: i32) -> impl Future<Output = Result<Vec<Post>, CoreError>>;
+    /// Appends to the post's workflow history, in this repository's transaction.
+    fn record_transition(
+        &self,
+        transition: &PostTransitionCreate,
+    ) -> impl Future<Output = Result<PostTransition, CoreError>>;
+    /// The post's workflow history, newest first.
+    fn find_transitions(
+        &self,
+        post_id: i32,
+    ) -> impl Future<Output = Result<Vec<PostTransition>, CoreError>>;
+}
+
+/// Post slugs are unique within their post type.
+fn slug_scope(type_id: i32) -> SlugScope<Post> {
+    SlugScope {
+        column: "slug",
+        filters: vec![Filter::Property {
+            property_name: "type_id".to_string(),
+            operator: FilterOperator::Equal,
+            value: FilterValue::Single(ScalarValue::Int(type_id)),
+        }],
+        fallback: "post",
+        underscores: false,
+        slug_of: |post| &post.slug,
+    }
 }
 #[derive(Clone)]
 pub struct PostService<R: PostRepository> {
@@ -95,12 +238,243 @@ pub struct PostService<R: PostRepository> {
 
 impl<R: PostRepository> PostService<R> {
     pub fn new(repository: Arc<R>) -> Self {
+        CACHE.configure(
+            POST_HTML,
+            CacheConfig {
+                capacity: 512,
+                ttl: Some(Duration::from_secs(3600)),
+            },
+        );
         Self { repository }
     }
 
+    /// Copy of this service whose writes join `tx`.
+    pub fn in_transaction(&self, tx: &R::Tx) -> Self {
+        Self {
+            repository: Arc::new(self.repository.in_transaction(tx)),
+        }
+    }
+
     pub async fn get_by_slug(&self, slug: &str) -> Result<Option<Post>, CoreError> {
         self.repository.find_by_slug(slug).await
     }
+
+    /// The post's Markdown content as HTML. Every update bumps the version, so
+    /// a cached rendering never outlives the content it was made from.
+    pub async fn render_html(&self, post: &Post) -> Result<String, CoreError> {
+        let key = format!("{}:{}", post.id, post.version);
+        CACHE
+            .get_or_compute(POST_HTML, &key, || async {
+                markdown::to_html_with_options(&post.content, &markdown::Options::gfm())
+                    .map_err(|_| CoreError::internal_server_error("error.markdown.render"))
+            })
+            .await
+    }
+
+    /// The workflow rules `actor` may apply to `post` now.
+    pub fn available_transitions(&self, post: &Post, actor: &WorkflowActor) -> Vec<WorkflowRule> {
+        WORKFLOW
+            .iter()
+            .filter(|rule| rule.from == post.status && actor.may(rule, post))
+            .copied()
+            .collect()
+    }
+
+    /// Moves the post to status `to` if the workflow allows `actor` to, and
+    /// records the change with `comment`. Publishing for the first time stamps
+    /// `published_at`.
+    pub async fn transition(
+        &self,
+        post_id: i32,
+        to: PostStatus,
+        comment: Option<String>,
+        actor: &WorkflowActor,
+    ) -> Result<Post, CoreError> {
+        self.with_transaction(move |repo, _| async move {
+            let mut post = repo
+                .find_by_id(post_id)
+                .await?
+                .ok_or(CoreError::not_found("error.not_found"))?;
+            let from = post.status;
+            let rule = WORKFLOW
+                .iter()
+                .find(|rule| rule.from == from && rule.to == to)
+                .ok_or_else(|| {
+                    CoreError::UnprocessableEntity(
+                        "error.post.transition.invalid",
+                        HashMap::from([
+                            ("from".to_string(), from.as_str().to_string()),
+                            ("to".to_string(), to.as_str().to_string()),
+                        ]),
+                    )
+                })?;
+            if !actor.may(rule, &post) {
+                return Err(CoreError::forbidden("error.post.transition.forbidden"));
+            }
+
+            post.status = to;
+            if to == PostStatus::PUBLISHED {
+                post.published_at
+                    .get_or_insert_with(time::OffsetDateTime::now_utc);
+                post.scheduled_at = None;
+            }
+            // An expiry left behind would archive the post again once it is
+            // republished
+            if from == PostStatus::PUBLISHED {
+                post.expires_at = None;
+            }
+            let before = repo
+                .snapshot_by_ids(vec![post_id], SoftDeleteScope::Include)
+                .await?;
+            let updated = repo.update(&post).await?;
+            let after = repo
+                .snapshot_by_ids(vec![post_id], SoftDeleteScope::Include)
+                .await?;
+            repo.record_audit(AuditEntry::from_snapshots(
+                AuditOperation::Update,
+                before,
+                after,
+            ))
+            .await?;
+            repo.record_transition(&PostTransitionCreate {
+                post_id,
+                from_status: from,
+                to_status: to,
+                user_id: actor.user_id,
+                comment: comment
+                    .map(|c| c.trim().to_string())
+                    .filter(|c| !c.is_empty()),
+            })
+            .await?;
+            Ok(updated)
+        })
+        .await
+    }
+
+    pub async fn get_transitions(&self, post_id: i32) -> Result<Vec<PostTransition>, CoreError> {
+        self.repository.find_transitions(post_id).await
+    }
+
+    /// Sets when the post goes live on its own (if it is in review by then)
+    /// and when it is archived on its own (if it is published by then).
+    /// `None` clears a date. Only editors decide this.
+    pub async fn schedule(
+        &self,
+        post_id: i32,
+        scheduled_at: Option<time::OffsetDateTime>,
+        expires_at: Option<time::OffsetDateTime>,
+        actor: &WorkflowActor,
+    ) -> Result<Post, CoreError> {
+        if !actor.is_editor() {
+            return Err(CoreError::forbidden("error.post.schedule.forbidden"));
+        }
+        if let (Some(scheduled_at), Some(expires_at)) = (scheduled_at, expires_at) {
+            if expires_at <= scheduled_at {
+                return Err(CoreError::unprocessable_entity(
+                    "error.post.schedule.expires_before_publication",
+                ));
+            }
+        }
+        self.with_transaction(move |repo, _| async move {
+            let mut post = repo
+                .find_by_id(post_id)
+                .await?
+                .ok_or(CoreError::not_found("error.not_found"))?;
+            post.scheduled_at = scheduled_at;
+            post.expires_at = expires_at;
+            let before = repo
+                .snapshot_by_ids(vec![post_id], SoftDeleteScope::Include)
+                .await?;
+            let updated = repo.update(&post).await?;
+            let after = repo
+                .snapshot_by_ids(vec![post_id], SoftDeleteScope::Include)
+                .await?;
+            repo.record_audit(AuditEntry::from_snapshots(
+                AuditOperation::Update,
+                before,
+                after,
+            ))
+            .await?;
+            Ok(updated)
+        })
+        .await
+    }
+
+    /// Publishes the posts in review whose `scheduled_at` has come and
+    /// archives the published ones whose `expires_at` has, as the scheduler.
+    /// Each post moves in its own transaction, so one failure does not hold
+    /// back the others.
+    pub async fn run_schedule(&self, now: time::OffsetDateTime) -> Result<ScheduleRun, CoreError> {
+        let due = |status: PostStatus, column: &str| {
+            vec![
+                Filter::Property {
+                    property_name: "status".to_string(),
+                    operator: FilterOperator::Equal,
+                    value: FilterValue::Single(ScalarValue::Int(status.as_i32())),
+                },
+                Filter::Property {
+                    property_name: column.to_string(),
+                    operator: FilterOperator::LessThanOrEqual,
+                    value: FilterValue::Single(ScalarValue::DateTime(now)),
+                },
+            ]
+        };
+        let mut run = ScheduleRun::default();
+        for post in self
+            .repository
+            .find_all(due(PostStatus::REVIEW, "scheduled_at"))
+            .await?
+        {
+            let comment = Some("Scheduled publication".to_string());
+            match self
+                .transition(
+                    post.id,
+                    PostStatus::PUBLISHED,
+                    comment,
+                    &WorkflowActor::SYSTEM,
+                )
+                .await
+            {
+                Ok(_) => run.published.push(post.id),
+                Err(e) => run.failed.push((post.id, e)),
+            }
+        }
+        for post in self
+            .repository
+            .find_all(due(PostStatus::PUBLISHED, "expires_at"))
+            .await?
+        {
+            let comment = Some("Expired".to_string());
+            match self
+                .transition(
+                    post.id,
+                    PostStatus::ARCHIVED,
+                    comment,
+                    &WorkflowActor::SYSTEM,
+                )
+                .await
+            {
+                Ok(_) => run.archived.push(post.id),
+                Err(e) => run.failed.push((post.id, e)),
+            }
+        }
+        Ok(run)
+    }
+
+    /// The row an upsert of `post` would update, trashed or not.
+    async fn find_by_upsert_key(repo: &R, post: &Post) -> Result<Option<Post>, CoreError> {
+        let equals = |column: &str, value| Filter::Property {
+            property_name: column.to_string(),
+            operator: FilterOperator::Equal,
+            value: FilterValue::Single(value),
+        };
+        let filters = vec![
+            equals(UPSERT_KEY[0], ScalarValue::Int(post.type_id)),
+            equals(UPSERT_KEY[1], ScalarValue::String(post.slug.clone())),
+            Filter::SoftDeleted(SoftDeleteScope::Include),
+        ];
+        Ok(repo.find_all(filters).await?.into_iter().next())
+    }
 }
 
 impl<R: PostRepository> ViewService for PostService<R> {
@@ -114,6 +488,96 @@ impl<R: PostRepository> ViewService for PostService<R> {
 
 impl<R: PostRepository> Service for PostService<R> {
     type Create = PostCreate;
+
+    fn prepare_create(
+        &self,
+        repo: &R,
+        create: &PostCreate,
+    ) -> impl Future<Output = Result<PostCreate, CoreError>> {
+        let mut create = create.clone();
+        async move {
+            let wanted = if create.slug.trim().is_empty() {
+                &create.title
+            } else {
+                &create.slug
+            };
+            create.slug =
+                SlugService::unique_slug(repo, &slug_scope(create.type_id), wanted, None).await?;
+            Ok(create)
+        }
+    }
+
+    /// Status only changes through `transition`, and `published_at` only
+    /// with it; the schedule only through `schedule`. An upsert (no id) is
+    /// held to the row with its `UPSERT_KEY`, and a new post it creates
+    /// starts as an unscheduled draft.
+    ///
+    /// A changed slug is made unique, and once the post has been published
+    /// its old slug keeps leading to it.
+    fn prepare_update(
+        &self,
+        repo: &R,
+        post: &Post,
+    ) -> impl Future<Output = Result<Post, CoreError>> {
+        let mut post = post.clone();
+        async move {
+            let upsert = post.id == 0;
+            let stored = if upsert {
+                Self::find_by_upsert_key(repo, &post).await?
+            } else {
+                repo.find_by_id(post.id).await?
+            };
+            match stored {
+                Some(stored) if stored.status != post.status => {
+                    return Err(CoreError::UnprocessableEntity(
+                        "error.post.status.transition_required",
+                        HashMap::from([
+                            ("status".to_string(), stored.status.as_str().to_string()),
+                            ("requested".to_string(), post.status.as_str().to_string()),
+                        ]),
+                    ));
+                }
+                Some(stored) => {
+                    post.published_at = stored.published_at;
+                    post.scheduled_at = stored.scheduled_at;
+                    post.expires_at = stored.expires_at;
+                    post.slug = SlugService::updated_slug(
+                        repo,
+                        &slug_scope(post.type_id),
+                        &post,
+                        Some(&stored),
+                    )
+                    .await?;
+                    if post.slug != stored.slug && stored.published_at.is_some() {
+                        repo.record_slug_redirect(&SlugRedirectCreate::renamed(
+                            POSTS,
+                            stored.type_id,
+                            stored.slug,
+                            post.id,
+                        ))
+                        .await?;
+                    }
+                }
+                None if upsert && post.status != PostStatus::DRAFT => {
+                    return Err(CoreError::UnprocessableEntity(
+                        "error.post.status.transition_required",
+                        HashMap::from([
+                            ("status".to_string(), PostStatus::DRAFT.as_str().to_string()),
+                            ("requested".to_string(), post.status.as_str().to_string()),
+                        ]),
+                    ));
+                }
+                None if upsert => {
+                    post.published_at = None;
+                    post.scheduled_at = None;
+                    post.expires_at = None;
+                }
+                // Gone or in the trash: the update would find no row either
+                None => return Err(CoreError::not_found("error.not_found")),
+            }
+            Ok(post)
+        }
+    }
 }
 
 pub trait PostInfoRepository: ViewRepository<PostInfo> + Send + Sync {}
@@ -138,3 +602,307 @@ impl<R: PostInfoRepository> ViewService for PostInfoService<R> {
         &self.post_info_repository
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infras::in_memory_repositories::{
+        PostInMemoryRepository, SlugRedirectInMemoryRepository,
+    };
+    use crate::infras::in_memory_repository::InMemoryDatabase;
+
+    const AUTHOR: WorkflowActor = WorkflowActor {
+        user_id: Some(7),
+        role: UserRole::USER,
+    };
+    const EDITOR: WorkflowActor = WorkflowActor {
+        user_id: Some(8),
+        role: UserRole::EDITOR,
+    };
+
+    async fn setup() -> (PostService<PostInMemoryRepository>, Post) {
+        let service = PostService::new(Arc::new(PostInMemoryRepository::new(
+            InMemoryDatabase::new(),
+        )));
+        let post = service
+            .create(&PostCreate {
+                title: "Workflow".into(),
+                slug: String::new(),
+                user_id: 7,
+                type_id: 1,
+            })
+            .await
+            .unwrap();
+        (service, post)
+    }
+
+    fn actions(
+        service: &PostService<PostInMemoryRepository>,
+        post: &Post,
+        actor: &WorkflowActor,
+    ) -> Vec<PostStatus> {
+        service
+            .available_transitions(post, actor)
+            .iter()
+            .map(|rule| rule.to)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_author_submits_and_editor_publishes() {
+        let (service, post) = setup().await;
+        let stranger = WorkflowActor {
+            user_id: Some(9),
+            role: UserRole::USER,
+        };
+        assert_eq!(
+            actions(&service, &post, &AUTHOR),
+            vec![PostStatus::REVIEW]
+        );
+        assert!(actions(&service, &post, &stranger).is_empty());
+
+        let post = service
+            .transition(post.id, PostStatus::REVIEW, Some(" ready ".into()), &AUTHOR)
+            .await
+            .unwrap();
+        assert_eq!(post.status, PostStatus::REVIEW);
+
+        let denied = service
+            .transition(post.id, PostStatus::PUBLISHED, None, &AUTHOR)
+            .await;
+        assert!(matches!(
+            denied,
+            Err(CoreError::Forbidden("error.post.transition.forbidden", _))
+        ));
+        for to in [PostStatus::ARCHIVED, PostStatus::DELETED] {
+            let invalid = service.transition(post.id, to, None, &EDITOR).await;
+            assert!(matches!(
+                invalid,
+                Err(CoreError::UnprocessableEntity(
+                    "error.post.transition.invalid",
+                    _
+                ))
+            ));
+        }
+
+        let published = service
+            .transition(post.id, PostStatus::PUBLISHED, Some(String::new()), &EDITOR)
+            .await
+            .unwrap();
+        assert_eq!(published.status, PostStatus::PUBLISHED);
+        let published_at = published
+            .published_at
+            .expect("publishing stamps published_at");
+
+        // Unpublishing and publishing again keeps the first publication date
+        service
+            .transition(post.id, PostStatus::DRAFT, None, &EDITOR)
+            .await
+            .unwrap();
+        let republished = service
+            .transition(post.id, PostStatus::PUBLISHED, None, &EDITOR)
+            .await
+            .unwrap();
+        assert_eq!(republished.published_at, Some(published_at));
+
+        let history: Vec<_> = service
+            .get_transitions(post.id)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|t| (t.from_status, t.to_status, t.user_id, t.comment))
+            .collect();
+        assert_eq!(
+            history,
+            vec![
+                (PostStatus::DRAFT, PostStatus::PUBLISHED, Some(8), None),
+                (PostStatus::PUBLISHED, PostStatus::DRAFT, Some(8), None),
+                (PostStatus::REVIEW, PostStatus::PUBLISHED, Some(8), None),
+                (
+                    PostStatus::DRAFT,
+                    PostStatus::REVIEW,
+                    Some(7),
+                    Some("ready".to_string())
+                ),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_update_cannot_change_status_or_published_at() {
+        let (service, post) = setup().await;
+        let published = service
+            .transition(post.id, PostStatus::PUBLISHED, None, &EDITOR)
+            .await
+            .unwrap();
+
+        let mut edit = published.clone();
+        edit.status = PostStatus::ARCHIVED;
+        let rejected = service.update(&edit).await;
+        assert!(matches!(
+            rejected,
+            Err(CoreError::UnprocessableEntity(
+                "error.post.status.transition_required",
+                _
+            ))
+        ));
+
+        let mut edit = published.clone();
+        edit.title = "Renamed".into();
+        edit.published_at = None;
+        let updated = service.update(&edit).await.unwrap();
+        assert_eq!(updated.title, "Renamed");
+        assert_eq!(updated.published_at, published.published_at);
+    }
+
+    #[tokio::test]
+    async fn test_upsert_cannot_change_status() {
+        let (service, post) = setup().await;
+        let published = service
+            .transition(post.id, PostStatus::PUBLISHED, None, &EDITOR)
+            .await
+            .unwrap();
+
+        // Upserts carry no id; the stored row is found by type and slug
+        let mut edit = published.clone();
+        edit.id = 0;
+        edit.status = PostStatus::DRAFT;
+        let rejected = service.upsert_by(&UPSERT_KEY, &[edit.clone()]).await;
+        assert!(matches!(
+            rejected[..],
+            [Err(CoreError::UnprocessableEntity(
+                "error.post.status.transition_required",
+                _
+            ))]
+        ));
+        edit.status = PostStatus::PUBLISHED;
+        edit.title = "Upserted".into();
+        let upserted = service.upsert_by(&UPSERT_KEY, &[edit.clone()]).await;
+        let upserted = upserted[0].as_ref().unwrap();
+        assert_eq!(upserted.id, published.id);
+        assert_eq!(upserted.title, "Upserted");
+
+        let mut new = edit;
+        new.slug = "brand-new".into();
+        let rejected = service.upsert_by(&UPSERT_KEY, &[new.clone()]).await;
+        assert!(rejected[0].is_err());
+        new.status = PostStatus::DRAFT;
+        let created = service.upsert_by(&UPSERT_KEY, &[new]).await;
+        let created = created[0].as_ref().unwrap();
+        assert_ne!(created.id, published.id);
+        assert_eq!(created.published_at, None);
+    }
+
+    #[tokio::test]
+    async fn test_run_schedule_publishes_and_archives_due_posts() {
+        let (service, post) = setup().await;
+        let now = time::OffsetDateTime::now_utc();
+        let hour = time::Duration::hours(1);
+
+        assert!(matches!(
+            service.schedule(post.id, Some(now), None, &AUTHOR).await,
+            Err(CoreError::Forbidden("error.post.schedule.forbidden", _))
+        ));
+        assert!(matches!(
+            service
+                .schedule(post.id, Some(now), Some(now - hour), &EDITOR)
+                .await,
+            Err(CoreError::UnprocessableEntity(
+                "error.post.schedule.expires_before_publication",
+                _
+            ))
+        ));
+        service
+            .schedule(post.id, Some(now + hour), Some(now + hour * 2), &EDITOR)
+            .await
+            .unwrap();
+
+        // Not due yet, and drafts wait for review even when they are
+        let run = service.run_schedule(now).await.unwrap();
+        assert!(run.published.is_empty() && run.failed.is_empty());
+        let run = service.run_schedule(now + hour).await.unwrap();
+        assert!(run.published.is_empty());
+
+        service
+            .transition(post.id, PostStatus::REVIEW, None, &AUTHOR)
+            .await
+            .unwrap();
+        let run = service.run_schedule(now + hour).await.unwrap();
+        assert_eq!(run.published, vec![post.id]);
+        let published = service.get_by_id(post.id).await.unwrap().unwrap();
+        assert_eq!(published.status, PostStatus::PUBLISHED);
+        assert!(published.published_at.is_some());
+        assert_eq!(published.scheduled_at, None);
+
+        let run = service.run_schedule(now + hour * 2).await.unwrap();
+        assert_eq!(run.archived, vec![post.id]);
+        let archived = service.get_by_id(post.id).await.unwrap().unwrap();
+        assert_eq!(archived.status, PostStatus::ARCHIVED);
+        assert_eq!(archived.expires_at, None);
+
+        let history = service.get_transitions(post.id).await.unwrap();
+        assert_eq!(history[0].comment.as_deref(), Some("Expired"));
+        assert_eq!(history[0].user_id, None);
+        assert_eq!(history[1].comment.as_deref(), Some("Scheduled publication"));
+    }
+
+    #[tokio::test]
+    async fn test_slugs_are_unique_and_published_renames_redirect() {
+        let (service, workflow) = setup().await;
+        assert_eq!(workflow.slug, "workflow");
+        let create = |title: &str, slug: &str| PostCreate {
+            title: title.into(),
+            slug: slug.into(),
+            user_id: 7,
+            type_id: 1,
+        };
+        let first = service
+            .create(&create("Bài viết mới nhất", ""))
+            .await
+            .unwrap();
+        assert_eq!(first.slug, "bai-viet-moi-nhat");
+        let second = service
+            .create(&create("Bài viết mới nhất!", ""))
+            .await
+            .unwrap();
+        assert_eq!(second.slug, "bai-viet-moi-nhat-2");
+        let chosen = service
+            .create(&create("Anything", "Workflow Notes"))
+            .await
+            .unwrap();
+        assert_eq!(chosen.slug, "workflow-notes");
+
+        // A draft is renamed freely
+        let mut draft = second.clone();
+        draft.slug = "workflow".into();
+        let draft = service.update(&draft).await.unwrap();
+        assert_eq!(draft.slug, "workflow-2");
+
+        let redirects: SlugRedirectInMemoryRepository = service.repository.sibling();
+        assert!(redirects.find_all(vec![]).await.unwrap().is_empty());
+
+        service
+            .transition(first.id, PostStatus::REVIEW, None, &AUTHOR)
+            .await
+            .unwrap();
+        let mut published = service
+            .transition(first.id, PostStatus::PUBLISHED, None, &EDITOR)
+            .await
+            .unwrap();
+        published.slug = "Bài mới".into();
+        let renamed = service.update(&published).await.unwrap();
+        assert_eq!(renamed.slug, "bai-moi");
+
+        // An update that fails takes its redirect with it
+        let mut stale = published.clone();
+        stale.slug = "bai-khac".into();
+        assert!(service.update(&stale).await.is_err());
+
+        let redirects = redirects.find_all(vec![]).await.unwrap();
+        assert_eq!(redirects.len(), 1);
+        assert_eq!(redirects[0].entity_type, POSTS);
+        assert_eq!(redirects[0].scope_id, 1);
+        assert_eq!(redirects[0].old_slug, "bai-viet-moi-nhat");
+        assert_eq!(redirects[0].entity_id, Some(first.id));
+    }
+}
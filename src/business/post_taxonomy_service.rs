@@ -1,4 +1,4 @@
-use crate::common::repository::{Repository, ViewRepository};
+use crate::common::repository::{Repository, Transactional, ViewRepository};
 use crate::common::service::{Service, ViewService};
 use crate::{define_readonly_struct_with_common_fields, define_struct_with_common_fields};
 use std::sync::Arc;
@@ -17,7 +17,7 @@ define_readonly_struct_with_common_fields!(PostTaxonomyInfo {
 });
 
 pub trait PostTaxonomyRepository:
-    Repository<PostTaxonomy, PostTaxonomyCreate> + Send + Sync
+    Repository<PostTaxonomy, PostTaxonomyCreate> + Transactional + Send + Sync
 {
 }
 pub trait PostTaxonomyInfoRepository: ViewRepository<PostTaxonomyInfo> + Send + Sync {}
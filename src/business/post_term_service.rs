@@ -0,0 +1,453 @@
+use crate::business::post_service::{Post, PostRepository, POSTS};
+use crate::business::term_service::{TermInfo, TermInfoRepository};
+use crate::common::audit::{AuditEntry, AuditOperation, Snapshot};
+use crate::common::cache::CACHE;
+use crate::common::error::CoreError;
+use crate::common::filter::{Filter, FilterOperator, FilterValue, ScalarValue, SoftDeleteScope};
+use crate::common::repository::run_in_transaction;
+use crate::common::sort::SortCriterion;
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::sync::Arc;
+
+/// Join table of posts and their terms.
+pub const POST_TERMS: &str = "post_terms";
+
+// Repositories. Posts own their rows of `post_terms`, which have no entity
+// of their own.
+pub trait PostTermRepository: PostRepository {
+    /// Ids of the terms of a post, trashed ones included.
+    fn find_term_ids(&self, post_id: i32) -> impl Future<Output = Result<Vec<i32>, CoreError>>;
+    /// Gives a post the terms it does not have yet; returns how many.
+    fn insert_terms(
+        &self,
+        post_id: i32,
+        term_ids: &[i32],
+    ) -> impl Future<Output = Result<u64, CoreError>>;
+    /// Takes terms off a post; returns how many it had.
+    fn delete_terms(
+        &self,
+        post_id: i32,
+        term_ids: &[i32],
+    ) -> impl Future<Output = Result<u64, CoreError>>;
+}
+
+// Service assigning terms to posts. Terms are checked and listed through
+// `terms`.
+#[derive(Clone)]
+pub struct PostTermService<R: PostTermRepository, T: TermInfoRepository> {
+    repository: Arc<R>,
+    terms: Arc<T>,
+}
+
+impl<R: PostTermRepository, T: TermInfoRepository> PostTermService<R, T> {
+    pub fn new(repository: Arc<R>, terms: Arc<T>) -> Self {
+        Self { repository, terms }
+    }
+
+    /// Terms of a post by taxonomy, then name; only those of the taxonomy
+    /// with code `taxonomy` when one is given. Trashed terms are left out.
+    pub async fn get_terms(
+        &self,
+        post_id: i32,
+        taxonomy: Option<&str>,
+    ) -> Result<Vec<TermInfo>, CoreError> {
+        let term_ids = self.repository.find_term_ids(post_id).await?;
+        let mut terms = self.find_terms(&term_ids).await?;
+        terms.retain(|term| taxonomy.is_none_or(|code| term.taxonomy_code == code));
+        terms.sort_by(|a, b| {
+            (&a.taxonomy_code, &a.name, a.id).cmp(&(&b.taxonomy_code, &b.name, b.id))
+        });
+        Ok(terms)
+    }
+
+    /// Adds terms to a post, keeping the ones it has. Returns its terms.
+    pub async fn assign(&self, post_id: i32, term_ids: &[i32]) -> Result<Vec<TermInfo>, CoreError> {
+        self.checked_terms(term_ids, None).await?;
+        self.write_terms(post_id, move |repo| async move {
+            repo.insert_terms(post_id, term_ids).await?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// Takes terms off a post; those it does not have are ignored. Returns
+    /// its remaining terms.
+    pub async fn unassign(
+        &self,
+        post_id: i32,
+        term_ids: &[i32],
+    ) -> Result<Vec<TermInfo>, CoreError> {
+        self.write_terms(post_id, move |repo| async move {
+            repo.delete_terms(post_id, term_ids).await?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// Makes `term_ids` the terms of a post: all of them, or only those of
+    /// the taxonomy with code `taxonomy`, leaving its other terms as they
+    /// are. Returns its terms.
+    pub async fn replace(
+        &self,
+        post_id: i32,
+        taxonomy: Option<&str>,
+        term_ids: &[i32],
+    ) -> Result<Vec<TermInfo>, CoreError> {
+        self.checked_terms(term_ids, taxonomy).await?;
+        self.write_terms(post_id, move |repo| async move {
+            let current = repo.find_term_ids(post_id).await?;
+            let replaced: Vec<i32> = match taxonomy {
+                Some(_) => self
+                    .find_terms(&current)
+                    .await?
+                    .into_iter()
+                    .filter(|term| Some(term.taxonomy_code.as_str()) == taxonomy)
+                    .map(|term| term.id)
+                    .collect(),
+                None => current,
+            };
+            let wanted: HashSet<i32> = term_ids.iter().copied().collect();
+            let removed: Vec<i32> = replaced
+                .into_iter()
+                .filter(|id| !wanted.contains(id))
+                .collect();
+            repo.delete_terms(post_id, &removed).await?;
+            repo.insert_terms(post_id, term_ids).await?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// Posts having the term `term_id`, sorted, paged and filtered like any
+    /// other list of posts.
+    pub async fn get_posts(
+        &self,
+        term_id: i32,
+        sort_criteria: Vec<SortCriterion>,
+        first_result: Option<i32>,
+        max_results: Option<i32>,
+        filters: Vec<Filter>,
+    ) -> Result<Vec<Post>, CoreError> {
+        let filters = self.with_term(term_id, filters).await?;
+        self.repository
+            .find_many(sort_criteria, first_result, max_results, filters)
+            .await
+    }
+
+    /// How many posts `get_posts` has in all.
+    pub async fn count_posts(&self, term_id: i32, filters: Vec<Filter>) -> Result<i64, CoreError> {
+        let filters = self.with_term(term_id, filters).await?;
+        self.repository.count(filters).await
+    }
+
+    /// `filters` narrowed to the posts having the term `term_id`.
+    async fn with_term(
+        &self,
+        term_id: i32,
+        mut filters: Vec<Filter>,
+    ) -> Result<Vec<Filter>, CoreError> {
+        let term = self
+            .terms
+            .find_by_id(term_id)
+            .await?
+            .ok_or(CoreError::not_found("error.not_found"))?;
+        filters.push(Filter::Term {
+            taxonomy: Some(term.taxonomy_code),
+            slug: Some(term.slug),
+        });
+        Ok(filters)
+    }
+
+    async fn find_terms(&self, term_ids: &[i32]) -> Result<Vec<TermInfo>, CoreError> {
+        if term_ids.is_empty() {
+            return Ok(vec![]);
+        }
+        let ids = term_ids.iter().map(|id| ScalarValue::Int(*id)).collect();
+        self.terms
+            .find_all(vec![Filter::Property {
+                property_name: "id".to_string(),
+                operator: FilterOperator::In,
+                value: FilterValue::List(ids),
+            }])
+            .await
+    }
+
+    /// Fails unless every term exists, out of the trash, and belongs to the
+    /// taxonomy with code `taxonomy` when one is given.
+    async fn checked_terms(
+        &self,
+        term_ids: &[i32],
+        taxonomy: Option<&str>,
+    ) -> Result<(), CoreError> {
+        let found: HashMap<i32, TermInfo> = self
+            .find_terms(term_ids)
+            .await?
+            .into_iter()
+            .map(|term| (term.id, term))
+            .collect();
+        for id in term_ids {
+            let details = HashMap::from([("term_id".to_string(), id.to_string())]);
+            match (found.get(id), taxonomy) {
+                (None, _) => {
+                    return Err(CoreError::UnprocessableEntity(
+                        "error.post_terms.term.unknown",
+                        details,
+                    ))
+                }
+                (Some(term), Some(code)) if term.taxonomy_code != code => {
+                    return Err(CoreError::UnprocessableEntity(
+                        "error.post_terms.term.taxonomy",
+                        details,
+                    ))
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Runs `write` on the terms of an existing post in one transaction,
+    /// auditing it as an update of the post's `term_ids`. Returns its terms.
+    async fn write_terms<F, Fut>(&self, post_id: i32, write: F) -> Result<Vec<TermInfo>, CoreError>
+    where
+        F: FnOnce(R) -> Fut,
+        Fut: Future<Output = Result<(), CoreError>>,
+    {
+        let tx = self.repository.begin().await?;
+        let repo = self.repository.in_transaction(&tx);
+        let writer = self.repository.in_transaction(&tx);
+        run_in_transaction(tx, move |_| async move {
+            repo.find_by_id(post_id)
+                .await?
+                .ok_or(CoreError::not_found("error.not_found"))?;
+            let before = Self::snapshot(&repo, post_id).await?;
+            write(writer).await?;
+            let after = Self::snapshot(&repo, post_id).await?;
+            repo.record_audit(AuditEntry::from_snapshots(
+                AuditOperation::Update,
+                before,
+                after,
+            ))
+            .await
+        })
+        .await?;
+        CACHE.notify_write(POSTS);
+        self.get_terms(post_id, None).await
+    }
+
+    /// The post as audited, with its term ids.
+    async fn snapshot(repo: &R, post_id: i32) -> Result<Vec<Snapshot>, CoreError> {
+        let mut snapshots = repo
+            .snapshot_by_ids(vec![post_id], SoftDeleteScope::Include)
+            .await?;
+        let term_ids = repo.find_term_ids(post_id).await?;
+        for snapshot in snapshots.iter_mut() {
+            snapshot
+                .values
+                .insert("term_ids".to_string(), serde_json::json!(term_ids));
+        }
+        Ok(snapshots)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::business::post_service::{PostCreate, PostService};
+    use crate::business::post_taxonomy_service::{PostTaxonomyCreate, PostTaxonomyService};
+    use crate::business::term_service::{TermCreate, TermService};
+    use crate::common::repository::ViewRepository;
+    use crate::common::service::Service;
+    use crate::infras::in_memory_repositories::{
+        AuditLogInfoInMemoryRepository, PostInMemoryRepository, PostTaxonomyInMemoryRepository,
+        TermInMemoryRepository, TermInfoInMemoryRepository,
+    };
+    use crate::infras::in_memory_repository::InMemoryDatabase;
+
+    type PostTerms = PostTermService<PostInMemoryRepository, TermInfoInMemoryRepository>;
+
+    struct Fixture {
+        post_terms: PostTerms,
+        audit_logs: AuditLogInfoInMemoryRepository,
+        posts: Vec<Post>,
+        // rust, leptos (tags), news (category)
+        terms: Vec<i32>,
+    }
+
+    async fn setup() -> Fixture {
+        let database = InMemoryDatabase::new();
+        let post_repository = Arc::new(PostInMemoryRepository::new(database.clone()));
+        let post_service = PostService::new(post_repository.clone());
+        let mut posts = vec![];
+        for title in ["First", "Second"] {
+            let post = post_service
+                .create(&PostCreate {
+                    title: title.into(),
+                    slug: String::new(),
+                    user_id: 7,
+                    type_id: 1,
+                })
+                .await
+                .unwrap();
+            posts.push(post);
+        }
+        let taxonomies = PostTaxonomyService::new(Arc::new(PostTaxonomyInMemoryRepository::new(
+            database.clone(),
+        )));
+        let term_service =
+            TermService::new(Arc::new(TermInMemoryRepository::new(database.clone())));
+        let mut terms = vec![];
+        for (code, names) in [("tag", vec!["Rust", "Leptos"]), ("category", vec!["News"])] {
+            let taxonomy = taxonomies
+                .create(&PostTaxonomyCreate {
+                    code: code.into(),
+                    name: code.into(),
+                })
+                .await
+                .unwrap();
+            for name in names {
+                let term = term_service
+                    .create(&TermCreate {
+                        taxonomy_id: taxonomy.id,
+                        slug: String::new(),
+                        name: name.into(),
+                    })
+                    .await
+                    .unwrap();
+                terms.push(term.id);
+            }
+        }
+        let post_terms = PostTermService::new(
+            post_repository,
+            Arc::new(TermInfoInMemoryRepository::new(database.clone())),
+        );
+        Fixture {
+            post_terms,
+            audit_logs: AuditLogInfoInMemoryRepository::new(database),
+            posts,
+            terms,
+        }
+    }
+
+    fn slugs(terms: &[TermInfo]) -> Vec<&str> {
+        terms.iter().map(|t| t.slug.as_str()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_assign_unassign_and_replace_by_taxonomy() {
+        let Fixture {
+            post_terms,
+            audit_logs,
+            posts,
+            terms,
+        } = setup().await;
+        let (rust, leptos, news) = (terms[0], terms[1], terms[2]);
+        let post = posts[0].id;
+
+        let assigned = post_terms.assign(post, &[rust, news, rust]).await.unwrap();
+        assert_eq!(slugs(&assigned), vec!["news", "rust"]);
+        let again = post_terms.assign(post, &[rust]).await.unwrap();
+        assert_eq!(slugs(&again), vec!["news", "rust"]);
+        // Audited as an update of the post; the no-op assign is not
+        let entries: Vec<_> = audit_logs
+            .find_all(vec![])
+            .await
+            .unwrap()
+            .into_iter()
+            .filter(|e| e.entity_type == POSTS && e.changes.contains("term_ids"))
+            .collect();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].entity_id, post);
+
+        // Replacing the tags leaves the category alone
+        let replaced = post_terms
+            .replace(post, Some("tag"), &[leptos])
+            .await
+            .unwrap();
+        assert_eq!(slugs(&replaced), vec!["news", "leptos"]);
+        let tags = post_terms.get_terms(post, Some("tag")).await.unwrap();
+        assert_eq!(slugs(&tags), vec!["leptos"]);
+
+        let wrong_taxonomy = post_terms.replace(post, Some("tag"), &[news]).await;
+        assert!(matches!(
+            wrong_taxonomy,
+            Err(CoreError::UnprocessableEntity(
+                "error.post_terms.term.taxonomy",
+                _
+            ))
+        ));
+        let unknown = post_terms.assign(post, &[999]).await;
+        assert!(matches!(
+            unknown,
+            Err(CoreError::UnprocessableEntity(
+                "error.post_terms.term.unknown",
+                _
+            ))
+        ));
+        let no_post = post_terms.assign(999, &[rust]).await;
+        assert!(matches!(no_post, Err(CoreError::NotFound(..))));
+
+        let remaining = post_terms.unassign(post, &[news, rust]).await.unwrap();
+        assert_eq!(slugs(&remaining), vec!["leptos"]);
+        let cleared = post_terms.replace(post, None, &[]).await.unwrap();
+        assert!(cleared.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_posts_are_found_by_term() {
+        let Fixture {
+            post_terms,
+            posts,
+            terms,
+            ..
+        } = setup().await;
+        let (rust, leptos, news) = (terms[0], terms[1], terms[2]);
+        post_terms.assign(posts[0].id, &[rust, news]).await.unwrap();
+        post_terms.assign(posts[1].id, &[rust]).await.unwrap();
+
+        let sort = vec![SortCriterion {
+            field: "id".to_string(),
+            ascending: true,
+            is_attribute: false,
+        }];
+        let tagged = post_terms
+            .get_posts(rust, sort.clone(), None, None, vec![])
+            .await
+            .unwrap();
+        assert_eq!(
+            tagged.iter().map(|p| p.id).collect::<Vec<_>>(),
+            vec![posts[0].id, posts[1].id]
+        );
+        let page = post_terms
+            .get_posts(rust, sort, Some(1), Some(1), vec![])
+            .await
+            .unwrap();
+        assert_eq!(page.len(), 1);
+        assert_eq!(page[0].id, posts[1].id);
+        assert_eq!(post_terms.count_posts(news, vec![]).await.unwrap(), 1);
+        assert_eq!(post_terms.count_posts(leptos, vec![]).await.unwrap(), 0);
+
+        // The same filter, straight on the posts
+        let repo = &post_terms.repository;
+        let by = |taxonomy: Option<&str>, slug: Option<&str>| {
+            vec![Filter::Term {
+                taxonomy: taxonomy.map(str::to_string),
+                slug: slug.map(str::to_string),
+            }]
+        };
+        assert_eq!(repo.count(by(Some("category"), None)).await.unwrap(), 1);
+        assert_eq!(repo.count(by(None, Some("rust"))).await.unwrap(), 2);
+        assert_eq!(
+            repo.count(by(Some("category"), Some("rust")))
+                .await
+                .unwrap(),
+            0
+        );
+        let untagged = vec![Filter::Not(Box::new(Filter::Term {
+            taxonomy: Some("category".into()),
+            slug: None,
+        }))];
+        assert_eq!(repo.count(untagged).await.unwrap(), 1);
+    }
+}
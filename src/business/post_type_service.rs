@@ -1,10 +1,14 @@
-use crate::common::repository::{Repository, ViewRepository};
+use crate::business::slug_service::{SlugScope, SlugService};
+use crate::common::error::CoreError;
+use crate::common::repository::{Repository, Transactional, ViewRepository};
 use crate::common::service::{Service, ViewService};
 use crate::{define_readonly_struct_with_common_fields, define_struct_with_common_fields};
+use std::future::Future;
 use std::sync::Arc;
 
 define_struct_with_common_fields!(PostType {
     req {
+        /// Made from the name when left empty.
         pub code: String,
         pub name: String,
     }
@@ -17,7 +21,7 @@ define_readonly_struct_with_common_fields!(PostTypeInfo {
 });
 
 // Repositories
-pub trait PostTypeRepository: Repository<PostType, PostTypeCreate> + Send + Sync {}
+pub trait PostTypeRepository: Repository<PostType, PostTypeCreate> + Transactional + Send + Sync {}
 pub trait PostTypeInfoRepository: ViewRepository<PostTypeInfo> + Send + Sync {}
 
 // Services
@@ -40,8 +44,52 @@ impl<R: PostTypeRepository> ViewService for PostTypeService<R> {
     }
 }
 
+/// Type codes are unique across all types, and may contain `_`.
+fn code_scope() -> SlugScope<PostType> {
+    SlugScope {
+        column: "code",
+        filters: vec![],
+        fallback: "type",
+        underscores: true,
+        slug_of: |post_type| &post_type.code,
+    }
+}
+
 impl<R: PostTypeRepository> Service for PostTypeService<R> {
     type Create = PostTypeCreate;
+
+    fn prepare_create(
+        &self,
+        repo: &R,
+        create: &PostTypeCreate,
+    ) -> impl Future<Output = Result<PostTypeCreate, CoreError>> {
+        let mut create = create.clone();
+        async move {
+            let wanted = if create.code.trim().is_empty() { &create.name } else { &create.code };
+            create.code =
+                SlugService::unique_slug(repo, &code_scope(), wanted, None).await?;
+            Ok(create)
+        }
+    }
+
+    fn prepare_update(
+        &self,
+        repo: &R,
+        post_type: &PostType,
+    ) -> impl Future<Output = Result<PostType, CoreError>> {
+        let mut post_type = post_type.clone();
+        async move {
+            let stored = repo.find_by_id(post_type.id).await?;
+            post_type.code = SlugService::updated_slug(
+                repo,
+                &code_scope(),
+                &post_type,
+                stored.as_ref(),
+            )
+            .await?;
+            Ok(post_type)
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -0,0 +1,521 @@
+use crate::business::post_collection_service::POST_COLLECTIONS;
+use crate::business::post_service::POSTS;
+use crate::business::term_service::TERMS;
+use crate::common::cache::{CacheConfig, CACHE};
+use crate::common::error::CoreError;
+use crate::common::filter::{Filter, FilterOperator, FilterValue, ScalarValue};
+use crate::common::repository::{Repository, Transactional};
+use crate::common::service::{Service, ViewService};
+use crate::common::sort::SortCriterion;
+use crate::define_struct_with_common_fields;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Unique key of `slug_redirects`.
+pub const SLUG_REDIRECT_KEY: [&str; 3] = ["entity_type", "scope_id", "old_slug"];
+
+/// `entity_type` of the redirects entered by hand.
+pub const PATHS: &str = "paths";
+
+/// Hops followed to collapse a chain of redirects into one.
+pub const MAX_REDIRECT_HOPS: usize = 5;
+
+/// Cache namespace of the prefix redirects entered by hand, which every
+/// unanswered request is matched against.
+pub const SLUG_REDIRECT_PREFIXES: &str = "SLUG_REDIRECT_PREFIXES";
+
+// Table entity: slug_redirects, the slugs an entity had before, and the
+// redirects entered by hand.
+define_struct_with_common_fields!(SlugRedirect {
+    req {
+        /// Table of the entity, e.g. `posts`, or `PATHS`.
+        pub entity_type: String,
+        /// Where the slug was unique, e.g. the post's `type_id`; 0 when the
+        /// slug is unique in the whole table.
+        pub scope_id: i32,
+        /// For `PATHS`, the path redirected, e.g. `/blog/2019/hello`.
+        pub old_slug: String,
+        /// The entity the slug belonged to; `None` for `PATHS`.
+        pub entity_id: Option<i32>,
+        /// For `PATHS`, where it leads: a path or an `http(s)` URL.
+        pub target: Option<String>,
+        /// For `PATHS`, whether the paths below `old_slug` are redirected
+        /// too, to the same place below `target`.
+        pub prefix: bool,
+    }
+    opt {}
+});
+
+impl SlugRedirectCreate {
+    /// `old_slug` of the entity of `entity_type` with id `entity_id`, which
+    /// was unique within `scope_id`.
+    pub fn renamed(entity_type: &str, scope_id: i32, old_slug: String, entity_id: i32) -> Self {
+        Self {
+            entity_type: entity_type.to_string(),
+            scope_id,
+            old_slug,
+            entity_id: Some(entity_id),
+            target: None,
+            prefix: false,
+        }
+    }
+}
+
+impl From<&SlugRedirectCreate> for SlugRedirect {
+    /// The row to upsert by `SLUG_REDIRECT_KEY`; identity columns are filled
+    /// in when it is written.
+    fn from(create: &SlugRedirectCreate) -> Self {
+        let now = time::OffsetDateTime::now_utc();
+        SlugRedirect {
+            id: 0,
+            uid: String::new(),
+            version: 0,
+            created_at: now,
+            updated_at: now,
+            entity_type: create.entity_type.clone(),
+            scope_id: create.scope_id,
+            old_slug: create.old_slug.clone(),
+            entity_id: create.entity_id,
+            target: create.target.clone(),
+            prefix: create.prefix,
+        }
+    }
+}
+
+/// Public path of the entity of `entity_type` with `slug`; terms are served
+/// under their taxonomy's code. `None` for entities without a page.
+pub fn public_path(entity_type: &str, taxonomy_code: Option<&str>, slug: &str) -> Option<String> {
+    match entity_type {
+        POSTS => Some(format!("/articles/{slug}")),
+        POST_COLLECTIONS => Some(format!("/collections/{slug}")),
+        TERMS => taxonomy_code.map(|code| format!("/terms/{code}/{slug}")),
+        _ => None,
+    }
+}
+
+/// The entity type and slug `path` would be the `public_path` of.
+fn parse_public_path(path: &str) -> Option<(&'static str, &str)> {
+    let (parent, slug) = path.rsplit_once('/')?;
+    let entity_type = match parent {
+        "/articles" => POSTS,
+        "/collections" => POST_COLLECTIONS,
+        _ => match parent.strip_prefix("/terms/") {
+            Some(code) if !code.is_empty() && !code.contains('/') => TERMS,
+            _ => return None,
+        },
+    };
+    (!slug.is_empty()).then_some((entity_type, slug))
+}
+
+/// Repositories of the entities whose old slugs are kept as redirects.
+pub trait SlugRedirectRecorder {
+    /// Points `redirect.old_slug` at the entity, replacing whatever it pointed
+    /// at before.
+    fn record_slug_redirect(
+        &self,
+        redirect: &SlugRedirectCreate,
+    ) -> impl Future<Output = Result<SlugRedirect, CoreError>>;
+}
+
+pub trait SlugRedirectRepository:
+    Repository<SlugRedirect, SlugRedirectCreate> + Transactional + Send + Sync
+{
+    /// The public paths of the entity `redirect` leads to: the one its old
+    /// slug gave it and the one it has now. `None` for `PATHS`, and once the
+    /// entity is trashed or, for a post, not published.
+    fn find_entity_paths(
+        &self,
+        redirect: &SlugRedirect,
+    ) -> impl Future<Output = Result<Option<(String, String)>, CoreError>>;
+}
+
+#[derive(Clone)]
+pub struct SlugRedirectService<R: SlugRedirectRepository> {
+    repository: Arc<R>,
+}
+
+/// Where one redirect leads.
+enum Hop {
+    /// Another path of this site, which may be redirected in turn.
+    Path(String),
+    /// A page that exists, or another site.
+    Final(String),
+}
+
+impl Hop {
+    fn to(target: String) -> Self {
+        if target.starts_with('/') {
+            Hop::Path(target)
+        } else {
+            Hop::Final(target)
+        }
+    }
+}
+
+fn equals(property_name: &str, value: ScalarValue) -> Filter {
+    Filter::Property {
+        property_name: property_name.to_string(),
+        operator: FilterOperator::Equal,
+        value: FilterValue::Single(value),
+    }
+}
+
+impl<R: SlugRedirectRepository> SlugRedirectService<R> {
+    pub fn new(repository: Arc<R>) -> Self {
+        CACHE.configure(
+            SLUG_REDIRECT_PREFIXES,
+            CacheConfig {
+                capacity: 1,
+                ttl: Some(Duration::from_secs(300)),
+            },
+        );
+        CACHE.invalidate_on_write(repository.get_table_name(), SLUG_REDIRECT_PREFIXES);
+        Self { repository }
+    }
+
+    /// Where a request for `path`, which no page answers, should be sent;
+    /// `None` if nothing redirects it. A chain of redirects is followed to
+    /// its end, so the client gets there in one hop; a loop leads nowhere.
+    pub async fn resolve(&self, path: &str) -> Result<Option<String>, CoreError> {
+        let mut visited = vec![path.to_string()];
+        while visited.len() <= MAX_REDIRECT_HOPS {
+            let current = visited.last().expect("starts with path");
+            match self.find_hop(current).await? {
+                None => break,
+                Some(Hop::Final(location)) => return Ok(Some(location)),
+                Some(Hop::Path(next)) if visited.contains(&next) => return Ok(None),
+                Some(Hop::Path(next)) => visited.push(next),
+            }
+        }
+        Ok(visited.pop().filter(|_| !visited.is_empty()))
+    }
+
+    /// The redirect answering `path`: one entered by hand for exactly that
+    /// path, then an old slug, then the longest prefix entered by hand.
+    async fn find_hop(&self, path: &str) -> Result<Option<Hop>, CoreError> {
+        let paths = || equals("entity_type", ScalarValue::String(PATHS.to_string()));
+        let exact = self
+            .repository
+            .find_all(vec![
+                paths(),
+                equals("old_slug", ScalarValue::String(path.to_string())),
+            ])
+            .await?;
+        if let Some(target) = exact.into_iter().find_map(|redirect| redirect.target) {
+            return Ok(Some(Hop::to(target)));
+        }
+
+        if let Some((entity_type, slug)) = parse_public_path(path) {
+            let newest_first = vec![SortCriterion {
+                field: "updated_at".to_string(),
+                ascending: false,
+                is_attribute: false,
+            }];
+            let filters = vec![
+                equals("entity_type", ScalarValue::String(entity_type.to_string())),
+                equals("old_slug", ScalarValue::String(slug.to_string())),
+            ];
+            let candidates = self
+                .repository
+                .find_many(newest_first, None, None, filters)
+                .await?;
+            for redirect in &candidates {
+                // The same slug may be old in several scopes, e.g. taxonomies
+                match self.repository.find_entity_paths(redirect).await? {
+                    Some((old, current)) if old == path && current != path => {
+                        return Ok(Some(Hop::Final(current)));
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        let prefixes = self.prefixes().await?;
+        let longest = prefixes
+            .iter()
+            .filter_map(|(source, target)| {
+                let rest = path.strip_prefix(source.as_str())?.strip_prefix('/')?;
+                Some((
+                    source.len(),
+                    format!("{}/{rest}", target.trim_end_matches('/')),
+                ))
+            })
+            .max_by_key(|(len, _)| *len);
+        Ok(longest.map(|(_, target)| Hop::to(target)))
+    }
+
+    /// The prefix redirects entered by hand, as `(path, target)`. Cached until
+    /// a redirect is written, since every unanswered request needs them.
+    async fn prefixes(&self) -> Result<Vec<(String, String)>, CoreError> {
+        CACHE
+            .get_or_compute(SLUG_REDIRECT_PREFIXES, PATHS, || async {
+                let filters = vec![
+                    equals("entity_type", ScalarValue::String(PATHS.to_string())),
+                    equals("prefix", ScalarValue::Bool(true)),
+                ];
+                let prefixes = self.repository.find_all(filters).await?;
+                Ok(prefixes
+                    .into_iter()
+                    .filter_map(|redirect| Some((redirect.old_slug, redirect.target?)))
+                    .collect::<Vec<_>>())
+            })
+            .await
+    }
+}
+
+impl<R: SlugRedirectRepository> ViewService for SlugRedirectService<R> {
+    type Entity = SlugRedirect;
+    type Repo = R;
+    fn get_repository(&self) -> &Self::Repo {
+        &self.repository
+    }
+}
+
+/// A redirect entered by hand, checked and normalized: `old_slug` becomes
+/// a path without a trailing `/`, the way requests are matched.
+fn checked_manual(
+    old_slug: &str,
+    target: Option<&str>,
+    prefix: bool,
+) -> Result<(String, String), CoreError> {
+    let invalid = |message: &'static str, value: &str| {
+        CoreError::UnprocessableEntity(
+            message,
+            HashMap::from([("value".to_string(), value.to_string())]),
+        )
+    };
+    let source = old_slug.trim().trim_end_matches('/');
+    if !source.starts_with('/')
+        || source.contains(['?', '#'])
+        || source.contains(char::is_whitespace)
+    {
+        return Err(invalid("error.redirect.source.invalid", old_slug));
+    }
+    let target = target.unwrap_or_default().trim();
+    let external = ["https://", "http://"]
+        .iter()
+        .any(|scheme| target.len() > scheme.len() && target.starts_with(scheme));
+    if !(target.starts_with('/') && !target.starts_with("//") || external)
+        || target.contains(char::is_whitespace)
+    {
+        return Err(invalid("error.redirect.target.invalid", target));
+    }
+    let target_path = target.trim_end_matches('/');
+    // A prefix redirect into its own prefix would grow the path forever
+    if target_path == source || prefix && target_path.starts_with(&format!("{source}/")) {
+        return Err(invalid("error.redirect.target.loops", target));
+    }
+    Ok((source.to_string(), target.to_string()))
+}
+
+impl<R: SlugRedirectRepository> Service for SlugRedirectService<R> {
+    type Create = SlugRedirectCreate;
+
+    /// Only redirects entered by hand are created here; old slugs are
+    /// recorded by the services of their entities.
+    fn prepare_create(
+        &self,
+        _repo: &R,
+        create: &SlugRedirectCreate,
+    ) -> impl Future<Output = Result<SlugRedirectCreate, CoreError>> {
+        let mut create = create.clone();
+        async move {
+            if create.entity_type != PATHS {
+                return Err(CoreError::bad_request("error.redirect.manual_only"));
+            }
+            let (source, target) =
+                checked_manual(&create.old_slug, create.target.as_deref(), create.prefix)?;
+            create.scope_id = 0;
+            create.entity_id = None;
+            create.old_slug = source;
+            create.target = Some(target);
+            Ok(create)
+        }
+    }
+
+    /// An old slug keeps leading to its entity; only where a redirect entered
+    /// by hand leads can change.
+    fn prepare_update(
+        &self,
+        repo: &R,
+        redirect: &SlugRedirect,
+    ) -> impl Future<Output = Result<SlugRedirect, CoreError>> {
+        let mut redirect = redirect.clone();
+        async move {
+            let stored = repo
+                .find_by_id(redirect.id)
+                .await?
+                .ok_or(CoreError::not_found("error.not_found"))?;
+            if stored.entity_type != PATHS || redirect.entity_type != PATHS {
+                return Err(CoreError::bad_request("error.redirect.manual_only"));
+            }
+            let (source, target) = checked_manual(
+                &redirect.old_slug,
+                redirect.target.as_deref(),
+                redirect.prefix,
+            )?;
+            redirect.scope_id = 0;
+            redirect.entity_id = None;
+            redirect.old_slug = source;
+            redirect.target = Some(target);
+            Ok(redirect)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::business::post_service::{Post, PostCreate, PostStatus};
+    use crate::infras::in_memory_repositories::{
+        PostInMemoryRepository, SlugRedirectInMemoryRepository,
+    };
+    use crate::infras::in_memory_repository::InMemoryDatabase;
+
+    fn manual(old_slug: &str, target: &str, prefix: bool) -> SlugRedirectCreate {
+        SlugRedirectCreate {
+            entity_type: PATHS.to_string(),
+            scope_id: 0,
+            old_slug: old_slug.to_string(),
+            entity_id: None,
+            target: Some(target.to_string()),
+            prefix,
+        }
+    }
+
+    #[test]
+    fn test_public_paths_round_trip() {
+        for (entity_type, code) in [
+            (POSTS, None),
+            (POST_COLLECTIONS, None),
+            (TERMS, Some("tags")),
+        ] {
+            let path = public_path(entity_type, code, "rust").unwrap();
+            assert_eq!(parse_public_path(&path), Some((entity_type, "rust")));
+        }
+        assert_eq!(parse_public_path("/articles/"), None);
+        assert_eq!(parse_public_path("/terms/rust"), None);
+        assert_eq!(parse_public_path("/about"), None);
+    }
+
+    #[tokio::test]
+    async fn test_resolve_collapses_chains_and_matches_prefixes() {
+        let database = InMemoryDatabase::new();
+        let redirects = SlugRedirectInMemoryRepository::new(database.clone());
+        let posts: PostInMemoryRepository = redirects.sibling();
+        let service = SlugRedirectService::new(Arc::new(redirects));
+
+        let post = posts
+            .create(&PostCreate {
+                title: "Hello".into(),
+                slug: "hello-again".into(),
+                user_id: 1,
+                type_id: 1,
+            })
+            .await
+            .unwrap();
+        let published = Post {
+            status: PostStatus::PUBLISHED,
+            ..post.clone()
+        };
+        posts.update(&published).await.unwrap();
+        posts
+            .record_slug_redirect(&SlugRedirectCreate::renamed(
+                POSTS,
+                1,
+                "hello".into(),
+                post.id,
+            ))
+            .await
+            .unwrap();
+
+        let mut created = vec![];
+        for create in [
+            manual("/old/hello/", "/articles/hello", false),
+            manual("/blog", "https://archive.example.com/blog/", true),
+            manual("/blog/2019", "/old", true),
+            manual("/a", "/b", false),
+            manual("/b", "/a", false),
+        ] {
+            created.push(service.create(&create).await.unwrap());
+        }
+
+        let resolve = |path: &'static str| service.resolve(path);
+        assert_eq!(
+            resolve("/articles/hello").await.unwrap().as_deref(),
+            Some("/articles/hello-again")
+        );
+        // Hand-entered, then an old slug: one hop for the client
+        assert_eq!(
+            resolve("/old/hello").await.unwrap().as_deref(),
+            Some("/articles/hello-again")
+        );
+        assert_eq!(
+            resolve("/blog/2020/x").await.unwrap().as_deref(),
+            Some("https://archive.example.com/blog/2020/x")
+        );
+        // The longest prefix wins, and leads on to the exact redirect
+        assert_eq!(
+            resolve("/blog/2019/hello").await.unwrap().as_deref(),
+            Some("/articles/hello-again")
+        );
+        assert_eq!(resolve("/a").await.unwrap(), None);
+        assert_eq!(resolve("/articles/hello-again").await.unwrap(), None);
+        assert_eq!(resolve("/blogs").await.unwrap(), None);
+
+        // Deleting a prefix drops the cached list
+        service.delete_by_id(created[1].id).await.unwrap();
+        assert_eq!(resolve("/blog/2020/x").await.unwrap(), None);
+
+        // Trashed posts are not redirected to
+        posts.delete_by_id(post.id).await.unwrap();
+        assert_eq!(resolve("/articles/hello").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_manual_redirects_are_checked() {
+        let service = SlugRedirectService::new(Arc::new(SlugRedirectInMemoryRepository::new(
+            InMemoryDatabase::new(),
+        )));
+        let rejected = |message: &'static str| move |result: Result<SlugRedirect, CoreError>| matches!(result, Err(CoreError::UnprocessableEntity(m, _)) if m == message);
+        let source_invalid = rejected("error.redirect.source.invalid");
+        let target_invalid = rejected("error.redirect.target.invalid");
+        let loops = rejected("error.redirect.target.loops");
+        assert!(source_invalid(
+            service.create(&manual("old", "/new", false)).await
+        ));
+        assert!(source_invalid(
+            service.create(&manual("/a b", "/new", false)).await
+        ));
+        assert!(target_invalid(
+            service.create(&manual("/old", "new", false)).await
+        ));
+        assert!(target_invalid(
+            service
+                .create(&manual("/old", "//evil.example.com", false))
+                .await
+        ));
+        assert!(target_invalid(
+            service.create(&manual("/old", "https://", false)).await
+        ));
+        assert!(loops(service.create(&manual("/old", "/old/", false)).await));
+        assert!(loops(
+            service.create(&manual("/old", "/old/new", true)).await
+        ));
+
+        let mut entity = manual("/old", "/new", false);
+        entity.entity_type = POSTS.to_string();
+        assert!(matches!(
+            service.create(&entity).await,
+            Err(CoreError::BadRequest("error.redirect.manual_only", _))
+        ));
+
+        let created = service
+            .create(&manual(" /old/ ", "/old/new", false))
+            .await
+            .unwrap();
+        assert_eq!(created.old_slug, "/old");
+    }
+}
@@ -0,0 +1,202 @@
+use crate::common::error::CoreError;
+use crate::common::filter::{Filter, FilterOperator, FilterValue, ScalarValue, SoftDeleteScope};
+use crate::common::repository::ViewRepository;
+use crate::common::service::Entity;
+use std::collections::HashSet;
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
+
+/// Longest slug generated from a title; longer ones are cut at a word.
+pub const MAX_SLUG_LEN: usize = 80;
+
+/// Lowercase ASCII slug of `text`: accents are stripped ("Bài viết mới" gives
+/// `bai-viet-moi`), letters without a decomposition are transliterated, and
+/// every other run of characters becomes one `-`. Empty when nothing of
+/// `text` can be written in ASCII.
+pub fn slugify(text: &str) -> String {
+    let mut slug = String::with_capacity(text.len());
+    let mut gap = false;
+    let mut buf = [0u8; 4];
+    for c in text.nfd().filter(|c| !is_combining_mark(*c)) {
+        let part: &str = match c {
+            c if c.is_ascii_alphanumeric() => c.to_ascii_lowercase().encode_utf8(&mut buf),
+            // "don't" reads better as `dont` than as `don-t`
+            '\'' | '’' => continue,
+            c => match transliterate(c) {
+                Some(part) => part,
+                None => {
+                    gap = true;
+                    continue;
+                }
+            },
+        };
+        if gap && !slug.is_empty() {
+            slug.push('-');
+        }
+        gap = false;
+        slug.push_str(part);
+    }
+    truncate(slug, MAX_SLUG_LEN)
+}
+
+/// Latin letters that Unicode does not decompose into a base letter and marks.
+fn transliterate(c: char) -> Option<&'static str> {
+    Some(match c {
+        'đ' | 'Đ' | 'ð' | 'Ð' => "d",
+        'ß' => "ss",
+        'æ' | 'Æ' => "ae",
+        'œ' | 'Œ' => "oe",
+        'ø' | 'Ø' => "o",
+        'ł' | 'Ł' => "l",
+        'þ' | 'Þ' => "th",
+        'ı' => "i",
+        _ => return None,
+    })
+}
+
+/// `slug` cut to at most `max` bytes, at a `-` when one is not too far back.
+fn truncate(mut slug: String, max: usize) -> String {
+    if slug.len() > max {
+        let cut = match slug[..=max].rfind('-') {
+            Some(dash) if dash > max / 2 => dash,
+            _ => max,
+        };
+        slug.truncate(cut);
+    }
+    slug.trim_end_matches('-').to_string()
+}
+
+/// Whether `slug` passes the schema's check: lowercase letters, digits and
+/// inner `-` (and `_` where `underscores` is allowed).
+pub fn is_valid_slug(slug: &str, underscores: bool) -> bool {
+    let inner = |c: char| {
+        c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || (underscores && c == '_')
+    };
+    let edge = |c: Option<char>| c.is_some_and(|c| c.is_ascii_lowercase() || c.is_ascii_digit());
+    edge(slug.chars().next()) && edge(slug.chars().last()) && slug.chars().all(inner)
+}
+
+/// Where a slug has to be unique.
+pub struct SlugScope<T> {
+    /// Column holding the slug, e.g. `slug` or `code`.
+    pub column: &'static str,
+    /// The rows the slug competes with, e.g. those of the same `type_id`;
+    /// none when it is unique in the whole table.
+    pub filters: Vec<Filter>,
+    /// Slug used when nothing of the title survives, e.g. a title in Chinese.
+    pub fallback: &'static str,
+    /// Codes may contain `_`; slugs may not.
+    pub underscores: bool,
+    pub slug_of: fn(&T) -> &str,
+}
+
+/// Turns wanted slugs into valid slugs that are free in their scope.
+pub struct SlugService;
+
+impl SlugService {
+    /// `wanted` as a valid slug: kept when it already is one, slugified
+    /// otherwise, and `scope.fallback` when that leaves nothing.
+    pub fn normalize<T>(wanted: &str, scope: &SlugScope<T>) -> String {
+        let wanted = wanted.trim();
+        if is_valid_slug(wanted, scope.underscores) {
+            return wanted.to_string();
+        }
+        match slugify(wanted) {
+            slug if slug.is_empty() => scope.fallback.to_string(),
+            slug => slug,
+        }
+    }
+
+    /// `wanted`, normalized, with the lowest `-2`, `-3`... suffix that makes
+    /// it free in `scope`. Trashed rows keep their slug, so they count; the
+    /// row `except_id` (the one being updated) does not.
+    pub async fn unique_slug<T, R>(
+        repo: &R,
+        scope: &SlugScope<T>,
+        wanted: &str,
+        except_id: Option<i32>,
+    ) -> Result<String, CoreError>
+    where
+        T: Entity,
+        R: ViewRepository<T>,
+    {
+        let base = Self::normalize(wanted, scope);
+        let mut filters = scope.filters.clone();
+        if let Some(id) = except_id {
+            filters.push(Filter::Property {
+                property_name: "id".to_string(),
+                operator: FilterOperator::NotEqual,
+                value: FilterValue::Single(ScalarValue::Int(id)),
+            });
+        }
+        filters.push(Filter::SoftDeleted(SoftDeleteScope::Include));
+        let taken: HashSet<String> = repo
+            .find_suffixed(scope.column, &base, filters)
+            .await?
+            .into_iter()
+            .collect();
+        if !taken.contains(&base) {
+            return Ok(base);
+        }
+        let slug = (2..)
+            .map(|n| {
+                let suffix = format!("-{n}");
+                let stem = truncate(base.clone(), MAX_SLUG_LEN.saturating_sub(suffix.len()));
+                format!("{stem}{suffix}")
+            })
+            .find(|slug| !taken.contains(slug))
+            .expect("a free suffix exists past the taken ones");
+        Ok(slug)
+    }
+
+    /// The slug `entity` should be updated with: unchanged if it is the one
+    /// `stored`, otherwise made unique like a new one. An upsert (nothing
+    /// stored) matches existing rows by the slug itself, so it is left as
+    /// given.
+    pub async fn updated_slug<T, R>(
+        repo: &R,
+        scope: &SlugScope<T>,
+        entity: &T,
+        stored: Option<&T>,
+    ) -> Result<String, CoreError>
+    where
+        T: Entity,
+        R: ViewRepository<T>,
+    {
+        let slug = (scope.slug_of)(entity);
+        match stored {
+            Some(stored) if (scope.slug_of)(stored) != slug => {
+                Self::unique_slug(repo, scope, slug, Some(entity.id())).await
+            }
+            _ => Ok(slug.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slugify_transliterates_and_normalizes() {
+        assert_eq!(slugify("Bài viết mới nhất"), "bai-viet-moi-nhat");
+        assert_eq!(slugify("Đường đến Hà Nội"), "duong-den-ha-noi");
+        assert_eq!(slugify("Straße & Smørrebrød"), "strasse-smorrebrod");
+        assert_eq!(slugify("  Don't   PANIC!! -- 42 "), "dont-panic-42");
+        assert_eq!(slugify("日本語"), "");
+
+        let long = slugify(&"word ".repeat(30));
+        assert!(long.len() <= MAX_SLUG_LEN && long.ends_with("word"));
+        assert!(is_valid_slug(&long, false));
+    }
+
+    #[test]
+    fn test_is_valid_slug() {
+        assert!(is_valid_slug("bai-viet-2", false));
+        assert!(!is_valid_slug("bai_viet", false));
+        assert!(is_valid_slug("bai_viet", true));
+        assert!(!is_valid_slug("-bai", false));
+        assert!(!is_valid_slug("Bai", false));
+        assert!(!is_valid_slug("", false));
+    }
+}
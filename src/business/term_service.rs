@@ -1,12 +1,21 @@
-use crate::common::repository::{Repository, ViewRepository};
+use crate::business::slug_redirect_service::{SlugRedirectCreate, SlugRedirectRecorder};
+use crate::business::slug_service::{SlugScope, SlugService};
+use crate::common::error::CoreError;
+use crate::common::filter::{Filter, FilterOperator, FilterValue, ScalarValue};
+use crate::common::repository::{Repository, Transactional, ViewRepository};
 use crate::common::service::{Service, ViewService};
 use crate::{define_readonly_struct_with_common_fields, define_struct_with_common_fields};
+use std::future::Future;
 use std::sync::Arc;
 
+/// Table of terms, as the `entity_type` of their old slugs.
+pub const TERMS: &str = "terms";
+
 // Table entity: terms
 define_struct_with_common_fields!(Term {
     req {
         pub taxonomy_id: i32,
+        /// Made from the name when left empty.
         pub slug: String,
         pub name: String,
     }
@@ -30,7 +39,10 @@ define_readonly_struct_with_common_fields!(TermInfo {
 });
 
 // Repositories
-pub trait TermRepository: Repository<Term, TermCreate> + Send + Sync {}
+pub trait TermRepository:
+    Repository<Term, TermCreate> + Transactional + SlugRedirectRecorder + Send + Sync
+{
+}
 pub trait TermInfoRepository: ViewRepository<TermInfo> + Send + Sync {}
 
 // Services for table (CRUD)
@@ -53,8 +65,72 @@ impl<R: TermRepository> ViewService for TermService<R> {
     }
 }
 
+/// Term slugs are unique within their taxonomy.
+fn slug_scope(taxonomy_id: i32) -> SlugScope<Term> {
+    SlugScope {
+        column: "slug",
+        filters: vec![Filter::Property {
+            property_name: "taxonomy_id".to_string(),
+            operator: FilterOperator::Equal,
+            value: FilterValue::Single(ScalarValue::Int(taxonomy_id)),
+        }],
+        fallback: "term",
+        underscores: false,
+        slug_of: |term| &term.slug,
+    }
+}
+
 impl<R: TermRepository> Service for TermService<R> {
     type Create = TermCreate;
+
+    fn prepare_create(
+        &self,
+        repo: &R,
+        create: &TermCreate,
+    ) -> impl Future<Output = Result<TermCreate, CoreError>> {
+        let mut create = create.clone();
+        async move {
+            let wanted = if create.slug.trim().is_empty() { &create.name } else { &create.slug };
+            create.slug = SlugService::unique_slug(
+                repo,
+                &slug_scope(create.taxonomy_id),
+                wanted,
+                None,
+            )
+            .await?;
+            Ok(create)
+        }
+    }
+
+    /// A changed slug is made unique, and the old one keeps leading to the
+    /// term from the taxonomy it was in.
+    fn prepare_update(
+        &self,
+        repo: &R,
+        term: &Term,
+    ) -> impl Future<Output = Result<Term, CoreError>> {
+        let mut term = term.clone();
+        async move {
+            let stored = repo.find_by_id(term.id).await?;
+            term.slug = SlugService::updated_slug(
+                repo,
+                &slug_scope(term.taxonomy_id),
+                &term,
+                stored.as_ref(),
+            )
+            .await?;
+            if let Some(stored) = stored.filter(|stored| stored.slug != term.slug) {
+                repo.record_slug_redirect(&SlugRedirectCreate::renamed(
+                    TERMS,
+                    stored.taxonomy_id,
+                    stored.slug,
+                    term.id,
+                ))
+                .await?;
+            }
+            Ok(term)
+        }
+    }
 }
 
 // Service for view (read-only)
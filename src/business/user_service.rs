@@ -1,8 +1,9 @@
 use crate::common::error::CoreError;
-use crate::common::repository::{Repository, ViewRepository};
+use crate::common::repository::{Repository, Transactional, ViewRepository};
 use crate::common::service::{Service, ViewService};
 use crate::{define_readonly_struct_with_common_fields, define_struct_with_common_fields};
 use std::future::Future;
+use std::str::FromStr;
 use std::sync::Arc;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -10,12 +11,15 @@ use std::sync::Arc;
 pub enum UserRole {
     USER = 0,
     ADMIN = 1,
+    /// Reviews and publishes posts written by others.
+    EDITOR = 2,
 }
 
 impl From<i32> for UserRole {
     fn from(role: i32) -> Self {
         match role {
             1 => UserRole::ADMIN,
+            2 => UserRole::EDITOR,
             _ => UserRole::USER,
         }
     }
@@ -30,6 +34,19 @@ impl UserRole {
         match self {
             UserRole::USER => "USER",
             UserRole::ADMIN => "ADMIN",
+            UserRole::EDITOR => "EDITOR",
+        }
+    }
+}
+
+impl FromStr for UserRole {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "USER" => Ok(UserRole::USER),
+            "ADMIN" => Ok(UserRole::ADMIN),
+            "EDITOR" => Ok(UserRole::EDITOR),
+            _ => Err(()),
         }
     }
 }
@@ -50,7 +67,7 @@ define_readonly_struct_with_common_fields!(UserInfo {
     pub role: String,
 });
 
-pub trait UserRepository: Repository<User, UserCreate> + Send + Sync {
+pub trait UserRepository: Repository<User, UserCreate> + Transactional + Send + Sync {
     fn find_by_username(&self, name: &str)
         -> impl Future<Output = Result<Option<User>, CoreError>>;
     fn find_by_email(&self, email: &str) -> impl Future<Output = Result<Option<User>, CoreError>>;
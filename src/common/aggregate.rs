@@ -0,0 +1,175 @@
+use crate::common::error::CoreError;
+use crate::common::filter::ScalarValue;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AggregateFunction {
+    Count,
+    Sum,
+    Avg,
+    Min,
+    Max,
+}
+
+impl AggregateFunction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AggregateFunction::Count => "count",
+            AggregateFunction::Sum => "sum",
+            AggregateFunction::Avg => "avg",
+            AggregateFunction::Min => "min",
+            AggregateFunction::Max => "max",
+        }
+    }
+
+    /// Whether this function applies to a column typed like `prototype`:
+    /// sums and averages take numbers, minimums and maximums anything ordered.
+    pub fn accepts(&self, prototype: &ScalarValue) -> bool {
+        match self {
+            AggregateFunction::Count => true,
+            AggregateFunction::Sum | AggregateFunction::Avg => {
+                matches!(prototype, ScalarValue::Int(_) | ScalarValue::Float(_))
+            }
+            AggregateFunction::Min | AggregateFunction::Max => {
+                !matches!(prototype, ScalarValue::Bool(_))
+            }
+        }
+    }
+}
+
+impl FromStr for AggregateFunction {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "count" => Ok(AggregateFunction::Count),
+            "sum" => Ok(AggregateFunction::Sum),
+            "avg" => Ok(AggregateFunction::Avg),
+            "min" => Ok(AggregateFunction::Min),
+            "max" => Ok(AggregateFunction::Max),
+            _ => Err(()),
+        }
+    }
+}
+
+/// A value computed over each group, written like `sum(views)`. Only `count`
+/// may go without a field (`count` or `count(*)`), counting rows rather than
+/// values.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Aggregate {
+    pub function: AggregateFunction,
+    pub field: Option<String>,
+}
+
+impl FromStr for Aggregate {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (function, field) = match s.split_once('(') {
+            Some((function, rest)) => {
+                let field = rest.strip_suffix(')').ok_or(())?.trim();
+                (function, (!field.is_empty() && field != "*").then(|| field.to_string()))
+            }
+            None => (s, None),
+        };
+        let function = AggregateFunction::from_str(function)?;
+        if field.is_none() && function != AggregateFunction::Count {
+            return Err(());
+        }
+        Ok(Aggregate { function, field })
+    }
+}
+
+impl Aggregate {
+    /// Canonical spelling, e.g. `count(*)` or `sum(views)`; results are keyed by it.
+    pub fn label(&self) -> String {
+        format!(
+            "{}({})",
+            self.function.as_str(),
+            self.field.as_deref().unwrap_or("*")
+        )
+    }
+
+    /// Rejects aggregates whose function does not apply to the type of their
+    /// field, such as `sum(title)`, given a prototype value per column.
+    pub fn check_types(
+        aggregates: &[Aggregate],
+        column_types: &HashMap<String, ScalarValue>,
+    ) -> Result<(), CoreError> {
+        let mismatch = aggregates.iter().find(|aggregate| {
+            aggregate
+                .field
+                .as_ref()
+                .and_then(|field| column_types.get(field))
+                .is_some_and(|prototype| !aggregate.function.accepts(prototype))
+        });
+        match mismatch {
+            Some(aggregate) => Err(CoreError::UnprocessableEntity(
+                "error.aggregate.type",
+                HashMap::from([("aggregate".into(), aggregate.label())]),
+            )),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Rows sharing the same values of the group-by fields. Sums, averages and
+/// counts are floats; minimums and maximums keep the column's type.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AggregateGroup {
+    /// `(field, value)` per group-by field, in request order; `None` groups
+    /// the rows without a value.
+    pub key: Vec<(String, Option<ScalarValue>)>,
+    pub count: i64,
+    /// `(label, value)` per aggregate, in request order; `None` when no row
+    /// of the group has a value.
+    pub values: Vec<(String, Option<ScalarValue>)>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_aggregate() {
+        let sum = Aggregate::from_str(" SUM( views ) ").unwrap();
+        assert_eq!(sum.function, AggregateFunction::Sum);
+        assert_eq!(sum.field.as_deref(), Some("views"));
+        assert_eq!(sum.label(), "sum(views)");
+
+        assert_eq!(Aggregate::from_str("count").unwrap().label(), "count(*)");
+        assert_eq!(Aggregate::from_str("count(*)").unwrap().field, None);
+        assert_eq!(
+            Aggregate::from_str("count(slug)").unwrap().label(),
+            "count(slug)"
+        );
+
+        assert!(Aggregate::from_str("avg").is_err());
+        assert!(Aggregate::from_str("max(*)").is_err());
+        assert!(Aggregate::from_str("median(views)").is_err());
+        assert!(Aggregate::from_str("sum(views").is_err());
+    }
+
+    #[test]
+    fn test_check_aggregate_types() {
+        let column_types = HashMap::from([
+            ("views".to_string(), ScalarValue::Int(0)),
+            ("title".to_string(), ScalarValue::String(String::new())),
+            ("published".to_string(), ScalarValue::Bool(false)),
+        ]);
+        let check = |aggregates: &[&str]| {
+            let aggregates = aggregates
+                .iter()
+                .map(|a| Aggregate::from_str(a).unwrap())
+                .collect::<Vec<_>>();
+            Aggregate::check_types(&aggregates, &column_types)
+        };
+        assert!(check(&["sum(views)", "max(title)", "count(published)"]).is_ok());
+        assert!(matches!(
+            check(&["count", "sum(title)"]),
+            Err(CoreError::UnprocessableEntity("error.aggregate.type", params))
+                if params["aggregate"] == "sum(title)"
+        ));
+        assert!(check(&["max(published)"]).is_err());
+    }
+}
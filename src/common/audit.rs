@@ -0,0 +1,216 @@
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+#[cfg(feature = "ssr")]
+use std::future::Future;
+
+/// What a write did to a row.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AuditOperation {
+    Create,
+    Update,
+    Delete,
+    Restore,
+    Purge,
+}
+
+impl AuditOperation {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditOperation::Create => "create",
+            AuditOperation::Update => "update",
+            AuditOperation::Delete => "delete",
+            AuditOperation::Restore => "restore",
+            AuditOperation::Purge => "purge",
+        }
+    }
+}
+
+/// The audited columns of a row, read just before or after a write.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Snapshot {
+    pub id: i32,
+    pub uid: String,
+    pub values: Map<String, Value>,
+}
+
+/// One row of the audit log, before it is stored.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AuditEntry {
+    pub entity_id: i32,
+    pub entity_uid: String,
+    pub operation: AuditOperation,
+    pub user_id: Option<i32>,
+    /// `{"column": {"old": ..., "new": ...}}` for every column that changed.
+    pub changes: Map<String, Value>,
+}
+
+impl AuditEntry {
+    /// The audited row's uid; a history row that names no row is refused.
+    #[cfg(feature = "ssr")]
+    pub fn entity_uuid(&self) -> Result<uuid::Uuid, crate::common::error::CoreError> {
+        uuid::Uuid::parse_str(&self.entity_uid).map_err(|_| {
+            crate::common::error::CoreError::UnprocessableEntity(
+                "error.audit.invalid.entity_uid",
+                HashMap::from([
+                    ("entity_id".to_string(), self.entity_id.to_string()),
+                    ("entity_uid".to_string(), self.entity_uid.clone()),
+                ]),
+            )
+        })
+    }
+
+    /// One entry per row found in `before` or `after`, by the current actor.
+    /// Creates and purges record every value, updates the changed ones only,
+    /// while deletes and restores leave the values alone and record none.
+    /// Updates that changed nothing are skipped.
+    pub fn from_snapshots(
+        operation: AuditOperation,
+        before: Vec<Snapshot>,
+        after: Vec<Snapshot>,
+    ) -> Vec<AuditEntry> {
+        let user_id = current_actor();
+        let mut after_by_id: HashMap<i32, Snapshot> =
+            after.into_iter().map(|s| (s.id, s)).collect();
+        let mut pairs: Vec<(Option<Snapshot>, Option<Snapshot>)> = before
+            .into_iter()
+            .map(|b| {
+                let a = after_by_id.remove(&b.id);
+                (Some(b), a)
+            })
+            .collect();
+        let mut created: Vec<Snapshot> = after_by_id.into_values().collect();
+        created.sort_by_key(|s| s.id);
+        pairs.extend(created.into_iter().map(|a| (None, Some(a))));
+
+        pairs
+            .into_iter()
+            .filter_map(|(before, after)| {
+                let changes = match operation {
+                    AuditOperation::Delete | AuditOperation::Restore => Map::new(),
+                    _ => diff_changes(
+                        before.as_ref().map(|s| &s.values),
+                        after.as_ref().map(|s| &s.values),
+                    ),
+                };
+                if operation == AuditOperation::Update && changes.is_empty() {
+                    return None;
+                }
+                let row = before.or(after)?;
+                Some(AuditEntry {
+                    entity_id: row.id,
+                    entity_uid: row.uid,
+                    operation,
+                    user_id,
+                    changes,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Columns whose value differs between `before` and `after`; a missing side
+/// counts as null.
+pub fn diff_changes(
+    before: Option<&Map<String, Value>>,
+    after: Option<&Map<String, Value>>,
+) -> Map<String, Value> {
+    let empty = Map::new();
+    let before = before.unwrap_or(&empty);
+    let after = after.unwrap_or(&empty);
+    let mut changes = Map::new();
+    for column in before
+        .keys()
+        .chain(after.keys().filter(|k| !before.contains_key(*k)))
+    {
+        let old = before.get(column).unwrap_or(&Value::Null);
+        let new = after.get(column).unwrap_or(&Value::Null);
+        if old != new {
+            changes.insert(column.clone(), json!({ "old": old, "new": new }));
+        }
+    }
+    changes
+}
+
+#[cfg(feature = "ssr")]
+tokio::task_local! {
+    static ACTOR: Option<i32>;
+}
+
+/// Runs `work` with `user_id` as the user behind every write it audits.
+#[cfg(feature = "ssr")]
+pub async fn with_actor<F: Future>(user_id: Option<i32>, work: F) -> F::Output {
+    ACTOR.scope(user_id, work).await
+}
+
+/// The signed-in user making the current request, if any.
+pub fn current_actor() -> Option<i32> {
+    #[cfg(feature = "ssr")]
+    {
+        ACTOR.try_with(|user_id| *user_id).ok().flatten()
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(id: i32, values: Value) -> Snapshot {
+        Snapshot {
+            id,
+            uid: format!("uid-{id}"),
+            values: values.as_object().unwrap().clone(),
+        }
+    }
+
+    #[test]
+    fn test_from_snapshots() {
+        let entries = AuditEntry::from_snapshots(
+            AuditOperation::Update,
+            vec![
+                snapshot(1, json!({"title": "a", "slug": "a"})),
+                snapshot(2, json!({"title": "b"})),
+            ],
+            vec![
+                snapshot(1, json!({"title": "c", "slug": "a"})),
+                snapshot(2, json!({"title": "b"})),
+            ],
+        );
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].entity_id, 1);
+        assert_eq!(entries[0].entity_uid, "uid-1");
+        #[cfg(feature = "ssr")]
+        assert!(matches!(
+            entries[0].entity_uuid(),
+            Err(crate::common::error::CoreError::UnprocessableEntity(
+                "error.audit.invalid.entity_uid",
+                _
+            ))
+        ));
+        assert_eq!(
+            Value::Object(entries[0].changes.clone()),
+            json!({"title": {"old": "a", "new": "c"}})
+        );
+
+        let entries = AuditEntry::from_snapshots(
+            AuditOperation::Create,
+            vec![],
+            vec![snapshot(3, json!({"title": "d"}))],
+        );
+        assert_eq!(
+            Value::Object(entries[0].changes.clone()),
+            json!({"title": {"old": null, "new": "d"}})
+        );
+
+        let entries = AuditEntry::from_snapshots(
+            AuditOperation::Delete,
+            vec![snapshot(3, json!({"title": "d"}))],
+            vec![],
+        );
+        assert_eq!(entries.len(), 1);
+        assert!(entries[0].changes.is_empty());
+    }
+}
@@ -1,20 +1,317 @@
+use futures::lock::Mutex as AsyncMutex;
 use once_cell::sync::Lazy;
 use std::any::Any;
 use std::collections::HashMap;
 use std::future::Future;
-use std::sync::RwLock;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
 
 use crate::common::error::CoreError;
+use crate::common::service::{ATTRIBUTE_TYPE_MAP, FIELD_TYPE_MAP};
 
-// Global in-memory cache keyed by a concatenation of service_name and key.
-// This keeps the cache simple and avoids nested maps or per-service type registries.
-static CACHE: Lazy<RwLock<HashMap<String, Box<dyn Any + Send + Sync>>>> =
-    Lazy::new(|| RwLock::new(HashMap::new()));
+/// Process-wide cache. Type maps are cached for ten minutes at most, and the
+/// attribute type maps are dropped as soon as an attribute is written.
+pub static CACHE: Lazy<Cache> = Lazy::new(|| {
+    let cache = Cache::default();
+    let type_maps = CacheConfig {
+        capacity: 256,
+        ttl: Some(Duration::from_secs(600)),
+    };
+    cache.configure(FIELD_TYPE_MAP, type_maps);
+    cache.configure(ATTRIBUTE_TYPE_MAP, type_maps);
+    cache.invalidate_on_write("attributes", ATTRIBUTE_TYPE_MAP);
+    cache
+});
 
-// Namespacing constant for field type maps, as per requirement
+/// Limits of one namespace. Past `capacity`, expired entries go first, then
+/// the least recently used ones.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CacheConfig {
+    pub capacity: usize,
+    /// `None` keeps entries until they are evicted or invalidated.
+    pub ttl: Option<Duration>,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            capacity: 1024,
+            ttl: None,
+        }
+    }
+}
+
+/// Counters of one namespace since it was first used.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+    pub expirations: u64,
+    pub entries: usize,
+}
+
+struct CacheEntry {
+    value: Arc<dyn Any + Send + Sync>,
+    expires_at: Option<u64>,
+    last_used: u64,
+}
+
+#[derive(Default)]
+struct Namespace {
+    config: CacheConfig,
+    entries: HashMap<String, CacheEntry>,
+    // Bumped on every access; orders entries for LRU eviction
+    tick: u64,
+    // Bumped on every clear, so a value computed before it is not stored after
+    generation: u64,
+    stats: CacheStats,
+}
+
+impl Namespace {
+    fn get(&mut self, key: &str, now: u64) -> Option<Arc<dyn Any + Send + Sync>> {
+        self.tick += 1;
+        let entry = self.entries.get_mut(key)?;
+        if entry.expires_at.is_some_and(|at| at <= now) {
+            self.entries.remove(key);
+            self.stats.expirations += 1;
+            return None;
+        }
+        entry.last_used = self.tick;
+        Some(entry.value.clone())
+    }
+
+    fn insert(&mut self, key: String, value: Arc<dyn Any + Send + Sync>, now: u64) {
+        self.tick += 1;
+        let expires_at = self.config.ttl.map(|ttl| now + ttl.as_millis() as u64);
+        self.entries.insert(
+            key,
+            CacheEntry {
+                value,
+                expires_at,
+                last_used: self.tick,
+            },
+        );
+        self.shrink(now);
+    }
+
+    fn shrink(&mut self, now: u64) {
+        if self.entries.len() <= self.config.capacity {
+            return;
+        }
+        let before = self.entries.len();
+        self.entries
+            .retain(|_, e| !e.expires_at.is_some_and(|at| at <= now));
+        self.stats.expirations += (before - self.entries.len()) as u64;
+        // A linear scan per eviction; namespaces are small
+        while self.entries.len() > self.config.capacity {
+            let lru = self
+                .entries
+                .iter()
+                .min_by_key(|(_, e)| e.last_used)
+                .map(|(k, _)| k.clone());
+            if let Some(key) = lru {
+                self.entries.remove(&key);
+                self.stats.evictions += 1;
+            }
+        }
+    }
+}
+
+/// In-memory cache split into namespaces, each with its own capacity, TTL and
+/// stats. Values of any `Clone` type are stored per `(namespace, key)`.
+#[derive(Default)]
+pub struct Cache {
+    namespaces: Mutex<HashMap<String, Namespace>>,
+    // One lock per key being computed, so concurrent misses compute it once
+    flights: Mutex<HashMap<String, Arc<AsyncMutex<()>>>>,
+    // Table name -> namespaces to clear when a row of it is written
+    write_hooks: RwLock<HashMap<String, Vec<String>>>,
+}
+
+impl Cache {
+    /// Sets the limits of `namespace`, keeping its entries and stats.
+    pub fn configure(&self, namespace: &str, config: CacheConfig) {
+        let mut namespaces = self.namespaces.lock().unwrap();
+        let ns = namespaces.entry(namespace.to_string()).or_default();
+        ns.config = config;
+        ns.shrink(now_millis());
+    }
+
+    pub fn get<V: Clone + 'static>(&self, namespace: &str, key: &str) -> Option<V> {
+        let mut namespaces = self.namespaces.lock().unwrap();
+        let ns = namespaces.entry(namespace.to_string()).or_default();
+        let value = ns
+            .get(key, now_millis())
+            .and_then(|v| v.downcast_ref::<V>().cloned());
+        match value {
+            Some(_) => ns.stats.hits += 1,
+            None => ns.stats.misses += 1,
+        }
+        value
+    }
+
+    pub fn insert<V: Send + Sync + 'static>(&self, namespace: &str, key: &str, value: V) {
+        let mut namespaces = self.namespaces.lock().unwrap();
+        namespaces
+            .entry(namespace.to_string())
+            .or_default()
+            .insert(key.to_string(), Arc::new(value), now_millis());
+    }
+
+    /// Cached value of `(namespace, key)`, computing and storing it on a miss.
+    /// Callers missing the same key at the same time wait for one `compute`
+    /// instead of each running their own. Errors are returned, not cached.
+    pub async fn get_or_compute<V, F, Fut>(
+        &self,
+        namespace: &str,
+        key: &str,
+        compute: F,
+    ) -> Result<V, CoreError>
+    where
+        V: Clone + Send + Sync + 'static,
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<V, CoreError>>,
+    {
+        if let Some(value) = self.lookup(namespace, key) {
+            return Ok(value);
+        }
+
+        let flight_key = format!("{namespace}:{key}");
+        let flight = self
+            .flights
+            .lock()
+            .unwrap()
+            .entry(flight_key.clone())
+            .or_default()
+            .clone();
+        let result = {
+            let _guard = flight.lock().await;
+            // Whoever held the lock before us may have computed it already
+            match self.lookup(namespace, key) {
+                Some(value) => Ok(value),
+                None => {
+                    let generation = self.count_miss(namespace);
+                    let computed = compute().await;
+                    if let Ok(value) = &computed {
+                        self.insert_unless_cleared(namespace, key, value.clone(), generation);
+                    }
+                    computed
+                }
+            }
+        };
+        let mut flights = self.flights.lock().unwrap();
+        // Only the map and this call still hold it: nobody else is waiting
+        if Arc::strong_count(&flight) == 2 {
+            flights.remove(&flight_key);
+        }
+        result
+    }
+
+    pub fn invalidate(&self, namespace: &str, key: &str) {
+        if let Some(ns) = self.namespaces.lock().unwrap().get_mut(namespace) {
+            ns.entries.remove(key);
+        }
+    }
+
+    pub fn invalidate_namespace(&self, namespace: &str) {
+        if let Some(ns) = self.namespaces.lock().unwrap().get_mut(namespace) {
+            ns.entries.clear();
+            ns.generation += 1;
+        }
+    }
+
+    pub fn stats(&self, namespace: &str) -> CacheStats {
+        self.namespaces
+            .lock()
+            .unwrap()
+            .get(namespace)
+            .map(|ns| CacheStats {
+                entries: ns.entries.len(),
+                ..ns.stats
+            })
+            .unwrap_or_default()
+    }
+
+    /// Clears `namespace` whenever a row of `table` is written, see
+    /// [`Cache::notify_write`].
+    pub fn invalidate_on_write(&self, table: &str, namespace: &str) {
+        let mut hooks = self.write_hooks.write().unwrap();
+        let namespaces = hooks.entry(table.to_string()).or_default();
+        if !namespaces.iter().any(|n| n == namespace) {
+            namespaces.push(namespace.to_string());
+        }
+    }
+
+    /// Called once a write to `table` is committed.
+    pub fn notify_write(&self, table: &str) {
+        let namespaces = self
+            .write_hooks
+            .read()
+            .unwrap()
+            .get(table)
+            .cloned()
+            .unwrap_or_default();
+        for namespace in namespaces {
+            self.invalidate_namespace(&namespace);
+        }
+    }
+
+    // Like `get`, but only counts hits: a miss is counted once, by the caller
+    // that goes on to compute the value.
+    fn lookup<V: Clone + 'static>(&self, namespace: &str, key: &str) -> Option<V> {
+        let mut namespaces = self.namespaces.lock().unwrap();
+        let ns = namespaces.entry(namespace.to_string()).or_default();
+        let value = ns
+            .get(key, now_millis())
+            .and_then(|v| v.downcast_ref::<V>().cloned());
+        if value.is_some() {
+            ns.stats.hits += 1;
+        }
+        value
+    }
+
+    // Returns the generation the value about to be computed belongs to
+    fn count_miss(&self, namespace: &str) -> u64 {
+        let mut namespaces = self.namespaces.lock().unwrap();
+        let ns = namespaces.entry(namespace.to_string()).or_default();
+        ns.stats.misses += 1;
+        ns.generation
+    }
+
+    // A write notified while the value was computed may have made it stale
+    fn insert_unless_cleared<V: Send + Sync + 'static>(
+        &self,
+        namespace: &str,
+        key: &str,
+        value: V,
+        generation: u64,
+    ) {
+        let mut namespaces = self.namespaces.lock().unwrap();
+        let ns = namespaces.entry(namespace.to_string()).or_default();
+        if ns.generation == generation {
+            ns.insert(key.to_string(), Arc::new(value), now_millis());
+        }
+    }
+}
+
+/// Wall clock in milliseconds. `Instant` is not available in the browser, and
+/// only differences between two readings matter here.
+fn now_millis() -> u64 {
+    #[cfg(target_arch = "wasm32")]
+    {
+        js_sys::Date::now() as u64
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or_default()
+    }
+}
 
-/// Get value from cache by (service_name, key); if missing, compute and store it via the provided async closure.
-/// The cache stores a single value per combined key ("service_name:key").
+/// [`Cache::get_or_compute`] on the process-wide cache.
 pub async fn cache_get_or_compute<V, F, Fut>(
     service_name: &str,
     key: &str,
@@ -25,23 +322,7 @@ where
     F: FnOnce() -> Fut,
     Fut: Future<Output = Result<V, CoreError>>,
 {
-    let combined_key = format!("{}:{}", service_name, key);
-
-    // Fast path: try read lock and downcast to the expected value type
-    if let Some(any_box) = CACHE.read().unwrap().get(&combined_key) {
-        if let Some(val) = any_box.downcast_ref::<V>() {
-            return Ok(val.clone());
-        }
-    }
-
-    // Miss: compute without holding locks
-    let computed: V = compute().await?;
-
-    // Store with write lock
-    let mut guard = CACHE.write().unwrap();
-    guard.insert(combined_key, Box::new(computed.clone()));
-
-    Ok(computed)
+    CACHE.get_or_compute(service_name, key, compute).await
 }
 
 /// Update/insert a cache value for (service_name, key) using a supplier function.
@@ -52,8 +333,86 @@ where
     V: Any + Send + Sync + 'static,
     F: FnOnce() -> V,
 {
-    let combined_key = format!("{}:{}", service_name, key);
-    let mut guard = CACHE.write().unwrap();
-    let value = make();
-    guard.insert(combined_key, Box::new(value));
+    CACHE.insert(service_name, key, make());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn test_namespace_evicts_expired_then_least_recently_used() {
+        let mut ns = Namespace {
+            config: CacheConfig {
+                capacity: 2,
+                ttl: Some(Duration::from_millis(100)),
+            },
+            ..Default::default()
+        };
+        ns.insert("a".into(), Arc::new(1), 0);
+        ns.insert("b".into(), Arc::new(2), 0);
+        ns.get("a", 10);
+        ns.insert("c".into(), Arc::new(3), 20);
+        assert!(ns.entries.contains_key("a"));
+        assert!(!ns.entries.contains_key("b"));
+        assert_eq!(ns.stats.evictions, 1);
+
+        assert!(ns.get("a", 100).is_none());
+        assert_eq!(ns.stats.expirations, 1);
+        assert!(ns.get("c", 100).is_some());
+    }
+
+    #[tokio::test]
+    async fn test_get_or_compute_single_flight() {
+        let cache = Cache::default();
+        let computed = AtomicUsize::new(0);
+        let compute = || async {
+            computed.fetch_add(1, Ordering::SeqCst);
+            tokio::task::yield_now().await;
+            Ok::<_, CoreError>(42)
+        };
+        let (a, b) = futures::join!(
+            cache.get_or_compute("NS", "k", compute),
+            cache.get_or_compute("NS", "k", compute)
+        );
+        assert_eq!((a.unwrap(), b.unwrap()), (42, 42));
+        assert_eq!(computed.load(Ordering::SeqCst), 1);
+        let stats = cache.stats("NS");
+        assert_eq!((stats.hits, stats.misses, stats.entries), (1, 1, 1));
+        assert!(cache.flights.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_notify_write_invalidates_hooked_namespaces() {
+        let cache = Cache::default();
+        cache.invalidate_on_write("attributes", "TYPES");
+        cache.insert("TYPES", "posts", 1);
+        cache.insert("OTHER", "posts", 2);
+        cache.notify_write("terms");
+        assert_eq!(cache.get::<i32>("TYPES", "posts"), Some(1));
+        cache.notify_write("attributes");
+        assert_eq!(cache.get::<i32>("TYPES", "posts"), None);
+        assert_eq!(cache.get::<i32>("OTHER", "posts"), Some(2));
+    }
+
+    #[tokio::test]
+    async fn test_get_or_compute_skips_values_computed_across_a_write() {
+        let cache = Cache::default();
+        cache.invalidate_on_write("attributes", "TYPES");
+        let value = cache
+            .get_or_compute("TYPES", "posts", || async {
+                cache.notify_write("attributes");
+                Ok::<_, CoreError>(1)
+            })
+            .await;
+        assert_eq!(value.unwrap(), 1);
+        assert_eq!(cache.get::<i32>("TYPES", "posts"), None);
+
+        let value = cache
+            .get_or_compute("TYPES", "posts", || async { Ok::<_, CoreError>(2) })
+            .await;
+        assert_eq!(value.unwrap(), 2);
+        assert_eq!(cache.get::<i32>("TYPES", "posts"), Some(2));
+    }
 }
@@ -0,0 +1,166 @@
+use crate::common::error::CoreError;
+use crate::common::filter::ScalarValue;
+use serde::{Deserialize, Serialize};
+use time::format_description::well_known::Rfc3339;
+use time::macros::format_description;
+use time::{Date, OffsetDateTime, Time};
+
+/// Position of a row in a keyset-paginated listing: the values of its sort
+/// keys, in sort order, plus its id as the final tie-breaker. A `None` key is
+/// a NULL, which sorts after every value as in Postgres.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cursor {
+    pub keys: Vec<Option<ScalarValue>>,
+    pub id: i32,
+}
+
+/// Which slice of a keyset-paginated listing to load.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CursorPosition {
+    First,
+    After(Cursor),
+    Before(Cursor),
+}
+
+/// One page of a keyset-paginated listing. Cursors are opaque strings that
+/// are passed back as `after` / `before` to load the neighbouring page.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CursorPage<T> {
+    pub items: Vec<T>,
+    pub next_cursor: Option<String>,
+    pub prev_cursor: Option<String>,
+}
+
+impl<T> CursorPage<T> {
+    pub fn map<U, F: FnMut(T) -> U>(self, mapper: F) -> CursorPage<U> {
+        CursorPage {
+            items: self.items.into_iter().map(mapper).collect(),
+            next_cursor: self.next_cursor,
+            prev_cursor: self.prev_cursor,
+        }
+    }
+}
+
+/// Type tag of a NULL key in an encoded cursor.
+const NULL_TAG: u8 = 7;
+
+#[derive(Serialize, Deserialize)]
+struct EncodedCursor {
+    k: Vec<(u8, String)>,
+    id: i32,
+}
+
+impl Cursor {
+    pub fn encode(&self) -> String {
+        let encoded = EncodedCursor {
+            k: self
+                .keys
+                .iter()
+                .map(|key| key.as_ref().map_or((NULL_TAG, String::new()), encode_scalar))
+                .collect(),
+            id: self.id,
+        };
+        serde_json::to_vec(&encoded)
+            .unwrap_or_default()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect()
+    }
+
+    pub fn decode(raw: &str) -> Result<Self, CoreError> {
+        let invalid = || CoreError::bad_request("error.cursor.invalid");
+        if raw.len() % 2 != 0 || !raw.is_ascii() {
+            return Err(invalid());
+        }
+        let bytes = (0..raw.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&raw[i..i + 2], 16))
+            .collect::<Result<Vec<u8>, _>>()
+            .map_err(|_| invalid())?;
+        let encoded: EncodedCursor = serde_json::from_slice(&bytes).map_err(|_| invalid())?;
+        let keys = encoded
+            .k
+            .into_iter()
+            .map(|(t, v)| match t {
+                NULL_TAG => Ok(None),
+                _ => decode_scalar(t, &v).map(Some).ok_or_else(invalid),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Cursor {
+            keys,
+            id: encoded.id,
+        })
+    }
+}
+
+fn encode_scalar(value: &ScalarValue) -> (u8, String) {
+    match value {
+        ScalarValue::String(s) => (0, s.clone()),
+        ScalarValue::Int(i) => (1, i.to_string()),
+        ScalarValue::Float(f) => (2, f.to_string()),
+        ScalarValue::Bool(b) => (3, b.to_string()),
+        ScalarValue::Date(d) => (
+            4,
+            d.format(&format_description!("[year]-[month]-[day]"))
+                .unwrap_or_default(),
+        ),
+        ScalarValue::DateTime(dt) => (5, dt.format(&Rfc3339).unwrap_or_default()),
+        ScalarValue::Time(t) => (
+            6,
+            t.format(&format_description!(
+                "[hour]:[minute]:[second].[subsecond digits:9]"
+            ))
+            .unwrap_or_default(),
+        ),
+    }
+}
+
+fn decode_scalar(t: u8, v: &str) -> Option<ScalarValue> {
+    match t {
+        0 => Some(ScalarValue::String(v.to_string())),
+        1 => v.parse().ok().map(ScalarValue::Int),
+        2 => v.parse().ok().map(ScalarValue::Float),
+        3 => v.parse().ok().map(ScalarValue::Bool),
+        4 => Date::parse(v, format_description!("[year]-[month]-[day]"))
+            .ok()
+            .map(ScalarValue::Date),
+        5 => OffsetDateTime::parse(v, &Rfc3339)
+            .ok()
+            .map(ScalarValue::DateTime),
+        6 => Time::parse(
+            v,
+            format_description!("[hour]:[minute]:[second].[subsecond digits:9]"),
+        )
+        .ok()
+        .map(ScalarValue::Time),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::macros::{date, datetime, time};
+
+    #[test]
+    fn test_cursor_round_trip() {
+        let cursor = Cursor {
+            keys: vec![
+                Some(ScalarValue::DateTime(datetime!(2025-07-16 10:20:30.123456 UTC))),
+                Some(ScalarValue::String("hello: world".into())),
+                Some(ScalarValue::Float(0.1)),
+                None,
+                Some(ScalarValue::Date(date!(2025 - 07 - 16))),
+                Some(ScalarValue::Time(time!(08:09:10.5))),
+            ],
+            id: 42,
+        };
+        assert_eq!(Cursor::decode(&cursor.encode()).unwrap(), cursor);
+    }
+
+    #[test]
+    fn test_cursor_rejects_garbage() {
+        assert!(Cursor::decode("not-a-cursor").is_err());
+        assert!(Cursor::decode("7b7d").is_err());
+    }
+}
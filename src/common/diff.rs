@@ -0,0 +1,121 @@
+use serde::{Deserialize, Serialize};
+
+/// One line of a line-level diff.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum DiffLine {
+    Same(String),
+    Added(String),
+    Removed(String),
+}
+
+/// Most cells of the LCS table `diff_lines` builds, about 4 MB.
+const MAX_TABLE_CELLS: usize = 1 << 20;
+
+/// Line-level diff turning `old` into `new`, along the longest common
+/// subsequence of lines. Lines shared at both ends are matched up front, so
+/// small edits to long texts stay cheap. When the rest is too large for the
+/// LCS table, it is all removed and added instead.
+pub fn diff_lines(old: &str, new: &str) -> Vec<DiffLine> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+
+    let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let old_mid = &old[prefix..old.len() - suffix];
+    let new_mid = &new[prefix..new.len() - suffix];
+    let table_cells = (old_mid.len() + 1).saturating_mul(new_mid.len() + 1);
+    let (old_mid, new_mid, old_rest, new_rest) = if table_cells > MAX_TABLE_CELLS {
+        (&[][..], &[][..], old_mid, new_mid)
+    } else {
+        (old_mid, new_mid, &[][..], &[][..])
+    };
+
+    // lcs[i][j]: length of the LCS of old_mid[i..] and new_mid[j..]
+    let mut lcs = vec![vec![0u32; new_mid.len() + 1]; old_mid.len() + 1];
+    for i in (0..old_mid.len()).rev() {
+        for j in (0..new_mid.len()).rev() {
+            lcs[i][j] = if old_mid[i] == new_mid[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut lines: Vec<DiffLine> = old[..prefix]
+        .iter()
+        .map(|l| DiffLine::Same(l.to_string()))
+        .collect();
+    let (mut i, mut j) = (0, 0);
+    while i < old_mid.len() && j < new_mid.len() {
+        if old_mid[i] == new_mid[j] {
+            lines.push(DiffLine::Same(old_mid[i].to_string()));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            lines.push(DiffLine::Removed(old_mid[i].to_string()));
+            i += 1;
+        } else {
+            lines.push(DiffLine::Added(new_mid[j].to_string()));
+            j += 1;
+        }
+    }
+    lines.extend(old_mid[i..].iter().map(|l| DiffLine::Removed(l.to_string())));
+    lines.extend(new_mid[j..].iter().map(|l| DiffLine::Added(l.to_string())));
+    lines.extend(old_rest.iter().map(|l| DiffLine::Removed(l.to_string())));
+    lines.extend(new_rest.iter().map(|l| DiffLine::Added(l.to_string())));
+    lines.extend(
+        old[old.len() - suffix..]
+            .iter()
+            .map(|l| DiffLine::Same(l.to_string())),
+    );
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_lines() {
+        assert_eq!(
+            diff_lines("a\nb\nc\nd", "a\nx\nc\nd\ne"),
+            vec![
+                DiffLine::Same("a".into()),
+                DiffLine::Removed("b".into()),
+                DiffLine::Added("x".into()),
+                DiffLine::Same("c".into()),
+                DiffLine::Same("d".into()),
+                DiffLine::Added("e".into()),
+            ]
+        );
+        assert_eq!(
+            diff_lines("same", "same"),
+            vec![DiffLine::Same("same".into())]
+        );
+        assert_eq!(
+            diff_lines("", "new"),
+            vec![DiffLine::Added("new".into())]
+        );
+    }
+
+    #[test]
+    fn test_diff_lines_too_large_for_lcs() {
+        let old: Vec<String> = (0..2000).map(|i| format!("old {i}")).collect();
+        let new: Vec<String> = (0..2000).map(|i| format!("new {i}")).collect();
+        let lines = diff_lines(
+            &format!("head\n{}\ntail", old.join("\n")),
+            &format!("head\n{}\ntail", new.join("\n")),
+        );
+        assert_eq!(lines.len(), 4002);
+        assert_eq!(lines[0], DiffLine::Same("head".into()));
+        assert_eq!(lines[1], DiffLine::Removed("old 0".into()));
+        assert_eq!(lines[2001], DiffLine::Added("new 0".into()));
+        assert_eq!(lines[4001], DiffLine::Same("tail".into()));
+    }
+}
@@ -0,0 +1,92 @@
+use crate::common::filter::{Filter, ScalarValue};
+use crate::common::sort::ATTRIBUTE_SORT_PREFIXES;
+use std::str::FromStr;
+
+/// A property (e.g. `status`) or attribute (e.g. `a:difficulty`) to count values of.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FacetField {
+    pub field: String,
+    pub is_attribute: bool,
+}
+
+impl FromStr for FacetField {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Err(());
+        }
+        Ok(
+            match ATTRIBUTE_SORT_PREFIXES
+                .iter()
+                .find_map(|prefix| s.strip_prefix(prefix))
+            {
+                Some(attr_name) => FacetField {
+                    field: attr_name.trim().to_string(),
+                    is_attribute: true,
+                },
+                None => FacetField {
+                    field: s.to_string(),
+                    is_attribute: false,
+                },
+            },
+        )
+    }
+}
+
+impl FacetField {
+    /// Whether `filter` is this facet's own, which is left out when counting it
+    /// so the other values of the facet stay selectable.
+    pub fn is_filtered_by(&self, filter: &Filter) -> bool {
+        match filter {
+            Filter::Property { property_name, .. } => {
+                !self.is_attribute && *property_name == self.field
+            }
+            Filter::Attribute { attr_name, .. } => self.is_attribute && *attr_name == self.field,
+            _ => false,
+        }
+    }
+}
+
+/// Number of matching rows holding `value`; `None` counts rows without a value.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FacetBucket {
+    pub value: Option<ScalarValue>,
+    pub count: i64,
+}
+
+/// Value counts of one facet, most frequent first.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Facet {
+    pub field: FacetField,
+    pub buckets: Vec<FacetBucket>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::filter::{FilterOperator, FilterValue};
+
+    #[test]
+    fn test_facet_field_is_filtered_by_its_own_filter_only() {
+        let status = FacetField::from_str("status").unwrap();
+        let difficulty = FacetField::from_str("a:difficulty").unwrap();
+        assert!(difficulty.is_attribute);
+        assert!(FacetField::from_str(" ").is_err());
+
+        let status_filter = Filter::Property {
+            property_name: "status".into(),
+            operator: FilterOperator::Equal,
+            value: FilterValue::Single(ScalarValue::String("published".into())),
+        };
+        let difficulty_filter = Filter::Attribute {
+            attr_name: "difficulty".into(),
+            operator: FilterOperator::Equal,
+            value: FilterValue::Single(ScalarValue::String("hard".into())),
+        };
+        assert!(status.is_filtered_by(&status_filter));
+        assert!(!status.is_filtered_by(&difficulty_filter));
+        assert!(difficulty.is_filtered_by(&difficulty_filter));
+        assert!(!difficulty.is_filtered_by(&Filter::Not(Box::new(difficulty_filter))));
+    }
+}
@@ -8,6 +8,8 @@ pub enum FilterOperator {
     LessThanOrEqual,
     Like,
     NotLike,
+    /// Trigram word similarity (`pg_trgm`), tolerant of typos.
+    Similar,
     In,
     NotIn,
     IsNull,
@@ -28,9 +30,61 @@ pub enum Filter {
         operator: FilterOperator,
         value: FilterValue,
     },
+    /// Keyword search over the searchable columns. A `fuzzy` search matches by
+    /// trigram similarity instead, so misspelled words still find rows.
     Search {
         value: String,
+        fuzzy: bool,
     },
+    /// Posts having a term of the taxonomy with code `taxonomy` and/or with
+    /// slug `slug`; with neither, posts having any term. Only posts take terms.
+    Term {
+        taxonomy: Option<String>,
+        slug: Option<String>,
+    },
+    And(Vec<Filter>),
+    Or(Vec<Filter>),
+    Not(Box<Filter>),
+    /// Which rows to see with respect to soft deletion. Without one at the top
+    /// level, queries only see rows that are not in the trash.
+    SoftDeleted(SoftDeleteScope),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SoftDeleteScope {
+    /// Live rows only, the default.
+    Exclude,
+    /// Rows in the trash only.
+    Only,
+    /// Live and trashed rows.
+    Include,
+}
+
+impl Filter {
+    /// Names of every property filtered on, including those nested in groups.
+    pub fn property_names(&self) -> Vec<&str> {
+        match self {
+            Filter::Property { property_name, .. } => vec![property_name.as_str()],
+            Filter::Attribute { .. }
+            | Filter::Search { .. }
+            | Filter::Term { .. }
+            | Filter::SoftDeleted(_) => vec![],
+            Filter::And(filters) | Filter::Or(filters) => {
+                filters.iter().flat_map(Filter::property_names).collect()
+            }
+            Filter::Not(filter) => filter.property_names(),
+        }
+    }
+
+    /// Whether this is or contains a `Filter::Term`.
+    pub fn has_term(&self) -> bool {
+        match self {
+            Filter::Term { .. } => true,
+            Filter::And(filters) | Filter::Or(filters) => filters.iter().any(Filter::has_term),
+            Filter::Not(filter) => filter.has_term(),
+            _ => false,
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -55,10 +109,7 @@ impl FilterOperator {
     pub fn is_value_compatible(&self, value: &FilterValue) -> bool {
         use ScalarValue::*;
         match self {
-            Self::Equal | Self::NotEqual => match value {
-                FilterValue::Single(_) => true,
-                _ => false,
-            },
+            Self::Equal | Self::NotEqual => matches!(value, FilterValue::Single(_)),
             Self::GreaterThan
             | Self::GreaterThanOrEqual
             | Self::LessThan
@@ -68,10 +119,9 @@ impl FilterOperator {
                 }
                 _ => false,
             },
-            Self::Like | Self::NotLike => match value {
-                FilterValue::Single(String(_)) => true,
-                _ => false,
-            },
+            Self::Like | Self::NotLike | Self::Similar => {
+                matches!(value, FilterValue::Single(String(_)))
+            }
             Self::In | Self::NotIn => match value {
                 FilterValue::List(vs) => {
                     if vs.is_empty() {
@@ -1,6 +1,12 @@
+pub mod aggregate;
+pub mod audit;
 pub mod cache;
+pub mod cursor;
+pub mod diff;
 pub mod error;
+pub mod facet;
 pub mod filter;
 pub mod repository;
+pub mod search;
 pub mod service;
 pub mod sort;
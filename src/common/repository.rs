@@ -1,5 +1,10 @@
+use crate::common::aggregate::{Aggregate, AggregateGroup};
+use crate::common::audit::{AuditEntry, Snapshot};
+use crate::common::cursor::{CursorPage, CursorPosition};
 use crate::common::error::CoreError;
-use crate::common::filter::{Filter, ScalarValue};
+use crate::common::facet::{Facet, FacetField};
+use crate::common::filter::{Filter, ScalarValue, SoftDeleteScope};
+use crate::common::search::{SearchHit, Suggestion};
 use crate::common::sort::SortCriterion;
 use std::collections::HashMap;
 use std::future::Future;
@@ -8,6 +13,22 @@ pub trait Creatable {
     type Entity;
 }
 
+/// An entity loaded together with its attribute values, keyed by attribute name.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WithAttributes<T> {
+    pub entity: T,
+    pub attributes: HashMap<String, ScalarValue>,
+}
+
+impl<T> WithAttributes<T> {
+    pub fn map<U, F: FnOnce(T) -> U>(self, mapper: F) -> WithAttributes<U> {
+        WithAttributes {
+            entity: mapper(self.entity),
+            attributes: self.attributes,
+        }
+    }
+}
+
 pub trait ViewRepository<T> {
     fn get_table_name(&self) -> &str;
     fn get_columns(&self) -> Vec<&str>;
@@ -17,6 +38,32 @@ pub trait ViewRepository<T> {
     }
 
     fn count(&self, filters: Vec<Filter>) -> impl Future<Output = Result<i64, CoreError>>;
+    /// Values of the text `column` that are `base` or start with `base-`,
+    /// among rows matching `filters`: those a suffixed `base` may collide with.
+    fn find_suffixed(
+        &self,
+        column: &str,
+        base: &str,
+        filters: Vec<Filter>,
+    ) -> impl Future<Output = Result<Vec<String>, CoreError>>;
+    /// Value counts for each of `fields` among rows matching `filters`, each
+    /// ignoring the facet's own filter.
+    fn facets(
+        &self,
+        fields: Vec<FacetField>,
+        filters: Vec<Filter>,
+    ) -> impl Future<Output = Result<Vec<Facet>, CoreError>>;
+    /// Rows matching `filters` grouped by the `group_by` columns, with
+    /// `aggregates` computed per group, ordered by the group values. Without
+    /// `group_by`, a single group covers every matching row.
+    fn aggregate(
+        &self,
+        group_by: Vec<String>,
+        aggregates: Vec<Aggregate>,
+        first_result: Option<i32>,
+        max_results: Option<i32>,
+        filters: Vec<Filter>,
+    ) -> impl Future<Output = Result<Vec<AggregateGroup>, CoreError>>;
     fn find_many(
         &self,
         sort_criteria: Vec<SortCriterion>,
@@ -24,6 +71,39 @@ pub trait ViewRepository<T> {
         max_results: Option<i32>,
         filters: Vec<Filter>,
     ) -> impl Future<Output = Result<Vec<T>, CoreError>>;
+    /// Same rows as `find_many`, each with its attribute values, in one query.
+    fn find_many_with_attributes(
+        &self,
+        sort_criteria: Vec<SortCriterion>,
+        first_result: Option<i32>,
+        max_results: Option<i32>,
+        filters: Vec<Filter>,
+    ) -> impl Future<Output = Result<Vec<WithAttributes<T>>, CoreError>>;
+    /// Full-text search for `query` among rows matching `filters`, best match first.
+    fn search(
+        &self,
+        query: String,
+        first_result: Option<i32>,
+        max_results: Option<i32>,
+        filters: Vec<Filter>,
+    ) -> impl Future<Output = Result<Vec<SearchHit<T>>, CoreError>>;
+    /// Rows matching `filters` whose searchable text is similar to `query`,
+    /// even when misspelled, most similar first.
+    fn suggest(
+        &self,
+        query: String,
+        max_results: Option<i32>,
+        filters: Vec<Filter>,
+    ) -> impl Future<Output = Result<Vec<Suggestion<T>>, CoreError>>;
+    /// Keyset pagination: up to `max_results` rows next to `position`, ordered
+    /// by `sort_criteria` with `id` as the final tie-breaker.
+    fn find_page(
+        &self,
+        sort_criteria: Vec<SortCriterion>,
+        position: CursorPosition,
+        max_results: i32,
+        filters: Vec<Filter>,
+    ) -> impl Future<Output = Result<CursorPage<T>, CoreError>>;
     fn find_by_id(&self, id: i32) -> impl Future<Output = Result<Option<T>, CoreError>>;
     fn find_by_uid(&self, uid: String) -> impl Future<Output = Result<Option<T>, CoreError>>;
     fn get_column_type_map(
@@ -36,9 +116,120 @@ pub trait Repository<T, C: Creatable<Entity = T>>: ViewRepository<T> {
     fn delete_by_ids(&self, ids: Vec<i32>) -> impl Future<Output = Result<u64, CoreError>>;
     fn delete_by_uid(&self, uid: String) -> impl Future<Output = Result<u64, CoreError>>;
     fn delete_by_uids(&self, uids: Vec<String>) -> impl Future<Output = Result<u64, CoreError>>;
+    /// Brings soft-deleted rows back from the trash.
+    fn restore_by_id(&self, id: i32) -> impl Future<Output = Result<u64, CoreError>>;
+    fn restore_by_ids(&self, ids: Vec<i32>) -> impl Future<Output = Result<u64, CoreError>>;
+    /// Deletes trashed rows for good.
+    fn purge_by_id(&self, id: i32) -> impl Future<Output = Result<u64, CoreError>>;
+    fn purge_by_ids(&self, ids: Vec<i32>) -> impl Future<Output = Result<u64, CoreError>>;
     fn create(&self, entity_create: &C) -> impl Future<Output = Result<T, CoreError>>;
     fn update(&self, entity: &T) -> impl Future<Output = Result<T, CoreError>>;
+    /// Inserts all rows in as few statements as possible, in input order.
+    fn create_many(&self, creates: &[C]) -> impl Future<Output = Result<Vec<T>, CoreError>>;
+    /// Updates all rows with optimistic locking, in input order. Fails as a
+    /// whole when any row is gone or stale.
+    fn update_many(&self, entities: &[T]) -> impl Future<Output = Result<Vec<T>, CoreError>>;
+    /// Inserts each entity as a new row, or updates the row that already has
+    /// the same values in the unique `key` columns, e.g. `["taxonomy_id", "slug"]`.
+    /// The entities' ids and versions are ignored.
+    fn upsert_by(
+        &self,
+        key: &[&str],
+        entities: &[T],
+    ) -> impl Future<Output = Result<Vec<T>, CoreError>>;
     fn get_attribute_type_map(
         &self,
     ) -> impl Future<Output = Result<HashMap<String, ScalarValue>, CoreError>>;
+    /// Audited columns of the rows in `scope`, read around a write to diff it.
+    fn snapshot_by_ids(
+        &self,
+        ids: Vec<i32>,
+        scope: SoftDeleteScope,
+    ) -> impl Future<Output = Result<Vec<Snapshot>, CoreError>>;
+    fn snapshot_by_uids(
+        &self,
+        uids: Vec<String>,
+        scope: SoftDeleteScope,
+    ) -> impl Future<Output = Result<Vec<Snapshot>, CoreError>>;
+    /// Snapshots of the rows an upsert of `entities` by `key` would update.
+    fn snapshot_by_key(
+        &self,
+        key: &[&str],
+        entities: &[T],
+        scope: SoftDeleteScope,
+    ) -> impl Future<Output = Result<Vec<Snapshot>, CoreError>>;
+    /// Appends to the audit log through this repository's connection, so the
+    /// entries commit or roll back with the write they describe.
+    fn record_audit(&self, entries: Vec<AuditEntry>) -> impl Future<Output = Result<(), CoreError>>;
+}
+
+/// A transaction spanning several repository calls.
+pub trait UnitOfWork: Clone {
+    fn commit(&self) -> impl Future<Output = Result<(), CoreError>>;
+    fn rollback(&self) -> impl Future<Output = Result<(), CoreError>>;
+}
+
+/// Repositories whose queries can run inside a unit of work. Repositories
+/// sharing the same `Tx` type can be bound to one transaction together.
+pub trait Transactional: Sized {
+    type Tx: UnitOfWork;
+
+    fn begin(&self) -> impl Future<Output = Result<Self::Tx, CoreError>>;
+    /// Copy of this repository that runs every query inside `tx`.
+    fn in_transaction(&self, tx: &Self::Tx) -> Self;
+}
+
+/// Run `work` inside `tx`: commit when it succeeds, roll back when it fails.
+pub async fn run_in_transaction<U, F, Fut, T>(tx: U, work: F) -> Result<T, CoreError>
+where
+    U: UnitOfWork,
+    F: FnOnce(U) -> Fut,
+    Fut: Future<Output = Result<T, CoreError>>,
+{
+    match work(tx.clone()).await {
+        Ok(value) => {
+            tx.commit().await?;
+            Ok(value)
+        }
+        Err(e) => {
+            // The original error is what the caller needs to see
+            let _ = tx.rollback().await;
+            Err(e)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[derive(Clone, Default)]
+    struct RecordingUnitOfWork(Rc<RefCell<Vec<&'static str>>>);
+
+    impl UnitOfWork for RecordingUnitOfWork {
+        async fn commit(&self) -> Result<(), CoreError> {
+            self.0.borrow_mut().push("commit");
+            Ok(())
+        }
+
+        async fn rollback(&self) -> Result<(), CoreError> {
+            self.0.borrow_mut().push("rollback");
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_run_in_transaction_commits_or_rolls_back() {
+        let tx = RecordingUnitOfWork::default();
+        let ok = run_in_transaction(tx.clone(), |_| async { Ok(1) }).await;
+        assert_eq!(ok.unwrap(), 1);
+
+        let err: Result<i32, CoreError> =
+            run_in_transaction(tx.clone(), |_| async { Err(CoreError::conflict("boom")) }).await;
+        assert!(matches!(err, Err(CoreError::Conflict("boom", _))));
+
+        assert_eq!(*tx.0.borrow(), vec!["commit", "rollback"]);
+    }
 }
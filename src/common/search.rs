@@ -0,0 +1,125 @@
+use serde::{Deserialize, Serialize};
+
+/// Text search configuration created by the full-text search migration.
+pub const SEARCH_CONFIG: &str = "simple_unaccent";
+/// Delimiters placed around matched words in `SearchHit::headline`. Control
+/// characters cannot clash with content, which is never rendered as HTML.
+pub const HIGHLIGHT_START: char = '\u{2}';
+pub const HIGHLIGHT_STOP: char = '\u{3}';
+
+/// A full-text search result: the matching entity, its relevance and a snippet
+/// of its text with the matched words between the highlight delimiters.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SearchHit<T> {
+    pub entity: T,
+    pub rank: f32,
+    pub headline: String,
+}
+
+impl<T> SearchHit<T> {
+    pub fn map<U, F: FnOnce(T) -> U>(self, mapper: F) -> SearchHit<U> {
+        SearchHit {
+            entity: mapper(self.entity),
+            rank: self.rank,
+            headline: self.headline,
+        }
+    }
+}
+
+/// A fuzzy match: an entity whose text is similar to the query, scored by
+/// trigram word similarity from 0 to 1. Backs "did you mean" and autocomplete.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Suggestion<T> {
+    pub entity: T,
+    pub similarity: f32,
+}
+
+impl<T> Suggestion<T> {
+    pub fn map<U, F: FnOnce(T) -> U>(self, mapper: F) -> Suggestion<U> {
+        Suggestion {
+            entity: mapper(self.entity),
+            similarity: self.similarity,
+        }
+    }
+}
+
+/// Turns user input into a `to_tsquery` expression. Every part must match: bare
+/// words match as prefixes (`lep` finds "leptos") and "quoted phrases" match
+/// the words next to each other. Returns `None` when nothing searchable is left.
+pub fn to_tsquery(input: &str) -> Option<String> {
+    let words = |text: &str| -> Vec<String> {
+        text.split(|c: char| !c.is_alphanumeric())
+            .filter(|w| !w.is_empty())
+            .map(str::to_lowercase)
+            .collect()
+    };
+    let mut parts = vec![];
+    // Text between quotes sits at odd positions; an unclosed quote runs to the end
+    for (i, chunk) in input.split('"').enumerate() {
+        if i % 2 == 1 {
+            let phrase = words(chunk);
+            match phrase.len() {
+                0 => {}
+                1 => parts.push(phrase[0].clone()),
+                _ => parts.push(format!("({})", phrase.join(" <-> "))),
+            }
+        } else {
+            parts.extend(words(chunk).into_iter().map(|w| format!("{w}:*")));
+        }
+    }
+    if parts.is_empty() {
+        None
+    } else {
+        Some(parts.join(" & "))
+    }
+}
+
+/// Splits a headline into text segments, flagging those that were matched.
+pub fn split_headline(headline: &str) -> Vec<(String, bool)> {
+    let mut segments = vec![];
+    let mut current = String::new();
+    let mut highlighted = false;
+    for c in headline.chars() {
+        if c == HIGHLIGHT_START || c == HIGHLIGHT_STOP {
+            if !current.is_empty() {
+                segments.push((std::mem::take(&mut current), highlighted));
+            }
+            highlighted = c == HIGHLIGHT_START;
+        } else {
+            current.push(c);
+        }
+    }
+    if !current.is_empty() {
+        segments.push((current, highlighted));
+    }
+    segments
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_tsquery() {
+        assert_eq!(to_tsquery("Lep rust"), Some("lep:* & rust:*".into()));
+        assert_eq!(
+            to_tsquery(r#"async "Server Functions" in"#),
+            Some("async:* & (server <-> functions) & in:*".into())
+        );
+        assert_eq!(to_tsquery("Việt & !'nam"), Some("việt:* & nam:*".into()));
+        assert_eq!(to_tsquery(r#""leptos"#), Some("leptos".into()));
+        assert_eq!(to_tsquery(" :* | & "), None);
+    }
+
+    #[test]
+    fn test_split_headline() {
+        assert_eq!(
+            split_headline("a \u{2}rust\u{3} b"),
+            vec![
+                ("a ".to_string(), false),
+                ("rust".to_string(), true),
+                (" b".to_string(), false)
+            ]
+        );
+    }
+}
@@ -1,7 +1,14 @@
-use crate::common::cache::cache_get_or_compute;
+use crate::common::aggregate::{Aggregate, AggregateGroup};
+use crate::common::audit::{AuditEntry, AuditOperation};
+use crate::common::cache::{cache_get_or_compute, CACHE};
+use crate::common::cursor::{CursorPage, CursorPosition};
 use crate::common::error::CoreError;
-use crate::common::filter::{Filter, ScalarValue};
-use crate::common::repository::{Creatable, Repository, ViewRepository};
+use crate::common::facet::{Facet, FacetField};
+use crate::common::filter::{Filter, ScalarValue, SoftDeleteScope};
+use crate::common::repository::{
+    run_in_transaction, Creatable, Repository, Transactional, ViewRepository, WithAttributes,
+};
+use crate::common::search::{SearchHit, Suggestion};
 use crate::common::sort::SortCriterion;
 use std::collections::HashMap;
 use std::future::Future;
@@ -10,7 +17,7 @@ pub type PropertyName = String;
 pub type AttributeName = String;
 
 // Common trait for business layer entities with standard fields
-pub trait Entity {
+pub trait Entity: Clone {
     fn id(&self) -> i32;
     fn uid(&self) -> &str;
     fn version(&self) -> i32;
@@ -62,11 +69,84 @@ pub trait ViewService {
         }
     }
 
+    fn get_many_with_attributes(
+        &self,
+        sort_criteria: Vec<SortCriterion>,
+        first_result: Option<i32>,
+        max_results: Option<i32>,
+        filters: Vec<Filter>,
+    ) -> impl Future<Output = Result<Vec<WithAttributes<Self::Entity>>, CoreError>> {
+        let repo = self.get_repository();
+        async move {
+            repo.find_many_with_attributes(sort_criteria, first_result, max_results, filters)
+                .await
+        }
+    }
+
+    fn search(
+        &self,
+        query: String,
+        first_result: Option<i32>,
+        max_results: Option<i32>,
+        filters: Vec<Filter>,
+    ) -> impl Future<Output = Result<Vec<SearchHit<Self::Entity>>, CoreError>> {
+        let repo = self.get_repository();
+        async move { repo.search(query, first_result, max_results, filters).await }
+    }
+
+    fn suggest(
+        &self,
+        query: String,
+        max_results: Option<i32>,
+        filters: Vec<Filter>,
+    ) -> impl Future<Output = Result<Vec<Suggestion<Self::Entity>>, CoreError>> {
+        let repo = self.get_repository();
+        async move { repo.suggest(query, max_results, filters).await }
+    }
+
+    fn get_page(
+        &self,
+        sort_criteria: Vec<SortCriterion>,
+        position: CursorPosition,
+        max_results: i32,
+        filters: Vec<Filter>,
+    ) -> impl Future<Output = Result<CursorPage<Self::Entity>, CoreError>> {
+        let repo = self.get_repository();
+        async move {
+            repo.find_page(sort_criteria, position, max_results, filters)
+                .await
+        }
+    }
+
     fn count(&self, filters: Vec<Filter>) -> impl Future<Output = Result<i64, CoreError>> {
         let repo = self.get_repository();
         async move { repo.count(filters).await }
     }
 
+    fn get_facets(
+        &self,
+        fields: Vec<FacetField>,
+        filters: Vec<Filter>,
+    ) -> impl Future<Output = Result<Vec<Facet>, CoreError>> {
+        let repo = self.get_repository();
+        async move { repo.facets(fields, filters).await }
+    }
+
+    fn aggregate(
+        &self,
+        group_by: Vec<String>,
+        aggregates: Vec<Aggregate>,
+        first_result: Option<i32>,
+        max_results: Option<i32>,
+        filters: Vec<Filter>,
+    ) -> impl Future<Output = Result<Vec<AggregateGroup>, CoreError>> {
+        let repo = self.get_repository();
+        async move {
+            repo.aggregate(group_by, aggregates, first_result, max_results, filters)
+                .await
+        }
+    }
+
     fn get_by_id(&self, id: i32) -> impl Future<Output = Result<Option<Self::Entity>, CoreError>> {
         let repo = self.get_repository();
         async move { repo.find_by_id(id).await }
@@ -82,7 +162,7 @@ pub trait ViewService {
 }
 
 pub trait Service: ViewService {
-    type Create: Creatable<Entity = Self::Entity>;
+    type Create: Creatable<Entity = Self::Entity> + Clone;
 
     fn get_attribute_type_map(
         &self,
@@ -100,55 +180,362 @@ pub trait Service: ViewService {
         }
     }
 
-    // Default write operations
+    /// Run several repository calls as one unit: `work` gets a copy of the
+    /// repository bound to a new transaction, plus the transaction itself so
+    /// other repositories can join it. Commits on `Ok`, rolls back on `Err`.
+    /// After a commit, caches hooked to this table are invalidated.
+    fn with_transaction<F, Fut, T>(&self, work: F) -> impl Future<Output = Result<T, CoreError>>
+    where
+        <Self as ViewService>::Repo: Transactional,
+        F: FnOnce(Self::Repo, <Self::Repo as Transactional>::Tx) -> Fut,
+        Fut: Future<Output = Result<T, CoreError>>,
+    {
+        let repo = self.get_repository();
+        async move {
+            let tx = repo.begin().await?;
+            let scoped = repo.in_transaction(&tx);
+            let result = run_in_transaction(tx, move |tx| work(scoped, tx)).await;
+            if result.is_ok() {
+                CACHE.notify_write(repo.get_table_name());
+            }
+            result
+        }
+    }
+
+    /// Business rules for a create, checked before it is written. Returns what
+    /// to write, which an override may complete or normalize. Lookups and
+    /// writes of its own go through `repo`, bound to the create's transaction.
+    fn prepare_create(
+        &self,
+        _repo: &Self::Repo,
+        entity_create: &Self::Create,
+    ) -> impl Future<Output = Result<Self::Create, CoreError>> {
+        let entity_create = entity_create.clone();
+        async move { Ok(entity_create) }
+    }
+
+    /// Counterpart of `prepare_create` for updates and upserts.
+    fn prepare_update(
+        &self,
+        _repo: &Self::Repo,
+        entity: &Self::Entity,
+    ) -> impl Future<Output = Result<Self::Entity, CoreError>>
+    where
+        Self::Entity: Entity,
+    {
+        let entity = entity.clone();
+        async move { Ok(entity) }
+    }
+
+    // Default write operations. Each one runs in a transaction together with
+    // the audit log entries describing it, after the entities pass `prepare_*`.
     fn create(
         &self,
         entity_create: &Self::Create,
     ) -> impl Future<Output = Result<Self::Entity, CoreError>>
     where
-        <Self as ViewService>::Repo: Repository<Self::Entity, Self::Create>,
+        <Self as ViewService>::Repo: Repository<Self::Entity, Self::Create> + Transactional,
+        Self::Entity: Entity,
     {
-        let repo = self.get_repository();
-        async move { repo.create(entity_create).await }
+        self.with_transaction(move |repo, _| async move {
+            let entity_create = self.prepare_create(&repo, entity_create).await?;
+            let created = repo.create(&entity_create).await?;
+            let after = repo
+                .snapshot_by_ids(vec![created.id()], SoftDeleteScope::Include)
+                .await?;
+            repo.record_audit(AuditEntry::from_snapshots(AuditOperation::Create, vec![], after))
+                .await?;
+            Ok(created)
+        })
     }
 
     fn update(&self, entity: &Self::Entity) -> impl Future<Output = Result<Self::Entity, CoreError>>
     where
-        <Self as ViewService>::Repo: Repository<Self::Entity, Self::Create>,
+        <Self as ViewService>::Repo: Repository<Self::Entity, Self::Create> + Transactional,
+        Self::Entity: Entity,
     {
-        let repo = self.get_repository();
-        async move { repo.update(entity).await }
+        self.with_transaction(move |repo, _| async move {
+            let entity = self.prepare_update(&repo, entity).await?;
+            let before = repo
+                .snapshot_by_ids(vec![entity.id()], SoftDeleteScope::Include)
+                .await?;
+            let updated = repo.update(&entity).await?;
+            let after = repo
+                .snapshot_by_ids(vec![updated.id()], SoftDeleteScope::Include)
+                .await?;
+            repo.record_audit(AuditEntry::from_snapshots(AuditOperation::Update, before, after))
+                .await?;
+            Ok(updated)
+        })
+    }
+
+    // Batch writes report one result per item, in input order. The batch is
+    // first written in one transaction; if that fails, each item is written in
+    // its own, so a bad item only fails itself.
+    fn create_many(
+        &self,
+        creates: &[Self::Create],
+    ) -> impl Future<Output = Vec<Result<Self::Entity, CoreError>>>
+    where
+        <Self as ViewService>::Repo: Repository<Self::Entity, Self::Create> + Transactional,
+        Self::Entity: Entity,
+    {
+        async move {
+            let batch = self
+                .with_transaction(move |repo, _| async move {
+                    let creates = prepare_each(creates, |c| self.prepare_create(&repo, c)).await?;
+                    let created = repo.create_many(&creates).await?;
+                    let after = repo
+                        .snapshot_by_ids(ids_of(&created), SoftDeleteScope::Include)
+                        .await?;
+                    repo.record_audit(AuditEntry::from_snapshots(
+                        AuditOperation::Create,
+                        vec![],
+                        after,
+                    ))
+                    .await?;
+                    Ok(created)
+                })
+                .await;
+            match batch {
+                Ok(created) => created.into_iter().map(Ok).collect(),
+                Err(e) if creates.len() <= 1 => vec![Err(e)],
+                Err(_) => {
+                    let mut results = Vec::with_capacity(creates.len());
+                    for create in creates {
+                        results.push(self.create(create).await);
+                    }
+                    results
+                }
+            }
+        }
+    }
+
+    fn update_many(
+        &self,
+        entities: &[Self::Entity],
+    ) -> impl Future<Output = Vec<Result<Self::Entity, CoreError>>>
+    where
+        <Self as ViewService>::Repo: Repository<Self::Entity, Self::Create> + Transactional,
+        Self::Entity: Entity,
+    {
+        async move {
+            let batch = self
+                .with_transaction(move |repo, _| async move {
+                    let entities = prepare_each(entities, |e| self.prepare_update(&repo, e)).await?;
+                    let before = repo
+                        .snapshot_by_ids(ids_of(&entities), SoftDeleteScope::Include)
+                        .await?;
+                    let updated = repo.update_many(&entities).await?;
+                    let after = repo
+                        .snapshot_by_ids(ids_of(&updated), SoftDeleteScope::Include)
+                        .await?;
+                    repo.record_audit(AuditEntry::from_snapshots(
+                        AuditOperation::Update,
+                        before,
+                        after,
+                    ))
+                    .await?;
+                    Ok(updated)
+                })
+                .await;
+            match batch {
+                Ok(updated) => updated.into_iter().map(Ok).collect(),
+                Err(e) if entities.len() <= 1 => vec![Err(e)],
+                Err(_) => {
+                    let mut results = Vec::with_capacity(entities.len());
+                    for entity in entities {
+                        results.push(self.update(entity).await);
+                    }
+                    results
+                }
+            }
+        }
+    }
+
+    /// Creates each entity, or updates the row already holding the same values
+    /// in the unique `key` columns. Audited as a create or an update to match.
+    fn upsert_by(
+        &self,
+        key: &[&str],
+        entities: &[Self::Entity],
+    ) -> impl Future<Output = Vec<Result<Self::Entity, CoreError>>>
+    where
+        <Self as ViewService>::Repo: Repository<Self::Entity, Self::Create> + Transactional,
+        Self::Entity: Entity,
+    {
+        async move {
+            match upsert_audited(self, key, entities).await {
+                Ok(upserted) => upserted.into_iter().map(Ok).collect(),
+                Err(e) if entities.len() <= 1 => vec![Err(e)],
+                Err(_) => {
+                    let mut results = Vec::with_capacity(entities.len());
+                    for entity in entities {
+                        results.push(
+                            upsert_audited(self, key, std::slice::from_ref(entity))
+                                .await
+                                .map(|mut upserted| upserted.remove(0)),
+                        );
+                    }
+                    results
+                }
+            }
+        }
     }
 
     fn delete_by_id(&self, id: i32) -> impl Future<Output = Result<u64, CoreError>>
     where
-        <Self as ViewService>::Repo: Repository<Self::Entity, Self::Create>,
+        <Self as ViewService>::Repo: Repository<Self::Entity, Self::Create> + Transactional,
     {
-        let repo = self.get_repository();
-        async move { repo.delete_by_id(id).await }
+        self.delete_by_ids(vec![id])
     }
 
     fn delete_by_ids(&self, ids: Vec<i32>) -> impl Future<Output = Result<u64, CoreError>>
     where
-        <Self as ViewService>::Repo: Repository<Self::Entity, Self::Create>,
+        <Self as ViewService>::Repo: Repository<Self::Entity, Self::Create> + Transactional,
     {
-        let repo = self.get_repository();
-        async move { repo.delete_by_ids(ids).await }
+        self.with_transaction(move |repo, _| async move {
+            let before = repo
+                .snapshot_by_ids(ids.clone(), SoftDeleteScope::Exclude)
+                .await?;
+            let deleted = repo.delete_by_ids(ids).await?;
+            repo.record_audit(AuditEntry::from_snapshots(AuditOperation::Delete, before, vec![]))
+                .await?;
+            Ok(deleted)
+        })
     }
 
     fn delete_by_uid(&self, uid: String) -> impl Future<Output = Result<u64, CoreError>>
     where
-        <Self as ViewService>::Repo: Repository<Self::Entity, Self::Create>,
+        <Self as ViewService>::Repo: Repository<Self::Entity, Self::Create> + Transactional,
     {
-        let repo = self.get_repository();
-        async move { repo.delete_by_uid(uid).await }
+        self.delete_by_uids(vec![uid])
     }
 
     fn delete_by_uids(&self, uids: Vec<String>) -> impl Future<Output = Result<u64, CoreError>>
     where
-        <Self as ViewService>::Repo: Repository<Self::Entity, Self::Create>,
+        <Self as ViewService>::Repo: Repository<Self::Entity, Self::Create> + Transactional,
     {
-        let repo = self.get_repository();
-        async move { repo.delete_by_uids(uids).await }
+        self.with_transaction(move |repo, _| async move {
+            let before = repo
+                .snapshot_by_uids(uids.clone(), SoftDeleteScope::Exclude)
+                .await?;
+            let deleted = repo.delete_by_uids(uids).await?;
+            repo.record_audit(AuditEntry::from_snapshots(AuditOperation::Delete, before, vec![]))
+                .await?;
+            Ok(deleted)
+        })
+    }
+
+    fn restore_by_id(&self, id: i32) -> impl Future<Output = Result<u64, CoreError>>
+    where
+        <Self as ViewService>::Repo: Repository<Self::Entity, Self::Create> + Transactional,
+    {
+        self.restore_by_ids(vec![id])
+    }
+
+    fn restore_by_ids(&self, ids: Vec<i32>) -> impl Future<Output = Result<u64, CoreError>>
+    where
+        <Self as ViewService>::Repo: Repository<Self::Entity, Self::Create> + Transactional,
+    {
+        self.with_transaction(move |repo, _| async move {
+            let before = repo
+                .snapshot_by_ids(ids.clone(), SoftDeleteScope::Only)
+                .await?;
+            let restored = repo.restore_by_ids(ids).await?;
+            repo.record_audit(AuditEntry::from_snapshots(AuditOperation::Restore, before, vec![]))
+                .await?;
+            Ok(restored)
+        })
+    }
+
+    fn purge_by_id(&self, id: i32) -> impl Future<Output = Result<u64, CoreError>>
+    where
+        <Self as ViewService>::Repo: Repository<Self::Entity, Self::Create> + Transactional,
+    {
+        self.purge_by_ids(vec![id])
+    }
+
+    fn purge_by_ids(&self, ids: Vec<i32>) -> impl Future<Output = Result<u64, CoreError>>
+    where
+        <Self as ViewService>::Repo: Repository<Self::Entity, Self::Create> + Transactional,
+    {
+        self.with_transaction(move |repo, _| async move {
+            let before = repo
+                .snapshot_by_ids(ids.clone(), SoftDeleteScope::Only)
+                .await?;
+            let purged = repo.purge_by_ids(ids).await?;
+            repo.record_audit(AuditEntry::from_snapshots(AuditOperation::Purge, before, vec![]))
+                .await?;
+            Ok(purged)
+        })
     }
 }
+
+fn ids_of<T: Entity>(entities: &[T]) -> Vec<i32> {
+    entities.iter().map(Entity::id).collect()
+}
+
+/// Runs `prepare` on each item in order, stopping at the first failure.
+async fn prepare_each<'a, T, F, Fut>(items: &'a [T], prepare: F) -> Result<Vec<T>, CoreError>
+where
+    F: Fn(&'a T) -> Fut,
+    Fut: Future<Output = Result<T, CoreError>>,
+{
+    let mut prepared = Vec::with_capacity(items.len());
+    for item in items {
+        prepared.push(prepare(item).await?);
+    }
+    Ok(prepared)
+}
+
+/// One upsert transaction, auditing rows that existed as updates and the rest
+/// as creates.
+async fn upsert_audited<S>(
+    service: &S,
+    key: &[&str],
+    entities: &[S::Entity],
+) -> Result<Vec<S::Entity>, CoreError>
+where
+    S: Service + ?Sized,
+    <S as ViewService>::Repo: Repository<S::Entity, S::Create> + Transactional,
+    S::Entity: Entity,
+{
+    service
+        .with_transaction(move |repo, _| async move {
+            let entities = prepare_each(entities, |e| service.prepare_update(&repo, e)).await?;
+            upsert_with_audit(&repo, key, &entities).await
+        })
+        .await
+}
+
+/// Upserts through a repository already bound to a transaction, auditing rows
+/// that existed as updates and the rest as creates.
+pub(crate) async fn upsert_with_audit<T, C, R>(
+    repo: &R,
+    key: &[&str],
+    entities: &[T],
+) -> Result<Vec<T>, CoreError>
+where
+    T: Entity,
+    C: Creatable<Entity = T>,
+    R: Repository<T, C>,
+{
+    let before = repo
+        .snapshot_by_key(key, entities, SoftDeleteScope::Include)
+        .await?;
+    let upserted = repo.upsert_by(key, entities).await?;
+    let (updated, created): (Vec<_>, Vec<_>) = repo
+        .snapshot_by_ids(ids_of(&upserted), SoftDeleteScope::Include)
+        .await?
+        .into_iter()
+        .partition(|after| before.iter().any(|b| b.id == after.id));
+    let mut entries = AuditEntry::from_snapshots(AuditOperation::Update, before, updated);
+    entries.extend(AuditEntry::from_snapshots(
+        AuditOperation::Create,
+        vec![],
+        created,
+    ));
+    repo.record_audit(entries).await?;
+    Ok(upserted)
+}
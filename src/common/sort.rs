@@ -1,8 +1,14 @@
 use std::str::FromStr;
 
+/// Prefixes marking a sort on an attribute value (e.g. `-a:rating` or `-a.rating`)
+/// rather than a column.
+pub const ATTRIBUTE_SORT_PREFIXES: [&str; 2] = ["a:", "a."];
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct SortCriterion {
     pub field: String,
     pub ascending: bool,
+    pub is_attribute: bool,
 }
 
 impl FromStr for SortCriterion {
@@ -12,6 +18,7 @@ impl FromStr for SortCriterion {
             return Ok(SortCriterion {
                 field: "".to_string(),
                 ascending: true,
+                is_attribute: false,
             });
         }
         let (ascending, field) = match s.chars().next().unwrap() {
@@ -19,9 +26,40 @@ impl FromStr for SortCriterion {
             '-' => (false, &s[1..].trim()),
             _ => (true, &s.trim()),
         };
+        let (is_attribute, field) = match ATTRIBUTE_SORT_PREFIXES
+            .iter()
+            .find_map(|prefix| field.strip_prefix(prefix))
+        {
+            Some(attr_name) => (true, attr_name.trim()),
+            None => (false, *field),
+        };
         Ok(SortCriterion {
             field: field.to_string(),
             ascending,
+            is_attribute,
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_sort_criterion() {
+        let sort = SortCriterion::from_str("-a:rating").unwrap();
+        assert_eq!(sort.field, "rating");
+        assert!(!sort.ascending);
+        assert!(sort.is_attribute);
+
+        let sort = SortCriterion::from_str("a.reading_time").unwrap();
+        assert_eq!(sort.field, "reading_time");
+        assert!(sort.ascending);
+        assert!(sort.is_attribute);
+
+        let sort = SortCriterion::from_str("+title").unwrap();
+        assert_eq!(sort.field, "title");
+        assert!(sort.ascending);
+        assert!(!sort.is_attribute);
+    }
+}
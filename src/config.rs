@@ -3,3 +3,77 @@ pub fn init_env() {
     dotenvy::dotenv().ok();
     env_logger::init();
 }
+
+/// What the server binary should do after connecting to the database.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StartupMode {
+    /// Apply pending migrations, then serve requests.
+    Serve,
+    /// `--migrate-only`: apply pending migrations and exit.
+    MigrateOnly,
+    /// `--dry-run`: list pending migrations without applying them and exit.
+    DryRun,
+    /// `--rollback-to <version>`: revert migrations newer than `version` and exit.
+    RollbackTo(i64),
+}
+
+/// Printed when the command line arguments make no sense.
+pub const USAGE: &str = "usage: lazycoder_leptos [--migrate-only | --dry-run | --rollback-to <version>]";
+
+impl StartupMode {
+    /// The mode the arguments ask for, or what is wrong with them.
+    pub fn from_args<I: IntoIterator<Item = String>>(args: I) -> Result<Self, String> {
+        let mut mode = None;
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let next = match arg.as_str() {
+                "--migrate-only" => StartupMode::MigrateOnly,
+                "--dry-run" => StartupMode::DryRun,
+                "--rollback-to" => {
+                    let Some(version) = args.next().and_then(|v| v.parse::<i64>().ok()) else {
+                        return Err("--rollback-to expects a migration version".to_string());
+                    };
+                    StartupMode::RollbackTo(version)
+                }
+                _ => return Err(format!("unknown argument `{arg}`")),
+            };
+            // Only one mode can run; a typo or a second flag must not pick one silently
+            if mode.replace(next).is_some() {
+                return Err(format!("`{arg}` conflicts with an earlier option"));
+            }
+        }
+        Ok(mode.unwrap_or(StartupMode::Serve))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<StartupMode, String> {
+        StartupMode::from_args(args.iter().map(|a| a.to_string()))
+    }
+
+    #[test]
+    fn test_startup_mode_from_args() {
+        assert_eq!(parse(&[]), Ok(StartupMode::Serve));
+        assert_eq!(parse(&["--dry-run"]), Ok(StartupMode::DryRun));
+        assert_eq!(parse(&["--rollback-to", "7"]), Ok(StartupMode::RollbackTo(7)));
+        assert!(parse(&["--rollback-to"]).is_err());
+        assert!(parse(&["--rollback-to", "latest"]).is_err());
+    }
+
+    #[test]
+    fn test_startup_mode_rejects_unknown_arguments() {
+        assert!(parse(&["--dryrun"]).is_err());
+        assert!(parse(&["--dry_run"]).is_err());
+        assert!(parse(&["serve"]).is_err());
+    }
+
+    #[test]
+    fn test_startup_mode_rejects_conflicting_arguments() {
+        assert!(parse(&["--rollback-to", "3", "--migrate-only"]).is_err());
+        assert!(parse(&["--dry-run", "--migrate-only"]).is_err());
+        assert!(parse(&["--dry-run", "--dry-run"]).is_err());
+    }
+}
@@ -1,33 +1,208 @@
 #![cfg(feature = "ssr")]
 
-use sqlx::postgres::PgPoolOptions;
-use sqlx::PgPool;
+use sqlx::postgres::{PgConnectOptions, PgPoolOptions};
+use sqlx::{Executor, PgPool};
 use std::env;
+use std::str::FromStr;
+use std::time::Duration;
 
-pub async fn init_pool() -> Result<PgPool, sqlx::Error> {
-    let db_url = env::var("DATABASE_URL").expect("DATABASE_URL must be set in .env");
-    PgPoolOptions::new()
-        .max_connections(5)
-        .connect(&db_url)
-        .await
+/// Word similarity (0 to 1) a fuzzy search needs to count as a match, unless
+/// overridden by `SEARCH_SIMILARITY_THRESHOLD`. Lower than pg_trgm's 0.6 so a
+/// transposed letter ("leptso") still matches.
+const DEFAULT_SIMILARITY_THRESHOLD: f32 = 0.4;
+
+/// The primary pool, plus the replica pool reads go to when one is configured.
+#[derive(Clone)]
+pub struct DbPools {
+    pub primary: PgPool,
+    pub read: Option<PgPool>,
+}
+
+/// Settings shared by the primary and the read pool.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PoolSettings {
+    /// `DATABASE_MAX_CONNECTIONS`, 5 by default.
+    pub max_connections: u32,
+    /// `DATABASE_ACQUIRE_TIMEOUT_SECS`: how long a query waits for a free
+    /// connection, 30 by default.
+    pub acquire_timeout: Duration,
+    /// `DATABASE_IDLE_TIMEOUT_SECS`: idle connections are closed after this
+    /// long, 600 by default; `0` keeps them open.
+    pub idle_timeout: Option<Duration>,
+    /// `DATABASE_STATEMENT_TIMEOUT_MS`: Postgres cancels statements running
+    /// longer; unset or `0` for no limit.
+    pub statement_timeout: Option<Duration>,
+    /// `DATABASE_APPLICATION_NAME`, shown in `pg_stat_activity`; the read pool
+    /// adds a `-read` suffix.
+    pub application_name: String,
 }
 
-pub async fn run_migrations(pool: &PgPool) -> Result<(), sqlx::Error> {
-    let schema_sql = include_str!("migrations/create_tables.sql");
-    run_statements_from_str(pool, schema_sql).await
+impl Default for PoolSettings {
+    fn default() -> Self {
+        PoolSettings {
+            max_connections: 5,
+            acquire_timeout: Duration::from_secs(30),
+            idle_timeout: Some(Duration::from_secs(600)),
+            statement_timeout: None,
+            application_name: "lazycoder".to_string(),
+        }
+    }
 }
 
-pub async fn run_view_migrations(pool: &PgPool) -> Result<(), sqlx::Error> {
-    let view_sql = include_str!("migrations/create_views.sql");
-    run_statements_from_str(pool, view_sql).await
+/// Database settings, read from the environment by `DbConfig::from_env`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DbConfig {
+    /// `DATABASE_URL`: the primary, which takes every write.
+    pub url: String,
+    /// `DATABASE_READ_URL`: optional replica for reads outside transactions.
+    pub read_url: Option<String>,
+    pub pool: PoolSettings,
+    /// `DATABASE_READ_YOUR_WRITES_SECS`: after a write, the client's reads
+    /// stay on the primary this long so replication lag can't hide the write.
+    /// 5 by default.
+    pub read_your_writes: Duration,
 }
 
-async fn run_statements_from_str(pool: &PgPool, sql: &str) -> Result<(), sqlx::Error> {
-    for statement in sql.split(";") {
-        let stmt = statement.trim();
-        if !stmt.is_empty() {
-            sqlx::query(stmt).execute(pool).await?;
+impl DbConfig {
+    pub fn from_env() -> Self {
+        Self::from_lookup(|name| env::var(name).ok())
+    }
+
+    /// Reads the settings through `lookup`; missing or unparsable values keep
+    /// their defaults.
+    pub fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Self {
+        let number = |name: &str| lookup(name).and_then(|v| v.trim().parse::<u64>().ok());
+        let defaults = PoolSettings::default();
+        DbConfig {
+            url: lookup("DATABASE_URL").expect("DATABASE_URL must be set in .env"),
+            read_url: lookup("DATABASE_READ_URL").filter(|v| !v.trim().is_empty()),
+            pool: PoolSettings {
+                max_connections: number("DATABASE_MAX_CONNECTIONS")
+                    .filter(|n| *n > 0)
+                    .map_or(defaults.max_connections, |n| n as u32),
+                acquire_timeout: number("DATABASE_ACQUIRE_TIMEOUT_SECS")
+                    .map_or(defaults.acquire_timeout, Duration::from_secs),
+                idle_timeout: match number("DATABASE_IDLE_TIMEOUT_SECS") {
+                    Some(0) => None,
+                    Some(secs) => Some(Duration::from_secs(secs)),
+                    None => defaults.idle_timeout,
+                },
+                statement_timeout: number("DATABASE_STATEMENT_TIMEOUT_MS")
+                    .filter(|ms| *ms > 0)
+                    .map(Duration::from_millis),
+                application_name: lookup("DATABASE_APPLICATION_NAME")
+                    .filter(|v| !v.trim().is_empty())
+                    .unwrap_or(defaults.application_name),
+            },
+            read_your_writes: Duration::from_secs(
+                number("DATABASE_READ_YOUR_WRITES_SECS").unwrap_or(5),
+            ),
         }
     }
-    Ok(())
+}
+
+pub async fn init_pools(config: &DbConfig) -> Result<DbPools, sqlx::Error> {
+    let primary = connect_with(&config.url, &config.pool).await?;
+    let read = match &config.read_url {
+        Some(read_url) => {
+            let settings = PoolSettings {
+                application_name: format!("{}-read", config.pool.application_name),
+                ..config.pool.clone()
+            };
+            Some(connect_with(read_url, &settings).await?)
+        }
+        None => None,
+    };
+    Ok(DbPools { primary, read })
+}
+
+/// Pool for `db_url` with the default settings.
+pub async fn connect(db_url: &str) -> Result<PgPool, sqlx::Error> {
+    connect_with(db_url, &PoolSettings::default()).await
+}
+
+/// Pool for `db_url` with every connection set up the way queries expect.
+pub async fn connect_with(db_url: &str, settings: &PoolSettings) -> Result<PgPool, sqlx::Error> {
+    let similarity_threshold = similarity_threshold();
+    let mut options =
+        PgConnectOptions::from_str(db_url)?.application_name(&settings.application_name);
+    if let Some(timeout) = settings.statement_timeout {
+        options = options.options([("statement_timeout", timeout.as_millis())]);
+    }
+    PgPoolOptions::new()
+        .max_connections(settings.max_connections)
+        .acquire_timeout(settings.acquire_timeout)
+        .idle_timeout(settings.idle_timeout)
+        .after_connect(move |conn, _meta| {
+            Box::pin(async move {
+                conn.execute(
+                    format!("SET pg_trgm.word_similarity_threshold = {similarity_threshold}")
+                        .as_str(),
+                )
+                .await?;
+                Ok(())
+            })
+        })
+        .connect_with(options)
+        .await
+}
+
+pub(crate) fn similarity_threshold() -> f32 {
+    env::var("SEARCH_SIMILARITY_THRESHOLD")
+        .ok()
+        .and_then(|v| v.parse::<f32>().ok())
+        .filter(|v| (0.0..=1.0).contains(v))
+        .unwrap_or(DEFAULT_SIMILARITY_THRESHOLD)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn config(vars: &[(&str, &str)]) -> DbConfig {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        DbConfig::from_lookup(|name| vars.get(name).cloned())
+    }
+
+    #[test]
+    fn test_config_defaults_and_overrides() {
+        let defaults = config(&[("DATABASE_URL", "postgres://primary")]);
+        assert_eq!(defaults.read_url, None);
+        assert_eq!(defaults.pool, PoolSettings::default());
+        assert_eq!(defaults.read_your_writes, Duration::from_secs(5));
+
+        let configured = config(&[
+            ("DATABASE_URL", "postgres://primary"),
+            ("DATABASE_READ_URL", "postgres://replica"),
+            ("DATABASE_MAX_CONNECTIONS", "20"),
+            ("DATABASE_ACQUIRE_TIMEOUT_SECS", "3"),
+            ("DATABASE_IDLE_TIMEOUT_SECS", "0"),
+            ("DATABASE_STATEMENT_TIMEOUT_MS", "2500"),
+            ("DATABASE_APPLICATION_NAME", "blog"),
+            ("DATABASE_READ_YOUR_WRITES_SECS", "10"),
+        ]);
+        assert_eq!(configured.read_url.as_deref(), Some("postgres://replica"));
+        assert_eq!(
+            configured.pool,
+            PoolSettings {
+                max_connections: 20,
+                acquire_timeout: Duration::from_secs(3),
+                idle_timeout: None,
+                statement_timeout: Some(Duration::from_millis(2500)),
+                application_name: "blog".to_string(),
+            }
+        );
+        assert_eq!(configured.read_your_writes, Duration::from_secs(10));
+
+        let invalid = config(&[
+            ("DATABASE_URL", "postgres://primary"),
+            ("DATABASE_MAX_CONNECTIONS", "0"),
+            ("DATABASE_STATEMENT_TIMEOUT_MS", "soon"),
+        ]);
+        assert_eq!(invalid.pool, PoolSettings::default());
+    }
 }
@@ -7,13 +7,19 @@ use crate::infras::sqlx_repository::{
     SqlxEntityMapper, SqlxRepository, SqlxViewMeta, SqlxViewRepository,
 };
 use crate::{define_orm_with_common_fields, define_readonly_orm_with_common_fields};
-use sqlx::PgPool;
+use crate::infras::sqlx_transaction::SqlxExecutor;
 use uuid::Uuid;
 
 define_orm_with_common_fields!(Attribute {
     pub name: String,
     pub entity_type: String,
     pub data_type: String,
+    pub required: bool,
+    pub min_value: Option<f64>,
+    pub max_value: Option<f64>,
+    pub pattern: Option<String>,
+    pub allowed_values: Option<String>,
+    pub default_value: Option<String>,
 });
 
 define_readonly_orm_with_common_fields!(AttributeInfo {
@@ -32,6 +38,15 @@ impl From<AttributeOrm> for Attribute {
             name: orm.name,
             entity_type: orm.entity_type,
             data_type: orm.data_type,
+            required: orm.required,
+            min_value: orm.min_value,
+            max_value: orm.max_value,
+            pattern: orm.pattern,
+            allowed_values: orm
+                .allowed_values
+                .and_then(|raw| serde_json::from_str(&raw).ok())
+                .unwrap_or_default(),
+            default_value: orm.default_value,
         }
     }
 }
@@ -52,11 +67,11 @@ impl From<AttributeInfoOrm> for AttributeInfo {
 
 #[derive(Clone)]
 pub struct AttributeSqlxRepository {
-    pool: PgPool,
+    executor: SqlxExecutor,
 }
 #[derive(Clone)]
 pub struct AttributeInfoSqlxRepository {
-    pool: PgPool,
+    executor: SqlxExecutor,
 }
 impl AttributeOrm {
     pub fn searchable_columns() -> Vec<&'static str> {
@@ -65,8 +80,10 @@ impl AttributeOrm {
 }
 
 impl AttributeSqlxRepository {
-    pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+    pub fn new(executor: impl Into<SqlxExecutor>) -> Self {
+        Self {
+            executor: executor.into(),
+        }
     }
 }
 
@@ -85,20 +102,19 @@ impl SqlxViewMeta for AttributeSqlxRepository {
 impl SqlxViewRepository for AttributeSqlxRepository {
     type Entity = Attribute;
     type Orm = AttributeOrm;
-    fn get_pool(&self) -> &PgPool {
-        &self.pool
+    fn get_executor(&self) -> &SqlxExecutor {
+        &self.executor
+    }
+    fn with_executor(&self, executor: SqlxExecutor) -> Self {
+        Self { executor }
     }
     fn from_orm(orm: Self::Orm) -> Self::Entity {
         Attribute::from(orm)
     }
 }
 
-impl SqlxEntityMapper for AttributeSqlxRepository {
-    type Entity = Attribute;
-    type EntityCreate = AttributeCreate;
-    type Orm = AttributeOrm;
-
-    fn to_orm_from_create(&self, create: &Self::EntityCreate) -> Self::Orm {
+impl From<&AttributeCreate> for AttributeOrm {
+    fn from(create: &AttributeCreate) -> Self {
         let now = time::OffsetDateTime::now_utc();
         AttributeOrm {
             id: 0,
@@ -109,10 +125,18 @@ impl SqlxEntityMapper for AttributeSqlxRepository {
             name: create.name.clone(),
             entity_type: create.entity_type.clone(),
             data_type: create.data_type.clone(),
+            required: false,
+            min_value: None,
+            max_value: None,
+            pattern: None,
+            allowed_values: None,
+            default_value: None,
         }
     }
+}
 
-    fn to_orm_from_entity(&self, entity: &Self::Entity) -> Self::Orm {
+impl From<&Attribute> for AttributeOrm {
+    fn from(entity: &Attribute) -> Self {
         AttributeOrm {
             id: entity.id,
             uid: Uuid::parse_str(&entity.uid).unwrap_or_else(|_| Uuid::nil()),
@@ -122,10 +146,31 @@ impl SqlxEntityMapper for AttributeSqlxRepository {
             name: entity.name.clone(),
             entity_type: entity.entity_type.clone(),
             data_type: entity.data_type.clone(),
+            required: entity.required,
+            min_value: entity.min_value,
+            max_value: entity.max_value,
+            pattern: entity.pattern.clone(),
+            allowed_values: (!entity.allowed_values.is_empty())
+                .then(|| serde_json::to_string(&entity.allowed_values).unwrap_or_default()),
+            default_value: entity.default_value.clone(),
         }
     }
 }
 
+impl SqlxEntityMapper for AttributeSqlxRepository {
+    type Entity = Attribute;
+    type EntityCreate = AttributeCreate;
+    type Orm = AttributeOrm;
+
+    fn to_orm_from_create(&self, create: &Self::EntityCreate) -> Self::Orm {
+        AttributeOrm::from(create)
+    }
+
+    fn to_orm_from_entity(&self, entity: &Self::Entity) -> Self::Orm {
+        AttributeOrm::from(entity)
+    }
+}
+
 impl SqlxRepository for AttributeSqlxRepository {
     type EntityCreate = AttributeCreate;
 }
@@ -139,8 +184,10 @@ impl AttributeInfoOrm {
 }
 
 impl AttributeInfoSqlxRepository {
-    pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+    pub fn new(executor: impl Into<SqlxExecutor>) -> Self {
+        Self {
+            executor: executor.into(),
+        }
     }
 }
 
@@ -159,8 +206,11 @@ impl SqlxViewMeta for AttributeInfoSqlxRepository {
 impl SqlxViewRepository for AttributeInfoSqlxRepository {
     type Entity = AttributeInfo;
     type Orm = AttributeInfoOrm;
-    fn get_pool(&self) -> &PgPool {
-        &self.pool
+    fn get_executor(&self) -> &SqlxExecutor {
+        &self.executor
+    }
+    fn with_executor(&self, executor: SqlxExecutor) -> Self {
+        Self { executor }
     }
     fn from_orm(orm: Self::Orm) -> Self::Entity {
         AttributeInfo::from(orm)
@@ -8,7 +8,7 @@ use crate::infras::sqlx_repository::{
     SqlxEntityMapper, SqlxRepository, SqlxViewMeta, SqlxViewRepository,
 };
 use crate::{define_orm_with_common_fields, define_readonly_orm_with_common_fields};
-use sqlx::PgPool;
+use crate::infras::sqlx_transaction::SqlxExecutor;
 use uuid::Uuid;
 
 define_orm_with_common_fields!(AttributeValue {
@@ -87,12 +87,12 @@ impl From<AttributeValueInfoOrm> for AttributeValueInfo {
 
 #[derive(Clone)]
 pub struct AttributeValueSqlxRepository {
-    pool: PgPool,
+    executor: SqlxExecutor,
 }
 
 #[derive(Clone)]
 pub struct AttributeValueInfoSqlxRepository {
-    pool: PgPool,
+    executor: SqlxExecutor,
 }
 impl AttributeValueOrm {
     pub fn searchable_columns() -> Vec<&'static str> {
@@ -101,8 +101,10 @@ impl AttributeValueOrm {
 }
 
 impl AttributeValueSqlxRepository {
-    pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+    pub fn new(executor: impl Into<SqlxExecutor>) -> Self {
+        Self {
+            executor: executor.into(),
+        }
     }
 }
 
@@ -121,20 +123,19 @@ impl SqlxViewMeta for AttributeValueSqlxRepository {
 impl SqlxViewRepository for AttributeValueSqlxRepository {
     type Entity = AttributeValue;
     type Orm = AttributeValueOrm;
-    fn get_pool(&self) -> &PgPool {
-        &self.pool
+    fn get_executor(&self) -> &SqlxExecutor {
+        &self.executor
+    }
+    fn with_executor(&self, executor: SqlxExecutor) -> Self {
+        Self { executor }
     }
     fn from_orm(orm: Self::Orm) -> Self::Entity {
         AttributeValue::from(orm)
     }
 }
 
-impl SqlxEntityMapper for AttributeValueSqlxRepository {
-    type Entity = AttributeValue;
-    type EntityCreate = AttributeValueCreate;
-    type Orm = AttributeValueOrm;
-
-    fn to_orm_from_create(&self, create: &Self::EntityCreate) -> Self::Orm {
+impl From<&AttributeValueCreate> for AttributeValueOrm {
+    fn from(create: &AttributeValueCreate) -> Self {
         let now = time::OffsetDateTime::now_utc();
         AttributeValueOrm {
             id: 0,
@@ -142,20 +143,22 @@ impl SqlxEntityMapper for AttributeValueSqlxRepository {
             version: 0,
             created_at: now,
             updated_at: now,
-            int_value: None,
-            double_value: None,
-            string_value: None,
-            boolean_value: None,
-            date_value: None,
-            datetime_value: None,
-            time_value: None,
+            int_value: create.int_value,
+            double_value: create.double_value,
+            string_value: create.string_value.clone(),
+            boolean_value: create.boolean_value,
+            date_value: create.date_value,
+            datetime_value: create.datetime_value,
+            time_value: create.time_value,
             attribute_id: create.attribute_id,
             entity_id: create.entity_id,
             entity_type: create.entity_type.clone(),
         }
     }
+}
 
-    fn to_orm_from_entity(&self, entity: &Self::Entity) -> Self::Orm {
+impl From<&AttributeValue> for AttributeValueOrm {
+    fn from(entity: &AttributeValue) -> Self {
         AttributeValueOrm {
             id: entity.id,
             uid: Uuid::parse_str(&entity.uid).unwrap_or_else(|_| Uuid::nil()),
@@ -176,6 +179,20 @@ impl SqlxEntityMapper for AttributeValueSqlxRepository {
     }
 }
 
+impl SqlxEntityMapper for AttributeValueSqlxRepository {
+    type Entity = AttributeValue;
+    type EntityCreate = AttributeValueCreate;
+    type Orm = AttributeValueOrm;
+
+    fn to_orm_from_create(&self, create: &Self::EntityCreate) -> Self::Orm {
+        AttributeValueOrm::from(create)
+    }
+
+    fn to_orm_from_entity(&self, entity: &Self::Entity) -> Self::Orm {
+        AttributeValueOrm::from(entity)
+    }
+}
+
 impl SqlxRepository for AttributeValueSqlxRepository {
     type EntityCreate = AttributeValueCreate;
 }
@@ -195,8 +212,10 @@ impl AttributeValueInfoOrm {
 }
 
 impl AttributeValueInfoSqlxRepository {
-    pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+    pub fn new(executor: impl Into<SqlxExecutor>) -> Self {
+        Self {
+            executor: executor.into(),
+        }
     }
 }
 
@@ -215,8 +234,11 @@ impl SqlxViewMeta for AttributeValueInfoSqlxRepository {
 impl SqlxViewRepository for AttributeValueInfoSqlxRepository {
     type Entity = AttributeValueInfo;
     type Orm = AttributeValueInfoOrm;
-    fn get_pool(&self) -> &PgPool {
-        &self.pool
+    fn get_executor(&self) -> &SqlxExecutor {
+        &self.executor
+    }
+    fn with_executor(&self, executor: SqlxExecutor) -> Self {
+        Self { executor }
     }
     fn from_orm(orm: Self::Orm) -> Self::Entity {
         AttributeValueInfo::from(orm)
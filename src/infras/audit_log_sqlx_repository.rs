@@ -0,0 +1,87 @@
+#![cfg(feature = "ssr")]
+
+use crate::business::audit_log_service::{AuditLogInfo, AuditLogInfoRepository};
+use crate::define_readonly_orm_with_common_fields;
+use crate::infras::sqlx_repository::{SqlxViewMeta, SqlxViewRepository};
+use crate::infras::sqlx_transaction::SqlxExecutor;
+
+// View: audit_log_info
+define_readonly_orm_with_common_fields!(AuditLogInfo {
+    pub entity_type: String,
+    pub entity_id: i32,
+    pub entity_uid: uuid::Uuid,
+    pub operation: String,
+    pub user_id: Option<i32>,
+    pub username: Option<String>,
+    pub changes: String,
+});
+
+impl AuditLogInfoOrm {
+    pub fn searchable_columns() -> Vec<&'static str> {
+        vec!["entity_type", "operation", "username"]
+    }
+}
+
+impl From<AuditLogInfoOrm> for AuditLogInfo {
+    fn from(orm: AuditLogInfoOrm) -> Self {
+        Self {
+            id: orm.id,
+            uid: orm.uid.to_string(),
+            version: orm.version,
+            created_at: orm.created_at,
+            updated_at: orm.updated_at,
+            entity_type: orm.entity_type,
+            entity_id: orm.entity_id,
+            entity_uid: orm.entity_uid.to_string(),
+            operation: orm.operation,
+            user_id: orm.user_id,
+            username: orm.username,
+            changes: orm.changes,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct AuditLogInfoSqlxRepository {
+    executor: SqlxExecutor,
+}
+
+impl AuditLogInfoSqlxRepository {
+    pub fn new(executor: impl Into<SqlxExecutor>) -> Self {
+        Self {
+            executor: executor.into(),
+        }
+    }
+}
+
+impl SqlxViewMeta for AuditLogInfoSqlxRepository {
+    fn get_table_name(&self) -> &str {
+        "audit_log_info"
+    }
+    fn get_columns(&self) -> Vec<&str> {
+        AuditLogInfoOrm::columns()
+    }
+    fn get_searchable_columns(&self) -> Vec<&str> {
+        AuditLogInfoOrm::searchable_columns()
+    }
+    // The log is append-only: nothing in it is ever trashed
+    fn get_deleted_at_column(&self) -> Option<&str> {
+        None
+    }
+}
+
+impl SqlxViewRepository for AuditLogInfoSqlxRepository {
+    type Entity = AuditLogInfo;
+    type Orm = AuditLogInfoOrm;
+    fn get_executor(&self) -> &SqlxExecutor {
+        &self.executor
+    }
+    fn with_executor(&self, executor: SqlxExecutor) -> Self {
+        Self { executor }
+    }
+    fn from_orm(orm: Self::Orm) -> Self::Entity {
+        AuditLogInfo::from(orm)
+    }
+}
+
+impl AuditLogInfoRepository for AuditLogInfoSqlxRepository {}
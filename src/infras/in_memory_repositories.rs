@@ -0,0 +1,666 @@
+#![cfg(feature = "ssr")]
+
+use crate::business::attribute_service::{
+    Attribute, AttributeCreate, AttributeInfo, AttributeInfoRepository, AttributeRepository,
+};
+use crate::business::attribute_value_service::{
+    AttributeValue, AttributeValueCreate, AttributeValueInfo, AttributeValueInfoRepository,
+    AttributeValueRepository,
+};
+use crate::business::audit_log_service::{AuditLogInfo, AuditLogInfoRepository};
+use crate::business::post_collection_service::{
+    PostCollection, PostCollectionCreate, PostCollectionInfo, PostCollectionInfoRepository,
+    PostCollectionRepository, POST_COLLECTIONS,
+};
+use crate::business::post_revision_service::{
+    PostRevision, PostRevisionCreate, PostRevisionInfo, PostRevisionInfoRepository,
+    PostRevisionRepository, RevisionKind,
+};
+use crate::business::post_service::{
+    Post, PostCreate, PostInfo, PostInfoRepository, PostRepository, PostStatus, PostTransition,
+    PostTransitionCreate, POSTS,
+};
+use crate::business::post_taxonomy_service::{
+    PostTaxonomy, PostTaxonomyCreate, PostTaxonomyInfo, PostTaxonomyInfoRepository,
+    PostTaxonomyRepository,
+};
+use crate::business::post_term_service::{PostTermRepository, POST_TERMS};
+use crate::business::post_type_service::{
+    PostType, PostTypeCreate, PostTypeInfo, PostTypeInfoRepository, PostTypeRepository,
+};
+use crate::business::slug_redirect_service::{
+    public_path, SlugRedirect, SlugRedirectCreate, SlugRedirectRecorder, SlugRedirectRepository,
+    SLUG_REDIRECT_KEY,
+};
+use crate::business::term_service::{
+    Term, TermCreate, TermInfo, TermInfoRepository, TermRepository, TERMS,
+};
+use crate::business::user_service::{
+    User, UserCreate, UserInfo, UserInfoRepository, UserRepository,
+};
+use crate::common::error::CoreError;
+use crate::common::filter::{Filter, FilterOperator, FilterValue, ScalarValue};
+use crate::common::repository::{Repository, ViewRepository};
+use crate::common::sort::SortCriterion;
+use crate::infras::attribute_sqlx_repository::{AttributeInfoOrm, AttributeOrm};
+use crate::infras::attribute_value_sqlx_repository::{AttributeValueInfoOrm, AttributeValueOrm};
+use crate::infras::audit_log_sqlx_repository::AuditLogInfoOrm;
+use crate::infras::in_memory_repository::{
+    InMemoryJoin, InMemoryRepository, InMemorySource, InMemoryViewMeta, Row, WEIGHT_A, WEIGHT_B,
+    WEIGHT_C,
+};
+use crate::infras::post_collection_sqlx_repository::{PostCollectionInfoOrm, PostCollectionOrm};
+use crate::infras::post_revision_sqlx_repository::{PostRevisionInfoOrm, PostRevisionOrm};
+use crate::infras::post_sqlx_repository::{
+    PostInfoOrm, PostOrm, PostTransitionOrm, POST_INSERT_ONLY_COLUMNS,
+};
+use crate::infras::post_taxonomy_sqlx_repository::{PostTaxonomyInfoOrm, PostTaxonomyOrm};
+use crate::infras::post_type_sqlx_repository::{PostTypeInfoOrm, PostTypeOrm};
+use crate::infras::slug_redirect_sqlx_repository::SlugRedirectOrm;
+use crate::infras::term_sqlx_repository::{TermInfoOrm, TermOrm};
+use crate::infras::user_sqlx_repository::{UserInfoOrm, UserOrm};
+
+pub type AttributeInMemoryRepository = InMemoryRepository<Attribute, AttributeOrm, AttributeCreate>;
+pub type AttributeInfoInMemoryRepository = InMemoryRepository<AttributeInfo, AttributeInfoOrm>;
+pub type AttributeValueInMemoryRepository =
+    InMemoryRepository<AttributeValue, AttributeValueOrm, AttributeValueCreate>;
+pub type AttributeValueInfoInMemoryRepository =
+    InMemoryRepository<AttributeValueInfo, AttributeValueInfoOrm>;
+pub type AuditLogInfoInMemoryRepository = InMemoryRepository<AuditLogInfo, AuditLogInfoOrm>;
+pub type PostCollectionInMemoryRepository =
+    InMemoryRepository<PostCollection, PostCollectionOrm, PostCollectionCreate>;
+pub type PostCollectionInfoInMemoryRepository =
+    InMemoryRepository<PostCollectionInfo, PostCollectionInfoOrm>;
+pub type PostRevisionInMemoryRepository =
+    InMemoryRepository<PostRevision, PostRevisionOrm, PostRevisionCreate>;
+pub type PostRevisionInfoInMemoryRepository =
+    InMemoryRepository<PostRevisionInfo, PostRevisionInfoOrm>;
+pub type PostInMemoryRepository = InMemoryRepository<Post, PostOrm, PostCreate>;
+pub type PostInfoInMemoryRepository = InMemoryRepository<PostInfo, PostInfoOrm>;
+pub type PostTransitionInMemoryRepository =
+    InMemoryRepository<PostTransition, PostTransitionOrm, PostTransitionCreate>;
+pub type PostTaxonomyInMemoryRepository =
+    InMemoryRepository<PostTaxonomy, PostTaxonomyOrm, PostTaxonomyCreate>;
+pub type PostTaxonomyInfoInMemoryRepository =
+    InMemoryRepository<PostTaxonomyInfo, PostTaxonomyInfoOrm>;
+pub type PostTypeInMemoryRepository = InMemoryRepository<PostType, PostTypeOrm, PostTypeCreate>;
+pub type PostTypeInfoInMemoryRepository = InMemoryRepository<PostTypeInfo, PostTypeInfoOrm>;
+pub type SlugRedirectInMemoryRepository =
+    InMemoryRepository<SlugRedirect, SlugRedirectOrm, SlugRedirectCreate>;
+pub type TermInMemoryRepository = InMemoryRepository<Term, TermOrm, TermCreate>;
+pub type TermInfoInMemoryRepository = InMemoryRepository<TermInfo, TermInfoOrm>;
+pub type UserInMemoryRepository = InMemoryRepository<User, UserOrm, UserCreate>;
+pub type UserInfoInMemoryRepository = InMemoryRepository<UserInfo, UserInfoOrm>;
+
+fn equals(property_name: &str, value: ScalarValue) -> Filter {
+    Filter::Property {
+        property_name: property_name.to_string(),
+        operator: FilterOperator::Equal,
+        value: FilterValue::Single(value),
+    }
+}
+
+impl InMemoryViewMeta for AttributeOrm {
+    fn get_table_name() -> &'static str {
+        "attributes"
+    }
+    fn get_searchable_columns() -> Vec<&'static str> {
+        AttributeOrm::searchable_columns()
+    }
+    fn get_unique_keys() -> Vec<Vec<&'static str>> {
+        vec![vec!["entity_type", "name"]]
+    }
+}
+
+impl InMemoryViewMeta for AttributeInfoOrm {
+    fn get_table_name() -> &'static str {
+        "attributes_info"
+    }
+    fn get_searchable_columns() -> Vec<&'static str> {
+        AttributeInfoOrm::searchable_columns()
+    }
+    fn get_source() -> InMemorySource {
+        InMemorySource::View {
+            base: "attributes",
+            joins: vec![],
+        }
+    }
+}
+
+impl InMemoryViewMeta for AttributeValueOrm {
+    fn get_table_name() -> &'static str {
+        "attribute_values"
+    }
+    fn get_searchable_columns() -> Vec<&'static str> {
+        AttributeValueOrm::searchable_columns()
+    }
+    fn get_unique_keys() -> Vec<Vec<&'static str>> {
+        vec![vec!["entity_type", "entity_id", "attribute_id"]]
+    }
+}
+
+impl InMemoryViewMeta for AttributeValueInfoOrm {
+    fn get_table_name() -> &'static str {
+        "attribute_values_info"
+    }
+    fn get_searchable_columns() -> Vec<&'static str> {
+        AttributeValueInfoOrm::searchable_columns()
+    }
+    fn get_source() -> InMemorySource {
+        InMemorySource::View {
+            base: "attribute_values",
+            joins: vec![InMemoryJoin {
+                foreign_key: "attribute_id",
+                table: "attributes",
+                columns: vec![
+                    ("name", "attribute_name"),
+                    ("entity_type", "attribute_entity_type"),
+                    ("data_type", "attribute_data_type"),
+                ],
+            }],
+        }
+    }
+}
+
+impl InMemoryViewMeta for AuditLogInfoOrm {
+    fn get_table_name() -> &'static str {
+        "audit_log_info"
+    }
+    fn get_searchable_columns() -> Vec<&'static str> {
+        AuditLogInfoOrm::searchable_columns()
+    }
+    fn get_source() -> InMemorySource {
+        InMemorySource::View {
+            base: "audit_log",
+            joins: vec![InMemoryJoin {
+                foreign_key: "user_id",
+                table: "users",
+                columns: vec![("username", "username")],
+            }],
+        }
+    }
+    fn get_deleted_at_column() -> Option<&'static str> {
+        None
+    }
+}
+
+impl InMemoryViewMeta for PostCollectionOrm {
+    fn get_table_name() -> &'static str {
+        "post_collections"
+    }
+    fn get_searchable_columns() -> Vec<&'static str> {
+        PostCollectionOrm::searchable_columns()
+    }
+    fn get_unique_keys() -> Vec<Vec<&'static str>> {
+        vec![vec!["slug"]]
+    }
+}
+
+impl InMemoryViewMeta for PostCollectionInfoOrm {
+    fn get_table_name() -> &'static str {
+        "post_collections_info"
+    }
+    fn get_searchable_columns() -> Vec<&'static str> {
+        PostCollectionInfoOrm::searchable_columns()
+    }
+    fn get_source() -> InMemorySource {
+        InMemorySource::View {
+            base: "post_collections",
+            joins: vec![],
+        }
+    }
+}
+
+impl InMemoryViewMeta for PostRevisionOrm {
+    fn get_table_name() -> &'static str {
+        "post_revisions"
+    }
+    fn get_searchable_columns() -> Vec<&'static str> {
+        PostRevisionOrm::searchable_columns()
+    }
+    fn get_deleted_at_column() -> Option<&'static str> {
+        None
+    }
+}
+
+impl InMemoryViewMeta for PostRevisionInfoOrm {
+    fn get_table_name() -> &'static str {
+        "post_revisions_info"
+    }
+    fn get_searchable_columns() -> Vec<&'static str> {
+        PostRevisionInfoOrm::searchable_columns()
+    }
+    fn get_source() -> InMemorySource {
+        InMemorySource::View {
+            base: "post_revisions",
+            joins: vec![InMemoryJoin {
+                foreign_key: "user_id",
+                table: "users",
+                columns: vec![("username", "username")],
+            }],
+        }
+    }
+    fn get_deleted_at_column() -> Option<&'static str> {
+        None
+    }
+}
+
+impl InMemoryViewMeta for PostOrm {
+    fn get_table_name() -> &'static str {
+        "posts"
+    }
+    fn get_searchable_columns() -> Vec<&'static str> {
+        PostOrm::searchable_columns()
+    }
+    fn get_search_vector_columns() -> Vec<(&'static str, f32)> {
+        vec![
+            ("title", WEIGHT_A),
+            ("summary", WEIGHT_B),
+            ("content", WEIGHT_C),
+        ]
+    }
+    fn get_headline_columns() -> Vec<&'static str> {
+        vec!["summary", "content"]
+    }
+    fn get_similarity_columns() -> Vec<&'static str> {
+        vec!["slug", "title", "summary"]
+    }
+    fn get_unique_keys() -> Vec<Vec<&'static str>> {
+        vec![vec!["type_id", "slug"]]
+    }
+    fn get_post_id_column() -> Option<&'static str> {
+        Some("id")
+    }
+    fn get_insert_only_columns() -> Vec<&'static str> {
+        POST_INSERT_ONLY_COLUMNS.to_vec()
+    }
+}
+
+impl InMemoryViewMeta for PostTransitionOrm {
+    fn get_table_name() -> &'static str {
+        "post_transitions"
+    }
+    fn get_searchable_columns() -> Vec<&'static str> {
+        PostTransitionOrm::searchable_columns()
+    }
+    fn get_deleted_at_column() -> Option<&'static str> {
+        None
+    }
+}
+
+impl InMemoryViewMeta for PostInfoOrm {
+    fn get_table_name() -> &'static str {
+        "posts_info"
+    }
+    fn get_searchable_columns() -> Vec<&'static str> {
+        PostInfoOrm::searchable_columns()
+    }
+    fn get_source() -> InMemorySource {
+        InMemorySource::View {
+            base: "posts",
+            joins: vec![InMemoryJoin {
+                foreign_key: "user_id",
+                table: "users",
+                columns: vec![("username", "username"), ("email", "email")],
+            }],
+        }
+    }
+    fn get_similarity_columns() -> Vec<&'static str> {
+        vec!["slug", "title", "summary"]
+    }
+    fn get_post_id_column() -> Option<&'static str> {
+        Some("id")
+    }
+}
+
+impl InMemoryViewMeta for PostTaxonomyOrm {
+    fn get_table_name() -> &'static str {
+        "post_taxonomies"
+    }
+    fn get_searchable_columns() -> Vec<&'static str> {
+        PostTaxonomyOrm::searchable_columns()
+    }
+    fn get_unique_keys() -> Vec<Vec<&'static str>> {
+        vec![vec!["code"]]
+    }
+}
+
+impl InMemoryViewMeta for PostTaxonomyInfoOrm {
+    fn get_table_name() -> &'static str {
+        "post_taxonomies_info"
+    }
+    fn get_searchable_columns() -> Vec<&'static str> {
+        PostTaxonomyInfoOrm::searchable_columns()
+    }
+    fn get_source() -> InMemorySource {
+        InMemorySource::View {
+            base: "post_taxonomies",
+            joins: vec![],
+        }
+    }
+}
+
+impl InMemoryViewMeta for PostTypeOrm {
+    fn get_table_name() -> &'static str {
+        "post_types"
+    }
+    fn get_searchable_columns() -> Vec<&'static str> {
+        PostTypeOrm::searchable_columns()
+    }
+    fn get_unique_keys() -> Vec<Vec<&'static str>> {
+        vec![vec!["code"]]
+    }
+}
+
+impl InMemoryViewMeta for PostTypeInfoOrm {
+    fn get_table_name() -> &'static str {
+        "post_types_info"
+    }
+    fn get_searchable_columns() -> Vec<&'static str> {
+        PostTypeInfoOrm::searchable_columns()
+    }
+    fn get_source() -> InMemorySource {
+        InMemorySource::View {
+            base: "post_types",
+            joins: vec![],
+        }
+    }
+}
+
+impl InMemoryViewMeta for SlugRedirectOrm {
+    fn get_table_name() -> &'static str {
+        "slug_redirects"
+    }
+    fn get_searchable_columns() -> Vec<&'static str> {
+        SlugRedirectOrm::searchable_columns()
+    }
+    fn get_deleted_at_column() -> Option<&'static str> {
+        None
+    }
+    fn get_unique_keys() -> Vec<Vec<&'static str>> {
+        vec![SLUG_REDIRECT_KEY.to_vec()]
+    }
+}
+
+impl InMemoryViewMeta for TermOrm {
+    fn get_table_name() -> &'static str {
+        "terms"
+    }
+    fn get_searchable_columns() -> Vec<&'static str> {
+        TermOrm::searchable_columns()
+    }
+    fn get_search_vector_columns() -> Vec<(&'static str, f32)> {
+        vec![("name", WEIGHT_A), ("description", WEIGHT_C)]
+    }
+    fn get_headline_columns() -> Vec<&'static str> {
+        vec!["name", "description"]
+    }
+    fn get_unique_keys() -> Vec<Vec<&'static str>> {
+        vec![vec!["taxonomy_id", "slug"]]
+    }
+}
+
+impl InMemoryViewMeta for TermInfoOrm {
+    fn get_table_name() -> &'static str {
+        "terms_info"
+    }
+    fn get_searchable_columns() -> Vec<&'static str> {
+        TermInfoOrm::searchable_columns()
+    }
+    fn get_source() -> InMemorySource {
+        InMemorySource::View {
+            base: "terms",
+            joins: vec![
+                InMemoryJoin {
+                    foreign_key: "taxonomy_id",
+                    table: "post_taxonomies",
+                    columns: vec![("code", "taxonomy_code"), ("name", "taxonomy_name")],
+                },
+                InMemoryJoin {
+                    foreign_key: "parent_id",
+                    table: "terms",
+                    columns: vec![("slug", "parent_slug"), ("name", "parent_name")],
+                },
+            ],
+        }
+    }
+}
+
+impl InMemoryViewMeta for UserOrm {
+    fn get_table_name() -> &'static str {
+        "users"
+    }
+    fn get_searchable_columns() -> Vec<&'static str> {
+        UserOrm::searchable_columns()
+    }
+    fn get_unaudited_columns() -> Vec<&'static str> {
+        vec!["password"]
+    }
+    fn get_unique_keys() -> Vec<Vec<&'static str>> {
+        vec![vec!["username"], vec!["email"]]
+    }
+}
+
+impl InMemoryViewMeta for UserInfoOrm {
+    fn get_table_name() -> &'static str {
+        "users_info"
+    }
+    fn get_searchable_columns() -> Vec<&'static str> {
+        vec!["username", "email", "role"]
+    }
+    fn get_source() -> InMemorySource {
+        InMemorySource::View {
+            base: "users",
+            joins: vec![],
+        }
+    }
+}
+
+impl AttributeRepository for AttributeInMemoryRepository {}
+impl AttributeInfoRepository for AttributeInfoInMemoryRepository {}
+impl AttributeValueRepository for AttributeValueInMemoryRepository {}
+impl AttributeValueInfoRepository for AttributeValueInfoInMemoryRepository {}
+impl AuditLogInfoRepository for AuditLogInfoInMemoryRepository {}
+impl PostCollectionRepository for PostCollectionInMemoryRepository {}
+impl PostCollectionInfoRepository for PostCollectionInfoInMemoryRepository {}
+impl PostInfoRepository for PostInfoInMemoryRepository {}
+impl PostRevisionInfoRepository for PostRevisionInfoInMemoryRepository {}
+impl PostTaxonomyRepository for PostTaxonomyInMemoryRepository {}
+impl PostTaxonomyInfoRepository for PostTaxonomyInfoInMemoryRepository {}
+impl PostTypeRepository for PostTypeInMemoryRepository {}
+impl PostTypeInfoRepository for PostTypeInfoInMemoryRepository {}
+impl TermRepository for TermInMemoryRepository {}
+impl TermInfoRepository for TermInfoInMemoryRepository {}
+impl UserInfoRepository for UserInfoInMemoryRepository {}
+
+impl PostRepository for PostInMemoryRepository {
+    async fn find_by_slug(&self, slug: &str) -> Result<Option<Post>, CoreError> {
+        let filters = vec![equals("slug", ScalarValue::String(slug.to_string()))];
+        Ok(self.find_many(vec![], None, Some(1), filters).await?.pop())
+    }
+
+    async fn find_by_author(&self, user_id: i32) -> Result<Vec<Post>, CoreError> {
+        self.find_all(vec![equals("user_id", ScalarValue::Int(user_id))])
+            .await
+    }
+
+    async fn record_transition(
+        &self,
+        transition: &PostTransitionCreate,
+    ) -> Result<PostTransition, CoreError> {
+        let transitions: PostTransitionInMemoryRepository = self.sibling();
+        transitions.create(transition).await
+    }
+
+    async fn find_transitions(&self, post_id: i32) -> Result<Vec<PostTransition>, CoreError> {
+        let filters = vec![equals("post_id", ScalarValue::Int(post_id))];
+        let transitions: PostTransitionInMemoryRepository = self.sibling();
+        transitions
+            .find_many(newest_first(), None, None, filters)
+            .await
+    }
+}
+
+impl<T, O, C> SlugRedirectRecorder for InMemoryRepository<T, O, C> {
+    async fn record_slug_redirect(
+        &self,
+        redirect: &SlugRedirectCreate,
+    ) -> Result<SlugRedirect, CoreError> {
+        let redirects: SlugRedirectInMemoryRepository = self.sibling();
+        let mut upserted = redirects
+            .upsert_by(&SLUG_REDIRECT_KEY, &[SlugRedirect::from(redirect)])
+            .await?;
+        Ok(upserted.remove(0))
+    }
+}
+
+impl SlugRedirectRepository for SlugRedirectInMemoryRepository {
+    async fn find_entity_paths(
+        &self,
+        redirect: &SlugRedirect,
+    ) -> Result<Option<(String, String)>, CoreError> {
+        let Some(entity_id) = redirect.entity_id else {
+            return Ok(None);
+        };
+        let paths = |code_then: Option<&str>, code_now: Option<&str>, slug: &str| {
+            let entity_type = redirect.entity_type.as_str();
+            public_path(entity_type, code_then, &redirect.old_slug).zip(public_path(
+                entity_type,
+                code_now,
+                slug,
+            ))
+        };
+        Ok(match redirect.entity_type.as_str() {
+            POSTS => {
+                let posts: PostInMemoryRepository = self.sibling();
+                posts
+                    .find_by_id(entity_id)
+                    .await?
+                    .filter(|post| post.status == PostStatus::PUBLISHED)
+                    .and_then(|post| paths(None, None, &post.slug))
+            }
+            POST_COLLECTIONS => {
+                let collections: PostCollectionInMemoryRepository = self.sibling();
+                collections
+                    .find_by_id(entity_id)
+                    .await?
+                    .and_then(|collection| paths(None, None, &collection.slug))
+            }
+            TERMS => {
+                let terms: TermInMemoryRepository = self.sibling();
+                let Some(term) = terms.find_by_id(entity_id).await? else {
+                    return Ok(None);
+                };
+                let taxonomies: PostTaxonomyInMemoryRepository = self.sibling();
+                let then = taxonomies.find_by_id(redirect.scope_id).await?;
+                let now = taxonomies.find_by_id(term.taxonomy_id).await?;
+                paths(
+                    then.as_ref().map(|t| t.code.as_str()),
+                    now.as_ref().map(|t| t.code.as_str()),
+                    &term.slug,
+                )
+            }
+            _ => None,
+        })
+    }
+}
+
+impl PostRevisionRepository for PostRevisionInMemoryRepository {
+    async fn find_latest(&self, post_id: i32) -> Result<Option<PostRevision>, CoreError> {
+        let filters = vec![equals("post_id", ScalarValue::Int(post_id))];
+        Ok(self
+            .find_many(newest_first(), None, Some(1), filters)
+            .await?
+            .pop())
+    }
+
+    async fn delete_oldest(
+        &self,
+        post_id: i32,
+        kind: RevisionKind,
+        keep: usize,
+    ) -> Result<u64, CoreError> {
+        let filters = vec![
+            equals("post_id", ScalarValue::Int(post_id)),
+            equals("kind", ScalarValue::String(kind.as_str().to_string())),
+        ];
+        let oldest = self
+            .find_many(newest_first(), Some(keep as i32), None, filters)
+            .await?;
+        self.delete_by_ids(oldest.iter().map(|r| r.id).collect())
+            .await
+    }
+}
+
+fn newest_first() -> Vec<SortCriterion> {
+    ["created_at", "id"]
+        .into_iter()
+        .map(|field| SortCriterion {
+            field: field.to_string(),
+            ascending: false,
+            is_attribute: false,
+        })
+        .collect()
+}
+
+impl PostTermRepository for PostInMemoryRepository {
+    async fn find_term_ids(&self, post_id: i32) -> Result<Vec<i32>, CoreError> {
+        let mut term_ids: Vec<i32> = self
+            .find_links(POST_TERMS, |link| {
+                link.get("post_id") == Some(&ScalarValue::Int(post_id))
+            })?
+            .iter()
+            .filter_map(|link| match link.get("term_id") {
+                Some(ScalarValue::Int(term_id)) => Some(*term_id),
+                _ => None,
+            })
+            .collect();
+        term_ids.sort();
+        Ok(term_ids)
+    }
+
+    async fn insert_terms(&self, post_id: i32, term_ids: &[i32]) -> Result<u64, CoreError> {
+        let mut inserted = 0;
+        for term_id in term_ids {
+            let link = Row::from([
+                ("post_id".to_string(), ScalarValue::Int(post_id)),
+                ("term_id".to_string(), ScalarValue::Int(*term_id)),
+            ]);
+            if self.insert_link(POST_TERMS, link)? {
+                inserted += 1;
+            }
+        }
+        Ok(inserted)
+    }
+
+    async fn delete_terms(&self, post_id: i32, term_ids: &[i32]) -> Result<u64, CoreError> {
+        self.delete_links(POST_TERMS, |link| {
+            link.get("post_id") == Some(&ScalarValue::Int(post_id))
+                && matches!(link.get("term_id"), Some(ScalarValue::Int(id)) if term_ids.contains(id))
+        })
+    }
+}
+
+impl UserRepository for UserInMemoryRepository {
+    async fn find_by_username(&self, name: &str) -> Result<Option<User>, CoreError> {
+        let filters = vec![equals("username", ScalarValue::String(name.to_string()))];
+        Ok(self.find_many(vec![], None, Some(1), filters).await?.pop())
+    }
+
+    async fn find_by_email(&self, email: &str) -> Result<Option<User>, CoreError> {
+        let filters = vec![equals("email", ScalarValue::String(email.to_string()))];
+        Ok(self.find_many(vec![], None, Some(1), filters).await?.pop())
+    }
+
+    async fn find_by_email_or_username(
+        &self,
+        email_or_username: &str,
+    ) -> Result<Option<User>, CoreError> {
+        let value = ScalarValue::String(email_or_username.to_string());
+        let filters = vec![Filter::Or(vec![
+            equals("email", value.clone()),
+            equals("username", value),
+        ])];
+        Ok(self.find_many(vec![], None, Some(1), filters).await?.pop())
+    }
+}
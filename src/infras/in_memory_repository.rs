@@ -0,0 +1,2191 @@
+#![cfg(feature = "ssr")]
+
+use super::sqlx_repository::{
+    attribute_prototype, OrmMeta, ATTRIBUTE_VALUE_COLUMNS, AUDIT_LOG_TABLE, DELETED_AT_COLUMN,
+};
+use crate::common::aggregate::{Aggregate, AggregateFunction, AggregateGroup};
+use crate::common::audit::{AuditEntry, Snapshot};
+use crate::common::cursor::{Cursor, CursorPage, CursorPosition};
+use crate::common::error::CoreError;
+use crate::common::facet::{Facet, FacetBucket, FacetField};
+use crate::common::filter::{Filter, FilterOperator, FilterValue, ScalarValue, SoftDeleteScope};
+use crate::common::repository::{
+    Creatable, Repository, Transactional, UnitOfWork, ViewRepository, WithAttributes,
+};
+use crate::common::search::{to_tsquery, SearchHit, Suggestion, HIGHLIGHT_START, HIGHLIGHT_STOP};
+use crate::common::service::Entity as BizEntity;
+use crate::common::sort::SortCriterion;
+use serde_json::{Map, Value};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::marker::PhantomData;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use time::format_description::well_known::Rfc3339;
+use time::{OffsetDateTime, UtcOffset};
+use uuid::Uuid;
+
+/// A stored row: column name to value. SQL NULL is a missing column.
+pub type Row = HashMap<String, ScalarValue>;
+
+/// Type of an Orm field, as stored in an in-memory row.
+pub trait OrmColumn: Sized {
+    /// Any value of the column's type, for column type maps.
+    fn prototype() -> ScalarValue;
+    fn to_scalar(&self) -> Option<ScalarValue>;
+    /// The stored value; NULL or a value of another type gives the default.
+    fn from_scalar(value: Option<&ScalarValue>) -> Self;
+}
+
+impl OrmColumn for i32 {
+    fn prototype() -> ScalarValue {
+        ScalarValue::Int(0)
+    }
+    fn to_scalar(&self) -> Option<ScalarValue> {
+        Some(ScalarValue::Int(*self))
+    }
+    fn from_scalar(value: Option<&ScalarValue>) -> Self {
+        match value {
+            Some(ScalarValue::Int(v)) => *v,
+            _ => 0,
+        }
+    }
+}
+
+impl OrmColumn for f64 {
+    fn prototype() -> ScalarValue {
+        ScalarValue::Float(0.0)
+    }
+    fn to_scalar(&self) -> Option<ScalarValue> {
+        Some(ScalarValue::Float(*self))
+    }
+    fn from_scalar(value: Option<&ScalarValue>) -> Self {
+        match value {
+            Some(ScalarValue::Float(v)) => *v,
+            Some(ScalarValue::Int(v)) => *v as f64,
+            _ => 0.0,
+        }
+    }
+}
+
+impl OrmColumn for bool {
+    fn prototype() -> ScalarValue {
+        ScalarValue::Bool(false)
+    }
+    fn to_scalar(&self) -> Option<ScalarValue> {
+        Some(ScalarValue::Bool(*self))
+    }
+    fn from_scalar(value: Option<&ScalarValue>) -> Self {
+        matches!(value, Some(ScalarValue::Bool(true)))
+    }
+}
+
+impl OrmColumn for String {
+    fn prototype() -> ScalarValue {
+        ScalarValue::String(String::new())
+    }
+    fn to_scalar(&self) -> Option<ScalarValue> {
+        Some(ScalarValue::String(self.clone()))
+    }
+    fn from_scalar(value: Option<&ScalarValue>) -> Self {
+        value.map(to_text).unwrap_or_default()
+    }
+}
+
+impl OrmColumn for Uuid {
+    fn prototype() -> ScalarValue {
+        ScalarValue::String(String::new())
+    }
+    fn to_scalar(&self) -> Option<ScalarValue> {
+        Some(ScalarValue::String(self.to_string()))
+    }
+    fn from_scalar(value: Option<&ScalarValue>) -> Self {
+        match value {
+            Some(ScalarValue::String(v)) => Uuid::parse_str(v).unwrap_or_else(|_| Uuid::nil()),
+            _ => Uuid::nil(),
+        }
+    }
+}
+
+impl OrmColumn for time::Date {
+    fn prototype() -> ScalarValue {
+        ScalarValue::Date(time::Date::from_calendar_date(1970, time::Month::January, 1).unwrap())
+    }
+    fn to_scalar(&self) -> Option<ScalarValue> {
+        Some(ScalarValue::Date(*self))
+    }
+    fn from_scalar(value: Option<&ScalarValue>) -> Self {
+        match value {
+            Some(ScalarValue::Date(v)) => *v,
+            _ => OffsetDateTime::UNIX_EPOCH.date(),
+        }
+    }
+}
+
+impl OrmColumn for OffsetDateTime {
+    fn prototype() -> ScalarValue {
+        ScalarValue::DateTime(OffsetDateTime::UNIX_EPOCH)
+    }
+    fn to_scalar(&self) -> Option<ScalarValue> {
+        Some(ScalarValue::DateTime(stored_datetime(*self)))
+    }
+    fn from_scalar(value: Option<&ScalarValue>) -> Self {
+        match value {
+            Some(ScalarValue::DateTime(v)) => *v,
+            _ => OffsetDateTime::UNIX_EPOCH,
+        }
+    }
+}
+
+impl OrmColumn for time::Time {
+    fn prototype() -> ScalarValue {
+        ScalarValue::Time(time::Time::MIDNIGHT)
+    }
+    fn to_scalar(&self) -> Option<ScalarValue> {
+        // Postgres keeps microseconds
+        Some(ScalarValue::Time(
+            self.replace_nanosecond(self.nanosecond() / 1_000 * 1_000)
+                .unwrap_or(*self),
+        ))
+    }
+    fn from_scalar(value: Option<&ScalarValue>) -> Self {
+        match value {
+            Some(ScalarValue::Time(v)) => *v,
+            _ => time::Time::MIDNIGHT,
+        }
+    }
+}
+
+impl<V: OrmColumn> OrmColumn for Option<V> {
+    fn prototype() -> ScalarValue {
+        V::prototype()
+    }
+    fn to_scalar(&self) -> Option<ScalarValue> {
+        self.as_ref().and_then(V::to_scalar)
+    }
+    fn from_scalar(value: Option<&ScalarValue>) -> Self {
+        value.map(|v| V::from_scalar(Some(v)))
+    }
+}
+
+/// Orm struct converted to and from an in-memory row, column by column.
+/// Implemented by `define_orm_with_common_fields!` and its read-only variant.
+pub trait OrmValues: Sized {
+    fn column_types() -> Vec<(&'static str, ScalarValue)>;
+    fn to_values(&self) -> Vec<(&'static str, Option<ScalarValue>)>;
+    fn from_values(values: &Row) -> Self;
+}
+
+/// A view column taken from the row a foreign key points to, like a
+/// `LEFT JOIN`: `(column of the joined table, column of the view)`.
+pub struct InMemoryJoin {
+    pub foreign_key: &'static str,
+    pub table: &'static str,
+    pub columns: Vec<(&'static str, &'static str)>,
+}
+
+/// Where the rows of a table or view live.
+pub enum InMemorySource {
+    Table,
+    /// Rows of `base` with columns of the rows they reference added.
+    View {
+        base: &'static str,
+        joins: Vec<InMemoryJoin>,
+    },
+}
+
+/// Per-table metadata of the in-memory backend, the counterpart of `SqlxViewMeta`
+/// plus what the schema itself knows: view definitions and unique constraints.
+pub trait InMemoryViewMeta: OrmValues {
+    fn get_table_name() -> &'static str;
+    fn get_searchable_columns() -> Vec<&'static str>;
+    fn get_source() -> InMemorySource {
+        InMemorySource::Table
+    }
+    /// Columns of the stored search vector, in order, with their weight as
+    /// `ts_rank` counts it. Without any, the table does not support `search`.
+    fn get_search_vector_columns() -> Vec<(&'static str, f32)> {
+        vec![]
+    }
+    fn get_headline_columns() -> Vec<&'static str> {
+        Self::get_searchable_columns()
+    }
+    fn get_deleted_at_column() -> Option<&'static str> {
+        Some(DELETED_AT_COLUMN)
+    }
+    fn get_similarity_columns() -> Vec<&'static str> {
+        Self::get_searchable_columns()
+    }
+    fn get_unaudited_columns() -> Vec<&'static str> {
+        vec![]
+    }
+    /// Unique constraints besides the one on `uid`.
+    fn get_unique_keys() -> Vec<Vec<&'static str>> {
+        vec![]
+    }
+    /// Column holding the post id of each row, for `Filter::Term`.
+    fn get_post_id_column() -> Option<&'static str> {
+        None
+    }
+    /// Columns an upsert sets when it inserts but leaves alone on conflict.
+    fn get_insert_only_columns() -> Vec<&'static str> {
+        vec![]
+    }
+}
+
+/// `ts_rank` weights of the `A` to `D` labels given by `setweight`.
+pub const WEIGHT_A: f32 = 1.0;
+pub const WEIGHT_B: f32 = 0.4;
+pub const WEIGHT_C: f32 = 0.2;
+
+#[derive(Clone, Default)]
+struct Table {
+    last_id: i32,
+    rows: BTreeMap<i32, Row>,
+}
+
+#[derive(Clone, Default)]
+struct DatabaseState {
+    tables: HashMap<String, Table>,
+}
+
+impl DatabaseState {
+    fn table(&self, name: &str) -> Option<&Table> {
+        self.tables.get(name)
+    }
+
+    fn table_mut(&mut self, name: &str) -> &mut Table {
+        self.tables.entry(name.to_string()).or_default()
+    }
+
+    fn rows(&self, name: &str) -> impl Iterator<Item = &Row> {
+        self.table(name).into_iter().flat_map(|t| t.rows.values())
+    }
+}
+
+/// Tables shared by every in-memory repository created from it. Cloning
+/// gives another handle to the same data.
+#[derive(Clone, Default)]
+pub struct InMemoryDatabase {
+    state: Arc<Mutex<DatabaseState>>,
+}
+
+impl InMemoryDatabase {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, DatabaseState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// A copy of the whole database taken at `begin`, put back on rollback.
+/// Writes are visible to everyone straight away and a rollback also undoes
+/// writes made outside the transaction meanwhile: there is no isolation.
+#[derive(Clone)]
+pub struct InMemoryTransaction {
+    database: InMemoryDatabase,
+    snapshot: Arc<Mutex<Option<DatabaseState>>>,
+    // Same as `SqlxTransaction`: only the outermost handle ends the transaction
+    owner: bool,
+}
+
+impl InMemoryTransaction {
+    fn take_snapshot(&self) -> Option<DatabaseState> {
+        self.snapshot
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take()
+    }
+
+    fn is_open(&self) -> bool {
+        self.snapshot
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .is_some()
+    }
+}
+
+impl UnitOfWork for InMemoryTransaction {
+    async fn commit(&self) -> Result<(), CoreError> {
+        if !self.owner {
+            return Ok(());
+        }
+        match self.take_snapshot() {
+            Some(_) => Ok(()),
+            None => Err(CoreError::internal_server_error("error.transaction.closed")),
+        }
+    }
+
+    async fn rollback(&self) -> Result<(), CoreError> {
+        if !self.owner {
+            return Ok(());
+        }
+        if let Some(snapshot) = self.take_snapshot() {
+            *self.database.lock() = snapshot;
+        }
+        Ok(())
+    }
+}
+
+/// Repository keeping its rows in an `InMemoryDatabase`, for tests and offline
+/// demos. Queries follow the Postgres repositories: same filters, ordering
+/// (`NULL`s last ascending), paging, soft deletion, optimistic locking and
+/// errors. Differences: strings sort by code point rather than collation, text
+/// search splits words on anything but letters and digits, ranks and headlines
+/// are approximations, and foreign keys are neither checked nor cascaded.
+pub struct InMemoryRepository<T, O, C = ()> {
+    database: InMemoryDatabase,
+    transaction: Option<InMemoryTransaction>,
+    marker: PhantomData<(T, O, C)>,
+}
+
+impl<T, O, C> Clone for InMemoryRepository<T, O, C> {
+    fn clone(&self) -> Self {
+        Self {
+            database: self.database.clone(),
+            transaction: self.transaction.clone(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T, O, C> InMemoryRepository<T, O, C> {
+    pub fn new(database: InMemoryDatabase) -> Self {
+        Self {
+            database,
+            transaction: None,
+            marker: PhantomData,
+        }
+    }
+
+    /// Repository of another table in the same database, taking part in this
+    /// one's transaction if it has one.
+    pub fn sibling<T2, O2, C2>(&self) -> InMemoryRepository<T2, O2, C2> {
+        InMemoryRepository {
+            database: self.database.clone(),
+            transaction: self.transaction.clone(),
+            marker: PhantomData,
+        }
+    }
+}
+
+/// Link tables, such as `post_terms`, have a composite key and no entity.
+/// Their rows are kept under made-up ids that nothing refers to.
+impl<T, O: InMemoryViewMeta, C> InMemoryRepository<T, O, C> {
+    /// Rows of the link table `table` for which `matches` holds.
+    pub fn find_links(
+        &self,
+        table: &str,
+        matches: impl Fn(&Row) -> bool,
+    ) -> Result<Vec<Row>, CoreError> {
+        let state = self.state()?;
+        Ok(state.rows(table).filter(|r| matches(r)).cloned().collect())
+    }
+
+    /// Adds `row` to the link table `table` unless it is there already, like
+    /// `ON CONFLICT DO NOTHING`; returns whether it was added.
+    pub fn insert_link(&self, table: &str, row: Row) -> Result<bool, CoreError> {
+        let mut state = self.state()?;
+        if state.rows(table).any(|r| *r == row) {
+            return Ok(false);
+        }
+        let table = state.table_mut(table);
+        table.last_id += 1;
+        table.rows.insert(table.last_id, row);
+        Ok(true)
+    }
+
+    /// Removes the rows of the link table `table` for which `matches` holds;
+    /// returns how many.
+    pub fn delete_links(
+        &self,
+        table: &str,
+        matches: impl Fn(&Row) -> bool,
+    ) -> Result<u64, CoreError> {
+        let mut state = self.state()?;
+        let rows = &mut state.table_mut(table).rows;
+        let before = rows.len();
+        rows.retain(|_, r| !matches(r));
+        Ok((before - rows.len()) as u64)
+    }
+}
+
+/// What a failed statement gives on Postgres, e.g. a unique violation.
+fn db_error(reason: &str) -> CoreError {
+    log::error!("In-memory database error: {reason}");
+    CoreError::internal_server_error("error.db.unknown")
+}
+
+impl<T, O: InMemoryViewMeta, C> InMemoryRepository<T, O, C> {
+    fn state(&self) -> Result<MutexGuard<'_, DatabaseState>, CoreError> {
+        match &self.transaction {
+            Some(tx) if !tx.is_open() => {
+                Err(CoreError::internal_server_error("error.transaction.closed"))
+            }
+            _ => Ok(self.database.lock()),
+        }
+    }
+
+    fn columns() -> Vec<&'static str> {
+        O::column_types().into_iter().map(|(c, _)| c).collect()
+    }
+
+    /// Rows of this table, or of this view computed from its tables.
+    fn rows(state: &DatabaseState) -> Vec<Row> {
+        let InMemorySource::View { base, joins } = O::get_source() else {
+            return state.rows(O::get_table_name()).cloned().collect();
+        };
+        let mut wanted = Self::columns();
+        wanted.extend(O::get_deleted_at_column());
+        state
+            .rows(base)
+            .map(|base_row| {
+                let mut row: Row = wanted
+                    .iter()
+                    .filter_map(|c| base_row.get(*c).map(|v| (c.to_string(), v.clone())))
+                    .collect();
+                for join in &joins {
+                    let joined = base_row.get(join.foreign_key).and_then(|id| match id {
+                        ScalarValue::Int(id) => state.table(join.table)?.rows.get(id),
+                        _ => None,
+                    });
+                    for (column, alias) in &join.columns {
+                        match joined.and_then(|r| r.get(*column)) {
+                            Some(value) => row.insert(alias.to_string(), value.clone()),
+                            None => row.remove(*alias),
+                        };
+                    }
+                }
+                row
+            })
+            .collect()
+    }
+
+    fn validate_query(
+        &self,
+        sort_criteria: &[SortCriterion],
+        filters: &[Filter],
+    ) -> Result<(), CoreError> {
+        let mut columns = Self::columns();
+        columns.extend(O::get_deleted_at_column());
+        for criterion in sort_criteria.iter().filter(|c| !c.is_attribute) {
+            if !columns.contains(&criterion.field.as_str()) {
+                return Err(CoreError::UnprocessableEntity(
+                    "error.sort.unknown.field",
+                    HashMap::from([("field".into(), criterion.field.clone())]),
+                ));
+            }
+        }
+        for property_name in filters.iter().flat_map(Filter::property_names) {
+            if !columns.contains(&property_name) {
+                return Err(CoreError::UnprocessableEntity(
+                    "error.filters.unknown.property",
+                    HashMap::from([("property".into(), property_name.to_string())]),
+                ));
+            }
+        }
+        if O::get_post_id_column().is_none() && filters.iter().any(Filter::has_term) {
+            return Err(CoreError::UnprocessableEntity(
+                "error.filters.terms.unsupported",
+                HashMap::from([("table".into(), O::get_table_name().to_string())]),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Rows matching every filter, with trashed rows left out unless a
+    /// top-level `Filter::SoftDeleted` says otherwise.
+    fn filter_rows(state: &DatabaseState, rows: Vec<Row>, filters: &[Filter]) -> Vec<Row> {
+        let mut filters = filters
+            .iter()
+            .filter(|f| !matches!(f, Filter::Search { value, .. } if value.trim().is_empty()))
+            .collect::<Vec<_>>();
+        let exclude_trashed = Filter::SoftDeleted(SoftDeleteScope::Exclude);
+        if !filters.iter().any(|f| matches!(f, Filter::SoftDeleted(_))) {
+            filters.push(&exclude_trashed);
+        }
+        rows.into_iter()
+            .filter(|row| {
+                filters
+                    .iter()
+                    .all(|f| Self::evaluate(state, row, f) == Some(true))
+            })
+            .collect()
+    }
+
+    /// Truth value of `filter` on `row`; `None` is SQL's unknown.
+    fn evaluate(state: &DatabaseState, row: &Row, filter: &Filter) -> Option<bool> {
+        match filter {
+            Filter::Property {
+                property_name,
+                operator,
+                value,
+            } => apply_operator(row.get(property_name), operator, value),
+            Filter::Attribute {
+                attr_name,
+                operator,
+                value,
+            } => {
+                let column = attribute_value_column(value);
+                Some(
+                    Self::attribute_rows(state, row)
+                        .filter(|(name, _)| name == attr_name)
+                        .any(|(_, av)| {
+                            apply_operator(av.get(column), operator, value) == Some(true)
+                        }),
+                )
+            }
+            Filter::Search { value, fuzzy } => {
+                let keyword = value.trim();
+                if keyword.is_empty() {
+                    return Some(true);
+                }
+                if *fuzzy {
+                    return Self::similar(row, keyword);
+                }
+                let vector_columns = O::get_search_vector_columns();
+                if !vector_columns.is_empty() {
+                    return Some(match to_tsquery(keyword) {
+                        Some(tsquery) => {
+                            let document = search_document(row, &vector_columns);
+                            parse_tsquery(&tsquery)
+                                .iter()
+                                .all(|part| !part.positions(&document).is_empty())
+                        }
+                        None => true,
+                    });
+                }
+                let words = lexemes(keyword);
+                let contains_all = |text: &str| {
+                    let found = lexemes(text).into_iter().collect::<HashSet<_>>();
+                    !words.is_empty() && words.iter().all(|w| found.contains(w))
+                };
+                let text = O::get_searchable_columns()
+                    .iter()
+                    .map(|c| row.get(*c).map(to_text).unwrap_or_default())
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                Some(
+                    contains_all(&text)
+                        || Self::attribute_rows(state, row).any(|(_, av)| {
+                            contains_all(&av.get("string_value").map(to_text).unwrap_or_default())
+                        }),
+                )
+            }
+            Filter::Term { taxonomy, slug } => {
+                let post_id = O::get_post_id_column().and_then(|column| row.get(column));
+                let live = |table: &str, id: Option<&ScalarValue>| match id {
+                    Some(ScalarValue::Int(id)) => state
+                        .table(table)?
+                        .rows
+                        .get(id)
+                        .filter(|r| !r.contains_key(DELETED_AT_COLUMN)),
+                    _ => None,
+                };
+                let text = |r: &Row, column: &str, wanted: &Option<String>| {
+                    wanted.as_ref().is_none_or(|wanted| {
+                        r.get(column) == Some(&ScalarValue::String(wanted.clone()))
+                    })
+                };
+                Some(
+                    state
+                        .rows("post_terms")
+                        .filter(|link| post_id.is_some() && link.get("post_id") == post_id)
+                        .filter_map(|link| live("terms", link.get("term_id")))
+                        .any(|term| {
+                            text(term, "slug", slug)
+                                && live("post_taxonomies", term.get("taxonomy_id"))
+                                    .is_some_and(|tx| text(tx, "code", taxonomy))
+                        }),
+                )
+            }
+            Filter::And(filters) => all3(filters.iter().map(|f| Self::evaluate(state, row, f))),
+            Filter::Or(filters) => any3(filters.iter().map(|f| Self::evaluate(state, row, f))),
+            Filter::Not(filter) => Self::evaluate(state, row, filter).map(|v| !v),
+            Filter::SoftDeleted(scope) => match (O::get_deleted_at_column(), scope) {
+                (_, SoftDeleteScope::Include) | (None, SoftDeleteScope::Exclude) => Some(true),
+                (None, SoftDeleteScope::Only) => Some(false),
+                (Some(column), SoftDeleteScope::Exclude) => Some(!row.contains_key(column)),
+                (Some(column), SoftDeleteScope::Only) => Some(row.contains_key(column)),
+            },
+        }
+    }
+
+    /// `keyword <% column` on any similarity column.
+    fn similar(row: &Row, keyword: &str) -> Option<bool> {
+        let threshold = crate::db::similarity_threshold();
+        any3(O::get_similarity_columns().iter().map(|column| {
+            row.get(*column)
+                .map(|v| word_similarity(keyword, &to_text(v)) >= threshold)
+        }))
+    }
+
+    /// Live attribute values of `row`, each with its attribute's name.
+    fn attribute_rows<'s>(
+        state: &'s DatabaseState,
+        row: &Row,
+    ) -> impl Iterator<Item = (String, &'s Row)> + 's {
+        let entity_id = row.get("id").cloned();
+        state
+            .rows("attribute_values")
+            .filter(move |av| {
+                !av.contains_key(DELETED_AT_COLUMN)
+                    && entity_id.is_some()
+                    && av.get("entity_id") == entity_id.as_ref()
+                    && av.get("entity_type")
+                        == Some(&ScalarValue::String(O::get_table_name().to_string()))
+            })
+            .filter_map(|av| {
+                let Some(ScalarValue::Int(attribute_id)) = av.get("attribute_id") else {
+                    return None;
+                };
+                let attribute = state.table("attributes")?.rows.get(attribute_id)?;
+                Some((attribute.get("name").map(to_text)?, av))
+            })
+    }
+
+    /// The typed value columns of the attribute `name` of `row`, all `None`
+    /// when it has none.
+    fn attribute_columns(state: &DatabaseState, row: &Row, name: &str) -> Vec<Option<ScalarValue>> {
+        let av = Self::attribute_rows(state, row).find(|(n, _)| n == name);
+        ATTRIBUTE_VALUE_COLUMNS
+            .iter()
+            .map(|column| av.as_ref().and_then(|(_, av)| av.get(*column).cloned()))
+            .collect()
+    }
+
+    fn sort_rows(state: &DatabaseState, rows: &mut Vec<Row>, sort_criteria: &[SortCriterion]) {
+        if sort_criteria.is_empty() {
+            return;
+        }
+        let mut keyed = std::mem::take(rows)
+            .into_iter()
+            .map(|row| {
+                let keys = sort_criteria
+                    .iter()
+                    .map(|c| {
+                        if c.is_attribute {
+                            Self::attribute_columns(state, &row, &c.field)
+                        } else {
+                            vec![row.get(&c.field).cloned()]
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                (keys, row)
+            })
+            .collect::<Vec<_>>();
+        keyed.sort_by(|(a, _), (b, _)| {
+            sort_criteria
+                .iter()
+                .zip(a.iter().zip(b))
+                .map(|(criterion, (a, b))| {
+                    let ordering = compare_keys(a, b);
+                    if criterion.ascending {
+                        ordering
+                    } else {
+                        ordering.reverse()
+                    }
+                })
+                .find(|o| o.is_ne())
+                .unwrap_or(Ordering::Equal)
+        });
+        rows.extend(keyed.into_iter().map(|(_, row)| row));
+    }
+
+    fn page<V>(items: Vec<V>, first_result: Option<i32>, max_results: Option<i32>) -> Vec<V> {
+        let offset = first_result.unwrap_or(0).max(0) as usize;
+        let iter = items.into_iter().skip(offset);
+        match max_results {
+            Some(limit) => iter.take(limit.max(0) as usize).collect(),
+            None => iter.collect(),
+        }
+    }
+
+    fn query(
+        &self,
+        sort_criteria: &[SortCriterion],
+        first_result: Option<i32>,
+        max_results: Option<i32>,
+        filters: &[Filter],
+    ) -> Result<Vec<Row>, CoreError> {
+        self.validate_query(sort_criteria, filters)?;
+        let state = self.state()?;
+        let mut rows = Self::filter_rows(&state, Self::rows(&state), filters);
+        Self::sort_rows(&state, &mut rows, sort_criteria);
+        Ok(Self::page(rows, first_result, max_results))
+    }
+
+    /// Snippet of the headline columns around the words `parts` match.
+    fn headline(row: &Row, parts: &[QueryPart]) -> String {
+        let text = O::get_headline_columns()
+            .iter()
+            .filter_map(|c| row.get(*c).map(to_text))
+            .collect::<Vec<_>>()
+            .join(" ");
+        headline(&text, parts)
+    }
+}
+
+impl<T, O, C> ViewRepository<T> for InMemoryRepository<T, O, C>
+where
+    O: InMemoryViewMeta,
+    T: From<O>,
+{
+    fn get_table_name(&self) -> &str {
+        O::get_table_name()
+    }
+    fn get_columns(&self) -> Vec<&str> {
+        Self::columns()
+    }
+    fn get_searchable_columns(&self) -> Vec<&str> {
+        O::get_searchable_columns()
+    }
+
+    async fn count(&self, filters: Vec<Filter>) -> Result<i64, CoreError> {
+        Ok(self.query(&[], None, None, &filters)?.len() as i64)
+    }
+
+    async fn find_suffixed(
+        &self,
+        column: &str,
+        base: &str,
+        filters: Vec<Filter>,
+    ) -> Result<Vec<String>, CoreError> {
+        if !Self::columns().contains(&column) {
+            return Err(CoreError::UnprocessableEntity(
+                "error.filters.unknown.property",
+                HashMap::from([("property".into(), column.to_string())]),
+            ));
+        }
+        let prefix = format!("{base}-");
+        Ok(self
+            .query(&[], None, None, &filters)?
+            .iter()
+            .filter_map(|row| match row.get(column) {
+                Some(ScalarValue::String(value)) if value == base || value.starts_with(&prefix) => {
+                    Some(value.clone())
+                }
+                _ => None,
+            })
+            .collect())
+    }
+
+    async fn facets(
+        &self,
+        fields: Vec<FacetField>,
+        filters: Vec<Filter>,
+    ) -> Result<Vec<Facet>, CoreError> {
+        self.validate_query(&[], &filters)?;
+        let columns = Self::columns();
+        if let Some(field) = fields
+            .iter()
+            .find(|f| !f.is_attribute && !columns.contains(&f.field.as_str()))
+        {
+            return Err(CoreError::UnprocessableEntity(
+                "error.facet.unknown.field",
+                HashMap::from([("field".into(), field.field.clone())]),
+            ));
+        }
+
+        let state = self.state()?;
+        let mut facets = Vec::with_capacity(fields.len());
+        for field in fields {
+            let facet_filters = filters
+                .iter()
+                .filter(|f| !field.is_filtered_by(f))
+                .cloned()
+                .collect::<Vec<_>>();
+            let mut counts: Vec<(Vec<Option<ScalarValue>>, i64)> = vec![];
+            for row in Self::filter_rows(&state, Self::rows(&state), &facet_filters) {
+                let value = if field.is_attribute {
+                    Self::attribute_columns(&state, &row, &field.field)
+                } else {
+                    vec![row.get(&field.field).cloned()]
+                };
+                match counts.iter_mut().find(|(v, _)| *v == value) {
+                    Some((_, count)) => *count += 1,
+                    None => counts.push((value, 1)),
+                }
+            }
+            counts.sort_by(|(a, a_count), (b, b_count)| {
+                b_count.cmp(a_count).then_with(|| compare_keys(a, b))
+            });
+            let buckets = counts
+                .into_iter()
+                .map(|(value, count)| FacetBucket {
+                    value: value.into_iter().flatten().next(),
+                    count,
+                })
+                .collect();
+            facets.push(Facet { field, buckets });
+        }
+        Ok(facets)
+    }
+
+    async fn aggregate(
+        &self,
+        group_by: Vec<String>,
+        aggregates: Vec<Aggregate>,
+        first_result: Option<i32>,
+        max_results: Option<i32>,
+        filters: Vec<Filter>,
+    ) -> Result<Vec<AggregateGroup>, CoreError> {
+        self.validate_query(&[], &filters)?;
+        let columns = Self::columns();
+        if let Some(field) = group_by
+            .iter()
+            .chain(aggregates.iter().filter_map(|a| a.field.as_ref()))
+            .find(|field| !columns.contains(&field.as_str()))
+        {
+            return Err(CoreError::UnprocessableEntity(
+                "error.aggregate.unknown.field",
+                HashMap::from([("field".into(), field.clone())]),
+            ));
+        }
+        let column_types = O::column_types()
+            .into_iter()
+            .map(|(column, prototype)| (column.to_string(), prototype))
+            .collect();
+        Aggregate::check_types(&aggregates, &column_types)?;
+
+        let rows = {
+            let state = self.state()?;
+            Self::filter_rows(&state, Self::rows(&state), &filters)
+        };
+        let mut groups: Vec<(Vec<Option<ScalarValue>>, Vec<Row>)> = vec![];
+        if group_by.is_empty() {
+            // Without GROUP BY, one group even when no row matches
+            groups.push((vec![], rows));
+        } else {
+            for row in rows {
+                let key = group_by
+                    .iter()
+                    .map(|f| row.get(f).cloned())
+                    .collect::<Vec<_>>();
+                match groups.iter_mut().find(|(k, _)| *k == key) {
+                    Some((_, members)) => members.push(row),
+                    None => groups.push((key, vec![row])),
+                }
+            }
+            groups.sort_by(|(a, _), (b, _)| compare_keys(a, b));
+        }
+
+        let groups = groups
+            .into_iter()
+            .map(|(key, members)| AggregateGroup {
+                key: group_by.iter().cloned().zip(key).collect(),
+                count: members.len() as i64,
+                values: aggregates
+                    .iter()
+                    .map(|aggregate| (aggregate.label(), aggregate_value(aggregate, &members)))
+                    .collect(),
+            })
+            .collect();
+        Ok(Self::page(groups, first_result, max_results))
+    }
+
+    async fn find_many(
+        &self,
+        sort_criteria: Vec<SortCriterion>,
+        first_result: Option<i32>,
+        max_results: Option<i32>,
+        filters: Vec<Filter>,
+    ) -> Result<Vec<T>, CoreError> {
+        Ok(self
+            .query(&sort_criteria, first_result, max_results, &filters)?
+            .iter()
+            .map(|row| T::from(O::from_values(row)))
+            .collect())
+    }
+
+    async fn find_many_with_attributes(
+        &self,
+        sort_criteria: Vec<SortCriterion>,
+        first_result: Option<i32>,
+        max_results: Option<i32>,
+        filters: Vec<Filter>,
+    ) -> Result<Vec<WithAttributes<T>>, CoreError> {
+        let rows = self.query(&sort_criteria, first_result, max_results, &filters)?;
+        let state = self.state()?;
+        Ok(rows
+            .iter()
+            .map(|row| WithAttributes {
+                entity: T::from(O::from_values(row)),
+                attributes: Self::attribute_rows(&state, row)
+                    .filter_map(|(name, av)| {
+                        let value = ATTRIBUTE_VALUE_COLUMNS
+                            .iter()
+                            .find_map(|column| av.get(*column).cloned())?;
+                        Some((name, value))
+                    })
+                    .collect(),
+            })
+            .collect())
+    }
+
+    async fn search(
+        &self,
+        query: String,
+        first_result: Option<i32>,
+        max_results: Option<i32>,
+        filters: Vec<Filter>,
+    ) -> Result<Vec<SearchHit<T>>, CoreError> {
+        self.validate_query(&[], &filters)?;
+        let vector_columns = O::get_search_vector_columns();
+        if vector_columns.is_empty() {
+            return Err(CoreError::UnprocessableEntity(
+                "error.search.unsupported",
+                HashMap::from([("table".into(), O::get_table_name().to_string())]),
+            ));
+        }
+        let Some(tsquery) = to_tsquery(&query) else {
+            return Ok(vec![]);
+        };
+        let parts = parse_tsquery(&tsquery);
+        let rows = {
+            let state = self.state()?;
+            Self::filter_rows(&state, Self::rows(&state), &filters)
+        };
+        let mut hits = rows
+            .into_iter()
+            .filter_map(|row| {
+                let document = search_document(&row, &vector_columns);
+                let mut rank = 0.0;
+                for part in &parts {
+                    let positions = part.positions(&document);
+                    if positions.is_empty() {
+                        return None;
+                    }
+                    rank += positions.iter().map(|&i| document[i].1).sum::<f32>();
+                }
+                Some((rank, row))
+            })
+            .collect::<Vec<_>>();
+        hits.sort_by(|(a_rank, a), (b_rank, b)| {
+            b_rank
+                .total_cmp(a_rank)
+                .then_with(|| compare_keys(&[b.get("id").cloned()], &[a.get("id").cloned()]))
+        });
+        Ok(Self::page(hits, first_result, max_results)
+            .into_iter()
+            .map(|(rank, row)| SearchHit {
+                headline: Self::headline(&row, &parts),
+                entity: T::from(O::from_values(&row)),
+                rank,
+            })
+            .collect())
+    }
+
+    async fn suggest(
+        &self,
+        query: String,
+        max_results: Option<i32>,
+        filters: Vec<Filter>,
+    ) -> Result<Vec<Suggestion<T>>, CoreError> {
+        self.validate_query(&[], &filters)?;
+        let query = query.trim().to_string();
+        if query.is_empty() {
+            return Ok(vec![]);
+        }
+        let rows = {
+            let state = self.state()?;
+            Self::filter_rows(&state, Self::rows(&state), &filters)
+        };
+        let mut suggestions = rows
+            .into_iter()
+            .filter(|row| Self::similar(row, &query) == Some(true))
+            .map(|row| {
+                let similarity = O::get_similarity_columns()
+                    .iter()
+                    .map(|c| word_similarity(&query, &row.get(*c).map(to_text).unwrap_or_default()))
+                    .fold(0.0f32, f32::max);
+                (similarity, row)
+            })
+            .collect::<Vec<_>>();
+        suggestions.sort_by(|(a_similarity, a), (b_similarity, b)| {
+            b_similarity
+                .total_cmp(a_similarity)
+                .then_with(|| compare_keys(&[b.get("id").cloned()], &[a.get("id").cloned()]))
+        });
+        Ok(Self::page(suggestions, None, max_results)
+            .into_iter()
+            .map(|(similarity, row)| Suggestion {
+                entity: T::from(O::from_values(&row)),
+                similarity,
+            })
+            .collect())
+    }
+
+    async fn find_page(
+        &self,
+        sort_criteria: Vec<SortCriterion>,
+        position: CursorPosition,
+        max_results: i32,
+        filters: Vec<Filter>,
+    ) -> Result<CursorPage<T>, CoreError> {
+        self.validate_query(&sort_criteria, &filters)?;
+        if let Some(criterion) = sort_criteria.iter().find(|c| c.is_attribute) {
+            return Err(CoreError::UnprocessableEntity(
+                "error.cursor.attribute.sort",
+                HashMap::from([("field".into(), criterion.field.clone())]),
+            ));
+        }
+        let mut sort_criteria = sort_criteria
+            .into_iter()
+            .filter(|c| !c.field.is_empty())
+            .collect::<Vec<_>>();
+        match sort_criteria.iter().position(|c| c.field == "id") {
+            Some(i) => sort_criteria.truncate(i + 1),
+            None => sort_criteria.push(SortCriterion {
+                field: "id".to_string(),
+                ascending: sort_criteria.last().is_none_or(|c| c.ascending),
+                is_attribute: false,
+            }),
+        }
+        let key_columns = sort_criteria[..sort_criteria.len() - 1]
+            .iter()
+            .map(|c| c.field.clone())
+            .collect::<Vec<_>>();
+        if let CursorPosition::After(cursor) | CursorPosition::Before(cursor) = &position {
+            if cursor.keys.len() != key_columns.len() {
+                return Err(CoreError::bad_request("error.cursor.invalid"));
+            }
+        }
+
+        let max_results = max_results.max(1);
+        let backwards = matches!(position, CursorPosition::Before(_));
+        let order = sort_criteria
+            .iter()
+            .map(|c| SortCriterion {
+                field: c.field.clone(),
+                ascending: c.ascending != backwards,
+                is_attribute: false,
+            })
+            .collect::<Vec<_>>();
+        let mut rows = {
+            let state = self.state()?;
+            let mut rows = Self::filter_rows(&state, Self::rows(&state), &filters);
+            if let CursorPosition::After(cursor) | CursorPosition::Before(cursor) = &position {
+                rows.retain(|row| keyset_predicate(row, &order, cursor) == Some(true));
+            }
+            Self::sort_rows(&state, &mut rows, &order);
+            rows
+        };
+        rows.truncate(max_results as usize + 1);
+
+        let has_more = rows.len() > max_results as usize;
+        rows.truncate(max_results as usize);
+        if backwards {
+            rows.reverse();
+        }
+
+        let cursor_of = |row: Option<&Row>| -> Result<Option<String>, CoreError> {
+            let Some(row) = row else {
+                return Ok(None);
+            };
+            let keys = key_columns
+                .iter()
+                .map(|column| row.get(column).cloned())
+                .collect::<Vec<_>>();
+            let id = i32::from_scalar(row.get("id"));
+            Ok(Some(Cursor { keys, id }.encode()))
+        };
+        let (first, last) = (rows.first(), rows.last());
+        let (prev_cursor, next_cursor) = match position {
+            CursorPosition::First => (None, if has_more { cursor_of(last)? } else { None }),
+            CursorPosition::After(_) => (
+                cursor_of(first)?,
+                if has_more { cursor_of(last)? } else { None },
+            ),
+            CursorPosition::Before(_) => (
+                if has_more { cursor_of(first)? } else { None },
+                cursor_of(last)?,
+            ),
+        };
+
+        Ok(CursorPage {
+            items: rows
+                .iter()
+                .map(|row| T::from(O::from_values(row)))
+                .collect(),
+            next_cursor,
+            prev_cursor,
+        })
+    }
+
+    async fn find_by_id(&self, id: i32) -> Result<Option<T>, CoreError> {
+        let state = self.state()?;
+        Ok(Self::rows(&state)
+            .into_iter()
+            .find(|row| {
+                row.get("id") == Some(&ScalarValue::Int(id))
+                    && O::get_deleted_at_column().is_none_or(|c| !row.contains_key(c))
+            })
+            .map(|row| T::from(O::from_values(&row))))
+    }
+
+    async fn find_by_uid(&self, uid: String) -> Result<Option<T>, CoreError> {
+        let uid = match Uuid::parse_str(&uid) {
+            Ok(u) => ScalarValue::String(u.to_string()),
+            Err(_) => return Err(CoreError::bad_request("error.invalid.uid")),
+        };
+        let state = self.state()?;
+        Ok(Self::rows(&state)
+            .into_iter()
+            .find(|row| {
+                row.get("uid") == Some(&uid)
+                    && O::get_deleted_at_column().is_none_or(|c| !row.contains_key(c))
+            })
+            .map(|row| T::from(O::from_values(&row))))
+    }
+
+    async fn get_column_type_map(&self) -> Result<HashMap<String, ScalarValue>, CoreError> {
+        Ok(O::column_types()
+            .into_iter()
+            .map(|(column, prototype)| (column.to_string(), prototype))
+            .collect())
+    }
+}
+
+impl<T, O, C> InMemoryRepository<T, O, C>
+where
+    O: InMemoryViewMeta + OrmMeta,
+{
+    fn validate_upsert_key(&self, key: &[&str]) -> Result<(), CoreError> {
+        if key.is_empty() {
+            return Err(CoreError::unprocessable_entity("error.upsert.key.empty"));
+        }
+        let columns = O::updatable_columns();
+        match key.iter().find(|k| !columns.contains(k)) {
+            Some(unknown) => Err(CoreError::UnprocessableEntity(
+                "error.upsert.key.unknown",
+                HashMap::from([("column".into(), unknown.to_string())]),
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// Fails like a unique violation when `row` holds the same values as
+    /// another row of `table` in a unique key. NULLs never clash.
+    fn check_unique(table: &Table, id: i32, row: &Row) -> Result<(), CoreError> {
+        let mut keys = O::get_unique_keys();
+        keys.push(vec!["uid"]);
+        for key in keys {
+            let Some(values) = key.iter().map(|c| row.get(*c)).collect::<Option<Vec<_>>>() else {
+                continue;
+            };
+            let clash = table.rows.iter().any(|(other_id, other)| {
+                *other_id != id
+                    && key
+                        .iter()
+                        .zip(&values)
+                        .all(|(c, v)| other.get(*c) == Some(*v))
+            });
+            if clash {
+                return Err(db_error(&format!(
+                    "duplicate key ({}) in {}",
+                    key.join(", "),
+                    O::get_table_name()
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    fn insert(
+        table: &mut Table,
+        values: Vec<(&'static str, Option<ScalarValue>)>,
+    ) -> Result<Row, CoreError> {
+        table.last_id += 1;
+        let id = table.last_id;
+        let mut row: Row = values
+            .into_iter()
+            .filter(|(c, _)| *c != "id")
+            .filter_map(|(c, v)| Some((c.to_string(), v?)))
+            .collect();
+        row.insert("id".to_string(), ScalarValue::Int(id));
+        Self::check_unique(table, id, &row)?;
+        table.rows.insert(id, row.clone());
+        Ok(row)
+    }
+
+    fn set_values(
+        row: &mut Row,
+        values: &HashMap<&'static str, Option<ScalarValue>>,
+        columns: &[&str],
+    ) {
+        for column in columns {
+            match values.get(column).cloned().flatten() {
+                Some(value) => row.insert(column.to_string(), value),
+                None => row.remove(*column),
+            };
+        }
+    }
+
+    /// Optimistically locked update of one live row, as `generic_update` does it.
+    fn update_row(
+        table: &mut Table,
+        values: Vec<(&'static str, Option<ScalarValue>)>,
+    ) -> Result<Row, CoreError> {
+        let values = values.into_iter().collect::<HashMap<_, _>>();
+        let id = i32::from_scalar(values.get("id").and_then(Option::as_ref));
+        let version = i32::from_scalar(values.get("version").and_then(Option::as_ref));
+        let current = table
+            .rows
+            .get(&id)
+            .filter(|row| O::get_deleted_at_column().is_none_or(|c| !row.contains_key(c)))
+            .map(|row| i32::from_scalar(row.get("version")));
+        if current != Some(version) {
+            return Err(match current {
+                Some(current_version) => CoreError::Conflict(
+                    "error.version.conflict",
+                    HashMap::from([
+                        ("id".into(), id.to_string()),
+                        ("expected_version".into(), version.to_string()),
+                        ("current_version".into(), current_version.to_string()),
+                    ]),
+                ),
+                None => CoreError::not_found("error.not_found"),
+            });
+        }
+        let mut row = table.rows[&id].clone();
+        row.insert("version".to_string(), ScalarValue::Int(version + 1));
+        row.insert("updated_at".to_string(), now());
+        Self::set_values(&mut row, &values, &O::updatable_columns());
+        Self::check_unique(table, id, &row)?;
+        table.rows.insert(id, row.clone());
+        Ok(row)
+    }
+
+    /// Rows marked deleted when the table has a trash, removed otherwise.
+    fn delete_where(&self, matches: impl Fn(&Row) -> bool) -> Result<u64, CoreError> {
+        let mut state = self.state()?;
+        let table = state.table_mut(O::get_table_name());
+        let ids = table
+            .rows
+            .iter()
+            .filter(|(_, row)| matches(row))
+            .map(|(id, _)| *id)
+            .collect::<Vec<_>>();
+        let mut affected = 0;
+        for id in ids {
+            match O::get_deleted_at_column() {
+                Some(column) => {
+                    let row = table.rows.get_mut(&id).expect("row exists");
+                    if !row.contains_key(column) {
+                        row.insert(column.to_string(), now());
+                        affected += 1;
+                    }
+                }
+                None => {
+                    table.rows.remove(&id);
+                    affected += 1;
+                }
+            }
+        }
+        Ok(affected)
+    }
+
+    fn snapshots_where(
+        &self,
+        scope: SoftDeleteScope,
+        matches: impl Fn(&Row) -> bool,
+    ) -> Result<Vec<Snapshot>, CoreError> {
+        let state = self.state()?;
+        let unaudited = O::get_unaudited_columns();
+        let audited = O::updatable_columns()
+            .into_iter()
+            .filter(|c| !unaudited.contains(c))
+            .collect::<Vec<_>>();
+        let scope = Filter::SoftDeleted(scope);
+        Ok(state
+            .rows(O::get_table_name())
+            .filter(|row| Self::evaluate(&state, row, &scope) == Some(true) && matches(row))
+            .map(|row| Snapshot {
+                id: i32::from_scalar(row.get("id")),
+                uid: String::from_scalar(row.get("uid")),
+                values: audited
+                    .iter()
+                    .map(|c| (c.to_string(), row.get(*c).map_or(Value::Null, to_json)))
+                    .collect::<Map<_, _>>(),
+            })
+            .collect())
+    }
+}
+
+fn parse_uids(uids: Vec<String>) -> Result<Vec<ScalarValue>, CoreError> {
+    uids.into_iter()
+        .map(|s| match Uuid::parse_str(&s) {
+            Ok(u) => Ok(ScalarValue::String(u.to_string())),
+            Err(_) => Err(CoreError::bad_request("error.invalid.uid")),
+        })
+        .collect()
+}
+
+fn now() -> ScalarValue {
+    ScalarValue::DateTime(stored_datetime(OffsetDateTime::now_utc()))
+}
+
+impl<T, O, C> Repository<T, C> for InMemoryRepository<T, O, C>
+where
+    C: Creatable<Entity = T>,
+    O: InMemoryViewMeta + OrmMeta + for<'a> From<&'a C> + for<'a> From<&'a T>,
+    T: From<O> + BizEntity,
+{
+    async fn delete_by_id(&self, id: i32) -> Result<u64, CoreError> {
+        self.delete_where(|row| row.get("id") == Some(&ScalarValue::Int(id)))
+    }
+
+    async fn delete_by_ids(&self, ids: Vec<i32>) -> Result<u64, CoreError> {
+        self.delete_where(|row| ids.contains(&i32::from_scalar(row.get("id"))))
+    }
+
+    async fn delete_by_uid(&self, uid: String) -> Result<u64, CoreError> {
+        let uids = parse_uids(vec![uid])?;
+        self.delete_where(|row| row.get("uid").is_some_and(|uid| uids.contains(uid)))
+    }
+
+    async fn delete_by_uids(&self, uids: Vec<String>) -> Result<u64, CoreError> {
+        let uids = parse_uids(uids)?;
+        self.delete_where(|row| row.get("uid").is_some_and(|uid| uids.contains(uid)))
+    }
+
+    async fn restore_by_id(&self, id: i32) -> Result<u64, CoreError> {
+        self.restore_by_ids(vec![id]).await
+    }
+
+    async fn restore_by_ids(&self, ids: Vec<i32>) -> Result<u64, CoreError> {
+        let Some(column) = O::get_deleted_at_column() else {
+            return Ok(0);
+        };
+        let mut state = self.state()?;
+        let table = state.table_mut(O::get_table_name());
+        Ok(ids
+            .iter()
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .filter_map(|id| table.rows.get_mut(id)?.remove(column))
+            .count() as u64)
+    }
+
+    async fn purge_by_id(&self, id: i32) -> Result<u64, CoreError> {
+        self.purge_by_ids(vec![id]).await
+    }
+
+    async fn purge_by_ids(&self, ids: Vec<i32>) -> Result<u64, CoreError> {
+        let mut state = self.state()?;
+        let table = state.table_mut(O::get_table_name());
+        let mut affected = 0;
+        for id in ids.iter().collect::<HashSet<_>>() {
+            let trashed = match (table.rows.get(id), O::get_deleted_at_column()) {
+                (Some(row), Some(column)) => row.contains_key(column),
+                (Some(_), None) => true,
+                (None, _) => false,
+            };
+            if trashed {
+                table.rows.remove(id);
+                affected += 1;
+            }
+        }
+        Ok(affected)
+    }
+
+    async fn create(&self, entity_create: &C) -> Result<T, CoreError> {
+        let mut state = self.state()?;
+        let row = Self::insert(
+            state.table_mut(O::get_table_name()),
+            O::from(entity_create).to_values(),
+        )?;
+        Ok(T::from(O::from_values(&row)))
+    }
+
+    async fn update(&self, entity: &T) -> Result<T, CoreError> {
+        let mut state = self.state()?;
+        let row = Self::update_row(
+            state.table_mut(O::get_table_name()),
+            O::from(entity).to_values(),
+        )?;
+        Ok(T::from(O::from_values(&row)))
+    }
+
+    async fn create_many(&self, creates: &[C]) -> Result<Vec<T>, CoreError> {
+        let mut state = self.state()?;
+        let table = state.table_mut(O::get_table_name());
+        let mut working = table.clone();
+        let rows = creates
+            .iter()
+            .map(|c| Self::insert(&mut working, O::from(c).to_values()))
+            .collect::<Result<Vec<_>, _>>()?;
+        *table = working;
+        Ok(rows
+            .iter()
+            .map(|row| T::from(O::from_values(row)))
+            .collect())
+    }
+
+    async fn update_many(&self, entities: &[T]) -> Result<Vec<T>, CoreError> {
+        let mut state = self.state()?;
+        let table = state.table_mut(O::get_table_name());
+        let mut working = table.clone();
+        let rows = entities
+            .iter()
+            .map(|e| Self::update_row(&mut working, O::from(e).to_values()))
+            .collect::<Result<Vec<_>, _>>()?;
+        *table = working;
+        Ok(rows
+            .iter()
+            .map(|row| T::from(O::from_values(row)))
+            .collect())
+    }
+
+    async fn upsert_by(&self, key: &[&str], entities: &[T]) -> Result<Vec<T>, CoreError> {
+        self.validate_upsert_key(key)?;
+        if entities.is_empty() {
+            return Ok(vec![]);
+        }
+        // ON CONFLICT needs a unique constraint on exactly the key columns
+        let key_set = key.iter().collect::<HashSet<_>>();
+        if !O::get_unique_keys()
+            .iter()
+            .any(|k| k.iter().collect::<HashSet<_>>() == key_set)
+        {
+            return Err(db_error(&format!(
+                "no unique constraint on ({}) in {}",
+                key.join(", "),
+                O::get_table_name()
+            )));
+        }
+
+        let mut state = self.state()?;
+        let table = state.table_mut(O::get_table_name());
+        let mut working = table.clone();
+        let mut touched = HashSet::new();
+        let mut rows = Vec::with_capacity(entities.len());
+        for entity in entities {
+            let values = O::from(entity)
+                .to_values()
+                .into_iter()
+                .collect::<HashMap<_, _>>();
+            let key_values = key
+                .iter()
+                .map(|c| values.get(c).cloned().flatten())
+                .collect::<Option<Vec<_>>>();
+            let existing = key_values.as_ref().and_then(|key_values| {
+                working.rows.iter().find_map(|(id, row)| {
+                    key.iter()
+                        .zip(key_values)
+                        .all(|(c, v)| row.get(*c) == Some(v))
+                        .then_some(*id)
+                })
+            });
+            let row = match existing {
+                Some(id) => {
+                    if !touched.insert(id) {
+                        return Err(db_error("upsert affects a row twice"));
+                    }
+                    let mut row = working.rows[&id].clone();
+                    let version = i32::from_scalar(row.get("version"));
+                    row.insert("version".to_string(), ScalarValue::Int(version + 1));
+                    row.insert("updated_at".to_string(), now());
+                    let insert_only = O::get_insert_only_columns();
+                    let columns = O::updatable_columns()
+                        .into_iter()
+                        .filter(|c| !key.contains(c) && !insert_only.contains(c))
+                        .collect::<Vec<_>>();
+                    Self::set_values(&mut row, &values, &columns);
+                    if let Some(column) = O::get_deleted_at_column() {
+                        row.remove(column);
+                    }
+                    Self::check_unique(&working, id, &row)?;
+                    working.rows.insert(id, row.clone());
+                    row
+                }
+                None => {
+                    let mut values = values;
+                    values.insert("uid", Some(ScalarValue::String(Uuid::now_v7().to_string())));
+                    values.insert("version", Some(ScalarValue::Int(0)));
+                    values.insert("created_at", Some(now()));
+                    values.insert("updated_at", Some(now()));
+                    let row = Self::insert(&mut working, values.into_iter().collect())?;
+                    touched.insert(i32::from_scalar(row.get("id")));
+                    row
+                }
+            };
+            rows.push(row);
+        }
+        *table = working;
+        Ok(rows
+            .iter()
+            .map(|row| T::from(O::from_values(row)))
+            .collect())
+    }
+
+    async fn get_attribute_type_map(&self) -> Result<HashMap<String, ScalarValue>, CoreError> {
+        let entity_type = O::get_table_name().to_string();
+        let state = self.state()?;
+        let mut map: HashMap<String, ScalarValue> = HashMap::new();
+        for row in state.rows("attributes").filter(|row| {
+            !row.contains_key(DELETED_AT_COLUMN)
+                && row.get("entity_type") == Some(&ScalarValue::String(entity_type.clone()))
+        }) {
+            let name = String::from_scalar(row.get("name"));
+            let data_type = String::from_scalar(row.get("data_type"));
+            if map.contains_key(&name) {
+                return Err(CoreError::UnprocessableEntity(
+                    "error.attribute.duplicate.name",
+                    HashMap::from([
+                        ("name".into(), name),
+                        ("entity_type".into(), entity_type.clone()),
+                    ]),
+                ));
+            }
+            let Some(scalar) = attribute_prototype(&data_type) else {
+                return Err(CoreError::UnprocessableEntity(
+                    "error.attribute.unsupported.datatype",
+                    HashMap::from([("datatype".into(), data_type), ("name".into(), name)]),
+                ));
+            };
+            map.insert(name, scalar);
+        }
+        Ok(map)
+    }
+
+    async fn snapshot_by_ids(
+        &self,
+        ids: Vec<i32>,
+        scope: SoftDeleteScope,
+    ) -> Result<Vec<Snapshot>, CoreError> {
+        self.snapshots_where(scope, |row| ids.contains(&i32::from_scalar(row.get("id"))))
+    }
+
+    async fn snapshot_by_uids(
+        &self,
+        uids: Vec<String>,
+        scope: SoftDeleteScope,
+    ) -> Result<Vec<Snapshot>, CoreError> {
+        let uids = parse_uids(uids)?;
+        self.snapshots_where(scope, |row| {
+            row.get("uid").is_some_and(|uid| uids.contains(uid))
+        })
+    }
+
+    async fn snapshot_by_key(
+        &self,
+        key: &[&str],
+        entities: &[T],
+        scope: SoftDeleteScope,
+    ) -> Result<Vec<Snapshot>, CoreError> {
+        self.validate_upsert_key(key)?;
+        let key_values = entities
+            .iter()
+            .map(|e| {
+                let values = O::from(e)
+                    .to_values()
+                    .into_iter()
+                    .collect::<HashMap<_, _>>();
+                key.iter()
+                    .map(|c| values.get(c).cloned().flatten())
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        self.snapshots_where(scope, |row| {
+            key_values.iter().any(|values| {
+                key.iter()
+                    .zip(values)
+                    .all(|(c, v)| v.is_some() && row.get(*c) == v.as_ref())
+            })
+        })
+    }
+
+    async fn record_audit(&self, entries: Vec<AuditEntry>) -> Result<(), CoreError> {
+        let entity_uids = entries
+            .iter()
+            .map(AuditEntry::entity_uuid)
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut state = self.state()?;
+        let table = state.table_mut(AUDIT_LOG_TABLE);
+        for (entry, entity_uid) in entries.into_iter().zip(entity_uids) {
+            table.last_id += 1;
+            let mut row = Row::from([
+                ("id".to_string(), ScalarValue::Int(table.last_id)),
+                (
+                    "uid".to_string(),
+                    ScalarValue::String(Uuid::now_v7().to_string()),
+                ),
+                ("version".to_string(), ScalarValue::Int(0)),
+                ("created_at".to_string(), now()),
+                ("updated_at".to_string(), now()),
+                (
+                    "entity_type".to_string(),
+                    ScalarValue::String(O::get_table_name().to_string()),
+                ),
+                ("entity_id".to_string(), ScalarValue::Int(entry.entity_id)),
+                (
+                    "entity_uid".to_string(),
+                    ScalarValue::String(entity_uid.to_string()),
+                ),
+                (
+                    "operation".to_string(),
+                    ScalarValue::String(entry.operation.as_str().to_string()),
+                ),
+                (
+                    "changes".to_string(),
+                    ScalarValue::String(Value::Object(entry.changes).to_string()),
+                ),
+            ]);
+            if let Some(user_id) = entry.user_id {
+                row.insert("user_id".to_string(), ScalarValue::Int(user_id));
+            }
+            table.rows.insert(table.last_id, row);
+        }
+        Ok(())
+    }
+}
+
+impl<T, O, C> Transactional for InMemoryRepository<T, O, C> {
+    type Tx = InMemoryTransaction;
+
+    async fn begin(&self) -> Result<InMemoryTransaction, CoreError> {
+        Ok(match &self.transaction {
+            Some(tx) => InMemoryTransaction {
+                database: tx.database.clone(),
+                snapshot: tx.snapshot.clone(),
+                owner: false,
+            },
+            None => InMemoryTransaction {
+                database: self.database.clone(),
+                snapshot: Arc::new(Mutex::new(Some(self.database.lock().clone()))),
+                owner: true,
+            },
+        })
+    }
+
+    fn in_transaction(&self, tx: &InMemoryTransaction) -> Self {
+        Self {
+            database: tx.database.clone(),
+            transaction: Some(tx.clone()),
+            marker: PhantomData,
+        }
+    }
+}
+
+/// Postgres keeps timestamps to the microsecond, in UTC.
+fn stored_datetime(value: OffsetDateTime) -> OffsetDateTime {
+    let value = value.to_offset(UtcOffset::UTC);
+    value
+        .replace_nanosecond(value.nanosecond() / 1_000 * 1_000)
+        .unwrap_or(value)
+}
+
+/// The value cast to `text`.
+fn to_text(value: &ScalarValue) -> String {
+    match value {
+        ScalarValue::String(v) => v.clone(),
+        ScalarValue::Int(v) => v.to_string(),
+        ScalarValue::Float(v) => v.to_string(),
+        ScalarValue::Bool(v) => v.to_string(),
+        ScalarValue::Date(v) => v.to_string(),
+        ScalarValue::DateTime(v) => v.format(&Rfc3339).unwrap_or_default(),
+        ScalarValue::Time(v) => v.to_string(),
+    }
+}
+
+/// The value as `jsonb_build_object` writes it.
+fn to_json(value: &ScalarValue) -> Value {
+    match value {
+        ScalarValue::Int(v) => Value::from(*v),
+        ScalarValue::Float(v) => Value::from(*v),
+        ScalarValue::Bool(v) => Value::from(*v),
+        ScalarValue::DateTime(v) => {
+            let text = v.format(&Rfc3339).unwrap_or_default();
+            Value::from(match text.strip_suffix('Z') {
+                Some(text) => format!("{text}+00:00"),
+                None => text,
+            })
+        }
+        other => Value::from(to_text(other)),
+    }
+}
+
+fn compare(a: &ScalarValue, b: &ScalarValue) -> Option<Ordering> {
+    use ScalarValue::*;
+    match (a, b) {
+        (String(a), String(b)) => Some(a.cmp(b)),
+        (Int(a), Int(b)) => Some(a.cmp(b)),
+        (Int(a), Float(b)) => (*a as f64).partial_cmp(b),
+        (Float(a), Int(b)) => a.partial_cmp(&(*b as f64)),
+        (Float(a), Float(b)) => a.partial_cmp(b),
+        (Bool(a), Bool(b)) => Some(a.cmp(b)),
+        (Date(a), Date(b)) => Some(a.cmp(b)),
+        (DateTime(a), DateTime(b)) => Some(a.cmp(b)),
+        (Time(a), Time(b)) => Some(a.cmp(b)),
+        _ => None,
+    }
+}
+
+/// Ascending order of sort keys, `NULL`s last as in Postgres.
+fn compare_keys(a: &[Option<ScalarValue>], b: &[Option<ScalarValue>]) -> Ordering {
+    a.iter()
+        .zip(b)
+        .map(|(a, b)| match (a, b) {
+            (Some(a), Some(b)) => compare(a, b).unwrap_or(Ordering::Equal),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        })
+        .find(|o| o.is_ne())
+        .unwrap_or(Ordering::Equal)
+}
+
+fn and3(a: Option<bool>, b: Option<bool>) -> Option<bool> {
+    match (a, b) {
+        (Some(false), _) | (_, Some(false)) => Some(false),
+        (Some(true), Some(true)) => Some(true),
+        _ => None,
+    }
+}
+
+fn or3(a: Option<bool>, b: Option<bool>) -> Option<bool> {
+    match (a, b) {
+        (Some(true), _) | (_, Some(true)) => Some(true),
+        (Some(false), Some(false)) => Some(false),
+        _ => None,
+    }
+}
+
+/// SQL `AND` of all `values`, `true` for none.
+fn all3(values: impl Iterator<Item = Option<bool>>) -> Option<bool> {
+    let mut result = Some(true);
+    for value in values {
+        result = and3(result, value);
+    }
+    result
+}
+
+/// SQL `OR` of all `values`, `false` for none.
+fn any3(values: impl Iterator<Item = Option<bool>>) -> Option<bool> {
+    let mut result = Some(false);
+    for value in values {
+        result = or3(result, value);
+    }
+    result
+}
+
+/// `column = value`, to the second for timestamps as `build_property_filter` does.
+fn equal(column: &ScalarValue, value: &ScalarValue) -> Option<bool> {
+    let truncate = |v: &OffsetDateTime| v.replace_nanosecond(0).unwrap_or(*v);
+    match (column, value) {
+        (ScalarValue::DateTime(c), ScalarValue::DateTime(v)) => Some(truncate(c) == truncate(v)),
+        _ => compare(column, value).map(Ordering::is_eq),
+    }
+}
+
+/// `column <operator> value` with SQL's NULL semantics; `None` is unknown.
+fn apply_operator(
+    column: Option<&ScalarValue>,
+    operator: &FilterOperator,
+    value: &FilterValue,
+) -> Option<bool> {
+    match operator {
+        FilterOperator::IsNull => return Some(column.is_none()),
+        FilterOperator::NotNull => return Some(column.is_some()),
+        _ => {}
+    }
+    let column = column?;
+    let ordering = |v: &ScalarValue| compare(column, v);
+    let in_list = |values: &[ScalarValue]| any3(values.iter().map(|v| equal(column, v)));
+    let like = |pattern: &str| match column {
+        ScalarValue::String(text) => Some(like_match(text, &format!("%{pattern}%"))),
+        _ => None,
+    };
+    match (operator, value) {
+        (FilterOperator::Equal, FilterValue::Single(v)) => equal(column, v),
+        (FilterOperator::NotEqual, FilterValue::Single(v)) => equal(column, v).map(|e| !e),
+        (FilterOperator::GreaterThan, FilterValue::Single(v)) => ordering(v).map(Ordering::is_gt),
+        (FilterOperator::GreaterThanOrEqual, FilterValue::Single(v)) => {
+            ordering(v).map(Ordering::is_ge)
+        }
+        (FilterOperator::LessThan, FilterValue::Single(v)) => ordering(v).map(Ordering::is_lt),
+        (FilterOperator::LessThanOrEqual, FilterValue::Single(v)) => {
+            ordering(v).map(Ordering::is_le)
+        }
+        (FilterOperator::Like, FilterValue::Single(ScalarValue::String(v))) => like(v),
+        (FilterOperator::NotLike, FilterValue::Single(ScalarValue::String(v))) => {
+            like(v).map(|m| !m)
+        }
+        (FilterOperator::Similar, FilterValue::Single(ScalarValue::String(v))) => match column {
+            ScalarValue::String(text) => {
+                Some(word_similarity(v, text) >= crate::db::similarity_threshold())
+            }
+            _ => None,
+        },
+        (FilterOperator::In, FilterValue::List(values)) => in_list(values),
+        (FilterOperator::NotIn, FilterValue::List(values)) => in_list(values).map(|m| !m),
+        (FilterOperator::Between, FilterValue::Range((low, high))) => and3(
+            ordering(low).map(Ordering::is_ge),
+            ordering(high).map(Ordering::is_le),
+        ),
+        (FilterOperator::NotBetween, FilterValue::Range((low, high))) => and3(
+            ordering(low).map(Ordering::is_ge),
+            ordering(high).map(Ordering::is_le),
+        )
+        .map(|m| !m),
+        // Statements Postgres would reject match nothing
+        _ => None,
+    }
+}
+
+/// `attribute_values` column an attribute filter compares, by the value's type.
+fn attribute_value_column(value: &FilterValue) -> &'static str {
+    let scalar = match value {
+        FilterValue::Single(s) => Some(s),
+        FilterValue::List(vs) => vs.first(),
+        FilterValue::Range((a, _)) => Some(a),
+        FilterValue::None => None,
+    };
+    match scalar {
+        Some(ScalarValue::Int(_)) => "int_value",
+        Some(ScalarValue::Float(_)) => "double_value",
+        Some(ScalarValue::Bool(_)) => "boolean_value",
+        Some(ScalarValue::Date(_)) => "date_value",
+        Some(ScalarValue::DateTime(_)) => "datetime_value",
+        Some(ScalarValue::Time(_)) => "time_value",
+        Some(ScalarValue::String(_)) | None => "string_value",
+    }
+}
+
+/// SQL `LIKE`: `%` matches any run of characters, `_` any one, `\` escapes.
+fn like_match(text: &str, pattern: &str) -> bool {
+    let text = text.chars().collect::<Vec<_>>();
+    let pattern = pattern.chars().collect::<Vec<_>>();
+    // (literal character or `None` for any one, whether it is `%`)
+    let mut tokens = vec![];
+    let mut j = 0;
+    while j < pattern.len() {
+        match pattern[j] {
+            '\\' if j + 1 < pattern.len() => {
+                tokens.push((Some(pattern[j + 1]), false));
+                j += 2;
+            }
+            '%' => {
+                tokens.push((None, true));
+                j += 1;
+            }
+            '_' => {
+                tokens.push((None, false));
+                j += 1;
+            }
+            c => {
+                tokens.push((Some(c), false));
+                j += 1;
+            }
+        }
+    }
+    // matches[k]: tokens[..k] match the text read so far
+    let mut matches = vec![false; tokens.len() + 1];
+    matches[0] = true;
+    for k in 0..tokens.len() {
+        matches[k + 1] = matches[k] && tokens[k].1;
+    }
+    for c in text {
+        let mut next = vec![false; tokens.len() + 1];
+        for (k, (literal, any_run)) in tokens.iter().enumerate() {
+            next[k + 1] = if *any_run {
+                next[k] || matches[k + 1]
+            } else {
+                matches[k] && literal.is_none_or(|l| l == c)
+            };
+        }
+        matches = next;
+    }
+    matches[tokens.len()]
+}
+
+/// Accent-free lowercase text, like the `unaccent` dictionary gives.
+fn fold(text: &str) -> String {
+    const FOLDS: [(&str, char); 14] = [
+        ("àáâãäåāăąǎạảấầẩẫậắằẳẵặ", 'a'),
+        ("çćĉċč", 'c'),
+        ("ďđ", 'd'),
+        ("èéêëēĕėęěẹẻẽếềểễệ", 'e'),
+        ("ĝğġģ", 'g'),
+        ("ìíîïĩīĭįǐịỉ", 'i'),
+        ("ñńņňŉ", 'n'),
+        ("òóôõöøōŏőǒọỏốồổỗộớờởỡợơ", 'o'),
+        ("ŕŗř", 'r'),
+        ("śŝşšș", 's'),
+        ("ţťŧț", 't'),
+        ("ùúûüũūŭůűųǔụủứừửữựư", 'u'),
+        ("ýÿŷỳỵỷỹ", 'y'),
+        ("źżž", 'z'),
+    ];
+    text.to_lowercase()
+        .chars()
+        .map(|c| {
+            FOLDS
+                .iter()
+                .find(|(accented, _)| accented.contains(c))
+                .map_or(c, |(_, plain)| *plain)
+        })
+        .collect()
+}
+
+/// Words of `text` as the search configuration indexes them.
+fn lexemes(text: &str) -> Vec<String> {
+    fold(text)
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// The weighted search vector of a row: its lexemes in order, each with the
+/// weight of the column it comes from.
+fn search_document(row: &Row, vector_columns: &[(&str, f32)]) -> Vec<(String, f32)> {
+    vector_columns
+        .iter()
+        .flat_map(|(column, weight)| {
+            lexemes(&row.get(*column).map(to_text).unwrap_or_default())
+                .into_iter()
+                .map(move |lexeme| (lexeme, *weight))
+        })
+        .collect()
+}
+
+/// One `&`-separated part of a query built by `to_tsquery`.
+enum QueryPart {
+    Prefix(String),
+    Word(String),
+    Phrase(Vec<String>),
+}
+
+impl QueryPart {
+    fn matches_word(&self, word: &str) -> bool {
+        match self {
+            QueryPart::Prefix(prefix) => word.starts_with(prefix.as_str()),
+            QueryPart::Word(w) => word == w,
+            QueryPart::Phrase(words) => words.iter().any(|w| w == word),
+        }
+    }
+
+    /// Positions in `document` where this part matches.
+    fn positions(&self, document: &[(String, f32)]) -> Vec<usize> {
+        match self {
+            QueryPart::Phrase(words) => (0..document.len())
+                .filter(|&i| {
+                    i + words.len() <= document.len()
+                        && words
+                            .iter()
+                            .enumerate()
+                            .all(|(k, w)| document[i + k].0 == *w)
+                })
+                .collect(),
+            part => (0..document.len())
+                .filter(|&i| part.matches_word(&document[i].0))
+                .collect(),
+        }
+    }
+}
+
+fn parse_tsquery(tsquery: &str) -> Vec<QueryPart> {
+    tsquery
+        .split(" & ")
+        .map(|part| {
+            if let Some(phrase) = part.strip_prefix('(').and_then(|p| p.strip_suffix(')')) {
+                QueryPart::Phrase(phrase.split(" <-> ").map(fold).collect())
+            } else if let Some(prefix) = part.strip_suffix(":*") {
+                QueryPart::Prefix(fold(prefix))
+            } else {
+                QueryPart::Word(fold(part))
+            }
+        })
+        .collect()
+}
+
+/// At most two fragments of `text` of up to 30 words each, with the words
+/// matching `parts` between `HIGHLIGHT_START` and `HIGHLIGHT_STOP`, joined by
+/// " ... ". Without a match, the first 12 words.
+fn headline(text: &str, parts: &[QueryPart]) -> String {
+    const MAX_WORDS: usize = 30;
+    const MIN_WORDS: usize = 12;
+    // Byte ranges of the words of the text
+    let mut words: Vec<(usize, usize)> = vec![];
+    let mut start = None;
+    for (i, c) in text
+        .char_indices()
+        .chain(std::iter::once((text.len(), ' ')))
+    {
+        match (c.is_alphanumeric(), start) {
+            (true, None) => start = Some(i),
+            (false, Some(s)) => {
+                words.push((s, i));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    let matched = words
+        .iter()
+        .map(|&(s, e)| {
+            let word = fold(&text[s..e]);
+            parts.iter().any(|p| p.matches_word(&word))
+        })
+        .collect::<Vec<_>>();
+
+    let mut fragments: Vec<(usize, usize)> = vec![];
+    for (i, _) in matched.iter().enumerate().filter(|(_, m)| **m) {
+        if fragments.len() == 2 || fragments.iter().any(|&(s, e)| (s..e).contains(&i)) {
+            continue;
+        }
+        let start = i.saturating_sub(MAX_WORDS / 3);
+        fragments.push((start, (start + MAX_WORDS).min(words.len())));
+    }
+    if fragments.is_empty() {
+        fragments.push((0, MIN_WORDS.min(words.len())));
+    }
+
+    fragments
+        .into_iter()
+        .filter(|(s, e)| s < e)
+        .map(|(first, end)| {
+            let mut fragment = String::new();
+            let mut cursor = words[first].0;
+            for (k, &(s, e)) in words.iter().enumerate().take(end).skip(first) {
+                fragment.push_str(&text[cursor..s]);
+                if matched[k] {
+                    fragment.push(HIGHLIGHT_START);
+                    fragment.push_str(&text[s..e]);
+                    fragment.push(HIGHLIGHT_STOP);
+                } else {
+                    fragment.push_str(&text[s..e]);
+                }
+                cursor = e;
+            }
+            fragment
+        })
+        .collect::<Vec<_>>()
+        .join(" ... ")
+}
+
+/// Trigrams of each word of `text`, in order, as pg_trgm extracts them: the
+/// lowercased word padded with two spaces in front and one behind.
+fn trigrams(text: &str) -> Vec<[char; 3]> {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .flat_map(|word| {
+            let padded = [' ', ' ']
+                .into_iter()
+                .chain(word.chars())
+                .chain([' '])
+                .collect::<Vec<_>>();
+            let mut word_trigrams = padded
+                .windows(3)
+                .map(|w| [w[0], w[1], w[2]])
+                .collect::<Vec<_>>();
+            let mut seen = HashSet::new();
+            word_trigrams.retain(|t| seen.insert(*t));
+            word_trigrams
+        })
+        .collect()
+}
+
+/// pg_trgm's `word_similarity(query, text)`: the best similarity between the
+/// trigrams of `query` and those of any run of consecutive trigrams of `text`.
+fn word_similarity(query: &str, text: &str) -> f32 {
+    let query = trigrams(query).into_iter().collect::<HashSet<_>>();
+    let text = trigrams(text);
+    if query.is_empty() || text.is_empty() {
+        return 0.0;
+    }
+    let mut best = 0.0f32;
+    for start in 0..text.len() {
+        let mut extent = HashSet::new();
+        let mut shared = 0usize;
+        for trigram in &text[start..] {
+            if extent.insert(*trigram) && query.contains(trigram) {
+                shared += 1;
+            }
+            let similarity = shared as f32 / (query.len() + extent.len() - shared) as f32;
+            best = best.max(similarity);
+        }
+    }
+    best
+}
+
+/// Aggregate of `rows`: counts, sums and averages as floats, minimums and
+/// maximums in the column's type, `None` when no row has a value.
+fn aggregate_value(aggregate: &Aggregate, rows: &[Row]) -> Option<ScalarValue> {
+    let Some(field) = &aggregate.field else {
+        return Some(ScalarValue::Float(rows.len() as f64));
+    };
+    let values = rows
+        .iter()
+        .filter_map(|row| row.get(field))
+        .collect::<Vec<_>>();
+    let numbers = || {
+        values.iter().filter_map(|v| match v {
+            ScalarValue::Int(v) => Some(*v as f64),
+            ScalarValue::Float(v) => Some(*v),
+            _ => None,
+        })
+    };
+    match aggregate.function {
+        AggregateFunction::Count => Some(ScalarValue::Float(values.len() as f64)),
+        _ if values.is_empty() => None,
+        AggregateFunction::Sum => Some(ScalarValue::Float(numbers().sum())),
+        AggregateFunction::Avg => Some(ScalarValue::Float(
+            numbers().sum::<f64>() / values.len() as f64,
+        )),
+        AggregateFunction::Min => values
+            .into_iter()
+            .min_by(|a, b| compare(a, b).unwrap_or(Ordering::Equal))
+            .cloned(),
+        AggregateFunction::Max => values
+            .into_iter()
+            .max_by(|a, b| compare(a, b).unwrap_or(Ordering::Equal))
+            .cloned(),
+    }
+}
+
+/// Whether `row` comes after `cursor` in `order`, which ends with `id`:
+/// `(k0 > v0 OR (k0 = v0 AND (... id > v_id)))`, flipped for descending keys.
+/// NULL is past every value, as in the SQL.
+fn keyset_predicate(row: &Row, order: &[SortCriterion], cursor: &Cursor) -> Option<bool> {
+    let values = cursor
+        .keys
+        .iter()
+        .cloned()
+        .chain(std::iter::once(Some(ScalarValue::Int(cursor.id))))
+        .collect::<Vec<_>>();
+    order
+        .iter()
+        .zip(&values)
+        .rev()
+        .fold(None::<Option<bool>>, |inner, (criterion, value)| {
+            let column = row.get(&criterion.field).map(|c| match value {
+                // Compared as text, as the SQL casts string keys
+                Some(ScalarValue::String(_)) => ScalarValue::String(to_text(c)),
+                _ => c.clone(),
+            });
+            let ordering = match (&column, value) {
+                (Some(c), Some(v)) => compare(c, v),
+                (Some(_), None) => Some(Ordering::Less),
+                (None, Some(_)) => Some(Ordering::Greater),
+                (None, None) => Some(Ordering::Equal),
+            };
+            let beyond = ordering.map(|o| {
+                if criterion.ascending {
+                    o.is_gt()
+                } else {
+                    o.is_lt()
+                }
+            });
+            Some(match inner {
+                None => beyond,
+                Some(inner) => or3(beyond, and3(ordering.map(Ordering::is_eq), inner)),
+            })
+        })
+        .flatten()
+}
@@ -55,12 +55,48 @@ macro_rules! define_orm_with_common_fields {
                     }
                 }
             }
+
+            impl $crate::infras::in_memory_repository::OrmValues for [<$name Orm>] {
+                fn column_types() -> Vec<(&'static str, $crate::common::filter::ScalarValue)> {
+                    use $crate::infras::in_memory_repository::OrmColumn;
+                    vec![
+                        ("id", <i32 as OrmColumn>::prototype()),
+                        ("uid", <uuid::Uuid as OrmColumn>::prototype()),
+                        ("version", <i32 as OrmColumn>::prototype()),
+                        ("created_at", <time::OffsetDateTime as OrmColumn>::prototype()),
+                        ("updated_at", <time::OffsetDateTime as OrmColumn>::prototype()),
+                        $((stringify!($field), <$ty as OrmColumn>::prototype()),)*
+                    ]
+                }
+                fn to_values(&self) -> Vec<(&'static str, Option<$crate::common::filter::ScalarValue>)> {
+                    use $crate::infras::in_memory_repository::OrmColumn;
+                    vec![
+                        ("id", self.id.to_scalar()),
+                        ("uid", self.uid.to_scalar()),
+                        ("version", self.version.to_scalar()),
+                        ("created_at", self.created_at.to_scalar()),
+                        ("updated_at", self.updated_at.to_scalar()),
+                        $((stringify!($field), self.$field.to_scalar()),)*
+                    ]
+                }
+                fn from_values(values: &$crate::infras::in_memory_repository::Row) -> Self {
+                    use $crate::infras::in_memory_repository::OrmColumn;
+                    Self {
+                        id: OrmColumn::from_scalar(values.get("id")),
+                        uid: OrmColumn::from_scalar(values.get("uid")),
+                        version: OrmColumn::from_scalar(values.get("version")),
+                        created_at: OrmColumn::from_scalar(values.get("created_at")),
+                        updated_at: OrmColumn::from_scalar(values.get("updated_at")),
+                        $($field: OrmColumn::from_scalar(values.get(stringify!($field))),)*
+                    }
+                }
+            }
         }
     };
 }
 #[macro_export]
 macro_rules! define_readonly_orm_with_common_fields {
-    ($name:ident {$($field:tt)*}) => {
+    ($name:ident { $(pub $field:ident : $ty:ty,)* }) => {
         paste::paste! {
             #[derive(sqlx::FromRow, Debug)]
             pub struct [<$name Orm>] {
@@ -69,7 +105,7 @@ macro_rules! define_readonly_orm_with_common_fields {
                 pub version: i32,
                 pub created_at: time::OffsetDateTime,
                 pub updated_at: time::OffsetDateTime,
-                $($field)*
+                $(pub $field: $ty,)*
             }
 
             impl [<$name Orm>] {
@@ -77,6 +113,42 @@ macro_rules! define_readonly_orm_with_common_fields {
                     vec!["id", "uid", "version", "created_at", "updated_at", $(stringify!($field),)*]
                 }
             }
+
+            impl $crate::infras::in_memory_repository::OrmValues for [<$name Orm>] {
+                fn column_types() -> Vec<(&'static str, $crate::common::filter::ScalarValue)> {
+                    use $crate::infras::in_memory_repository::OrmColumn;
+                    vec![
+                        ("id", <i32 as OrmColumn>::prototype()),
+                        ("uid", <uuid::Uuid as OrmColumn>::prototype()),
+                        ("version", <i32 as OrmColumn>::prototype()),
+                        ("created_at", <time::OffsetDateTime as OrmColumn>::prototype()),
+                        ("updated_at", <time::OffsetDateTime as OrmColumn>::prototype()),
+                        $((stringify!($field), <$ty as OrmColumn>::prototype()),)*
+                    ]
+                }
+                fn to_values(&self) -> Vec<(&'static str, Option<$crate::common::filter::ScalarValue>)> {
+                    use $crate::infras::in_memory_repository::OrmColumn;
+                    vec![
+                        ("id", self.id.to_scalar()),
+                        ("uid", self.uid.to_scalar()),
+                        ("version", self.version.to_scalar()),
+                        ("created_at", self.created_at.to_scalar()),
+                        ("updated_at", self.updated_at.to_scalar()),
+                        $((stringify!($field), self.$field.to_scalar()),)*
+                    ]
+                }
+                fn from_values(values: &$crate::infras::in_memory_repository::Row) -> Self {
+                    use $crate::infras::in_memory_repository::OrmColumn;
+                    Self {
+                        id: OrmColumn::from_scalar(values.get("id")),
+                        uid: OrmColumn::from_scalar(values.get("uid")),
+                        version: OrmColumn::from_scalar(values.get("version")),
+                        created_at: OrmColumn::from_scalar(values.get("created_at")),
+                        updated_at: OrmColumn::from_scalar(values.get("updated_at")),
+                        $($field: OrmColumn::from_scalar(values.get(stringify!($field))),)*
+                    }
+                }
+            }
         }
     };
 }
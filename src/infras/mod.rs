@@ -1,21 +1,35 @@
 #[cfg(feature = "ssr")]
 pub mod attribute_sqlx_repository;
 #[cfg(feature = "ssr")]
+pub mod audit_log_sqlx_repository;
+#[cfg(feature = "ssr")]
 pub mod attribute_value_sqlx_repository;
 #[cfg(feature = "ssr")]
 pub mod error;
+#[cfg(feature = "ssr")]
+pub mod in_memory_repositories;
+#[cfg(feature = "ssr")]
+pub mod in_memory_repository;
 pub mod macros;
 #[cfg(feature = "ssr")]
 pub mod post_collection_sqlx_repository;
 #[cfg(feature = "ssr")]
+pub mod post_revision_sqlx_repository;
+#[cfg(feature = "ssr")]
 pub mod post_sqlx_repository;
 #[cfg(feature = "ssr")]
 pub mod post_taxonomy_sqlx_repository;
 #[cfg(feature = "ssr")]
 pub mod post_type_sqlx_repository;
+#[cfg(all(test, feature = "ssr"))]
+mod repository_conformance;
+#[cfg(feature = "ssr")]
+pub mod slug_redirect_sqlx_repository;
 #[cfg(feature = "ssr")]
 mod sqlx_repository;
 #[cfg(feature = "ssr")]
+pub mod sqlx_transaction;
+#[cfg(feature = "ssr")]
 pub mod term_sqlx_repository;
 #[cfg(feature = "ssr")]
 pub mod user_sqlx_repository;
@@ -4,11 +4,16 @@ use crate::business::post_collection_service::{
     PostCollection, PostCollectionCreate, PostCollectionInfo, PostCollectionInfoRepository,
     PostCollectionRepository,
 };
+use crate::business::slug_redirect_service::{
+    SlugRedirect, SlugRedirectCreate, SlugRedirectRecorder,
+};
+use crate::common::error::CoreError;
+use crate::infras::slug_redirect_sqlx_repository::SlugRedirectSqlxRepository;
 use crate::infras::sqlx_repository::{
     SqlxEntityMapper, SqlxRepository, SqlxViewMeta, SqlxViewRepository,
 };
 use crate::{define_orm_with_common_fields, define_readonly_orm_with_common_fields};
-use sqlx::PgPool;
+use crate::infras::sqlx_transaction::SqlxExecutor;
 use uuid::Uuid;
 
 define_orm_with_common_fields!(PostCollection {
@@ -57,11 +62,11 @@ impl From<PostCollectionInfoOrm> for PostCollectionInfo {
 
 #[derive(Clone)]
 pub struct PostCollectionSqlxRepository {
-    pool: PgPool,
+    executor: SqlxExecutor,
 }
 #[derive(Clone)]
 pub struct PostCollectionInfoSqlxRepository {
-    pool: PgPool,
+    executor: SqlxExecutor,
 }
 impl PostCollectionOrm {
     pub fn searchable_columns() -> Vec<&'static str> {
@@ -70,8 +75,10 @@ impl PostCollectionOrm {
 }
 
 impl PostCollectionSqlxRepository {
-    pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+    pub fn new(executor: impl Into<SqlxExecutor>) -> Self {
+        Self {
+            executor: executor.into(),
+        }
     }
 }
 
@@ -90,20 +97,19 @@ impl SqlxViewMeta for PostCollectionSqlxRepository {
 impl SqlxViewRepository for PostCollectionSqlxRepository {
     type Entity = PostCollection;
     type Orm = PostCollectionOrm;
-    fn get_pool(&self) -> &PgPool {
-        &self.pool
+    fn get_executor(&self) -> &SqlxExecutor {
+        &self.executor
+    }
+    fn with_executor(&self, executor: SqlxExecutor) -> Self {
+        Self { executor }
     }
     fn from_orm(orm: Self::Orm) -> Self::Entity {
         PostCollection::from(orm)
     }
 }
 
-impl SqlxEntityMapper for PostCollectionSqlxRepository {
-    type Entity = PostCollection;
-    type EntityCreate = PostCollectionCreate;
-    type Orm = PostCollectionOrm;
-
-    fn to_orm_from_create(&self, create: &Self::EntityCreate) -> Self::Orm {
+impl From<&PostCollectionCreate> for PostCollectionOrm {
+    fn from(create: &PostCollectionCreate) -> Self {
         let now = time::OffsetDateTime::now_utc();
         PostCollectionOrm {
             id: 0,
@@ -117,8 +123,10 @@ impl SqlxEntityMapper for PostCollectionSqlxRepository {
             visibility: create.visibility.clone(),
         }
     }
+}
 
-    fn to_orm_from_entity(&self, entity: &Self::Entity) -> Self::Orm {
+impl From<&PostCollection> for PostCollectionOrm {
+    fn from(entity: &PostCollection) -> Self {
         PostCollectionOrm {
             id: entity.id,
             uid: Uuid::parse_str(&entity.uid).unwrap_or_else(|_| Uuid::nil()),
@@ -133,12 +141,37 @@ impl SqlxEntityMapper for PostCollectionSqlxRepository {
     }
 }
 
+impl SqlxEntityMapper for PostCollectionSqlxRepository {
+    type Entity = PostCollection;
+    type EntityCreate = PostCollectionCreate;
+    type Orm = PostCollectionOrm;
+
+    fn to_orm_from_create(&self, create: &Self::EntityCreate) -> Self::Orm {
+        PostCollectionOrm::from(create)
+    }
+
+    fn to_orm_from_entity(&self, entity: &Self::Entity) -> Self::Orm {
+        PostCollectionOrm::from(entity)
+    }
+}
+
 impl SqlxRepository for PostCollectionSqlxRepository {
     type EntityCreate = PostCollectionCreate;
 }
 
 impl PostCollectionRepository for PostCollectionSqlxRepository {}
 
+impl SlugRedirectRecorder for PostCollectionSqlxRepository {
+    async fn record_slug_redirect(
+        &self,
+        redirect: &SlugRedirectCreate,
+    ) -> Result<SlugRedirect, CoreError> {
+        SlugRedirectSqlxRepository::new(self.executor.clone())
+            .record_slug_redirect(redirect)
+            .await
+    }
+}
+
 impl PostCollectionInfoOrm {
     pub fn searchable_columns() -> Vec<&'static str> {
         vec!["slug", "title", "description", "visibility"]
@@ -146,8 +179,10 @@ impl PostCollectionInfoOrm {
 }
 
 impl PostCollectionInfoSqlxRepository {
-    pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+    pub fn new(executor: impl Into<SqlxExecutor>) -> Self {
+        Self {
+            executor: executor.into(),
+        }
     }
 }
 
@@ -166,8 +201,11 @@ impl SqlxViewMeta for PostCollectionInfoSqlxRepository {
 impl SqlxViewRepository for PostCollectionInfoSqlxRepository {
     type Entity = PostCollectionInfo;
     type Orm = PostCollectionInfoOrm;
-    fn get_pool(&self) -> &PgPool {
-        &self.pool
+    fn get_executor(&self) -> &SqlxExecutor {
+        &self.executor
+    }
+    fn with_executor(&self, executor: SqlxExecutor) -> Self {
+        Self { executor }
     }
     fn from_orm(orm: Self::Orm) -> Self::Entity {
         PostCollectionInfo::from(orm)
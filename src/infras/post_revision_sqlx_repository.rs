@@ -0,0 +1,263 @@
+#![cfg(feature = "ssr")]
+
+use crate::business::post_revision_service::{
+    PostRevision, PostRevisionCreate, PostRevisionInfo, PostRevisionInfoRepository,
+    PostRevisionRepository, RevisionKind,
+};
+use crate::business::post_service::PostStatus;
+use crate::common::error::CoreError;
+use crate::infras::sqlx_repository::{
+    SqlxEntityMapper, SqlxRepository, SqlxViewMeta, SqlxViewRepository,
+};
+use crate::infras::sqlx_transaction::SqlxExecutor;
+use crate::{define_orm_with_common_fields, define_readonly_orm_with_common_fields};
+use std::str::FromStr;
+use uuid::Uuid;
+
+// Table: post_revisions
+define_orm_with_common_fields!(PostRevision {
+    pub post_id: i32,
+    pub kind: String,
+    pub title: String,
+    pub summary: String,
+    pub content: String,
+    pub status: i32,
+    pub user_id: Option<i32>,
+});
+
+// View: post_revisions_info
+define_readonly_orm_with_common_fields!(PostRevisionInfo {
+    pub post_id: i32,
+    pub kind: String,
+    pub title: String,
+    pub summary: String,
+    pub content: String,
+    pub status: i32,
+    pub user_id: Option<i32>,
+    pub username: Option<String>,
+});
+
+impl PostRevisionOrm {
+    pub fn searchable_columns() -> Vec<&'static str> {
+        vec!["title", "summary", "content"]
+    }
+}
+
+impl PostRevisionInfoOrm {
+    pub fn searchable_columns() -> Vec<&'static str> {
+        vec!["title", "summary", "content", "username"]
+    }
+}
+
+impl From<PostRevisionOrm> for PostRevision {
+    fn from(orm: PostRevisionOrm) -> Self {
+        Self {
+            id: orm.id,
+            uid: orm.uid.to_string(),
+            version: orm.version,
+            created_at: orm.created_at,
+            updated_at: orm.updated_at,
+            post_id: orm.post_id,
+            kind: RevisionKind::from_str(&orm.kind).unwrap_or(RevisionKind::AUTOSAVE),
+            title: orm.title,
+            summary: orm.summary,
+            content: orm.content,
+            status: PostStatus::from(orm.status),
+            user_id: orm.user_id,
+        }
+    }
+}
+
+impl From<PostRevisionInfoOrm> for PostRevisionInfo {
+    fn from(orm: PostRevisionInfoOrm) -> Self {
+        Self {
+            id: orm.id,
+            uid: orm.uid.to_string(),
+            version: orm.version,
+            created_at: orm.created_at,
+            updated_at: orm.updated_at,
+            post_id: orm.post_id,
+            kind: RevisionKind::from_str(&orm.kind).unwrap_or(RevisionKind::AUTOSAVE),
+            title: orm.title,
+            summary: orm.summary,
+            content: orm.content,
+            status: PostStatus::from(orm.status),
+            user_id: orm.user_id,
+            username: orm.username,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct PostRevisionSqlxRepository {
+    executor: SqlxExecutor,
+}
+#[derive(Clone)]
+pub struct PostRevisionInfoSqlxRepository {
+    executor: SqlxExecutor,
+}
+
+impl PostRevisionSqlxRepository {
+    pub fn new(executor: impl Into<SqlxExecutor>) -> Self {
+        Self {
+            executor: executor.into(),
+        }
+    }
+}
+
+impl SqlxViewMeta for PostRevisionSqlxRepository {
+    fn get_table_name(&self) -> &str {
+        "post_revisions"
+    }
+    fn get_columns(&self) -> Vec<&str> {
+        PostRevisionOrm::columns()
+    }
+    fn get_searchable_columns(&self) -> Vec<&str> {
+        PostRevisionOrm::searchable_columns()
+    }
+    // Pruned revisions are gone for good; they are copies to begin with
+    fn get_deleted_at_column(&self) -> Option<&str> {
+        None
+    }
+}
+
+impl SqlxViewRepository for PostRevisionSqlxRepository {
+    type Entity = PostRevision;
+    type Orm = PostRevisionOrm;
+    fn get_executor(&self) -> &SqlxExecutor {
+        &self.executor
+    }
+    fn with_executor(&self, executor: SqlxExecutor) -> Self {
+        Self { executor }
+    }
+    fn from_orm(orm: Self::Orm) -> Self::Entity {
+        PostRevision::from(orm)
+    }
+}
+
+impl From<&PostRevisionCreate> for PostRevisionOrm {
+    fn from(create: &PostRevisionCreate) -> Self {
+        let now = time::OffsetDateTime::now_utc();
+        PostRevisionOrm {
+            id: 0,
+            uid: Uuid::now_v7(),
+            version: 0,
+            created_at: now,
+            updated_at: now,
+            post_id: create.post_id,
+            kind: create.kind.as_str().to_string(),
+            title: create.title.clone(),
+            summary: create.summary.clone(),
+            content: create.content.clone(),
+            status: create.status.as_i32(),
+            user_id: create.user_id,
+        }
+    }
+}
+
+impl From<&PostRevision> for PostRevisionOrm {
+    fn from(entity: &PostRevision) -> Self {
+        PostRevisionOrm {
+            id: entity.id,
+            uid: Uuid::parse_str(&entity.uid).unwrap_or_else(|_| Uuid::nil()),
+            version: entity.version,
+            created_at: entity.created_at,
+            updated_at: entity.updated_at,
+            post_id: entity.post_id,
+            kind: entity.kind.as_str().to_string(),
+            title: entity.title.clone(),
+            summary: entity.summary.clone(),
+            content: entity.content.clone(),
+            status: entity.status.as_i32(),
+            user_id: entity.user_id,
+        }
+    }
+}
+
+impl SqlxEntityMapper for PostRevisionSqlxRepository {
+    type Entity = PostRevision;
+    type EntityCreate = PostRevisionCreate;
+    type Orm = PostRevisionOrm;
+
+    fn to_orm_from_create(&self, create: &Self::EntityCreate) -> Self::Orm {
+        PostRevisionOrm::from(create)
+    }
+
+    fn to_orm_from_entity(&self, entity: &Self::Entity) -> Self::Orm {
+        PostRevisionOrm::from(entity)
+    }
+}
+
+impl SqlxRepository for PostRevisionSqlxRepository {
+    type EntityCreate = PostRevisionCreate;
+}
+
+impl PostRevisionRepository for PostRevisionSqlxRepository {
+    async fn find_latest(&self, post_id: i32) -> Result<Option<PostRevision>, CoreError> {
+        let result = sqlx::query_as::<_, PostRevisionOrm>(
+            "SELECT * FROM post_revisions WHERE post_id=$1 ORDER BY created_at DESC, id DESC LIMIT 1",
+        )
+        .bind(post_id)
+        .fetch_optional(&mut *self.acquire().await?)
+        .await?;
+
+        Ok(result.map(Self::from_orm))
+    }
+
+    async fn delete_oldest(
+        &self,
+        post_id: i32,
+        kind: RevisionKind,
+        keep: usize,
+    ) -> Result<u64, CoreError> {
+        let result = sqlx::query(
+            "DELETE FROM post_revisions WHERE id IN (SELECT id FROM post_revisions WHERE post_id=$1 AND kind=$2 ORDER BY created_at DESC, id DESC OFFSET $3)",
+        )
+        .bind(post_id)
+        .bind(kind.as_str())
+        .bind(keep as i64)
+        .execute(&mut *self.acquire().await?)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+impl PostRevisionInfoSqlxRepository {
+    pub fn new(executor: impl Into<SqlxExecutor>) -> Self {
+        Self {
+            executor: executor.into(),
+        }
+    }
+}
+
+impl SqlxViewMeta for PostRevisionInfoSqlxRepository {
+    fn get_table_name(&self) -> &str {
+        "post_revisions_info"
+    }
+    fn get_columns(&self) -> Vec<&str> {
+        PostRevisionInfoOrm::columns()
+    }
+    fn get_searchable_columns(&self) -> Vec<&str> {
+        PostRevisionInfoOrm::searchable_columns()
+    }
+    fn get_deleted_at_column(&self) -> Option<&str> {
+        None
+    }
+}
+
+impl SqlxViewRepository for PostRevisionInfoSqlxRepository {
+    type Entity = PostRevisionInfo;
+    type Orm = PostRevisionInfoOrm;
+    fn get_executor(&self) -> &SqlxExecutor {
+        &self.executor
+    }
+    fn with_executor(&self, executor: SqlxExecutor) -> Self {
+        Self { executor }
+    }
+    fn from_orm(orm: Self::Orm) -> Self::Entity {
+        PostRevisionInfo::from(orm)
+    }
+}
+
+impl PostRevisionInfoRepository for PostRevisionInfoSqlxRepository {}
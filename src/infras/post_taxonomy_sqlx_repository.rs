@@ -8,7 +8,7 @@ use crate::infras::sqlx_repository::{
     SqlxEntityMapper, SqlxRepository, SqlxViewMeta, SqlxViewRepository,
 };
 use crate::{define_orm_with_common_fields, define_readonly_orm_with_common_fields};
-use sqlx::PgPool;
+use crate::infras::sqlx_transaction::SqlxExecutor;
 use uuid::Uuid;
 
 // Table
@@ -45,11 +45,11 @@ impl From<PostTaxonomyInfoOrm> for PostTaxonomyInfo {
 }
 #[derive(Clone)]
 pub struct PostTaxonomySqlxRepository {
-    pool: PgPool,
+    executor: SqlxExecutor,
 }
 #[derive(Clone)]
 pub struct PostTaxonomyInfoSqlxRepository {
-    pool: PgPool,
+    executor: SqlxExecutor,
 }
 
 impl PostTaxonomyOrm {
@@ -59,8 +59,10 @@ impl PostTaxonomyOrm {
 }
 
 impl PostTaxonomySqlxRepository {
-    pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+    pub fn new(executor: impl Into<SqlxExecutor>) -> Self {
+        Self {
+            executor: executor.into(),
+        }
     }
 }
 
@@ -79,20 +81,19 @@ impl SqlxViewMeta for PostTaxonomySqlxRepository {
 impl SqlxViewRepository for PostTaxonomySqlxRepository {
     type Entity = PostTaxonomy;
     type Orm = PostTaxonomyOrm;
-    fn get_pool(&self) -> &PgPool {
-        &self.pool
+    fn get_executor(&self) -> &SqlxExecutor {
+        &self.executor
+    }
+    fn with_executor(&self, executor: SqlxExecutor) -> Self {
+        Self { executor }
     }
     fn from_orm(orm: Self::Orm) -> Self::Entity {
         PostTaxonomy::from(orm)
     }
 }
 
-impl SqlxEntityMapper for PostTaxonomySqlxRepository {
-    type Entity = PostTaxonomy;
-    type EntityCreate = PostTaxonomyCreate;
-    type Orm = PostTaxonomyOrm;
-
-    fn to_orm_from_create(&self, create: &Self::EntityCreate) -> Self::Orm {
+impl From<&PostTaxonomyCreate> for PostTaxonomyOrm {
+    fn from(create: &PostTaxonomyCreate) -> Self {
         let now = time::OffsetDateTime::now_utc();
         PostTaxonomyOrm {
             id: 0,
@@ -104,8 +105,10 @@ impl SqlxEntityMapper for PostTaxonomySqlxRepository {
             name: create.name.clone(),
         }
     }
+}
 
-    fn to_orm_from_entity(&self, entity: &Self::Entity) -> Self::Orm {
+impl From<&PostTaxonomy> for PostTaxonomyOrm {
+    fn from(entity: &PostTaxonomy) -> Self {
         PostTaxonomyOrm {
             id: entity.id,
             uid: Uuid::parse_str(&entity.uid).unwrap_or_else(|_| Uuid::nil()),
@@ -118,6 +121,20 @@ impl SqlxEntityMapper for PostTaxonomySqlxRepository {
     }
 }
 
+impl SqlxEntityMapper for PostTaxonomySqlxRepository {
+    type Entity = PostTaxonomy;
+    type EntityCreate = PostTaxonomyCreate;
+    type Orm = PostTaxonomyOrm;
+
+    fn to_orm_from_create(&self, create: &Self::EntityCreate) -> Self::Orm {
+        PostTaxonomyOrm::from(create)
+    }
+
+    fn to_orm_from_entity(&self, entity: &Self::Entity) -> Self::Orm {
+        PostTaxonomyOrm::from(entity)
+    }
+}
+
 impl SqlxRepository for PostTaxonomySqlxRepository {
     type EntityCreate = PostTaxonomyCreate;
 }
@@ -131,8 +148,10 @@ impl PostTaxonomyInfoOrm {
 }
 
 impl PostTaxonomyInfoSqlxRepository {
-    pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+    pub fn new(executor: impl Into<SqlxExecutor>) -> Self {
+        Self {
+            executor: executor.into(),
+        }
     }
 }
 
@@ -151,8 +170,11 @@ impl SqlxViewMeta for PostTaxonomyInfoSqlxRepository {
 impl SqlxViewRepository for PostTaxonomyInfoSqlxRepository {
     type Entity = PostTaxonomyInfo;
     type Orm = PostTaxonomyInfoOrm;
-    fn get_pool(&self) -> &PgPool {
-        &self.pool
+    fn get_executor(&self) -> &SqlxExecutor {
+        &self.executor
+    }
+    fn with_executor(&self, executor: SqlxExecutor) -> Self {
+        Self { executor }
     }
     fn from_orm(orm: Self::Orm) -> Self::Entity {
         PostTaxonomyInfo::from(orm)
@@ -7,7 +7,7 @@ use crate::infras::sqlx_repository::{
     SqlxEntityMapper, SqlxRepository, SqlxViewMeta, SqlxViewRepository,
 };
 use crate::{define_orm_with_common_fields, define_readonly_orm_with_common_fields};
-use sqlx::PgPool;
+use crate::infras::sqlx_transaction::SqlxExecutor;
 use uuid::Uuid;
 
 define_orm_with_common_fields!(PostType {
@@ -42,15 +42,17 @@ impl From<PostTypeOrm> for PostType {
 
 #[derive(Clone)]
 pub struct PostTypeSqlxRepository {
-    pool: PgPool,
+    executor: SqlxExecutor,
 }
 #[derive(Clone)]
 pub struct PostTypeInfoSqlxRepository {
-    pool: PgPool,
+    executor: SqlxExecutor,
 }
 impl PostTypeSqlxRepository {
-    pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+    pub fn new(executor: impl Into<SqlxExecutor>) -> Self {
+        Self {
+            executor: executor.into(),
+        }
     }
 }
 
@@ -66,12 +68,8 @@ impl SqlxViewMeta for PostTypeSqlxRepository {
     }
 }
 
-impl SqlxEntityMapper for PostTypeSqlxRepository {
-    type Entity = PostType;
-    type EntityCreate = PostTypeCreate;
-    type Orm = PostTypeOrm;
-
-    fn to_orm_from_create(&self, create: &Self::EntityCreate) -> Self::Orm {
+impl From<&PostTypeCreate> for PostTypeOrm {
+    fn from(create: &PostTypeCreate) -> Self {
         let now = time::OffsetDateTime::now_utc();
         PostTypeOrm {
             id: 0,
@@ -83,8 +81,10 @@ impl SqlxEntityMapper for PostTypeSqlxRepository {
             name: create.name.clone(),
         }
     }
+}
 
-    fn to_orm_from_entity(&self, entity: &Self::Entity) -> Self::Orm {
+impl From<&PostType> for PostTypeOrm {
+    fn from(entity: &PostType) -> Self {
         PostTypeOrm {
             id: entity.id,
             uid: Uuid::parse_str(&entity.uid).unwrap_or_else(|_| Uuid::nil()),
@@ -97,6 +97,20 @@ impl SqlxEntityMapper for PostTypeSqlxRepository {
     }
 }
 
+impl SqlxEntityMapper for PostTypeSqlxRepository {
+    type Entity = PostType;
+    type EntityCreate = PostTypeCreate;
+    type Orm = PostTypeOrm;
+
+    fn to_orm_from_create(&self, create: &Self::EntityCreate) -> Self::Orm {
+        PostTypeOrm::from(create)
+    }
+
+    fn to_orm_from_entity(&self, entity: &Self::Entity) -> Self::Orm {
+        PostTypeOrm::from(entity)
+    }
+}
+
 impl SqlxRepository for PostTypeSqlxRepository {
     type EntityCreate = PostTypeCreate;
 }
@@ -104,8 +118,11 @@ impl SqlxRepository for PostTypeSqlxRepository {
 impl SqlxViewRepository for PostTypeSqlxRepository {
     type Entity = PostType;
     type Orm = PostTypeOrm;
-    fn get_pool(&self) -> &PgPool {
-        &self.pool
+    fn get_executor(&self) -> &SqlxExecutor {
+        &self.executor
+    }
+    fn with_executor(&self, executor: SqlxExecutor) -> Self {
+        Self { executor }
     }
     fn from_orm(orm: Self::Orm) -> Self::Entity {
         PostType::from(orm)
@@ -135,8 +152,10 @@ impl From<PostTypeInfoOrm> for PostTypeInfo {
 }
 
 impl PostTypeInfoSqlxRepository {
-    pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+    pub fn new(executor: impl Into<SqlxExecutor>) -> Self {
+        Self {
+            executor: executor.into(),
+        }
     }
 }
 
@@ -155,8 +174,11 @@ impl SqlxViewMeta for PostTypeInfoSqlxRepository {
 impl SqlxViewRepository for PostTypeInfoSqlxRepository {
     type Entity = PostTypeInfo;
     type Orm = PostTypeInfoOrm;
-    fn get_pool(&self) -> &PgPool {
-        &self.pool
+    fn get_executor(&self) -> &SqlxExecutor {
+        &self.executor
+    }
+    fn with_executor(&self, executor: SqlxExecutor) -> Self {
+        Self { executor }
     }
     fn from_orm(orm: Self::Orm) -> Self::Entity {
         PostTypeInfo::from(orm)
@@ -0,0 +1,1258 @@
+//! Behaviour every repository backend must share, checked against the
+//! in-memory backend and against the Postgres database in
+//! `TEST_DATABASE_URL`; the Postgres run is ignored unless asked for with
+//! `cargo test -- --ignored`.
+//! Rows are tagged per run so a database with other data in it works too.
+
+use crate::business::attribute_service::{AttributeCreate, AttributeRepository};
+use crate::business::attribute_value_service::{
+    AttributeValue, AttributeValueCreate, AttributeValueRepository,
+};
+use crate::business::audit_log_service::AuditLogInfoRepository;
+use crate::business::post_collection_service::{
+    PostCollectionCreate, PostCollectionRepository, POST_COLLECTIONS,
+};
+use crate::business::post_revision_service::{
+    PostRevisionCreate, PostRevisionRepository, RevisionKind,
+};
+use crate::business::post_service::{
+    PostCreate, PostInfoRepository, PostRepository, PostStatus, PostTransitionCreate,
+};
+use crate::business::post_taxonomy_service::{PostTaxonomyCreate, PostTaxonomyRepository};
+use crate::business::post_term_service::PostTermRepository;
+use crate::business::post_type_service::{PostType, PostTypeCreate, PostTypeRepository};
+use crate::business::slug_redirect_service::{
+    SlugRedirectCreate, SlugRedirectRepository, PATHS,
+};
+use crate::business::term_service::{TermCreate, TermRepository};
+use crate::business::user_service::{UserCreate, UserRepository, UserRole};
+use crate::common::aggregate::Aggregate;
+use crate::common::audit::{AuditEntry, AuditOperation};
+use crate::common::cursor::{Cursor, CursorPosition};
+use crate::common::error::CoreError;
+use crate::common::facet::{FacetBucket, FacetField};
+use crate::common::filter::{Filter, FilterOperator, FilterValue, ScalarValue, SoftDeleteScope};
+use crate::common::repository::UnitOfWork;
+use crate::common::sort::SortCriterion;
+use crate::infras::in_memory_repositories::{
+    AttributeInMemoryRepository, AttributeValueInMemoryRepository, AuditLogInfoInMemoryRepository,
+    PostCollectionInMemoryRepository, PostInMemoryRepository, PostInfoInMemoryRepository,
+    PostRevisionInMemoryRepository, PostTaxonomyInMemoryRepository, PostTypeInMemoryRepository,
+    SlugRedirectInMemoryRepository, TermInMemoryRepository, UserInMemoryRepository,
+};
+use crate::infras::in_memory_repository::InMemoryDatabase;
+use std::str::FromStr;
+
+struct Backend<PT, U, P, PI, TX, T> {
+    post_types: PT,
+    users: U,
+    posts: P,
+    posts_info: PI,
+    taxonomies: TX,
+    terms: T,
+}
+
+/// The repositories of the entities kept alongside posts.
+struct Records<A, AV, PC, SR, PR, AL> {
+    attributes: A,
+    attribute_values: AV,
+    collections: PC,
+    redirects: SR,
+    revisions: PR,
+    audit_log: AL,
+}
+
+fn property(name: &str, operator: FilterOperator, value: FilterValue) -> Filter {
+    Filter::Property {
+        property_name: name.to_string(),
+        operator,
+        value,
+    }
+}
+
+fn text(value: &str) -> FilterValue {
+    FilterValue::Single(ScalarValue::String(value.to_string()))
+}
+
+fn sort(field: &str, ascending: bool) -> SortCriterion {
+    SortCriterion {
+        field: field.to_string(),
+        ascending,
+        is_attribute: false,
+    }
+}
+
+async fn check_crud_and_locking<R: PostTypeRepository>(repository: &R, tag: &str) {
+    let created = repository
+        .create(&PostTypeCreate {
+            code: format!("{tag}-news"),
+            name: "News".to_string(),
+        })
+        .await
+        .unwrap();
+    assert_eq!(created.version, 0);
+    let found = repository
+        .find_by_uid(created.uid.clone())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(found.id, created.id);
+    assert_eq!(found.created_at, created.created_at);
+
+    let mut renamed = found.clone();
+    renamed.name = "Breaking news".to_string();
+    let updated = repository.update(&renamed).await.unwrap();
+    assert_eq!(updated.version, 1);
+    assert_eq!(updated.name, "Breaking news");
+
+    let stale = repository.update(&renamed).await.unwrap_err();
+    assert!(matches!(
+        stale,
+        CoreError::Conflict("error.version.conflict", _)
+    ));
+
+    let duplicate = repository
+        .create(&PostTypeCreate {
+            code: format!("{tag}-news"),
+            name: "Again".to_string(),
+        })
+        .await;
+    assert!(duplicate.is_err());
+}
+
+async fn check_soft_delete<R: PostTypeRepository>(repository: &R, tag: &str) {
+    let code = format!("{tag}-trash");
+    let created = repository
+        .create(&PostTypeCreate {
+            code: code.clone(),
+            name: "Trash".to_string(),
+        })
+        .await
+        .unwrap();
+    let in_scope = |scope| {
+        vec![
+            property("code", FilterOperator::Equal, text(&code)),
+            Filter::SoftDeleted(scope),
+        ]
+    };
+
+    assert_eq!(repository.purge_by_id(created.id).await.unwrap(), 0);
+    assert_eq!(repository.delete_by_id(created.id).await.unwrap(), 1);
+    assert_eq!(repository.delete_by_id(created.id).await.unwrap(), 0);
+    assert!(repository.find_by_id(created.id).await.unwrap().is_none());
+    assert_eq!(
+        repository
+            .count(in_scope(SoftDeleteScope::Exclude))
+            .await
+            .unwrap(),
+        0
+    );
+    assert_eq!(
+        repository
+            .count(in_scope(SoftDeleteScope::Only))
+            .await
+            .unwrap(),
+        1
+    );
+    // Trashed rows are read-only: updates find no row, alone or in a batch
+    let renamed = PostType {
+        name: "Renamed".to_string(),
+        ..created.clone()
+    };
+    assert!(matches!(
+        repository.update(&renamed).await,
+        Err(CoreError::NotFound(..))
+    ));
+    assert!(matches!(
+        repository.update_many(std::slice::from_ref(&renamed)).await,
+        Err(CoreError::NotFound(..))
+    ));
+
+    assert_eq!(repository.restore_by_id(created.id).await.unwrap(), 1);
+    assert_eq!(repository.restore_by_id(created.id).await.unwrap(), 0);
+    assert!(repository.find_by_id(created.id).await.unwrap().is_some());
+
+    assert_eq!(
+        repository.delete_by_uid(created.uid.clone()).await.unwrap(),
+        1
+    );
+    assert_eq!(repository.purge_by_id(created.id).await.unwrap(), 1);
+    assert_eq!(
+        repository
+            .count(in_scope(SoftDeleteScope::Include))
+            .await
+            .unwrap(),
+        0
+    );
+}
+
+async fn check_upsert<R: PostTypeRepository>(repository: &R, tag: &str) {
+    let existing = repository
+        .create(&PostTypeCreate {
+            code: format!("{tag}-page"),
+            name: "Page".to_string(),
+        })
+        .await
+        .unwrap();
+    repository.delete_by_id(existing.id).await.unwrap();
+
+    let mut changed = existing.clone();
+    changed.name = "Static page".to_string();
+    let mut added = existing.clone();
+    added.code = format!("{tag}-event");
+    added.name = "Event".to_string();
+    let upserted = repository
+        .upsert_by(&["code"], &[changed, added])
+        .await
+        .unwrap();
+    assert_eq!(upserted.len(), 2);
+    assert_eq!(upserted[0].id, existing.id);
+    assert_eq!(upserted[0].version, 1);
+    assert_eq!(upserted[0].name, "Static page");
+    assert_ne!(upserted[1].id, existing.id);
+    assert_ne!(upserted[1].uid, existing.uid);
+    assert_eq!(upserted[1].version, 0);
+    // Upserting revives trashed rows
+    assert!(repository.find_by_id(existing.id).await.unwrap().is_some());
+
+    // ON CONFLICT needs a unique constraint on the key
+    assert!(repository.upsert_by(&["name"], &upserted).await.is_err());
+    assert!(repository.upsert_by(&["title"], &upserted).await.is_err());
+}
+
+async fn check_find_suffixed<R: PostTypeRepository>(repository: &R, tag: &str) {
+    for code in ["a_b", "a_b-2", "axb-3", "a_bc", "a_b-x-4"] {
+        repository
+            .create(&PostTypeCreate {
+                code: format!("{tag}-{code}"),
+                name: code.to_string(),
+            })
+            .await
+            .unwrap();
+    }
+    let mut codes = repository
+        .find_suffixed("code", &format!("{tag}-a_b"), vec![])
+        .await
+        .unwrap();
+    codes.sort();
+    assert_eq!(
+        codes,
+        [
+            format!("{tag}-a_b"),
+            format!("{tag}-a_b-2"),
+            format!("{tag}-a_b-x-4")
+        ]
+    );
+    assert!(repository
+        .find_suffixed("title", "a", vec![])
+        .await
+        .is_err());
+}
+
+async fn check_transactions<R: PostTypeRepository>(repository: &R, tag: &str) {
+    let tx = repository.begin().await.unwrap();
+    let created = repository
+        .in_transaction(&tx)
+        .create(&PostTypeCreate {
+            code: format!("{tag}-draft"),
+            name: "Rolled back".to_string(),
+        })
+        .await
+        .unwrap();
+    tx.rollback().await.unwrap();
+    assert!(repository.find_by_id(created.id).await.unwrap().is_none());
+
+    let tx = repository.begin().await.unwrap();
+    let in_tx = repository.in_transaction(&tx);
+    // A nested begin joins the transaction; only the outer one commits it
+    let nested = in_tx.begin().await.unwrap();
+    let created = in_tx
+        .in_transaction(&nested)
+        .create(&PostTypeCreate {
+            code: format!("{tag}-kept"),
+            name: "Committed".to_string(),
+        })
+        .await
+        .unwrap();
+    nested.commit().await.unwrap();
+    tx.commit().await.unwrap();
+    assert!(tx.commit().await.is_err());
+    assert!(in_tx.find_by_id(created.id).await.is_err());
+    assert!(repository.find_by_id(created.id).await.unwrap().is_some());
+}
+
+async fn check_posts<PT, U, P, PI, TX, T>(backend: &Backend<PT, U, P, PI, TX, T>, tag: &str)
+where
+    PT: PostTypeRepository,
+    U: UserRepository,
+    P: PostRepository,
+    PI: PostInfoRepository,
+{
+    let user = backend
+        .users
+        .create(&UserCreate {
+            username: format!("{tag}-writer"),
+            email: format!("{tag}@example.com"),
+            password: "secret".to_string(),
+            role: UserRole::USER,
+        })
+        .await
+        .unwrap();
+    let found = backend
+        .users
+        .find_by_email_or_username(&format!("{tag}-writer"))
+        .await
+        .unwrap();
+    assert_eq!(found.map(|u| u.id), Some(user.id));
+    let found = backend
+        .users
+        .find_by_email(&format!("{tag}@example.com"))
+        .await
+        .unwrap();
+    assert_eq!(found.map(|u| u.id), Some(user.id));
+    assert!(backend.users.find_by_username(tag).await.unwrap().is_none());
+
+    let post_type = backend
+        .post_types
+        .create(&PostTypeCreate {
+            code: format!("{tag}-article"),
+            name: "Article".to_string(),
+        })
+        .await
+        .unwrap();
+    let words = ["alpha", "bravo", "charlie", "delta", "echo"];
+    let creates = words
+        .iter()
+        .map(|word| PostCreate {
+            title: format!("{tag} {word}"),
+            slug: format!("{tag}-{word}"),
+            user_id: user.id,
+            type_id: post_type.id,
+        })
+        .collect::<Vec<_>>();
+    let mut posts = backend.posts.create_many(&creates).await.unwrap();
+    for post in posts.iter_mut().take(2) {
+        post.status = PostStatus::PUBLISHED;
+    }
+    posts[3].content = "The quick brown fox".to_string();
+    let now = time::OffsetDateTime::now_utc();
+    posts[3].scheduled_at = Some(now);
+    posts[4].scheduled_at = Some(now + time::Duration::days(1));
+    let posts = backend.posts.update_many(&posts).await.unwrap();
+    assert!(posts.iter().all(|p| p.version == 1));
+
+    // What the scheduler asks for: due dates, unset ones never matching
+    let due = backend
+        .posts
+        .find_all(vec![
+            Filter::Property {
+                property_name: "type_id".to_string(),
+                operator: FilterOperator::Equal,
+                value: FilterValue::Single(ScalarValue::Int(post_type.id)),
+            },
+            Filter::Property {
+                property_name: "scheduled_at".to_string(),
+                operator: FilterOperator::LessThanOrEqual,
+                value: FilterValue::Single(ScalarValue::DateTime(now)),
+            },
+        ])
+        .await
+        .unwrap();
+    assert_eq!(
+        due.iter().map(|p| p.id).collect::<Vec<_>>(),
+        vec![posts[3].id]
+    );
+
+    let found = backend.posts.find_by_slug(&posts[2].slug).await.unwrap();
+    assert_eq!(found.map(|p| p.id), Some(posts[2].id));
+    assert_eq!(
+        backend.posts.find_by_author(user.id).await.unwrap().len(),
+        5
+    );
+    let info = backend
+        .posts_info
+        .find_by_id(posts[0].id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(info.username, user.username);
+    assert_eq!(info.email, user.email);
+
+    // Workflow history is written in the post repository's transaction
+    let transition = |from, to, comment: Option<&str>| PostTransitionCreate {
+        post_id: posts[0].id,
+        from_status: from,
+        to_status: to,
+        user_id: Some(user.id),
+        comment: comment.map(str::to_string),
+    };
+    let tx = backend.posts.begin().await.unwrap();
+    backend
+        .posts
+        .in_transaction(&tx)
+        .record_transition(&transition(PostStatus::DRAFT, PostStatus::ARCHIVED, None))
+        .await
+        .unwrap();
+    tx.rollback().await.unwrap();
+    for (from, to, comment) in [
+        (PostStatus::DRAFT, PostStatus::REVIEW, Some("ready")),
+        (PostStatus::REVIEW, PostStatus::PUBLISHED, None),
+    ] {
+        backend
+            .posts
+            .record_transition(&transition(from, to, comment))
+            .await
+            .unwrap();
+    }
+    let history = backend.posts.find_transitions(posts[0].id).await.unwrap();
+    assert_eq!(
+        history
+            .iter()
+            .map(|t| (t.to_status, t.comment.as_deref()))
+            .collect::<Vec<_>>(),
+        vec![
+            (PostStatus::PUBLISHED, None),
+            (PostStatus::REVIEW, Some("ready"))
+        ]
+    );
+
+    let tagged = property("title", FilterOperator::Like, text(tag));
+    let titles = |posts: Vec<crate::business::post_service::Post>| {
+        posts
+            .into_iter()
+            .map(|p| p.title.trim_start_matches(tag).trim().to_string())
+            .collect::<Vec<_>>()
+    };
+
+    // Filters
+    let published = property(
+        "status",
+        FilterOperator::Equal,
+        FilterValue::Single(ScalarValue::Int(PostStatus::PUBLISHED.as_i32())),
+    );
+    let filtered = |filters: Vec<Filter>| {
+        let mut all = vec![tagged.clone()];
+        all.extend(filters);
+        all
+    };
+    assert_eq!(
+        backend
+            .posts
+            .count(filtered(vec![published.clone()]))
+            .await
+            .unwrap(),
+        2
+    );
+    assert_eq!(
+        backend
+            .posts
+            .count(filtered(vec![Filter::Not(Box::new(published.clone()))]))
+            .await
+            .unwrap(),
+        3
+    );
+    let either = Filter::Or(vec![
+        property("title", FilterOperator::Like, text("echo")),
+        property(
+            "id",
+            FilterOperator::In,
+            FilterValue::List(vec![ScalarValue::Int(posts[1].id)]),
+        ),
+    ]);
+    let found = backend
+        .posts
+        .find_many(
+            vec![sort("title", true)],
+            None,
+            None,
+            filtered(vec![either]),
+        )
+        .await
+        .unwrap();
+    assert_eq!(titles(found), ["bravo", "echo"]);
+    let (low, high) = (posts[1].id.min(posts[2].id), posts[1].id.max(posts[2].id));
+    let between = property(
+        "id",
+        FilterOperator::Between,
+        FilterValue::Range((ScalarValue::Int(low), ScalarValue::Int(high))),
+    );
+    let found = backend
+        .posts
+        .find_many(vec![], None, None, filtered(vec![between]))
+        .await
+        .unwrap();
+    assert!(found.iter().all(|p| (low..=high).contains(&p.id)));
+    assert!(found.iter().any(|p| p.id == posts[1].id));
+    let result = backend
+        .posts
+        .find_all(vec![property("nope", FilterOperator::Equal, text("x"))])
+        .await;
+    assert!(matches!(
+        result,
+        Err(CoreError::UnprocessableEntity(
+            "error.filters.unknown.property",
+            _
+        ))
+    ));
+
+    // Sorting and paging
+    let found = backend
+        .posts
+        .find_many(
+            vec![sort("title", false)],
+            Some(1),
+            Some(2),
+            vec![tagged.clone()],
+        )
+        .await
+        .unwrap();
+    assert_eq!(titles(found), ["delta", "charlie"]);
+    let found = backend
+        .posts
+        .find_many(
+            vec![sort("status", false), sort("title", true)],
+            None,
+            None,
+            vec![tagged.clone()],
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        titles(found),
+        ["alpha", "bravo", "charlie", "delta", "echo"]
+    );
+
+    let mut pages = vec![];
+    let mut position = CursorPosition::First;
+    loop {
+        let page = backend
+            .posts
+            .find_page(vec![sort("title", true)], position, 2, vec![tagged.clone()])
+            .await
+            .unwrap();
+        let next = page.next_cursor.clone();
+        pages.push(page);
+        match next {
+            Some(cursor) => position = CursorPosition::After(Cursor::decode(&cursor).unwrap()),
+            None => break,
+        }
+    }
+    let page_titles = pages
+        .iter()
+        .map(|p| titles(p.items.clone()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        page_titles,
+        [
+            vec!["alpha", "bravo"],
+            vec!["charlie", "delta"],
+            vec!["echo"]
+        ]
+    );
+    let before = Cursor::decode(pages[2].prev_cursor.as_ref().unwrap()).unwrap();
+    let page = backend
+        .posts
+        .find_page(
+            vec![sort("title", true)],
+            CursorPosition::Before(before),
+            2,
+            vec![tagged.clone()],
+        )
+        .await
+        .unwrap();
+    assert_eq!(titles(page.items), ["charlie", "delta"]);
+    assert!(page.prev_cursor.is_some() && page.next_cursor.is_some());
+
+    // Keys read back as strings, e.g. uuids, page in the order they sort in
+    let mut uids = vec![];
+    let mut position = CursorPosition::First;
+    loop {
+        let page = backend
+            .posts
+            .find_page(vec![sort("uid", true)], position, 2, vec![tagged.clone()])
+            .await
+            .unwrap();
+        uids.extend(page.items.into_iter().map(|p| p.uid));
+        match page.next_cursor {
+            Some(cursor) => position = CursorPosition::After(Cursor::decode(&cursor).unwrap()),
+            None => break,
+        }
+    }
+    assert_eq!(uids.len(), 5);
+    assert!(uids.windows(2).all(|pair| pair[0] < pair[1]));
+
+    // NULL keys sort after every value: first when descending, and pages cross them
+    let by_schedule = || vec![sort("scheduled_at", false)];
+    let mut pages = vec![];
+    let mut position = CursorPosition::First;
+    loop {
+        let page = backend
+            .posts
+            .find_page(by_schedule(), position, 2, vec![tagged.clone()])
+            .await
+            .unwrap();
+        let next = page.next_cursor.clone();
+        pages.push(page);
+        match next {
+            Some(cursor) => position = CursorPosition::After(Cursor::decode(&cursor).unwrap()),
+            None => break,
+        }
+    }
+    let page_titles = pages
+        .iter()
+        .map(|p| titles(p.items.clone()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        page_titles,
+        [
+            vec!["charlie", "bravo"],
+            vec!["alpha", "echo"],
+            vec!["delta"]
+        ]
+    );
+    let before = Cursor::decode(pages[2].prev_cursor.as_ref().unwrap()).unwrap();
+    let page = backend
+        .posts
+        .find_page(by_schedule(), CursorPosition::Before(before), 2, vec![tagged.clone()])
+        .await
+        .unwrap();
+    assert_eq!(titles(page.items), ["alpha", "echo"]);
+    let before = Cursor::decode(page.prev_cursor.as_ref().unwrap()).unwrap();
+    let page = backend
+        .posts
+        .find_page(by_schedule(), CursorPosition::Before(before), 2, vec![tagged.clone()])
+        .await
+        .unwrap();
+    assert_eq!(titles(page.items), ["charlie", "bravo"]);
+
+    // Facets and aggregates
+    let facets = backend
+        .posts
+        .facets(
+            vec![FacetField::from_str("status").unwrap()],
+            vec![tagged.clone(), published],
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        facets[0].buckets,
+        [
+            FacetBucket {
+                value: Some(ScalarValue::Int(PostStatus::DRAFT.as_i32())),
+                count: 3,
+            },
+            FacetBucket {
+                value: Some(ScalarValue::Int(PostStatus::PUBLISHED.as_i32())),
+                count: 2,
+            },
+        ]
+    );
+    let groups = backend
+        .posts
+        .aggregate(
+            vec!["status".to_string()],
+            vec![
+                Aggregate::from_str("count(*)").unwrap(),
+                Aggregate::from_str("max(title)").unwrap(),
+            ],
+            None,
+            None,
+            vec![tagged.clone()],
+        )
+        .await
+        .unwrap();
+    let summary = groups
+        .iter()
+        .map(|g| (g.key[0].1.clone(), g.count, g.values.clone()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        summary,
+        [
+            (
+                Some(ScalarValue::Int(PostStatus::DRAFT.as_i32())),
+                3,
+                vec![
+                    ("count(*)".to_string(), Some(ScalarValue::Float(3.0))),
+                    (
+                        "max(title)".to_string(),
+                        Some(ScalarValue::String(format!("{tag} echo")))
+                    ),
+                ],
+            ),
+            (
+                Some(ScalarValue::Int(PostStatus::PUBLISHED.as_i32())),
+                2,
+                vec![
+                    ("count(*)".to_string(), Some(ScalarValue::Float(2.0))),
+                    (
+                        "max(title)".to_string(),
+                        Some(ScalarValue::String(format!("{tag} bravo")))
+                    ),
+                ],
+            ),
+        ]
+    );
+
+    for aggregate in ["sum(title)", "avg(created_at)"] {
+        let result = backend
+            .posts
+            .aggregate(
+                vec![],
+                vec![Aggregate::from_str(aggregate).unwrap()],
+                None,
+                None,
+                vec![tagged.clone()],
+            )
+            .await;
+        assert!(
+            matches!(
+                &result,
+                Err(CoreError::UnprocessableEntity("error.aggregate.type", _))
+            ),
+            "{aggregate}"
+        );
+    }
+
+    // Full-text search
+    let hits = backend
+        .posts
+        .search("brown fox".to_string(), None, None, vec![tagged.clone()])
+        .await
+        .unwrap();
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0].entity.id, posts[3].id);
+    assert!(hits[0].headline.contains("brown"));
+    let hits = backend
+        .posts
+        .search("brown cat".to_string(), None, None, vec![tagged.clone()])
+        .await
+        .unwrap();
+    assert!(hits.is_empty());
+
+    // Upserts edit the content only; status, dates and author stay
+    let mut edits = vec![posts[0].clone(), posts[3].clone()];
+    for edit in edits.iter_mut() {
+        edit.id = 0;
+        edit.title = format!("{} (upserted)", edit.title);
+        edit.status = PostStatus::DRAFT;
+        edit.user_id = 0;
+        edit.scheduled_at = None;
+    }
+    let upserted = backend
+        .posts
+        .upsert_by(&["type_id", "slug"], &edits)
+        .await
+        .unwrap();
+    assert_eq!(upserted[0].id, posts[0].id);
+    assert!(upserted[0].title.ends_with("(upserted)"));
+    assert_eq!(upserted[0].status, PostStatus::PUBLISHED);
+    assert_eq!(upserted[0].user_id, user.id);
+    assert_eq!(upserted[1].scheduled_at, posts[3].scheduled_at);
+}
+
+async fn check_post_terms<PT, U, P, PI, TX, T>(backend: &Backend<PT, U, P, PI, TX, T>, tag: &str)
+where
+    PT: PostTypeRepository,
+    U: UserRepository,
+    P: PostTermRepository,
+    PI: PostInfoRepository,
+    TX: PostTaxonomyRepository,
+    T: TermRepository,
+{
+    let user = backend
+        .users
+        .create(&UserCreate {
+            username: format!("{tag}-tagger"),
+            email: format!("{tag}-tagger@example.com"),
+            password: "secret".to_string(),
+            role: UserRole::USER,
+        })
+        .await
+        .unwrap();
+    let post_type = backend
+        .post_types
+        .create(&PostTypeCreate {
+            code: format!("{tag}-note"),
+            name: "Note".to_string(),
+        })
+        .await
+        .unwrap();
+    let creates = ["one", "two"]
+        .iter()
+        .map(|word| PostCreate {
+            title: format!("{tag} {word}"),
+            slug: format!("{tag}-note-{word}"),
+            user_id: user.id,
+            type_id: post_type.id,
+        })
+        .collect::<Vec<_>>();
+    let posts = backend.posts.create_many(&creates).await.unwrap();
+    let taxonomy = backend
+        .taxonomies
+        .create(&PostTaxonomyCreate {
+            code: format!("{tag}-tag"),
+            name: "Tag".to_string(),
+        })
+        .await
+        .unwrap();
+    let mut terms = vec![];
+    for slug in ["rust", "leptos"] {
+        let term = backend
+            .terms
+            .create(&TermCreate {
+                taxonomy_id: taxonomy.id,
+                slug: slug.to_string(),
+                name: slug.to_string(),
+            })
+            .await
+            .unwrap();
+        terms.push(term.id);
+    }
+    let (rust, leptos) = (terms[0], terms[1]);
+
+    let posts_repo = &backend.posts;
+    assert_eq!(
+        posts_repo
+            .insert_terms(posts[0].id, &[rust, leptos, rust])
+            .await
+            .unwrap(),
+        2
+    );
+    assert_eq!(
+        posts_repo.insert_terms(posts[0].id, &[rust]).await.unwrap(),
+        0
+    );
+    assert_eq!(
+        posts_repo.insert_terms(posts[1].id, &[rust]).await.unwrap(),
+        1
+    );
+    let mut expected = vec![rust, leptos];
+    expected.sort();
+    assert_eq!(
+        posts_repo.find_term_ids(posts[0].id).await.unwrap(),
+        expected
+    );
+
+    let of_type = property(
+        "type_id",
+        FilterOperator::Equal,
+        FilterValue::Single(ScalarValue::Int(post_type.id)),
+    );
+    let with_term = |taxonomy: Option<String>, slug: Option<&str>| Filter::Term {
+        taxonomy,
+        slug: slug.map(str::to_string),
+    };
+    let code = Some(taxonomy.code.clone());
+    let count = |filter: Filter| posts_repo.count(vec![of_type.clone(), filter]);
+    assert_eq!(
+        count(with_term(code.clone(), Some("rust"))).await.unwrap(),
+        2
+    );
+    assert_eq!(
+        count(with_term(code.clone(), Some("leptos")))
+            .await
+            .unwrap(),
+        1
+    );
+    assert_eq!(
+        count(with_term(Some("other".into()), None)).await.unwrap(),
+        0
+    );
+    let untagged = Filter::Not(Box::new(with_term(code.clone(), Some("leptos"))));
+    assert_eq!(count(untagged).await.unwrap(), 1);
+    let by_user = property(
+        "user_id",
+        FilterOperator::Equal,
+        FilterValue::Single(ScalarValue::Int(user.id)),
+    );
+    let info = backend
+        .posts_info
+        .count(vec![by_user, with_term(code.clone(), None)])
+        .await
+        .unwrap();
+    assert_eq!(info, 2);
+
+    // A trashed term stays on its posts but no longer matches
+    backend.terms.delete_by_id(leptos).await.unwrap();
+    assert_eq!(
+        count(with_term(code.clone(), Some("leptos")))
+            .await
+            .unwrap(),
+        0
+    );
+    assert_eq!(
+        posts_repo.find_term_ids(posts[0].id).await.unwrap(),
+        expected
+    );
+
+    assert_eq!(
+        posts_repo.delete_terms(posts[0].id, &[rust]).await.unwrap(),
+        1
+    );
+    assert_eq!(count(with_term(code, Some("rust"))).await.unwrap(), 1);
+
+    let unsupported = backend
+        .post_types
+        .count(vec![with_term(None, Some("rust"))])
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        unsupported,
+        CoreError::UnprocessableEntity("error.filters.terms.unsupported", _)
+    ));
+}
+
+async fn check_attributes<A, AV>(attributes: &A, values: &AV, tag: &str)
+where
+    A: AttributeRepository,
+    AV: AttributeValueRepository,
+{
+    let entity_type = format!("{tag}-thing");
+    let rank = AttributeCreate {
+        name: "rank".to_string(),
+        entity_type: entity_type.clone(),
+        data_type: "int".to_string(),
+    };
+    let attribute = attributes.create(&rank).await.unwrap();
+    assert!(attributes.create(&rank).await.is_err());
+
+    let value = |entity_id: i32, int_value: Option<i32>| AttributeValueCreate {
+        attribute_id: attribute.id,
+        entity_id,
+        entity_type: entity_type.clone(),
+        int_value,
+        double_value: None,
+        string_value: None,
+        boolean_value: None,
+        date_value: None,
+        datetime_value: None,
+        time_value: None,
+    };
+    let created = values
+        .create_many(&[value(1, Some(3)), value(2, None), value(3, Some(1))])
+        .await
+        .unwrap();
+    assert!(values.create(&value(1, Some(4))).await.is_err());
+
+    let of_entity_type = property("entity_type", FilterOperator::Equal, text(&entity_type));
+    let entity_ids =
+        |rows: Vec<AttributeValue>| rows.iter().map(|v| v.entity_id).collect::<Vec<_>>();
+    // Unset values sort after every value, as Postgres orders NULLs
+    let ascending = values
+        .find_many(
+            vec![sort("int_value", true)],
+            None,
+            None,
+            vec![of_entity_type.clone()],
+        )
+        .await
+        .unwrap();
+    assert_eq!(entity_ids(ascending), vec![3, 1, 2]);
+    let descending = values
+        .find_many(
+            vec![sort("int_value", false)],
+            None,
+            None,
+            vec![of_entity_type.clone()],
+        )
+        .await
+        .unwrap();
+    assert_eq!(entity_ids(descending), vec![2, 1, 3]);
+    let unset = property("int_value", FilterOperator::IsNull, FilterValue::None);
+    assert_eq!(
+        values.count(vec![of_entity_type.clone(), unset]).await.unwrap(),
+        1
+    );
+
+    let mut changed = created[1].clone();
+    changed.int_value = Some(2);
+    let upserted = values
+        .upsert_by(&["entity_type", "entity_id", "attribute_id"], &[changed])
+        .await
+        .unwrap();
+    assert_eq!(upserted[0].id, created[1].id);
+    assert_eq!(upserted[0].int_value, Some(2));
+
+    // Trashed values leave the listing but still hold their key
+    values.delete_by_id(created[0].id).await.unwrap();
+    assert_eq!(values.count(vec![of_entity_type]).await.unwrap(), 2);
+    assert!(values.create(&value(1, Some(4))).await.is_err());
+}
+
+async fn check_slug_redirects<PC, SR>(collections: &PC, redirects: &SR, tag: &str)
+where
+    PC: PostCollectionRepository,
+    SR: SlugRedirectRepository,
+{
+    let collection = collections
+        .create(&PostCollectionCreate {
+            slug: format!("{tag}-new"),
+            title: "Reading list".to_string(),
+            visibility: "public".to_string(),
+        })
+        .await
+        .unwrap();
+    let renamed =
+        SlugRedirectCreate::renamed(POST_COLLECTIONS, 0, format!("{tag}-old"), collection.id);
+    let recorded = collections.record_slug_redirect(&renamed).await.unwrap();
+    assert_eq!(
+        collections.record_slug_redirect(&renamed).await.unwrap().id,
+        recorded.id
+    );
+    let old_slug = property("old_slug", FilterOperator::Equal, text(&format!("{tag}-old")));
+    assert_eq!(redirects.count(vec![old_slug]).await.unwrap(), 1);
+    assert_eq!(
+        redirects.find_entity_paths(&recorded).await.unwrap(),
+        Some((
+            format!("/collections/{tag}-old"),
+            format!("/collections/{tag}-new")
+        ))
+    );
+
+    let by_hand = redirects
+        .create(&SlugRedirectCreate {
+            entity_type: PATHS.to_string(),
+            scope_id: 0,
+            old_slug: format!("/{tag}/blog"),
+            entity_id: None,
+            target: Some("/articles".to_string()),
+            prefix: true,
+        })
+        .await
+        .unwrap();
+    assert_eq!(redirects.find_entity_paths(&by_hand).await.unwrap(), None);
+
+    collections.delete_by_id(collection.id).await.unwrap();
+    assert_eq!(redirects.find_entity_paths(&recorded).await.unwrap(), None);
+}
+
+async fn check_revisions<PT, U, P, PI, TX, T, PR>(
+    backend: &Backend<PT, U, P, PI, TX, T>,
+    revisions: &PR,
+    tag: &str,
+) where
+    PT: PostTypeRepository,
+    U: UserRepository,
+    P: PostRepository,
+    PR: PostRevisionRepository,
+{
+    let user = backend
+        .users
+        .create(&UserCreate {
+            username: format!("{tag}-editor"),
+            email: format!("{tag}-editor@example.com"),
+            password: "secret".to_string(),
+            role: UserRole::USER,
+        })
+        .await
+        .unwrap();
+    let post_type = backend
+        .post_types
+        .create(&PostTypeCreate {
+            code: format!("{tag}-draft"),
+            name: "Draft".to_string(),
+        })
+        .await
+        .unwrap();
+    let post = backend
+        .posts
+        .create(&PostCreate {
+            title: format!("{tag} revised"),
+            slug: format!("{tag}-revised"),
+            user_id: user.id,
+            type_id: post_type.id,
+        })
+        .await
+        .unwrap();
+    assert!(revisions.find_latest(post.id).await.unwrap().is_none());
+
+    let saves = [
+        (RevisionKind::AUTOSAVE, "one"),
+        (RevisionKind::CHECKPOINT, "two"),
+        (RevisionKind::AUTOSAVE, "three"),
+        (RevisionKind::AUTOSAVE, "four"),
+    ];
+    for (kind, title) in saves {
+        revisions
+            .create(&PostRevisionCreate {
+                post_id: post.id,
+                kind,
+                title: title.to_string(),
+                summary: String::new(),
+                content: String::new(),
+                status: PostStatus::DRAFT,
+                user_id: Some(user.id),
+            })
+            .await
+            .unwrap();
+    }
+    let latest = revisions.find_latest(post.id).await.unwrap().unwrap();
+    assert_eq!(latest.title, "four");
+
+    // Pruning keeps the newest of the kind and leaves the other kind alone
+    assert_eq!(
+        revisions
+            .delete_oldest(post.id, RevisionKind::AUTOSAVE, 1)
+            .await
+            .unwrap(),
+        2
+    );
+    let of_post = property(
+        "post_id",
+        FilterOperator::Equal,
+        FilterValue::Single(ScalarValue::Int(post.id)),
+    );
+    let kept = revisions
+        .find_many(vec![sort("id", true)], None, None, vec![of_post])
+        .await
+        .unwrap();
+    let titles = kept.iter().map(|r| r.title.as_str()).collect::<Vec<_>>();
+    assert_eq!(titles, vec!["two", "four"]);
+}
+
+async fn check_audit_log<PT, AL>(post_types: &PT, audit_log: &AL, tag: &str)
+where
+    PT: PostTypeRepository,
+    AL: AuditLogInfoRepository,
+{
+    let post_type = post_types
+        .create(&PostTypeCreate {
+            code: format!("{tag}-audited"),
+            name: "Audited".to_string(),
+        })
+        .await
+        .unwrap();
+    let changes = serde_json::json!({"name": {"old": "Audited", "new": "Logged"}});
+    let entry = |entity_uid: &str| AuditEntry {
+        entity_id: post_type.id,
+        entity_uid: entity_uid.to_string(),
+        operation: AuditOperation::Update,
+        user_id: None,
+        changes: changes.as_object().unwrap().clone(),
+    };
+    post_types
+        .record_audit(vec![entry(&post_type.uid)])
+        .await
+        .unwrap();
+    let invalid = post_types
+        .record_audit(vec![entry("not-a-uuid")])
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        invalid,
+        CoreError::UnprocessableEntity("error.audit.invalid.entity_uid", _)
+    ));
+
+    let history = audit_log
+        .find_all(vec![
+            property("entity_type", FilterOperator::Equal, text("post_types")),
+            property(
+                "entity_id",
+                FilterOperator::Equal,
+                FilterValue::Single(ScalarValue::Int(post_type.id)),
+            ),
+        ])
+        .await
+        .unwrap();
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].entity_uid, post_type.uid);
+    assert_eq!(history[0].operation, "update");
+    assert_eq!(history[0].username, None);
+    let recorded: serde_json::Value = serde_json::from_str(&history[0].changes).unwrap();
+    assert_eq!(recorded, changes);
+}
+
+async fn check_backend<PT, U, P, PI, TX, T, A, AV, PC, SR, PR, AL>(
+    backend: Backend<PT, U, P, PI, TX, T>,
+    records: Records<A, AV, PC, SR, PR, AL>,
+    tag: &str,
+) where
+    PT: PostTypeRepository,
+    U: UserRepository,
+    P: PostTermRepository,
+    PI: PostInfoRepository,
+    TX: PostTaxonomyRepository,
+    T: TermRepository,
+    A: AttributeRepository,
+    AV: AttributeValueRepository,
+    PC: PostCollectionRepository,
+    SR: SlugRedirectRepository,
+    PR: PostRevisionRepository,
+    AL: AuditLogInfoRepository,
+{
+    check_crud_and_locking(&backend.post_types, tag).await;
+    check_soft_delete(&backend.post_types, tag).await;
+    check_upsert(&backend.post_types, tag).await;
+    check_find_suffixed(&backend.post_types, tag).await;
+    check_transactions(&backend.post_types, tag).await;
+    check_posts(&backend, tag).await;
+    check_post_terms(&backend, tag).await;
+    check_attributes(&records.attributes, &records.attribute_values, tag).await;
+    check_slug_redirects(&records.collections, &records.redirects, tag).await;
+    check_revisions(&backend, &records.revisions, tag).await;
+    check_audit_log(&backend.post_types, &records.audit_log, tag).await;
+}
+
+fn run_tag() -> String {
+    format!("c{}", &uuid::Uuid::now_v7().simple().to_string()[20..])
+}
+
+#[tokio::test]
+async fn test_in_memory_repositories() {
+    let database = InMemoryDatabase::new();
+    let backend = Backend {
+        post_types: PostTypeInMemoryRepository::new(database.clone()),
+        users: UserInMemoryRepository::new(database.clone()),
+        posts: PostInMemoryRepository::new(database.clone()),
+        posts_info: PostInfoInMemoryRepository::new(database.clone()),
+        taxonomies: PostTaxonomyInMemoryRepository::new(database.clone()),
+        terms: TermInMemoryRepository::new(database.clone()),
+    };
+    let records = Records {
+        attributes: AttributeInMemoryRepository::new(database.clone()),
+        attribute_values: AttributeValueInMemoryRepository::new(database.clone()),
+        collections: PostCollectionInMemoryRepository::new(database.clone()),
+        redirects: SlugRedirectInMemoryRepository::new(database.clone()),
+        revisions: PostRevisionInMemoryRepository::new(database.clone()),
+        audit_log: AuditLogInfoInMemoryRepository::new(database),
+    };
+    check_backend(backend, records, &run_tag()).await;
+}
+
+#[tokio::test]
+#[ignore = "needs a Postgres database in TEST_DATABASE_URL"]
+async fn test_postgres_repositories() {
+    use crate::infras::attribute_sqlx_repository::AttributeSqlxRepository;
+    use crate::infras::attribute_value_sqlx_repository::AttributeValueSqlxRepository;
+    use crate::infras::audit_log_sqlx_repository::AuditLogInfoSqlxRepository;
+    use crate::infras::post_collection_sqlx_repository::PostCollectionSqlxRepository;
+    use crate::infras::post_revision_sqlx_repository::PostRevisionSqlxRepository;
+    use crate::infras::post_sqlx_repository::{PostInfoSqlxRepository, PostSqlxRepository};
+    use crate::infras::post_taxonomy_sqlx_repository::PostTaxonomySqlxRepository;
+    use crate::infras::post_type_sqlx_repository::PostTypeSqlxRepository;
+    use crate::infras::slug_redirect_sqlx_repository::SlugRedirectSqlxRepository;
+    use crate::infras::term_sqlx_repository::TermSqlxRepository;
+    use crate::infras::user_sqlx_repository::UserSqlxRepository;
+    use crate::migrations::Migrator;
+
+    let db_url = std::env::var("TEST_DATABASE_URL").expect("TEST_DATABASE_URL is not set");
+    let pool = crate::db::connect(&db_url).await.unwrap();
+    Migrator::new(pool.clone()).run().await.unwrap();
+    let backend = Backend {
+        post_types: PostTypeSqlxRepository::new(pool.clone()),
+        users: UserSqlxRepository::new(pool.clone()),
+        posts: PostSqlxRepository::new(pool.clone()),
+        posts_info: PostInfoSqlxRepository::new(pool.clone()),
+        taxonomies: PostTaxonomySqlxRepository::new(pool.clone()),
+        terms: TermSqlxRepository::new(pool.clone()),
+    };
+    let records = Records {
+        attributes: AttributeSqlxRepository::new(pool.clone()),
+        attribute_values: AttributeValueSqlxRepository::new(pool.clone()),
+        collections: PostCollectionSqlxRepository::new(pool.clone()),
+        redirects: SlugRedirectSqlxRepository::new(pool.clone()),
+        revisions: PostRevisionSqlxRepository::new(pool.clone()),
+        audit_log: AuditLogInfoSqlxRepository::new(pool),
+    };
+    check_backend(backend, records, &run_tag()).await;
+}
@@ -0,0 +1,215 @@
+#![cfg(feature = "ssr")]
+
+use crate::business::post_collection_service::POST_COLLECTIONS;
+use crate::business::post_service::{PostStatus, POSTS};
+use crate::business::slug_redirect_service::{
+    public_path, SlugRedirect, SlugRedirectCreate, SlugRedirectRecorder, SlugRedirectRepository,
+    SLUG_REDIRECT_KEY,
+};
+use crate::business::term_service::TERMS;
+use crate::common::error::CoreError;
+use crate::common::repository::{Repository, ViewRepository};
+use crate::define_orm_with_common_fields;
+use crate::infras::post_collection_sqlx_repository::PostCollectionSqlxRepository;
+use crate::infras::post_sqlx_repository::PostSqlxRepository;
+use crate::infras::post_taxonomy_sqlx_repository::PostTaxonomySqlxRepository;
+use crate::infras::sqlx_repository::{
+    SqlxEntityMapper, SqlxRepository, SqlxViewMeta, SqlxViewRepository,
+};
+use crate::infras::sqlx_transaction::SqlxExecutor;
+use crate::infras::term_sqlx_repository::TermSqlxRepository;
+use uuid::Uuid;
+
+// Table: slug_redirects
+define_orm_with_common_fields!(SlugRedirect {
+    pub entity_type: String,
+    pub scope_id: i32,
+    pub old_slug: String,
+    pub entity_id: Option<i32>,
+    pub target: Option<String>,
+    pub prefix: bool,
+});
+
+impl SlugRedirectOrm {
+    pub fn searchable_columns() -> Vec<&'static str> {
+        vec!["old_slug"]
+    }
+}
+
+impl From<SlugRedirectOrm> for SlugRedirect {
+    fn from(orm: SlugRedirectOrm) -> Self {
+        Self {
+            id: orm.id,
+            uid: orm.uid.to_string(),
+            version: orm.version,
+            created_at: orm.created_at,
+            updated_at: orm.updated_at,
+            entity_type: orm.entity_type,
+            scope_id: orm.scope_id,
+            old_slug: orm.old_slug,
+            entity_id: orm.entity_id,
+            target: orm.target,
+            prefix: orm.prefix,
+        }
+    }
+}
+
+impl From<&SlugRedirectCreate> for SlugRedirectOrm {
+    fn from(create: &SlugRedirectCreate) -> Self {
+        let now = time::OffsetDateTime::now_utc();
+        SlugRedirectOrm {
+            id: 0,
+            uid: Uuid::now_v7(),
+            version: 0,
+            created_at: now,
+            updated_at: now,
+            entity_type: create.entity_type.clone(),
+            scope_id: create.scope_id,
+            old_slug: create.old_slug.clone(),
+            entity_id: create.entity_id,
+            target: create.target.clone(),
+            prefix: create.prefix,
+        }
+    }
+}
+
+impl From<&SlugRedirect> for SlugRedirectOrm {
+    fn from(entity: &SlugRedirect) -> Self {
+        SlugRedirectOrm {
+            id: entity.id,
+            uid: Uuid::parse_str(&entity.uid).unwrap_or_else(|_| Uuid::nil()),
+            version: entity.version,
+            created_at: entity.created_at,
+            updated_at: entity.updated_at,
+            entity_type: entity.entity_type.clone(),
+            scope_id: entity.scope_id,
+            old_slug: entity.old_slug.clone(),
+            entity_id: entity.entity_id,
+            target: entity.target.clone(),
+            prefix: entity.prefix,
+        }
+    }
+}
+
+/// Old slugs of posts, terms and collections, written through the
+/// repository of the entity that was renamed, and redirects entered by hand.
+#[derive(Clone)]
+pub struct SlugRedirectSqlxRepository {
+    executor: SqlxExecutor,
+}
+
+impl SlugRedirectSqlxRepository {
+    pub fn new(executor: impl Into<SqlxExecutor>) -> Self {
+        Self {
+            executor: executor.into(),
+        }
+    }
+}
+
+impl SqlxViewMeta for SlugRedirectSqlxRepository {
+    fn get_table_name(&self) -> &str {
+        "slug_redirects"
+    }
+    fn get_columns(&self) -> Vec<&str> {
+        SlugRedirectOrm::columns()
+    }
+    fn get_searchable_columns(&self) -> Vec<&str> {
+        SlugRedirectOrm::searchable_columns()
+    }
+    fn get_deleted_at_column(&self) -> Option<&str> {
+        None
+    }
+}
+
+impl SqlxViewRepository for SlugRedirectSqlxRepository {
+    type Entity = SlugRedirect;
+    type Orm = SlugRedirectOrm;
+    fn get_executor(&self) -> &SqlxExecutor {
+        &self.executor
+    }
+    fn with_executor(&self, executor: SqlxExecutor) -> Self {
+        Self { executor }
+    }
+    fn from_orm(orm: Self::Orm) -> Self::Entity {
+        SlugRedirect::from(orm)
+    }
+}
+
+impl SqlxEntityMapper for SlugRedirectSqlxRepository {
+    type Entity = SlugRedirect;
+    type EntityCreate = SlugRedirectCreate;
+    type Orm = SlugRedirectOrm;
+
+    fn to_orm_from_create(&self, create: &Self::EntityCreate) -> Self::Orm {
+        SlugRedirectOrm::from(create)
+    }
+
+    fn to_orm_from_entity(&self, entity: &Self::Entity) -> Self::Orm {
+        SlugRedirectOrm::from(entity)
+    }
+}
+
+impl SqlxRepository for SlugRedirectSqlxRepository {
+    type EntityCreate = SlugRedirectCreate;
+}
+
+impl SlugRedirectRecorder for SlugRedirectSqlxRepository {
+    async fn record_slug_redirect(
+        &self,
+        redirect: &SlugRedirectCreate,
+    ) -> Result<SlugRedirect, CoreError> {
+        let mut upserted =
+            Repository::upsert_by(self, &SLUG_REDIRECT_KEY, &[SlugRedirect::from(redirect)])
+                .await?;
+        Ok(upserted.remove(0))
+    }
+}
+
+impl SlugRedirectRepository for SlugRedirectSqlxRepository {
+    async fn find_entity_paths(
+        &self,
+        redirect: &SlugRedirect,
+    ) -> Result<Option<(String, String)>, CoreError> {
+        let Some(entity_id) = redirect.entity_id else {
+            return Ok(None);
+        };
+        let paths = |code_then: Option<&str>, code_now: Option<&str>, slug: &str| {
+            let entity_type = redirect.entity_type.as_str();
+            public_path(entity_type, code_then, &redirect.old_slug).zip(public_path(
+                entity_type,
+                code_now,
+                slug,
+            ))
+        };
+        Ok(match redirect.entity_type.as_str() {
+            POSTS => {
+                let posts = PostSqlxRepository::new(self.executor.clone());
+                ViewRepository::find_by_id(&posts, entity_id)
+                    .await?
+                    .filter(|post| post.status == PostStatus::PUBLISHED)
+                    .and_then(|post| paths(None, None, &post.slug))
+            }
+            POST_COLLECTIONS => {
+                let collections = PostCollectionSqlxRepository::new(self.executor.clone());
+                ViewRepository::find_by_id(&collections, entity_id)
+                    .await?
+                    .and_then(|collection| paths(None, None, &collection.slug))
+            }
+            TERMS => {
+                let terms = TermSqlxRepository::new(self.executor.clone());
+                let Some(term) = ViewRepository::find_by_id(&terms, entity_id).await? else {
+                    return Ok(None);
+                };
+                let taxonomies = PostTaxonomySqlxRepository::new(self.executor.clone());
+                let then = ViewRepository::find_by_id(&taxonomies, redirect.scope_id).await?;
+                let now = ViewRepository::find_by_id(&taxonomies, term.taxonomy_id).await?;
+                paths(
+                    then.as_ref().map(|t| t.code.as_str()),
+                    now.as_ref().map(|t| t.code.as_str()),
+                    &term.slug,
+                )
+            }
+            _ => None,
+        })
+    }
+}
@@ -1,47 +1,29 @@
 #![cfg(feature = "ssr")]
 
+use crate::business::attribute_service::AttributeDataType;
+use crate::common::aggregate::{Aggregate, AggregateFunction, AggregateGroup};
+use crate::common::audit::{AuditEntry, Snapshot};
+use crate::common::cache::cache_get_or_compute;
+use crate::common::cursor::{Cursor, CursorPage, CursorPosition};
 use crate::common::error::CoreError;
-use crate::common::filter::{Filter, FilterOperator, FilterValue, ScalarValue};
-use crate::common::repository::{Creatable, Repository, ViewRepository};
-use crate::common::service::Entity as BizEntity;
+use crate::common::facet::{Facet, FacetBucket, FacetField};
+use crate::common::filter::{Filter, FilterOperator, FilterValue, ScalarValue, SoftDeleteScope};
+use crate::common::repository::{
+    Creatable, Repository, Transactional, ViewRepository, WithAttributes,
+};
+use crate::common::search::{
+    to_tsquery, SearchHit, Suggestion, HIGHLIGHT_START, HIGHLIGHT_STOP, SEARCH_CONFIG,
+};
+use crate::common::service::{Entity as BizEntity, FIELD_TYPE_MAP};
 use crate::common::sort::SortCriterion;
-use crate::define_orm_with_common_fields;
 use sqlx::postgres::PgRow;
-use sqlx::{FromRow, PgPool, Postgres, QueryBuilder, Row};
+use crate::infras::sqlx_transaction::{SqlxConnection, SqlxExecutor, SqlxTransaction};
+use sqlx::{Column, FromRow, Postgres, QueryBuilder, Row, TypeInfo};
 use std::collections::HashMap;
+use time::format_description::well_known::Rfc3339;
+use time::macros::format_description;
 use uuid::Uuid;
 
-#[derive(Debug, sqlx::Type)]
-#[sqlx(type_name = "attribute_datatype", rename_all = "lowercase")]
-pub enum AttributeDataType {
-    Int,
-    Float,
-    String,
-    Boolean,
-    Date,
-    DateTime,
-    Time,
-}
-
-define_orm_with_common_fields!(Attribute {
-    pub name: String,
-    pub entity_type: String,
-    pub data_type: String,
-});
-
-define_orm_with_common_fields!(AttributeValue {
-    pub attribute_id: i32,
-    pub entity_id: i32,
-    pub entity_type: String,
-    pub int_value: Option<i32>,
-    pub double_value: Option<f64>,
-    pub string_value: Option<String>,
-    pub boolean_value: Option<bool>,
-    pub date_value: Option<time::Date>,
-    pub datetime_value: Option<time::OffsetDateTime>,
-    pub time_value: Option<time::Time>,
-});
-
 pub trait OrmMeta {
     fn columns() -> Vec<&'static str>;
     fn auto_columns() -> Vec<&'static str> {
@@ -54,8 +36,10 @@ pub trait OrmMeta {
             .filter(|c| !auto.contains(c))
             .collect()
     }
+    /// Columns written from the entity on update. `version` and `updated_at` are
+    /// maintained by `generic_update` itself.
     fn updatable_columns() -> Vec<&'static str> {
-        let exclude = vec!["id", "uid", "created_at"];
+        let exclude = vec!["id", "uid", "version", "created_at", "updated_at"];
         Self::columns()
             .into_iter()
             .filter(|c| !exclude.contains(c))
@@ -81,13 +65,194 @@ pub trait SqlxViewMeta {
     fn get_table_name(&self) -> &str;
     fn get_columns(&self) -> Vec<&str>;
     fn get_searchable_columns(&self) -> Vec<&str>;
+    /// Stored, GIN-indexed `tsvector` column backing full-text search, if the
+    /// table has one. Without it, search falls back to unindexed matching.
+    fn get_search_vector_column(&self) -> Option<&str> {
+        None
+    }
+    /// Columns that search snippets are cut from.
+    fn get_headline_columns(&self) -> Vec<&str> {
+        self.get_searchable_columns()
+    }
+    /// Column marking a row as soft-deleted. With one, deletes move rows to the
+    /// trash, and queries skip trashed rows unless a `Filter::SoftDeleted` asks.
+    fn get_deleted_at_column(&self) -> Option<&str> {
+        Some(DELETED_AT_COLUMN)
+    }
+    /// Columns compared by trigram similarity in fuzzy search and suggestions.
+    /// Keep them short and trigram-indexed; long bodies make matching slow.
+    fn get_similarity_columns(&self) -> Vec<&str> {
+        self.get_searchable_columns()
+    }
+    /// Columns kept out of the audit log, such as secrets.
+    fn get_unaudited_columns(&self) -> Vec<&str> {
+        vec![]
+    }
+    /// Column holding the post id of each row, which `Filter::Term` matches
+    /// `post_terms` on. Tables of anything but posts have none.
+    fn get_post_id_column(&self) -> Option<&str> {
+        None
+    }
+    /// Columns an upsert sets when it inserts but leaves alone when the row
+    /// is already there, such as ones only a dedicated operation may change.
+    fn get_insert_only_columns(&self) -> Vec<&str> {
+        vec![]
+    }
+}
+
+/// Soft-delete timestamp column, shared by every table and `_info` view.
+pub const DELETED_AT_COLUMN: &str = "deleted_at";
+
+/// Table that every audited write appends to.
+pub const AUDIT_LOG_TABLE: &str = "audit_log";
+
+/// Postgres caps a statement at 65535 bind parameters; bulk writes are split
+/// into statements that stay below it.
+const MAX_BIND_PARAMS: usize = u16::MAX as usize;
+
+/// Rows of `columns_per_row` bound values that fit in one statement.
+fn rows_per_statement(columns_per_row: usize) -> usize {
+    (MAX_BIND_PARAMS / columns_per_row.max(1)).max(1)
+}
+
+/// Typed value columns of `attribute_values`.
+pub const ATTRIBUTE_VALUE_COLUMNS: [&str; 7] = [
+    "int_value",
+    "double_value",
+    "string_value",
+    "boolean_value",
+    "date_value",
+    "datetime_value",
+    "time_value",
+];
+
+/// Column holding the JSON-encoded attribute values in `find_many_with_attributes`.
+const ATTRIBUTES_COLUMN: &str = "entity_attribute_values";
+
+/// Decodes the `ATTRIBUTES_COLUMN` of a row: an object mapping each attribute name
+/// to its value columns, in `ATTRIBUTE_VALUE_COLUMNS` order, of which one is set.
+fn attributes_from_json(raw: &str) -> Result<HashMap<String, ScalarValue>, CoreError> {
+    let invalid = |name: &str| {
+        CoreError::InternalServerError(
+            "error.attribute.invalid.value",
+            HashMap::from([("name".into(), name.to_string())]),
+        )
+    };
+    let encoded: HashMap<String, Vec<serde_json::Value>> =
+        serde_json::from_str(raw).map_err(|_| invalid(ATTRIBUTES_COLUMN))?;
+    let mut attributes = HashMap::new();
+    for (name, columns) in encoded {
+        let Some((index, value)) = columns.iter().enumerate().find(|(_, v)| !v.is_null()) else {
+            continue;
+        };
+        let value = match (ATTRIBUTE_VALUE_COLUMNS[index], value) {
+            ("int_value", v) => v.as_i64().map(|v| ScalarValue::Int(v as i32)),
+            ("double_value", v) => v.as_f64().map(ScalarValue::Float),
+            ("string_value", v) => v.as_str().map(|v| ScalarValue::String(v.to_string())),
+            ("boolean_value", v) => v.as_bool().map(ScalarValue::Bool),
+            ("date_value", v) => v
+                .as_str()
+                .and_then(|v| time::Date::parse(v, format_description!("[year]-[month]-[day]")).ok())
+                .map(ScalarValue::Date),
+            ("datetime_value", v) => v
+                .as_str()
+                .and_then(|v| time::OffsetDateTime::parse(v, &Rfc3339).ok())
+                .map(ScalarValue::DateTime),
+            ("time_value", v) => v
+                .as_str()
+                .and_then(|v| {
+                    time::Time::parse(
+                        v,
+                        format_description!("[hour]:[minute]:[second][optional [.[subsecond]]]"),
+                    )
+                    .ok()
+                })
+                .map(ScalarValue::Time),
+            _ => None,
+        };
+        attributes.insert(name.clone(), value.ok_or_else(|| invalid(&name))?);
+    }
+    Ok(attributes)
+}
+
+/// Placeholder value of the type an attribute's `data_type` stores, or `None`
+/// for a data type no value column can hold.
+pub fn attribute_prototype(data_type: &str) -> Option<ScalarValue> {
+    data_type
+        .parse::<AttributeDataType>()
+        .ok()
+        .map(|data_type| data_type.prototype())
+}
+
+/// Reads a column of a fetched row as a scalar; `None` for SQL NULL.
+fn optional_scalar_from_row(row: &PgRow, column: &str) -> Result<Option<ScalarValue>, CoreError> {
+    Ok(match row.try_column(column)?.type_info().name() {
+        "INT2" => row
+            .try_get::<Option<i16>, _>(column)?
+            .map(|v| ScalarValue::Int(v as i32)),
+        "INT4" => row.try_get::<Option<i32>, _>(column)?.map(ScalarValue::Int),
+        "FLOAT4" => row
+            .try_get::<Option<f32>, _>(column)?
+            .map(|v| ScalarValue::Float(v as f64)),
+        "FLOAT8" => row.try_get::<Option<f64>, _>(column)?.map(ScalarValue::Float),
+        "BOOL" => row.try_get::<Option<bool>, _>(column)?.map(ScalarValue::Bool),
+        "DATE" => row.try_get::<Option<time::Date>, _>(column)?.map(ScalarValue::Date),
+        "TIMESTAMPTZ" => row
+            .try_get::<Option<time::OffsetDateTime>, _>(column)?
+            .map(ScalarValue::DateTime),
+        "TIMESTAMP" => row
+            .try_get::<Option<time::PrimitiveDateTime>, _>(column)?
+            .map(|v| ScalarValue::DateTime(v.assume_utc())),
+        "TIME" => row.try_get::<Option<time::Time>, _>(column)?.map(ScalarValue::Time),
+        "UUID" => row
+            .try_get::<Option<Uuid>, _>(column)?
+            .map(|v| ScalarValue::String(v.to_string())),
+        _ => row.try_get::<Option<String>, _>(column)?.map(ScalarValue::String),
+    })
+}
+
+/// `text` with the `LIKE` wildcards `%` and `_`, and the `\` escaping
+/// them, matched literally.
+fn escape_like(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '%' | '_' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// A filter whose value `operator` cannot compare with, e.g. a range for `=`.
+fn invalid_operand(operator: &FilterOperator) -> CoreError {
+    CoreError::UnprocessableEntity(
+        "error.filters.invalid.value",
+        HashMap::from([("operator".into(), format!("{operator:?}"))]),
+    )
 }
 
+// Only implemented by this crate's repositories, whose futures are all Send
+#[allow(async_fn_in_trait)]
 pub trait SqlxViewRepository: SqlxViewMeta {
     type Entity;
     type Orm: for<'r> FromRow<'r, PgRow> + Send + Unpin;
 
-    fn get_pool(&self) -> &PgPool;
+    fn get_executor(&self) -> &SqlxExecutor;
+
+    /// Copy of this repository that sends its queries to `executor`.
+    fn with_executor(&self, executor: SqlxExecutor) -> Self
+    where
+        Self: Sized;
+
+    async fn acquire(&self) -> Result<SqlxConnection, CoreError> {
+        self.get_executor().acquire().await
+    }
+
+    /// Like `acquire`, but may hand out a read replica connection.
+    async fn acquire_read(&self) -> Result<SqlxConnection, CoreError> {
+        self.get_executor().acquire_read().await
+    }
 
     /// List of columns that are stored as textual types (e.g., text/varchar)
     /// This is used to avoid type-mismatch when a numeric-looking literal is provided for a string column.
@@ -99,14 +264,14 @@ pub trait SqlxViewRepository: SqlxViewMeta {
     async fn get_column_type_map(&self) -> Result<HashMap<String, ScalarValue>, CoreError> {
         // Repository: fetch directly from DB; caching is handled at the service layer
         let table = SqlxViewMeta::get_table_name(self).to_string();
-        let pool = self.get_pool();
+        let mut conn = self.acquire_read().await?;
         let cols = SqlxViewMeta::get_columns(self);
 
         let rows = sqlx::query(
             "SELECT column_name, data_type, udt_name FROM information_schema.columns WHERE table_schema = 'public' AND table_name = $1"
         )
         .bind(&table)
-        .fetch_all(pool)
+        .fetch_all(&mut *conn)
         .await?;
 
         let wanted_set: std::collections::HashSet<&str> = cols.into_iter().collect();
@@ -151,33 +316,379 @@ pub trait SqlxViewRepository: SqlxViewMeta {
         max_results: Option<i32>,
         filters: Vec<Filter>,
     ) -> Result<Vec<Self::Entity>, CoreError> {
+        self.validate_query(&sort_criteria, &filters)?;
         let mut query_builder =
-            self.build_find_many_query(sort_criteria, first_result, max_results, filters, false);
+            self.build_find_many_query(sort_criteria, first_result, max_results, filters, false)?;
         log::info!("{}", query_builder.sql());
         let result = query_builder
             .build_query_as::<Self::Orm>()
-            .fetch_all(self.get_pool())
+            .fetch_all(&mut *self.acquire_read().await?)
             .await?;
 
         Ok(result.into_iter().map(|orm| Self::from_orm(orm)).collect())
     }
 
+    async fn find_many_with_attributes(
+        &self,
+        sort_criteria: Vec<SortCriterion>,
+        first_result: Option<i32>,
+        max_results: Option<i32>,
+        filters: Vec<Filter>,
+    ) -> Result<Vec<WithAttributes<Self::Entity>>, CoreError> {
+        self.validate_query(&sort_criteria, &filters)?;
+        let mut query_builder = self.build_find_many_with_attributes_query(
+            sort_criteria,
+            first_result,
+            max_results,
+            filters,
+        )?;
+        log::info!("{}", query_builder.sql());
+        let rows = query_builder
+            .build()
+            .fetch_all(&mut *self.acquire_read().await?)
+            .await?;
+
+        rows.iter()
+            .map(|row| {
+                Ok(WithAttributes {
+                    entity: Self::from_orm(Self::Orm::from_row(row)?),
+                    attributes: attributes_from_json(&row.try_get::<String, _>(ATTRIBUTES_COLUMN)?)?,
+                })
+            })
+            .collect()
+    }
+
+    async fn search(
+        &self,
+        query: String,
+        first_result: Option<i32>,
+        max_results: Option<i32>,
+        filters: Vec<Filter>,
+    ) -> Result<Vec<SearchHit<Self::Entity>>, CoreError> {
+        self.validate_query(&[], &filters)?;
+        let Some(vector_column) = SqlxViewMeta::get_search_vector_column(self) else {
+            return Err(CoreError::UnprocessableEntity(
+                "error.search.unsupported",
+                HashMap::from([("table".into(), SqlxViewMeta::get_table_name(self).to_string())]),
+            ));
+        };
+        let Some(tsquery) = to_tsquery(&query) else {
+            return Ok(vec![]);
+        };
+        let mut query_builder =
+            self.build_search_query(vector_column, tsquery, first_result, max_results, filters)?;
+        log::info!("{}", query_builder.sql());
+        let rows = query_builder
+            .build()
+            .fetch_all(&mut *self.acquire_read().await?)
+            .await?;
+
+        rows.iter()
+            .map(|row| {
+                Ok(SearchHit {
+                    entity: Self::from_orm(Self::Orm::from_row(row)?),
+                    rank: row.try_get("search_rank")?,
+                    headline: row.try_get("search_headline")?,
+                })
+            })
+            .collect()
+    }
+
+    async fn suggest(
+        &self,
+        query: String,
+        max_results: Option<i32>,
+        filters: Vec<Filter>,
+    ) -> Result<Vec<Suggestion<Self::Entity>>, CoreError> {
+        self.validate_query(&[], &filters)?;
+        let query = query.trim().to_string();
+        if query.is_empty() {
+            return Ok(vec![]);
+        }
+        let mut query_builder = self.build_suggest_query(query, max_results, filters)?;
+        log::info!("{}", query_builder.sql());
+        let rows = query_builder
+            .build()
+            .fetch_all(&mut *self.acquire_read().await?)
+            .await?;
+
+        rows.iter()
+            .map(|row| {
+                Ok(Suggestion {
+                    entity: Self::from_orm(Self::Orm::from_row(row)?),
+                    similarity: row.try_get("suggestion_similarity")?,
+                })
+            })
+            .collect()
+    }
+
+    async fn find_page(
+        &self,
+        sort_criteria: Vec<SortCriterion>,
+        position: CursorPosition,
+        max_results: i32,
+        filters: Vec<Filter>,
+    ) -> Result<CursorPage<Self::Entity>, CoreError> {
+        self.validate_query(&sort_criteria, &filters)?;
+        if let Some(criterion) = sort_criteria.iter().find(|c| c.is_attribute) {
+            // Cursor keys are read back from the row's own columns
+            return Err(CoreError::UnprocessableEntity(
+                "error.cursor.attribute.sort",
+                HashMap::from([("field".into(), criterion.field.clone())]),
+            ));
+        }
+        // id makes the order total: keep criteria up to it, or append it
+        let mut sort_criteria = sort_criteria
+            .into_iter()
+            .filter(|c| !c.field.is_empty())
+            .collect::<Vec<_>>();
+        match sort_criteria.iter().position(|c| c.field == "id") {
+            Some(i) => sort_criteria.truncate(i + 1),
+            None => sort_criteria.push(SortCriterion {
+                field: "id".to_string(),
+                ascending: sort_criteria.last().is_none_or(|c| c.ascending),
+                is_attribute: false,
+            }),
+        }
+        let key_columns = sort_criteria[..sort_criteria.len() - 1]
+            .iter()
+            .map(|c| c.field.clone())
+            .collect::<Vec<_>>();
+        if let CursorPosition::After(cursor) | CursorPosition::Before(cursor) = &position {
+            if cursor.keys.len() != key_columns.len() {
+                return Err(CoreError::bad_request("error.cursor.invalid"));
+            }
+        }
+
+        // A cursor carries keys of text-like columns, e.g. uuids, as strings: those
+        // are ordered as text too, so that the order and the keyset agree
+        let table = SqlxViewMeta::get_table_name(self).to_string();
+        let column_types = cache_get_or_compute(FIELD_TYPE_MAP, &table, || async {
+            SqlxViewRepository::get_column_type_map(self).await
+        })
+        .await?;
+        let sort_criteria = sort_criteria
+            .into_iter()
+            .map(|criterion| match column_types.get(&criterion.field) {
+                Some(ScalarValue::String(_)) => SortCriterion {
+                    field: format!("{}::text", criterion.field),
+                    ..criterion
+                },
+                _ => criterion,
+            })
+            .collect::<Vec<_>>();
+
+        let max_results = max_results.max(1);
+        let mut query_builder =
+            self.build_find_page_query(&sort_criteria, &position, max_results, filters)?;
+        log::info!("{}", query_builder.sql());
+        let mut rows = query_builder
+            .build()
+            .fetch_all(&mut *self.acquire_read().await?)
+            .await?;
+
+        let has_more = rows.len() > max_results as usize;
+        rows.truncate(max_results as usize);
+        if matches!(position, CursorPosition::Before(_)) {
+            rows.reverse();
+        }
+
+        let cursor_of = |row: Option<&PgRow>| -> Result<Option<String>, CoreError> {
+            let Some(row) = row else {
+                return Ok(None);
+            };
+            let keys = key_columns
+                .iter()
+                .map(|column| optional_scalar_from_row(row, column))
+                .collect::<Result<Vec<_>, _>>()?;
+            let id = row.try_get("id")?;
+            Ok(Some(Cursor { keys, id }.encode()))
+        };
+        let (first, last) = (rows.first(), rows.last());
+        let (prev_cursor, next_cursor) = match position {
+            CursorPosition::First => (None, if has_more { cursor_of(last)? } else { None }),
+            CursorPosition::After(_) => {
+                (cursor_of(first)?, if has_more { cursor_of(last)? } else { None })
+            }
+            CursorPosition::Before(_) => {
+                (if has_more { cursor_of(first)? } else { None }, cursor_of(last)?)
+            }
+        };
+
+        let items = rows
+            .iter()
+            .map(|row| Self::Orm::from_row(row).map(Self::from_orm))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(CursorPage {
+            items,
+            next_cursor,
+            prev_cursor,
+        })
+    }
+
     async fn count(&self, filters: Vec<Filter>) -> Result<i64, CoreError> {
-        let mut query_builder = self.build_find_many_query(vec![], None, None, filters, true);
+        self.validate_query(&[], &filters)?;
+        let mut query_builder = self.build_find_many_query(vec![], None, None, filters, true)?;
         let result = query_builder
             .build_query_scalar()
-            .fetch_one(self.get_pool())
+            .fetch_one(&mut *self.acquire_read().await?)
             .await?;
         Ok(result)
     }
 
+    async fn find_suffixed(
+        &self,
+        column: &str,
+        base: &str,
+        filters: Vec<Filter>,
+    ) -> Result<Vec<String>, CoreError> {
+        self.validate_query(&[], &filters)?;
+        if !SqlxViewMeta::get_columns(self).contains(&column) {
+            return Err(CoreError::UnprocessableEntity(
+                "error.filters.unknown.property",
+                HashMap::from([("property".into(), column.to_string())]),
+            ));
+        }
+        let mut query_builder = QueryBuilder::new(format!(
+            "SELECT {column} FROM {}",
+            SqlxViewMeta::get_table_name(self)
+        ));
+        let has_where = self.push_filters(&mut query_builder, filters)?;
+        query_builder
+            .push(if has_where { " AND (" } else { " WHERE (" })
+            .push(format!("{column} = "))
+            .push_bind(base.to_string())
+            .push(format!(" OR {column} LIKE "))
+            .push_bind(format!("{}-%", escape_like(base)))
+            .push(" ESCAPE '\\')");
+        let values = query_builder
+            .build_query_scalar()
+            .fetch_all(&mut *self.acquire_read().await?)
+            .await?;
+        Ok(values)
+    }
+
+    async fn facets(
+        &self,
+        fields: Vec<FacetField>,
+        filters: Vec<Filter>,
+    ) -> Result<Vec<Facet>, CoreError> {
+        self.validate_query(&[], &filters)?;
+        let columns = SqlxViewMeta::get_columns(self);
+        if let Some(field) = fields
+            .iter()
+            .find(|f| !f.is_attribute && !columns.contains(&f.field.as_str()))
+        {
+            return Err(CoreError::UnprocessableEntity(
+                "error.facet.unknown.field",
+                HashMap::from([("field".into(), field.field.clone())]),
+            ));
+        }
+
+        let mut facets = Vec::with_capacity(fields.len());
+        for field in fields {
+            let facet_filters = filters
+                .iter()
+                .filter(|f| !field.is_filtered_by(f))
+                .cloned()
+                .collect();
+            let mut query_builder = self.build_facet_query(&field, facet_filters)?;
+            log::info!("{}", query_builder.sql());
+            let rows = query_builder
+                .build()
+                .fetch_all(&mut *self.acquire_read().await?)
+                .await?;
+            let value_columns = if field.is_attribute {
+                ATTRIBUTE_VALUE_COLUMNS
+                    .iter()
+                    .map(|column| format!("fv_{column}"))
+                    .collect()
+            } else {
+                vec!["facet_value".to_string()]
+            };
+            let buckets = rows
+                .iter()
+                .map(|row| {
+                    let mut value = None;
+                    for column in &value_columns {
+                        value = optional_scalar_from_row(row, column)?;
+                        if value.is_some() {
+                            break;
+                        }
+                    }
+                    Ok(FacetBucket {
+                        value,
+                        count: row.try_get("facet_count")?,
+                    })
+                })
+                .collect::<Result<Vec<_>, CoreError>>()?;
+            facets.push(Facet { field, buckets });
+        }
+        Ok(facets)
+    }
+
+    async fn aggregate(
+        &self,
+        group_by: Vec<String>,
+        aggregates: Vec<Aggregate>,
+        first_result: Option<i32>,
+        max_results: Option<i32>,
+        filters: Vec<Filter>,
+    ) -> Result<Vec<AggregateGroup>, CoreError> {
+        self.validate_query(&[], &filters)?;
+        let columns = SqlxViewMeta::get_columns(self);
+        if let Some(field) = group_by
+            .iter()
+            .chain(aggregates.iter().filter_map(|a| a.field.as_ref()))
+            .find(|field| !columns.contains(&field.as_str()))
+        {
+            return Err(CoreError::UnprocessableEntity(
+                "error.aggregate.unknown.field",
+                HashMap::from([("field".into(), field.clone())]),
+            ));
+        }
+        if aggregates.iter().any(|a| a.function != AggregateFunction::Count) {
+            let column_types = SqlxViewRepository::get_column_type_map(self).await?;
+            Aggregate::check_types(&aggregates, &column_types)?;
+        }
+
+        let mut query_builder =
+            self.build_aggregate_query(&group_by, &aggregates, first_result, max_results, filters)?;
+        let rows = query_builder
+            .build()
+            .fetch_all(&mut *self.acquire_read().await?)
+            .await?;
+        rows.iter()
+            .map(|row| {
+                let key = group_by
+                    .iter()
+                    .enumerate()
+                    .map(|(i, field)| Ok((field.clone(), optional_scalar_from_row(row, &format!("g{i}"))?)))
+                    .collect::<Result<Vec<_>, CoreError>>()?;
+                let values = aggregates
+                    .iter()
+                    .enumerate()
+                    .map(|(i, aggregate)| {
+                        Ok((aggregate.label(), optional_scalar_from_row(row, &format!("a{i}"))?))
+                    })
+                    .collect::<Result<Vec<_>, CoreError>>()?;
+                Ok(AggregateGroup {
+                    key,
+                    count: row.try_get("group_count")?,
+                    values,
+                })
+            })
+            .collect()
+    }
+
     async fn find_by_id(&self, id: i32) -> Result<Option<Self::Entity>, CoreError> {
         let result = sqlx::query_as::<_, Self::Orm>(&format!(
-            "SELECT * FROM {} WHERE id=$1",
-            SqlxViewMeta::get_table_name(self)
+            "SELECT * FROM {} WHERE id=$1{}",
+            SqlxViewMeta::get_table_name(self),
+            self.live_rows_condition()
         ))
         .bind(id)
-        .fetch_optional(self.get_pool())
+        .fetch_optional(&mut *self.acquire_read().await?)
         .await?;
 
         Ok(result.map(|orm| Self::from_orm(orm)))
@@ -185,15 +696,63 @@ pub trait SqlxViewRepository: SqlxViewMeta {
 
     async fn find_by_uid(&self, uid: Uuid) -> Result<Option<Self::Entity>, CoreError> {
         let result = sqlx::query_as::<_, Self::Orm>(&format!(
-            "SELECT * FROM {} WHERE uid=$1",
-            SqlxViewMeta::get_table_name(self)
+            "SELECT * FROM {} WHERE uid=$1{}",
+            SqlxViewMeta::get_table_name(self),
+            self.live_rows_condition()
         ))
         .bind(uid)
-        .fetch_optional(self.get_pool())
+        .fetch_optional(&mut *self.acquire_read().await?)
         .await?;
 
         Ok(result.map(|orm| Self::from_orm(orm)))
     }
+    /// `" AND deleted_at IS NULL"` when rows can be soft-deleted, for hand-written
+    /// lookups by key.
+    fn live_rows_condition(&self) -> String {
+        SqlxViewMeta::get_deleted_at_column(self)
+            .map(|column| format!(" AND {column} IS NULL"))
+            .unwrap_or_default()
+    }
+
+    /// Only known columns are ever formatted into SQL: sort fields and property
+    /// filters must name a column of this table or view.
+    fn validate_query(
+        &self,
+        sort_criteria: &[SortCriterion],
+        filters: &[Filter],
+    ) -> Result<(), CoreError> {
+        let mut columns = SqlxViewMeta::get_columns(self);
+        // Not an entity field, but the trash is sorted and filtered by it
+        columns.extend(SqlxViewMeta::get_deleted_at_column(self));
+        for criterion in sort_criteria.iter().filter(|c| !c.is_attribute) {
+            if !columns.contains(&criterion.field.as_str()) {
+                return Err(CoreError::UnprocessableEntity(
+                    "error.sort.unknown.field",
+                    HashMap::from([("field".into(), criterion.field.clone())]),
+                ));
+            }
+        }
+        for property_name in filters.iter().flat_map(Filter::property_names) {
+            if !columns.contains(&property_name) {
+                return Err(CoreError::UnprocessableEntity(
+                    "error.filters.unknown.property",
+                    HashMap::from([("property".into(), property_name.to_string())]),
+                ));
+            }
+        }
+        if SqlxViewMeta::get_post_id_column(self).is_none() && filters.iter().any(Filter::has_term)
+        {
+            return Err(CoreError::UnprocessableEntity(
+                "error.filters.terms.unsupported",
+                HashMap::from([(
+                    "table".into(),
+                    SqlxViewMeta::get_table_name(self).to_string(),
+                )]),
+            ));
+        }
+        Ok(())
+    }
+
     fn build_find_many_query(
         &self,
         sort_criteria: Vec<SortCriterion>,
@@ -201,83 +760,471 @@ pub trait SqlxViewRepository: SqlxViewMeta {
         max_results: Option<i32>,
         filters: Vec<Filter>,
         count: bool,
-    ) -> QueryBuilder<'_, Postgres> {
+    ) -> Result<QueryBuilder<'_, Postgres>, CoreError> {
         let mut query_builder = QueryBuilder::new(format!(
             "SELECT {} FROM {}",
             if count { "COUNT(*)" } else { "*" },
             SqlxViewMeta::get_table_name(self)
         ));
+        if !count {
+            self.push_attribute_sort_joins(&mut query_builder, &sort_criteria);
+        }
+        self.push_filters(&mut query_builder, filters)?;
+        Self::push_order_by(&mut query_builder, &sort_criteria);
 
-        let (property_filters, attribute_filters, search_filters): (Vec<_>, Vec<_>, Vec<_>) =
-            filters
-                .into_iter()
-                .fold((vec![], vec![], vec![]), |mut acc, f| {
-                    match &f {
-                        Filter::Property { .. } => acc.0.push(f),
-                        Filter::Attribute { .. } => acc.1.push(f),
-                        Filter::Search { .. } => acc.2.push(f),
-                    }
-                    acc
-                });
+        if !count {
+            Self::push_offset_limit(&mut query_builder, first_result, max_results);
+        }
 
-        let mut has_where = false;
+        Ok(query_builder)
+    }
 
-        for filter in property_filters {
-            if !has_where {
-                query_builder.push(" WHERE ");
-                has_where = true;
-            } else {
-                query_builder.push(" AND ");
+    /// `build_find_many_query` plus an `ATTRIBUTES_COLUMN` aggregating each row's
+    /// attribute values, so they come back in the same round trip.
+    fn build_find_many_with_attributes_query(
+        &self,
+        sort_criteria: Vec<SortCriterion>,
+        first_result: Option<i32>,
+        max_results: Option<i32>,
+        filters: Vec<Filter>,
+    ) -> Result<QueryBuilder<'_, Postgres>, CoreError> {
+        let table = SqlxViewMeta::get_table_name(self);
+        let columns = ATTRIBUTE_VALUE_COLUMNS
+            .iter()
+            .map(|column| format!("av.{column}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let mut query_builder = QueryBuilder::new(format!(
+            "SELECT *, (SELECT COALESCE(json_object_agg(a.name, json_build_array({columns})), '{{}}')::text FROM attribute_values av JOIN attributes a ON a.id = av.attribute_id WHERE av.deleted_at IS NULL AND av.entity_id = {table}.id AND av.entity_type = "
+        ));
+        query_builder.push_bind(table);
+        query_builder.push(format!(") AS {ATTRIBUTES_COLUMN} FROM {table}"));
+        self.push_attribute_sort_joins(&mut query_builder, &sort_criteria);
+        self.push_filters(&mut query_builder, filters)?;
+        Self::push_order_by(&mut query_builder, &sort_criteria);
+        Self::push_offset_limit(&mut query_builder, first_result, max_results);
+        Ok(query_builder)
+    }
+
+    /// Rows matching `tsquery` on `vector_column`, ranked, each with a snippet
+    /// of its headline columns.
+    fn build_search_query<'a>(
+        &'a self,
+        vector_column: &str,
+        tsquery: String,
+        first_result: Option<i32>,
+        max_results: Option<i32>,
+        filters: Vec<Filter>,
+    ) -> Result<QueryBuilder<'a, Postgres>, CoreError> {
+        let table = SqlxViewMeta::get_table_name(self);
+        let document = SqlxViewMeta::get_headline_columns(self).join(", ");
+        let mut query_builder = QueryBuilder::new(format!(
+            "SELECT {table}.*, ts_rank({table}.{vector_column}, search_query) AS search_rank, ts_headline('{SEARCH_CONFIG}', concat_ws(' ', {document}), search_query, "
+        ));
+        query_builder.push_bind(format!(
+            "StartSel={HIGHLIGHT_START}, StopSel={HIGHLIGHT_STOP}, MaxFragments=2, MaxWords=30, MinWords=12"
+        ));
+        query_builder.push(format!(
+            ") AS search_headline FROM {table}, to_tsquery('{SEARCH_CONFIG}', "
+        ));
+        query_builder.push_bind(tsquery);
+        query_builder.push(") AS search_query");
+        let has_where = self.push_filters(&mut query_builder, filters)?;
+        query_builder.push(if has_where { " AND " } else { " WHERE " });
+        query_builder.push(format!(
+            "{table}.{vector_column} @@ search_query ORDER BY search_rank DESC, {table}.id DESC"
+        ));
+        Self::push_offset_limit(&mut query_builder, first_result, max_results);
+        Ok(query_builder)
+    }
+
+    /// Rows similar to `query` on the similarity columns, scored by their best
+    /// word similarity.
+    fn build_suggest_query<'a>(
+        &'a self,
+        query: String,
+        max_results: Option<i32>,
+        filters: Vec<Filter>,
+    ) -> Result<QueryBuilder<'a, Postgres>, CoreError> {
+        let table = SqlxViewMeta::get_table_name(self);
+        let mut query_builder = QueryBuilder::new(format!("SELECT {table}.*, GREATEST("));
+        for (i, column) in SqlxViewMeta::get_similarity_columns(self).iter().enumerate() {
+            if i > 0 {
+                query_builder.push(", ");
             }
+            query_builder.push("word_similarity(");
+            query_builder.push_bind(query.clone());
+            query_builder.push(format!(", coalesce({table}.{column}::text, ''))"));
+        }
+        query_builder.push(format!(") AS suggestion_similarity FROM {table}"));
+        let has_where = self.push_filters(&mut query_builder, filters)?;
+        query_builder.push(if has_where { " AND " } else { " WHERE " });
+        self.push_similarity_condition(&mut query_builder, query);
+        query_builder.push(format!(
+            " ORDER BY suggestion_similarity DESC, {table}.id DESC"
+        ));
+        Self::push_offset_limit(&mut query_builder, None, max_results);
+        Ok(query_builder)
+    }
 
-            if let Filter::Property {
-                property_name,
-                operator,
-                value,
-            } = filter
-            {
-                self.build_property_filter(&mut query_builder, &property_name, operator, value);
+    /// Matches rows where any similarity column contains a word similar to
+    /// `keyword`. `<%` uses the trigram indexes and the connection's
+    /// `pg_trgm.word_similarity_threshold`.
+    fn push_similarity_condition<'a>(
+        &'a self,
+        query_builder: &mut QueryBuilder<'a, Postgres>,
+        keyword: String,
+    ) {
+        let table = SqlxViewMeta::get_table_name(self);
+        let columns = SqlxViewMeta::get_similarity_columns(self);
+        if columns.is_empty() {
+            query_builder.push("FALSE");
+            return;
+        }
+        query_builder.push("(");
+        for (i, column) in columns.iter().enumerate() {
+            if i > 0 {
+                query_builder.push(" OR ");
             }
+            query_builder.push_bind(keyword.clone());
+            query_builder.push(format!(" <% {table}.{column}::text"));
         }
+        query_builder.push(")");
+    }
 
-        for filter in attribute_filters {
+    /// Counts rows per value of `field`, most frequent first. An attribute facet
+    /// groups by all its typed value columns, exposed as `fv_<column>`.
+    fn build_facet_query<'a>(
+        &'a self,
+        field: &FacetField,
+        filters: Vec<Filter>,
+    ) -> Result<QueryBuilder<'a, Postgres>, CoreError> {
+        let table = SqlxViewMeta::get_table_name(self);
+        let (mut query_builder, value_count) = if field.is_attribute {
+            let outer = ATTRIBUTE_VALUE_COLUMNS
+                .iter()
+                .map(|column| format!("fv_{column}"))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let inner = ATTRIBUTE_VALUE_COLUMNS
+                .iter()
+                .map(|column| format!("av.{column} AS fv_{column}"))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let mut query_builder = QueryBuilder::new(format!(
+                "SELECT {outer}, COUNT(*) AS facet_count FROM {table} LEFT JOIN LATERAL (SELECT {inner} FROM attribute_values av JOIN attributes a ON a.id = av.attribute_id WHERE av.deleted_at IS NULL AND av.entity_id = {table}.id AND av.entity_type = "
+            ));
+            query_builder.push_bind(table);
+            query_builder.push(" AND a.name = ");
+            query_builder.push_bind(field.field.clone());
+            query_builder.push(") fv ON TRUE");
+            (query_builder, ATTRIBUTE_VALUE_COLUMNS.len())
+        } else {
+            let query_builder = QueryBuilder::new(format!(
+                "SELECT {} AS facet_value, COUNT(*) AS facet_count FROM {table}",
+                field.field
+            ));
+            (query_builder, 1)
+        };
+        self.push_filters(&mut query_builder, filters)?;
+        let positions = (1..=value_count)
+            .map(|i| i.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        query_builder.push(format!(
+            " GROUP BY {positions} ORDER BY facet_count DESC, {positions}"
+        ));
+        Ok(query_builder)
+    }
+
+    /// `SELECT <group columns as g{i}>, COUNT(*) AS group_count, <aggregates
+    /// as a{i}> ... GROUP BY ... ORDER BY ...`. Fields are validated columns.
+    fn build_aggregate_query<'a>(
+        &'a self,
+        group_by: &[String],
+        aggregates: &[Aggregate],
+        first_result: Option<i32>,
+        max_results: Option<i32>,
+        filters: Vec<Filter>,
+    ) -> Result<QueryBuilder<'a, Postgres>, CoreError> {
+        let mut select = group_by
+            .iter()
+            .enumerate()
+            .map(|(i, field)| format!("{field} AS g{i}"))
+            .collect::<Vec<_>>();
+        select.push("COUNT(*) AS group_count".to_string());
+        select.extend(aggregates.iter().enumerate().map(|(i, aggregate)| {
+            let field = aggregate.field.as_deref().unwrap_or("*");
+            let expression = match aggregate.function {
+                AggregateFunction::Count => format!("COUNT({field})::float8"),
+                AggregateFunction::Sum => format!("SUM({field})::float8"),
+                AggregateFunction::Avg => format!("AVG({field})::float8"),
+                AggregateFunction::Min => format!("MIN({field})"),
+                AggregateFunction::Max => format!("MAX({field})"),
+            };
+            format!("{expression} AS a{i}")
+        }));
+        let mut query_builder = QueryBuilder::new(format!(
+            "SELECT {} FROM {}",
+            select.join(", "),
+            SqlxViewMeta::get_table_name(self)
+        ));
+        self.push_filters(&mut query_builder, filters)?;
+        if !group_by.is_empty() {
+            let positions = (1..=group_by.len())
+                .map(|i| i.to_string())
+                .collect::<Vec<_>>()
+                .join(", ");
+            query_builder.push(format!(" GROUP BY {positions} ORDER BY {positions}"));
+        }
+        Self::push_offset_limit(&mut query_builder, first_result, max_results);
+        Ok(query_builder)
+    }
+
+    fn push_offset_limit(
+        query_builder: &mut QueryBuilder<'_, Postgres>,
+        first_result: Option<i32>,
+        max_results: Option<i32>,
+    ) {
+        query_builder.push(format!(" OFFSET {}", first_result.unwrap_or(0)));
+        match max_results {
+            Some(limit) => query_builder.push(format!(" LIMIT {}", limit)),
+            None => query_builder.push(" LIMIT ALL"),
+        };
+    }
+
+    /// Keyset variant of `build_find_many_query`. `sort_criteria` must end with
+    /// `id`; one row more than `max_results` is fetched to detect a further page.
+    fn build_find_page_query(
+        &self,
+        sort_criteria: &[SortCriterion],
+        position: &CursorPosition,
+        max_results: i32,
+        filters: Vec<Filter>,
+    ) -> Result<QueryBuilder<'_, Postgres>, CoreError> {
+        let mut query_builder = QueryBuilder::new(format!(
+            "SELECT * FROM {}",
+            SqlxViewMeta::get_table_name(self)
+        ));
+        let has_where = self.push_filters(&mut query_builder, filters)?;
+
+        // Paging backwards walks the same order in reverse; rows are flipped back afterwards
+        let backwards = matches!(position, CursorPosition::Before(_));
+        if let CursorPosition::After(cursor) | CursorPosition::Before(cursor) = position {
+            query_builder.push(if has_where { " AND " } else { " WHERE " });
+            Self::push_keyset_predicate(&mut query_builder, sort_criteria, cursor, backwards);
+        }
+
+        // NULLs sort after every value, as the keyset predicate expects
+        let order = sort_criteria
+            .iter()
+            .map(|c| match c.ascending != backwards {
+                true => format!("{} ASC NULLS LAST", c.field),
+                false => format!("{} DESC NULLS FIRST", c.field),
+            })
+            .collect::<Vec<_>>();
+        query_builder.push(format!(" ORDER BY {}", order.join(", ")));
+        query_builder.push(format!(" LIMIT {}", max_results + 1));
+
+        Ok(query_builder)
+    }
+
+    /// `(k0 > v0 OR (k0 = v0 AND (k1 > v1 OR (k1 = v1 AND id > v_id))))`, with the
+    /// comparison flipped for descending keys. NULL is past every value, so
+    /// `k > v` also holds for a NULL `k`, and `k = NULL` reads `k IS NULL`.
+    fn push_keyset_predicate(
+        query_builder: &mut QueryBuilder<'_, Postgres>,
+        sort_criteria: &[SortCriterion],
+        cursor: &Cursor,
+        backwards: bool,
+    ) {
+        let values = cursor
+            .keys
+            .iter()
+            .cloned()
+            .chain(std::iter::once(Some(ScalarValue::Int(cursor.id))))
+            .collect::<Vec<_>>();
+        let last = sort_criteria.len() - 1;
+        for (i, (criterion, value)) in sort_criteria.iter().zip(values).enumerate() {
+            let column = &criterion.field;
+            let upwards = criterion.ascending != backwards;
+            query_builder.push("(");
+            match (&value, upwards) {
+                // id, the last key, is never NULL
+                (Some(value), _) if i == last => {
+                    query_builder.push(format!("{} {} ", column, if upwards { ">" } else { "<" }));
+                    Self::push_scalar(query_builder, value.clone());
+                }
+                (Some(value), true) => {
+                    query_builder.push(format!("({} > ", column));
+                    Self::push_scalar(query_builder, value.clone());
+                    query_builder.push(format!(" OR {} IS NULL)", column));
+                }
+                (Some(value), false) => {
+                    query_builder.push(format!("{} < ", column));
+                    Self::push_scalar(query_builder, value.clone());
+                }
+                (None, true) => {
+                    query_builder.push("FALSE");
+                }
+                (None, false) => {
+                    query_builder.push(format!("{} IS NOT NULL", column));
+                }
+            }
+            if i < last {
+                match value {
+                    Some(value) => {
+                        query_builder.push(format!(" OR ({} = ", column));
+                        Self::push_scalar(query_builder, value);
+                    }
+                    None => {
+                        query_builder.push(format!(" OR ({} IS NULL", column));
+                    }
+                }
+                query_builder.push(" AND ");
+            }
+        }
+        query_builder.push(")".repeat(sort_criteria.len() * 2 - 1));
+    }
+
+    fn push_scalar(query_builder: &mut QueryBuilder<'_, Postgres>, value: ScalarValue) {
+        match value {
+            ScalarValue::String(v) => query_builder.push_bind(v),
+            ScalarValue::Int(v) => query_builder.push_bind(v),
+            ScalarValue::Float(v) => query_builder.push_bind(v),
+            ScalarValue::Bool(v) => query_builder.push_bind(v),
+            ScalarValue::Date(v) => query_builder.push_bind(v),
+            ScalarValue::DateTime(v) => query_builder.push_bind(v),
+            ScalarValue::Time(v) => query_builder.push_bind(v),
+        };
+    }
+
+    /// Appends a WHERE clause for `filters`; returns whether one was written.
+    fn push_filters<'a>(
+        &'a self,
+        query_builder: &mut QueryBuilder<'a, Postgres>,
+        filters: Vec<Filter>,
+    ) -> Result<bool, CoreError> {
+        let (property_filters, attribute_filters, search_filters, mut group_filters): (
+            Vec<_>,
+            Vec<_>,
+            Vec<_>,
+            Vec<_>,
+        ) = filters
+            .into_iter()
+            .filter(|f| !matches!(f, Filter::Search { value, .. } if value.trim().is_empty()))
+            .fold((vec![], vec![], vec![], vec![]), |mut acc, f| {
+                match &f {
+                    Filter::Property { .. } => acc.0.push(f),
+                    Filter::Attribute { .. } | Filter::Term { .. } => acc.1.push(f),
+                    Filter::Search { .. } => acc.2.push(f),
+                    Filter::And(_) | Filter::Or(_) | Filter::Not(_) | Filter::SoftDeleted(_) => {
+                        acc.3.push(f)
+                    }
+                }
+                acc
+            });
+        if SqlxViewMeta::get_deleted_at_column(self).is_some()
+            && !group_filters
+                .iter()
+                .any(|f| matches!(f, Filter::SoftDeleted(_)))
+        {
+            group_filters.push(Filter::SoftDeleted(SoftDeleteScope::Exclude));
+        }
+
+        let mut has_where = false;
+
+        for filter in property_filters
+            .into_iter()
+            .chain(attribute_filters)
+            .chain(search_filters)
+            .chain(group_filters)
+        {
             if !has_where {
                 query_builder.push(" WHERE ");
                 has_where = true;
             } else {
                 query_builder.push(" AND ");
             }
+            self.push_filter(query_builder, filter)?;
+        }
 
-            query_builder.push(format!(
-                "EXISTS (SELECT 1 FROM attribute_values av JOIN attributes a ON a.id = av.attribute_id WHERE av.entity_id = {}.id AND av.entity_type = ",
-                SqlxViewMeta::get_table_name(self)
-            ));
-            query_builder.push_bind(SqlxViewMeta::get_table_name(self));
+        Ok(has_where)
+    }
 
-            if let Filter::Attribute {
+    /// Appends a single boolean condition for `filter`, recursing into groups.
+    fn push_filter<'a>(
+        &'a self,
+        query_builder: &mut QueryBuilder<'a, Postgres>,
+        filter: Filter,
+    ) -> Result<(), CoreError> {
+        match filter {
+            Filter::Property {
+                property_name,
+                operator,
+                value,
+            } => {
+                self.build_property_filter(query_builder, &property_name, operator, value)?;
+            }
+            Filter::Attribute {
                 attr_name,
                 operator,
                 value,
-            } = filter
-            {
-                self.build_attribute_filter(&mut query_builder, attr_name, operator, value);
+            } => {
+                query_builder.push(format!(
+                    "EXISTS (SELECT 1 FROM attribute_values av JOIN attributes a ON a.id = av.attribute_id WHERE av.deleted_at IS NULL AND av.entity_id = {}.id AND av.entity_type = ",
+                    SqlxViewMeta::get_table_name(self)
+                ));
+                query_builder.push_bind(SqlxViewMeta::get_table_name(self));
+                self.build_attribute_filter(query_builder, attr_name, operator, value)?;
+                query_builder.push(")");
             }
-
-            query_builder.push(")");
-        }
-
-        for filter in search_filters {
-            if let Filter::Search { value } = filter {
+            Filter::Term { taxonomy, slug } => {
+                // Terms in the trash are kept on their posts but no longer match
+                let Some(post_id) = SqlxViewMeta::get_post_id_column(self) else {
+                    query_builder.push("FALSE");
+                    return Ok(());
+                };
+                query_builder.push(format!(
+                    "EXISTS (SELECT 1 FROM post_terms pt JOIN terms t ON t.id = pt.term_id JOIN post_taxonomies tx ON tx.id = t.taxonomy_id WHERE t.deleted_at IS NULL AND tx.deleted_at IS NULL AND pt.post_id = {}.{post_id}",
+                    SqlxViewMeta::get_table_name(self)
+                ));
+                if let Some(taxonomy) = taxonomy {
+                    query_builder.push(" AND tx.code = ").push_bind(taxonomy);
+                }
+                if let Some(slug) = slug {
+                    query_builder.push(" AND t.slug = ").push_bind(slug);
+                }
+                query_builder.push(")");
+            }
+            Filter::Search { value, fuzzy } => {
                 let keyword = value.trim();
                 if keyword.is_empty() {
-                    continue;
+                    query_builder.push("TRUE");
+                    return Ok(());
                 }
 
-                if !has_where {
-                    query_builder.push(" WHERE ");
-                    has_where = true;
-                } else {
-                    query_builder.push(" AND ");
+                if fuzzy {
+                    self.push_similarity_condition(query_builder, keyword.to_string());
+                    return Ok(());
+                }
+
+                if let Some(vector_column) = SqlxViewMeta::get_search_vector_column(self) {
+                    match to_tsquery(keyword) {
+                        Some(tsquery) => {
+                            query_builder
+                                .push(format!(
+                                    "{}.{vector_column} @@ to_tsquery('{SEARCH_CONFIG}', ",
+                                    SqlxViewMeta::get_table_name(self)
+                                ))
+                                .push_bind(tsquery)
+                                .push(")");
+                        }
+                        None => {
+                            query_builder.push("TRUE");
+                        }
+                    }
+                    return Ok(());
                 }
 
                 query_builder.push("(");
@@ -304,7 +1251,7 @@ pub trait SqlxViewRepository: SqlxViewMeta {
                 query_builder.push(" OR ");
 
                 {
-                    query_builder.push(" (EXISTS ( SELECT 1 FROM attribute_values av JOIN attributes a ON a.id = av.attribute_id WHERE av.entity_id = ")
+                    query_builder.push(" (EXISTS ( SELECT 1 FROM attribute_values av JOIN attributes a ON a.id = av.attribute_id WHERE av.deleted_at IS NULL AND av.entity_id = ")
                         .push(SqlxViewMeta::get_table_name(self)).push(".id")
                         .push(" AND av.entity_type = ")
                         .push_bind(SqlxViewMeta::get_table_name(self))
@@ -315,31 +1262,113 @@ pub trait SqlxViewRepository: SqlxViewMeta {
 
                 query_builder.push(")");
             }
+            Filter::And(filters) => self.push_filter_group(query_builder, filters, " AND ", "TRUE")?,
+            Filter::Or(filters) => self.push_filter_group(query_builder, filters, " OR ", "FALSE")?,
+            Filter::Not(filter) => {
+                query_builder.push("NOT (");
+                self.push_filter(query_builder, *filter)?;
+                query_builder.push(")");
+            }
+            Filter::SoftDeleted(scope) => self.push_soft_delete_condition(query_builder, scope),
         }
+        Ok(())
+    }
 
+    /// The condition selecting the rows `scope` sees.
+    fn push_soft_delete_condition(
+        &self,
+        query_builder: &mut QueryBuilder<'_, Postgres>,
+        scope: SoftDeleteScope,
+    ) {
+        let table = SqlxViewMeta::get_table_name(self);
+        match (SqlxViewMeta::get_deleted_at_column(self), scope) {
+            (_, SoftDeleteScope::Include) | (None, SoftDeleteScope::Exclude) => {
+                query_builder.push("TRUE");
+            }
+            (None, SoftDeleteScope::Only) => {
+                query_builder.push("FALSE");
+            }
+            (Some(column), SoftDeleteScope::Exclude) => {
+                query_builder.push(format!("{table}.{column} IS NULL"));
+            }
+            (Some(column), SoftDeleteScope::Only) => {
+                query_builder.push(format!("{table}.{column} IS NOT NULL"));
+            }
+        }
+    }
+
+    /// A parenthesized group joined by `separator`; an empty group is its identity.
+    fn push_filter_group<'a>(
+        &'a self,
+        query_builder: &mut QueryBuilder<'a, Postgres>,
+        filters: Vec<Filter>,
+        separator: &'static str,
+        identity: &'static str,
+    ) -> Result<(), CoreError> {
+        if filters.is_empty() {
+            query_builder.push(identity);
+            return Ok(());
+        }
+        query_builder.push("(");
+        for (i, filter) in filters.into_iter().enumerate() {
+            if i > 0 {
+                query_builder.push(separator);
+            }
+            self.push_filter(query_builder, filter)?;
+        }
+        query_builder.push(")");
+        Ok(())
+    }
+
+    /// One lateral join per attribute sort, exposing the attribute's typed value
+    /// columns as `sa{i}_<column>` so they cannot clash with the entity's own.
+    fn push_attribute_sort_joins<'a>(
+        &'a self,
+        query_builder: &mut QueryBuilder<'a, Postgres>,
+        sort_criteria: &[SortCriterion],
+    ) {
+        let table = SqlxViewMeta::get_table_name(self);
+        for (i, criterion) in sort_criteria.iter().enumerate() {
+            if !criterion.is_attribute {
+                continue;
+            }
+            let columns = ATTRIBUTE_VALUE_COLUMNS
+                .iter()
+                .map(|column| format!("av.{column} AS sa{i}_{column}"))
+                .collect::<Vec<_>>()
+                .join(", ");
+            query_builder.push(format!(
+                " LEFT JOIN LATERAL (SELECT {columns} FROM attribute_values av JOIN attributes a ON a.id = av.attribute_id WHERE av.deleted_at IS NULL AND av.entity_id = {table}.id AND av.entity_type = "
+            ));
+            query_builder.push_bind(table);
+            query_builder.push(" AND a.name = ");
+            query_builder.push_bind(criterion.field.clone());
+            query_builder.push(format!(") sa{i} ON TRUE"));
+        }
+    }
+
+    fn push_order_by(query_builder: &mut QueryBuilder<'_, Postgres>, sort_criteria: &[SortCriterion]) {
         if !sort_criteria.is_empty() {
             query_builder.push(" ORDER BY ");
             for (i, criterion) in sort_criteria.iter().enumerate() {
                 if i > 0 {
                     query_builder.push(", ");
                 }
-                query_builder.push(format!(
-                    "{} {}",
-                    criterion.field,
-                    if criterion.ascending { "ASC" } else { "DESC" }
-                ));
+                let direction = if criterion.ascending { "ASC" } else { "DESC" };
+                if criterion.is_attribute {
+                    // Only the column matching the attribute's data type is ever set,
+                    // so ordering by all of them orders by that one.
+                    let columns = ATTRIBUTE_VALUE_COLUMNS
+                        .iter()
+                        .map(|column| format!("sa{i}_{column} {direction}"))
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    query_builder.push(columns);
+                } else {
+                    query_builder.push(format!("{} {}", criterion.field, direction));
+                }
             }
         }
-
-        if !count {
-            query_builder.push(format!(" OFFSET {}", first_result.unwrap_or(0)));
-            match max_results {
-                Some(limit) => query_builder.push(format!(" LIMIT {}", limit)),
-                None => query_builder.push(" LIMIT ALL"),
-            };
-        }
-
-        query_builder
     }
     fn build_property_filter(
         &self,
@@ -347,7 +1376,7 @@ pub trait SqlxViewRepository: SqlxViewMeta {
         field: &str,
         operator: FilterOperator,
         value: FilterValue,
-    ) {
+    ) -> Result<(), CoreError> {
         let is_string_col = self.get_string_properties().contains(&field);
         if is_string_col {
             // Compare on the left side as text to avoid type mismatch
@@ -400,7 +1429,7 @@ pub trait SqlxViewRepository: SqlxViewMeta {
                 )),
                 FilterValue::None => FilterValue::None,
             };
-            Self::handle_operator(builder, operator, coerced);
+            Self::handle_operator(builder, operator, coerced)
         } else {
             // Default behavior for non-text columns
             let use_trunc = matches!(operator, FilterOperator::Equal | FilterOperator::NotEqual)
@@ -410,7 +1439,7 @@ pub trait SqlxViewRepository: SqlxViewMeta {
             } else {
                 builder.push(format!("{} ", field));
             }
-            Self::handle_operator(builder, operator, value);
+            Self::handle_operator(builder, operator, value)
         }
     }
 
@@ -420,7 +1449,7 @@ pub trait SqlxViewRepository: SqlxViewMeta {
         attr_name: String,
         operator: FilterOperator,
         value: FilterValue,
-    ) {
+    ) -> Result<(), CoreError> {
         builder.push(" AND a.name = ");
         builder.push_bind(attr_name);
 
@@ -470,15 +1499,15 @@ pub trait SqlxViewRepository: SqlxViewMeta {
         } else {
             builder.push(format!(" AND av.{} ", value_column));
         }
-        Self::handle_operator(builder, operator, value);
+        Self::handle_operator(builder, operator, value)
     }
 
     fn handle_operator(
         builder: &mut QueryBuilder<Postgres>,
         operator: FilterOperator,
         value: FilterValue,
-    ) {
-        match operator {
+    ) -> Result<(), CoreError> {
+        match &operator {
             FilterOperator::Equal => {
                 builder.push("= ");
                 match value {
@@ -509,7 +1538,7 @@ pub trait SqlxViewRepository: SqlxViewMeta {
                             }
                         };
                     }
-                    _ => unimplemented!("Equal expects a single scalar value"),
+                    _ => return Err(invalid_operand(&operator)),
                 }
             }
 
@@ -543,7 +1572,7 @@ pub trait SqlxViewRepository: SqlxViewMeta {
                             }
                         };
                     }
-                    _ => unimplemented!("NotEqual expects a single scalar value"),
+                    _ => return Err(invalid_operand(&operator)),
                 }
             }
 
@@ -552,7 +1581,7 @@ pub trait SqlxViewRepository: SqlxViewMeta {
                     builder.push("LIKE ");
                     builder.push_bind(format!("%{}%", v));
                 }
-                _ => unimplemented!("Like only supports string single value"),
+                _ => return Err(invalid_operand(&operator)),
             },
 
             FilterOperator::NotLike => match value {
@@ -560,7 +1589,17 @@ pub trait SqlxViewRepository: SqlxViewMeta {
                     builder.push("NOT LIKE ");
                     builder.push_bind(format!("%{}%", v));
                 }
-                _ => unimplemented!("NotLike only supports string single value"),
+                _ => return Err(invalid_operand(&operator)),
+            },
+
+            // `column %> value` is `value <% column`: some word of the column is
+            // similar to the value
+            FilterOperator::Similar => match value {
+                FilterValue::Single(ScalarValue::String(v)) => {
+                    builder.push("%> ");
+                    builder.push_bind(v);
+                }
+                _ => return Err(invalid_operand(&operator)),
             },
 
             FilterOperator::GreaterThan => {
@@ -583,10 +1622,10 @@ pub trait SqlxViewRepository: SqlxViewMeta {
                             ScalarValue::Time(v) => {
                                 builder.push_bind(v);
                             }
-                            _ => unimplemented!("GreaterThan unsupported scalar"),
+                            _ => return Err(invalid_operand(&operator)),
                         };
                     }
-                    _ => unimplemented!("GreaterThan expects a single scalar"),
+                    _ => return Err(invalid_operand(&operator)),
                 }
             }
 
@@ -610,10 +1649,10 @@ pub trait SqlxViewRepository: SqlxViewMeta {
                             ScalarValue::Time(v) => {
                                 builder.push_bind(v);
                             }
-                            _ => unimplemented!("GreaterThanOrEqual unsupported scalar"),
+                            _ => return Err(invalid_operand(&operator)),
                         };
                     }
-                    _ => unimplemented!("GreaterThanOrEqual expects a single scalar"),
+                    _ => return Err(invalid_operand(&operator)),
                 }
             }
 
@@ -637,10 +1676,10 @@ pub trait SqlxViewRepository: SqlxViewMeta {
                             ScalarValue::Time(v) => {
                                 builder.push_bind(v);
                             }
-                            _ => unimplemented!("LessThan unsupported scalar"),
+                            _ => return Err(invalid_operand(&operator)),
                         };
                     }
-                    _ => unimplemented!("LessThan expects a single scalar"),
+                    _ => return Err(invalid_operand(&operator)),
                 }
             }
 
@@ -664,41 +1703,49 @@ pub trait SqlxViewRepository: SqlxViewMeta {
                             ScalarValue::Time(v) => {
                                 builder.push_bind(v);
                             }
-                            _ => unimplemented!("LessThanOrEqual unsupported scalar"),
+                            _ => return Err(invalid_operand(&operator)),
                         };
                     }
-                    _ => unimplemented!("LessThanOrEqual expects a single scalar"),
+                    _ => return Err(invalid_operand(&operator)),
                 }
             }
 
             FilterOperator::In => match value {
                 FilterValue::List(vs) => {
-                    builder.push("IN ");
-                    builder.push_tuples(vs, |mut b, v| {
+                    builder.push("IN (");
+                    for (i, v) in vs.into_iter().enumerate() {
+                        if i > 0 {
+                            builder.push(", ");
+                        }
                         match v {
-                            ScalarValue::Int(i) => b.push_bind(i),
-                            ScalarValue::Float(f) => b.push_bind(f),
-                            ScalarValue::String(s) => b.push_bind(s),
-                            _ => unimplemented!("Unsupported element type in IN list"),
+                            ScalarValue::Int(i) => builder.push_bind(i),
+                            ScalarValue::Float(f) => builder.push_bind(f),
+                            ScalarValue::String(s) => builder.push_bind(s),
+                            _ => return Err(invalid_operand(&operator)),
                         };
-                    });
+                    }
+                    builder.push(")");
                 }
-                _ => unimplemented!("In expects a list"),
+                _ => return Err(invalid_operand(&operator)),
             },
 
             FilterOperator::NotIn => match value {
                 FilterValue::List(vs) => {
-                    builder.push("NOT IN ");
-                    builder.push_tuples(vs, |mut b, v| {
+                    builder.push("NOT IN (");
+                    for (i, v) in vs.into_iter().enumerate() {
+                        if i > 0 {
+                            builder.push(", ");
+                        }
                         match v {
-                            ScalarValue::Int(i) => b.push_bind(i),
-                            ScalarValue::Float(f) => b.push_bind(f),
-                            ScalarValue::String(s) => b.push_bind(s),
-                            _ => unimplemented!("Unsupported element type in NOT IN list"),
+                            ScalarValue::Int(i) => builder.push_bind(i),
+                            ScalarValue::Float(f) => builder.push_bind(f),
+                            ScalarValue::String(s) => builder.push_bind(s),
+                            _ => return Err(invalid_operand(&operator)),
                         };
-                    });
+                    }
+                    builder.push(")");
                 }
-                _ => unimplemented!("NotIn expects a list"),
+                _ => return Err(invalid_operand(&operator)),
             },
 
             FilterOperator::IsNull => {
@@ -720,7 +1767,7 @@ pub trait SqlxViewRepository: SqlxViewMeta {
                             ScalarValue::Date(v1) => builder.push_bind(v1),
                             ScalarValue::DateTime(v1) => builder.push_bind(v1),
                             ScalarValue::Time(v1) => builder.push_bind(v1),
-                            _ => unimplemented!("Unsupported start type for BETWEEN"),
+                            _ => return Err(invalid_operand(&operator)),
                         };
                         builder.push(" AND ");
                         match b {
@@ -729,10 +1776,10 @@ pub trait SqlxViewRepository: SqlxViewMeta {
                             ScalarValue::Date(v2) => builder.push_bind(v2),
                             ScalarValue::DateTime(v2) => builder.push_bind(v2),
                             ScalarValue::Time(v2) => builder.push_bind(v2),
-                            _ => unimplemented!("Unsupported end type for BETWEEN"),
+                            _ => return Err(invalid_operand(&operator)),
                         };
                     }
-                    _ => unimplemented!("Between expects a range"),
+                    _ => return Err(invalid_operand(&operator)),
                 }
             }
 
@@ -746,7 +1793,7 @@ pub trait SqlxViewRepository: SqlxViewMeta {
                             ScalarValue::Date(v1) => builder.push_bind(v1),
                             ScalarValue::DateTime(v1) => builder.push_bind(v1),
                             ScalarValue::Time(v1) => builder.push_bind(v1),
-                            _ => unimplemented!("Unsupported start type for NOT BETWEEN"),
+                            _ => return Err(invalid_operand(&operator)),
                         };
                         builder.push(" AND ");
                         match b {
@@ -755,15 +1802,17 @@ pub trait SqlxViewRepository: SqlxViewMeta {
                             ScalarValue::Date(v2) => builder.push_bind(v2),
                             ScalarValue::DateTime(v2) => builder.push_bind(v2),
                             ScalarValue::Time(v2) => builder.push_bind(v2),
-                            _ => unimplemented!("Unsupported end type for NOT BETWEEN"),
+                            _ => return Err(invalid_operand(&operator)),
                         };
                     }
-                    _ => unimplemented!("NotBetween expects a range"),
+                    _ => return Err(invalid_operand(&operator)),
                 }
             }
         };
+        Ok(())
     }
 }
+#[allow(async_fn_in_trait)]
 pub trait SqlxRepository: SqlxViewRepository
 where
     Self::EntityCreate: Creatable<Entity = Self::Entity>,
@@ -801,86 +1850,377 @@ where
         self.generic_update(entity).await
     }
 
-    // Generic helpers using OrmMeta + OrmBind + SqlxEntityMapper. Repos can call these.
-    async fn generic_create(
+    // Generic helpers using OrmMeta + OrmBind + SqlxEntityMapper. Repos can call these.
+    async fn generic_create(
+        &self,
+        entity_create: &<Self as SqlxRepository>::EntityCreate,
+    ) -> Result<<Self as SqlxViewRepository>::Entity, CoreError>
+    where
+        Self: SqlxEntityMapper<
+            Entity = <Self as SqlxViewRepository>::Entity,
+            EntityCreate = <Self as SqlxRepository>::EntityCreate,
+            Orm = <Self as SqlxViewRepository>::Orm,
+        >,
+        <Self as SqlxViewRepository>::Orm: OrmMeta + OrmBind,
+    {
+        let orm = <Self as SqlxEntityMapper>::to_orm_from_create(self, entity_create);
+        let table = SqlxViewMeta::get_table_name(self);
+        let insert_cols = <<Self as SqlxViewRepository>::Orm as OrmMeta>::insertable_columns();
+
+        let mut qb = QueryBuilder::<Postgres>::new("INSERT INTO ");
+        qb.push(table).push(" (");
+        for (i, col) in insert_cols.iter().enumerate() {
+            if i > 0 {
+                qb.push(", ");
+            }
+            qb.push(*col);
+        }
+        qb.push(") VALUES (");
+        for (i, col) in insert_cols.iter().enumerate() {
+            if i > 0 {
+                qb.push(", ");
+            }
+            orm.bind_column(col, &mut qb);
+        }
+        qb.push(") RETURNING *");
+
+        let orm_row: <Self as SqlxViewRepository>::Orm =
+            qb.build_query_as().fetch_one(&mut *self.acquire().await?).await?;
+        Ok(<Self as SqlxViewRepository>::from_orm(orm_row))
+    }
+
+    async fn generic_update(
+        &self,
+        entity: &<Self as SqlxViewRepository>::Entity,
+    ) -> Result<<Self as SqlxViewRepository>::Entity, CoreError>
+    where
+        Self: SqlxEntityMapper<
+            Entity = <Self as SqlxViewRepository>::Entity,
+            EntityCreate = <Self as SqlxRepository>::EntityCreate,
+            Orm = <Self as SqlxViewRepository>::Orm,
+        >,
+        <Self as SqlxViewRepository>::Orm: OrmMeta + OrmBind,
+        <Self as SqlxViewRepository>::Entity: BizEntity,
+    {
+        let orm = <Self as SqlxEntityMapper>::to_orm_from_entity(self, entity);
+        let table = SqlxViewMeta::get_table_name(self);
+        let set_cols = <<Self as SqlxViewRepository>::Orm as OrmMeta>::updatable_columns();
+
+        // Optimistic locking: only the caller's version may be updated, and every
+        // successful update bumps it.
+        let mut qb = QueryBuilder::<Postgres>::new("UPDATE ");
+        qb.push(table)
+            .push(" SET version = version + 1, updated_at = NOW()");
+        if !set_cols.is_empty() {
+            qb.push(", ");
+            orm.bind_update_pairs(&set_cols, &mut qb);
+        }
+        qb.push(" WHERE id = ");
+        qb.push_bind(entity.id());
+        qb.push(" AND version = ");
+        qb.push_bind(entity.version());
+        // A trashed row is read-only until it is restored
+        if let Some(column) = SqlxViewMeta::get_deleted_at_column(self) {
+            qb.push(format!(" AND {column} IS NULL"));
+        }
+        qb.push(" RETURNING *");
+
+        let orm_row: Option<<Self as SqlxViewRepository>::Orm> =
+            qb.build_query_as().fetch_optional(&mut *self.acquire().await?).await?;
+        match orm_row {
+            Some(orm_row) => Ok(<Self as SqlxViewRepository>::from_orm(orm_row)),
+            None => Err(self.version_conflict(entity.id(), entity.version()).await),
+        }
+    }
+
+    /// Explain why an optimistic update matched no row: either the row is gone
+    /// or trashed, or it was changed since `expected_version` was read.
+    async fn version_conflict(&self, id: i32, expected_version: i32) -> CoreError {
+        let mut conn = match self.acquire().await {
+            Ok(conn) => conn,
+            Err(e) => return e,
+        };
+        let live = SqlxViewMeta::get_deleted_at_column(self)
+            .map(|column| format!(" AND {column} IS NULL"))
+            .unwrap_or_default();
+        let current: Result<Option<i32>, sqlx::Error> = sqlx::query_scalar(&format!(
+            "SELECT version FROM {} WHERE id = $1{}",
+            SqlxViewMeta::get_table_name(self),
+            live
+        ))
+        .bind(id)
+        .fetch_optional(&mut *conn)
+        .await;
+        match current {
+            Ok(Some(current_version)) => CoreError::Conflict(
+                "error.version.conflict",
+                HashMap::from([
+                    ("id".into(), id.to_string()),
+                    ("expected_version".into(), expected_version.to_string()),
+                    ("current_version".into(), current_version.to_string()),
+                ]),
+            ),
+            Ok(None) => CoreError::not_found("error.not_found"),
+            Err(e) => e.into(),
+        }
+    }
+
+    /// Multi-row insert, split to stay under the bind parameter limit. Rows
+    /// come back in input order.
+    async fn generic_create_many(
+        &self,
+        creates: &[<Self as SqlxRepository>::EntityCreate],
+    ) -> Result<Vec<<Self as SqlxViewRepository>::Entity>, CoreError>
+    where
+        Self: SqlxEntityMapper<
+            Entity = <Self as SqlxViewRepository>::Entity,
+            EntityCreate = <Self as SqlxRepository>::EntityCreate,
+            Orm = <Self as SqlxViewRepository>::Orm,
+        >,
+        <Self as SqlxViewRepository>::Orm: OrmMeta + OrmBind,
+    {
+        let orms: Vec<_> = creates
+            .iter()
+            .map(|c| <Self as SqlxEntityMapper>::to_orm_from_create(self, c))
+            .collect();
+        let cols = <<Self as SqlxViewRepository>::Orm as OrmMeta>::insertable_columns();
+        let mut created = Vec::with_capacity(orms.len());
+        for chunk in orms.chunks(rows_per_statement(cols.len())) {
+            let mut qb = self.build_insert_many_query(chunk, false);
+            qb.push(" RETURNING *");
+            let rows: Vec<<Self as SqlxViewRepository>::Orm> =
+                qb.build_query_as().fetch_all(&mut *self.acquire().await?).await?;
+            created.extend(rows.into_iter().map(<Self as SqlxViewRepository>::from_orm));
+        }
+        Ok(created)
+    }
+
+    /// Optimistically locked multi-row update. Fails as a whole when any row is
+    /// gone or stale, with the error `generic_update` would give for the first
+    /// such row. Rows come back in input order.
+    async fn generic_update_many(
+        &self,
+        entities: &[<Self as SqlxViewRepository>::Entity],
+    ) -> Result<Vec<<Self as SqlxViewRepository>::Entity>, CoreError>
+    where
+        Self: SqlxEntityMapper<
+            Entity = <Self as SqlxViewRepository>::Entity,
+            EntityCreate = <Self as SqlxRepository>::EntityCreate,
+            Orm = <Self as SqlxViewRepository>::Orm,
+        >,
+        <Self as SqlxViewRepository>::Orm: OrmMeta + OrmBind,
+        <Self as SqlxViewRepository>::Entity: BizEntity,
+    {
+        let orms: Vec<_> = entities
+            .iter()
+            .map(|e| <Self as SqlxEntityMapper>::to_orm_from_entity(self, e))
+            .collect();
+        let cols = <<Self as SqlxViewRepository>::Orm as OrmMeta>::updatable_columns();
+        let mut updated: HashMap<i32, <Self as SqlxViewRepository>::Entity> = HashMap::new();
+        for chunk in orms.chunks(rows_per_statement(cols.len() + 2)) {
+            let rows: Vec<<Self as SqlxViewRepository>::Orm> = self
+                .build_update_many_query(chunk)
+                .build_query_as()
+                .fetch_all(&mut *self.acquire().await?)
+                .await?;
+            for row in rows {
+                let entity = <Self as SqlxViewRepository>::from_orm(row);
+                updated.insert(entity.id(), entity);
+            }
+        }
+        let mut result = Vec::with_capacity(entities.len());
+        for entity in entities {
+            match updated.remove(&entity.id()) {
+                Some(row) => result.push(row),
+                None => return Err(self.version_conflict(entity.id(), entity.version()).await),
+            }
+        }
+        Ok(result)
+    }
+
+    /// Inserts each entity as a new row, or updates the row already holding
+    /// its `key` values. New rows get a fresh uid and version 0; updates bump
+    /// the version and take every other updatable column from the entity, and
+    /// bring back a trashed row. Rows come back in input order; two entities
+    /// sharing a key fail the statement.
+    async fn generic_upsert_by(
+        &self,
+        key: &[&str],
+        entities: &[<Self as SqlxViewRepository>::Entity],
+    ) -> Result<Vec<<Self as SqlxViewRepository>::Entity>, CoreError>
+    where
+        Self: SqlxEntityMapper<
+            Entity = <Self as SqlxViewRepository>::Entity,
+            EntityCreate = <Self as SqlxRepository>::EntityCreate,
+            Orm = <Self as SqlxViewRepository>::Orm,
+        >,
+        <Self as SqlxViewRepository>::Orm: OrmMeta + OrmBind,
+    {
+        self.validate_upsert_key(key)?;
+        let orms: Vec<_> = entities
+            .iter()
+            .map(|e| <Self as SqlxEntityMapper>::to_orm_from_entity(self, e))
+            .collect();
+        let cols = <<Self as SqlxViewRepository>::Orm as OrmMeta>::insertable_columns();
+        let mut upserted = Vec::with_capacity(orms.len());
+        for chunk in orms.chunks(rows_per_statement(cols.len())) {
+            let rows: Vec<<Self as SqlxViewRepository>::Orm> = self
+                .build_upsert_query(key, chunk)
+                .build_query_as()
+                .fetch_all(&mut *self.acquire().await?)
+                .await?;
+            upserted.extend(rows.into_iter().map(<Self as SqlxViewRepository>::from_orm));
+        }
+        Ok(upserted)
+    }
+
+    /// An upsert key must be made of columns written from the entity; they are
+    /// formatted into the `ON CONFLICT` clause.
+    fn validate_upsert_key(&self, key: &[&str]) -> Result<(), CoreError>
+    where
+        <Self as SqlxViewRepository>::Orm: OrmMeta,
+    {
+        if key.is_empty() {
+            return Err(CoreError::unprocessable_entity("error.upsert.key.empty"));
+        }
+        let columns = <<Self as SqlxViewRepository>::Orm as OrmMeta>::updatable_columns();
+        match key.iter().find(|k| !columns.contains(k)) {
+            Some(unknown) => Err(CoreError::UnprocessableEntity(
+                "error.upsert.key.unknown",
+                HashMap::from([("column".into(), unknown.to_string())]),
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// `INSERT INTO <table> (<insertable columns>) VALUES (...), (...)`. With
+    /// `fresh_identity`, rows get a new uid, version 0 and the current time
+    /// instead of the ones they carry.
+    fn build_insert_many_query<'a>(
+        &self,
+        orms: &'a [<Self as SqlxViewRepository>::Orm],
+        fresh_identity: bool,
+    ) -> QueryBuilder<'a, Postgres>
+    where
+        <Self as SqlxViewRepository>::Orm: OrmMeta + OrmBind,
+    {
+        let cols = <<Self as SqlxViewRepository>::Orm as OrmMeta>::insertable_columns();
+        let mut qb = QueryBuilder::<Postgres>::new("INSERT INTO ");
+        qb.push(SqlxViewMeta::get_table_name(self))
+            .push(" (")
+            .push(cols.join(", "))
+            .push(") VALUES ");
+        for (i, orm) in orms.iter().enumerate() {
+            if i > 0 {
+                qb.push(", ");
+            }
+            qb.push("(");
+            for (j, col) in cols.iter().enumerate() {
+                if j > 0 {
+                    qb.push(", ");
+                }
+                match *col {
+                    "uid" if fresh_identity => {
+                        qb.push_bind(Uuid::now_v7());
+                    }
+                    "version" if fresh_identity => {
+                        qb.push("0");
+                    }
+                    "created_at" | "updated_at" if fresh_identity => {
+                        qb.push("NOW()");
+                    }
+                    _ => orm.bind_column(col, &mut qb),
+                }
+            }
+            qb.push(")");
+        }
+        qb
+    }
+
+    /// One `UPDATE ... FROM (VALUES ...)` matching each row on id and version.
+    fn build_update_many_query<'a>(
         &self,
-        entity_create: &<Self as SqlxRepository>::EntityCreate,
-    ) -> Result<<Self as SqlxViewRepository>::Entity, CoreError>
+        orms: &'a [<Self as SqlxViewRepository>::Orm],
+    ) -> QueryBuilder<'a, Postgres>
     where
-        Self: SqlxEntityMapper<
-            Entity = <Self as SqlxViewRepository>::Entity,
-            EntityCreate = <Self as SqlxRepository>::EntityCreate,
-            Orm = <Self as SqlxViewRepository>::Orm,
-        >,
         <Self as SqlxViewRepository>::Orm: OrmMeta + OrmBind,
     {
-        let orm = <Self as SqlxEntityMapper>::to_orm_from_create(self, entity_create);
-        let table = SqlxViewMeta::get_table_name(self);
-        let insert_cols = <<Self as SqlxViewRepository>::Orm as OrmMeta>::insertable_columns();
-
-        let mut qb = QueryBuilder::<Postgres>::new("INSERT INTO ");
-        qb.push(table).push(" (");
-        for (i, col) in insert_cols.iter().enumerate() {
+        let cols = <<Self as SqlxViewRepository>::Orm as OrmMeta>::updatable_columns();
+        let mut qb = QueryBuilder::<Postgres>::new("UPDATE ");
+        qb.push(SqlxViewMeta::get_table_name(self))
+            .push(" AS t SET version = t.version + 1, updated_at = NOW()");
+        for col in cols.iter() {
+            qb.push(format!(", {col} = v.{col}"));
+        }
+        qb.push(" FROM (VALUES ");
+        for (i, orm) in orms.iter().enumerate() {
             if i > 0 {
                 qb.push(", ");
             }
-            qb.push(*col);
-        }
-        qb.push(") VALUES (");
-        for (i, col) in insert_cols.iter().enumerate() {
-            if i > 0 {
+            qb.push("(");
+            orm.bind_column("id", &mut qb);
+            qb.push(", ");
+            orm.bind_column("version", &mut qb);
+            for col in cols.iter() {
                 qb.push(", ");
+                orm.bind_column(col, &mut qb);
             }
-            orm.bind_column(col, &mut qb);
+            qb.push(")");
         }
-        qb.push(") RETURNING *");
-
-        let orm_row: <Self as SqlxViewRepository>::Orm =
-            qb.build_query_as().fetch_one(self.get_pool()).await?;
-        Ok(<Self as SqlxViewRepository>::from_orm(orm_row))
+        qb.push(") AS v (id, version");
+        for col in cols.iter() {
+            qb.push(", ").push(*col);
+        }
+        qb.push(") WHERE t.id = v.id AND t.version = v.version");
+        if let Some(column) = SqlxViewMeta::get_deleted_at_column(self) {
+            qb.push(format!(" AND t.{column} IS NULL"));
+        }
+        qb.push(" RETURNING t.*");
+        qb
     }
 
-    async fn generic_update(
+    /// Multi-row insert that updates rows already holding the same `key`.
+    fn build_upsert_query<'a>(
         &self,
-        entity: &<Self as SqlxViewRepository>::Entity,
-    ) -> Result<<Self as SqlxViewRepository>::Entity, CoreError>
+        key: &[&str],
+        orms: &'a [<Self as SqlxViewRepository>::Orm],
+    ) -> QueryBuilder<'a, Postgres>
     where
-        Self: SqlxEntityMapper<
-            Entity = <Self as SqlxViewRepository>::Entity,
-            EntityCreate = <Self as SqlxRepository>::EntityCreate,
-            Orm = <Self as SqlxViewRepository>::Orm,
-        >,
         <Self as SqlxViewRepository>::Orm: OrmMeta + OrmBind,
-        <Self as SqlxViewRepository>::Entity: BizEntity,
     {
-        let orm = <Self as SqlxEntityMapper>::to_orm_from_entity(self, entity);
         let table = SqlxViewMeta::get_table_name(self);
-        let set_cols = <<Self as SqlxViewRepository>::Orm as OrmMeta>::updatable_columns();
-
-        let mut qb = QueryBuilder::<Postgres>::new("UPDATE ");
-        qb.push(table).push(" SET ");
-        orm.bind_update_pairs(&set_cols, &mut qb);
-        qb.push(" WHERE id = ");
-        qb.push_bind(entity.id());
+        let mut qb = self.build_insert_many_query(orms, true);
+        qb.push(" ON CONFLICT (")
+            .push(key.join(", "))
+            .push(format!(
+                ") DO UPDATE SET version = {table}.version + 1, updated_at = NOW()"
+            ));
+        let insert_only = SqlxViewMeta::get_insert_only_columns(self);
+        for col in <<Self as SqlxViewRepository>::Orm as OrmMeta>::updatable_columns()
+            .iter()
+            .filter(|c| !key.contains(c) && !insert_only.contains(c))
+        {
+            qb.push(format!(", {col} = EXCLUDED.{col}"));
+        }
+        if let Some(column) = SqlxViewMeta::get_deleted_at_column(self) {
+            qb.push(format!(", {column} = NULL"));
+        }
         qb.push(" RETURNING *");
-
-        let orm_row: <Self as SqlxViewRepository>::Orm =
-            qb.build_query_as().fetch_one(self.get_pool()).await?;
-        Ok(<Self as SqlxViewRepository>::from_orm(orm_row))
+        qb
     }
 
     // Provided defaults: common attribute type map and delete helpers
     async fn get_attribute_type_map(&self) -> Result<HashMap<String, ScalarValue>, CoreError> {
         // Repository: fetch directly from DB; caching is handled at the service layer
         let entity_type = SqlxViewMeta::get_table_name(self).to_string();
-        let pool = self.get_pool();
+        let mut conn = self.acquire().await?;
 
         // Select attribute name and textual data type for the owner entity_type
         let rows = sqlx::query(
-            "SELECT name, data_type::text AS data_type FROM attributes WHERE entity_type = $1",
+            "SELECT name, data_type::text AS data_type FROM attributes WHERE entity_type = $1 AND deleted_at IS NULL",
         )
         .bind(&entity_type)
-        .fetch_all(pool)
+        .fetch_all(&mut *conn)
         .await?;
 
         let mut map: HashMap<String, ScalarValue> = HashMap::new();
@@ -889,55 +2229,44 @@ where
             let dt: String = row.get("data_type");
             if map.contains_key(&name) {
                 return Err(CoreError::UnprocessableEntity(
-                    "error.attribute.duplicate.name".into(),
+                    "error.attribute.duplicate.name",
                     std::collections::HashMap::from([
                         ("name".into(), name),
                         ("entity_type".into(), entity_type.clone()),
                     ]),
                 ));
             }
-            let scalar = match dt.as_str() {
-                "int" | "integer" => ScalarValue::Int(0),
-                "double" | "float" | "double precision" | "real" | "numeric" => {
-                    ScalarValue::Float(0.0)
-                }
-                "string" | "text" | "character varying" | "character" => {
-                    ScalarValue::String(String::new())
-                }
-                "boolean" | "bool" => ScalarValue::Bool(false),
-                "date" => ScalarValue::Date(
-                    time::Date::from_calendar_date(1970, time::Month::January, 1).unwrap(),
-                ),
-                "datetime"
-                | "timestamp"
-                | "timestamp with time zone"
-                | "timestamp without time zone" => {
-                    ScalarValue::DateTime(time::OffsetDateTime::UNIX_EPOCH)
-                }
-                "time" => ScalarValue::Time(time::Time::from_hms(0, 0, 0).unwrap()),
-                other => {
-                    return Err(CoreError::UnprocessableEntity(
-                        "error.attribute.unsupported.datatype".into(),
-                        std::collections::HashMap::from([
-                            ("datatype".into(), other.to_string()),
-                            ("name".into(), name),
-                        ]),
-                    ));
-                }
+            let Some(scalar) = attribute_prototype(&dt) else {
+                return Err(CoreError::UnprocessableEntity(
+                    "error.attribute.unsupported.datatype",
+                    std::collections::HashMap::from([
+                        ("datatype".into(), dt),
+                        ("name".into(), name),
+                    ]),
+                ));
             };
             map.insert(name, scalar);
         }
         Ok(map)
     }
 
+    /// Deletes move rows to the trash when the table supports soft deletion;
+    /// otherwise they are removed for good.
+    fn delete_query_prefix(&self) -> String {
+        let table = SqlxViewMeta::get_table_name(self);
+        match SqlxViewMeta::get_deleted_at_column(self) {
+            Some(column) => {
+                format!("UPDATE {table} SET {column} = now() WHERE {column} IS NULL AND ")
+            }
+            None => format!("DELETE FROM {table} WHERE "),
+        }
+    }
+
     async fn delete_by_id(&self, id: i32) -> Result<u64, CoreError> {
-        let result = sqlx::query(&format!(
-            "DELETE FROM {} WHERE id = $1",
-            SqlxViewMeta::get_table_name(self)
-        ))
-        .bind(id)
-        .execute(self.get_pool())
-        .await?;
+        let result = sqlx::query(&format!("{}id = $1", self.delete_query_prefix()))
+            .bind(id)
+            .execute(&mut *self.acquire().await?)
+            .await?;
 
         Ok(result.rows_affected())
     }
@@ -947,10 +2276,8 @@ where
             return Ok(0);
         }
 
-        let mut builder = QueryBuilder::<Postgres>::new(format!(
-            "DELETE FROM {} WHERE id IN ",
-            SqlxViewMeta::get_table_name(self)
-        ));
+        let mut builder =
+            QueryBuilder::<Postgres>::new(format!("{}id IN ", self.delete_query_prefix()));
 
         // Follow the same convention used in filter IN/NOT IN: use tuples for values
         builder.push_tuples(ids, |mut b, id| {
@@ -958,18 +2285,15 @@ where
         });
 
         let query = builder.build();
-        let result = query.execute(self.get_pool()).await?;
+        let result = query.execute(&mut *self.acquire().await?).await?;
         Ok(result.rows_affected())
     }
 
     async fn delete_by_uid(&self, uid: Uuid) -> Result<u64, CoreError> {
-        let result = sqlx::query(&format!(
-            "DELETE FROM {} WHERE uid = $1",
-            SqlxViewMeta::get_table_name(self)
-        ))
-        .bind(uid)
-        .execute(self.get_pool())
-        .await?;
+        let result = sqlx::query(&format!("{}uid = $1", self.delete_query_prefix()))
+            .bind(uid)
+            .execute(&mut *self.acquire().await?)
+            .await?;
 
         Ok(result.rows_affected())
     }
@@ -979,17 +2303,210 @@ where
             return Ok(0);
         }
 
+        let mut builder =
+            QueryBuilder::<Postgres>::new(format!("{}uid IN ", self.delete_query_prefix()));
+        builder.push_tuples(uids, |mut b, id| {
+            b.push_bind(id);
+        });
+        let query = builder.build();
+        let result = query.execute(&mut *self.acquire().await?).await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Takes rows out of the trash. Tables without soft deletion have no trash.
+    async fn restore_by_ids(&self, ids: Vec<i32>) -> Result<u64, CoreError> {
+        let Some(column) = SqlxViewMeta::get_deleted_at_column(self) else {
+            return Ok(0);
+        };
+        if ids.is_empty() {
+            return Ok(0);
+        }
+
         let mut builder = QueryBuilder::<Postgres>::new(format!(
-            "DELETE FROM {} WHERE uid IN ",
+            "UPDATE {} SET {column} = NULL WHERE {column} IS NOT NULL AND id IN ",
             SqlxViewMeta::get_table_name(self)
         ));
-        builder.push_tuples(uids, |mut b, id| {
+        builder.push_tuples(ids, |mut b, id| {
+            b.push_bind(id);
+        });
+        let query = builder.build();
+        let result = query.execute(&mut *self.acquire().await?).await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Removes rows for good, cascading to their dependents. Only trashed rows
+    /// are purged, so a live row always goes through the trash first.
+    async fn purge_by_ids(&self, ids: Vec<i32>) -> Result<u64, CoreError> {
+        if ids.is_empty() {
+            return Ok(0);
+        }
+
+        let table = SqlxViewMeta::get_table_name(self);
+        let mut builder = QueryBuilder::<Postgres>::new(
+            match SqlxViewMeta::get_deleted_at_column(self) {
+                Some(column) => format!("DELETE FROM {table} WHERE {column} IS NOT NULL AND id IN "),
+                None => format!("DELETE FROM {table} WHERE id IN "),
+            },
+        );
+        builder.push_tuples(ids, |mut b, id| {
             b.push_bind(id);
         });
         let query = builder.build();
-        let result = query.execute(self.get_pool()).await?;
+        let result = query.execute(&mut *self.acquire().await?).await?;
         Ok(result.rows_affected())
     }
+
+    /// `SELECT id, uid, <audited columns as a JSON object> FROM <table> WHERE
+    /// <scope> AND `, ready for the key condition. The audited columns are the
+    /// updatable ones, minus `get_unaudited_columns`.
+    fn build_snapshot_query_prefix(&self, scope: SoftDeleteScope) -> QueryBuilder<'_, Postgres>
+    where
+        <Self as SqlxViewRepository>::Orm: OrmMeta,
+    {
+        let unaudited = SqlxViewMeta::get_unaudited_columns(self);
+        let values = <<Self as SqlxViewRepository>::Orm as OrmMeta>::updatable_columns()
+            .into_iter()
+            .filter(|c| !unaudited.contains(c))
+            .map(|c| format!("'{c}', {c}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let mut builder = QueryBuilder::<Postgres>::new(format!(
+            "SELECT id, uid::text AS uid, jsonb_build_object({values})::text AS snapshot_values FROM {} WHERE ",
+            SqlxViewMeta::get_table_name(self)
+        ));
+        self.push_soft_delete_condition(&mut builder, scope);
+        builder.push(" AND ");
+        builder
+    }
+
+    async fn fetch_snapshots(
+        &self,
+        mut builder: QueryBuilder<'_, Postgres>,
+    ) -> Result<Vec<Snapshot>, CoreError> {
+        builder.push(" ORDER BY id");
+        let rows: Vec<(i32, String, String)> = builder
+            .build_query_as()
+            .fetch_all(&mut *self.acquire().await?)
+            .await?;
+        rows.into_iter()
+            .map(|(id, uid, values)| {
+                let values = serde_json::from_str(&values).map_err(|_| {
+                    CoreError::internal_server_error("error.audit.snapshot.invalid")
+                })?;
+                Ok(Snapshot { id, uid, values })
+            })
+            .collect()
+    }
+
+    async fn snapshot_by_ids(
+        &self,
+        ids: Vec<i32>,
+        scope: SoftDeleteScope,
+    ) -> Result<Vec<Snapshot>, CoreError>
+    where
+        <Self as SqlxViewRepository>::Orm: OrmMeta,
+    {
+        if ids.is_empty() {
+            return Ok(vec![]);
+        }
+        let mut builder = self.build_snapshot_query_prefix(scope);
+        builder.push("id IN ");
+        builder.push_tuples(ids, |mut b, id| {
+            b.push_bind(id);
+        });
+        self.fetch_snapshots(builder).await
+    }
+
+    async fn snapshot_by_uids(
+        &self,
+        uids: Vec<Uuid>,
+        scope: SoftDeleteScope,
+    ) -> Result<Vec<Snapshot>, CoreError>
+    where
+        <Self as SqlxViewRepository>::Orm: OrmMeta,
+    {
+        if uids.is_empty() {
+            return Ok(vec![]);
+        }
+        let mut builder = self.build_snapshot_query_prefix(scope);
+        builder.push("uid IN ");
+        builder.push_tuples(uids, |mut b, uid| {
+            b.push_bind(uid);
+        });
+        self.fetch_snapshots(builder).await
+    }
+
+    /// Snapshots of the rows holding the same `key` values as `entities`, i.e.
+    /// the rows an upsert of `entities` would update.
+    async fn snapshot_by_key(
+        &self,
+        key: &[&str],
+        entities: &[<Self as SqlxViewRepository>::Entity],
+        scope: SoftDeleteScope,
+    ) -> Result<Vec<Snapshot>, CoreError>
+    where
+        Self: SqlxEntityMapper<
+            Entity = <Self as SqlxViewRepository>::Entity,
+            EntityCreate = <Self as SqlxRepository>::EntityCreate,
+            Orm = <Self as SqlxViewRepository>::Orm,
+        >,
+        <Self as SqlxViewRepository>::Orm: OrmMeta + OrmBind,
+    {
+        self.validate_upsert_key(key)?;
+        if entities.is_empty() {
+            return Ok(vec![]);
+        }
+        let orms: Vec<_> = entities
+            .iter()
+            .map(|e| <Self as SqlxEntityMapper>::to_orm_from_entity(self, e))
+            .collect();
+        let mut snapshots = Vec::new();
+        for chunk in orms.chunks(rows_per_statement(key.len())) {
+            let mut builder = self.build_snapshot_query_prefix(scope);
+            builder.push("(").push(key.join(", ")).push(") IN (VALUES ");
+            for (i, orm) in chunk.iter().enumerate() {
+                if i > 0 {
+                    builder.push(", ");
+                }
+                builder.push("(");
+                for (j, col) in key.iter().enumerate() {
+                    if j > 0 {
+                        builder.push(", ");
+                    }
+                    orm.bind_column(col, &mut builder);
+                }
+                builder.push(")");
+            }
+            builder.push(")");
+            snapshots.extend(self.fetch_snapshots(builder).await?);
+        }
+        Ok(snapshots)
+    }
+
+    async fn record_audit(&self, entries: Vec<AuditEntry>) -> Result<(), CoreError> {
+        if entries.is_empty() {
+            return Ok(());
+        }
+        let entity_type = SqlxViewMeta::get_table_name(self).to_string();
+        let entity_uids = entries
+            .iter()
+            .map(AuditEntry::entity_uuid)
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut builder = QueryBuilder::<Postgres>::new(format!(
+            "INSERT INTO {AUDIT_LOG_TABLE} (uid, entity_type, entity_id, entity_uid, operation, user_id, changes) "
+        ));
+        builder.push_values(entries.into_iter().zip(entity_uids), |mut b, (entry, entity_uid)| {
+            b.push_bind(Uuid::now_v7())
+                .push_bind(entity_type.clone())
+                .push_bind(entry.entity_id)
+                .push_bind(entity_uid)
+                .push_bind(entry.operation.as_str())
+                .push_bind(entry.user_id)
+                .push_bind(serde_json::Value::Object(entry.changes).to_string());
+        });
+        builder.build().execute(&mut *self.acquire().await?).await?;
+        Ok(())
+    }
 }
 
 // Blanket implementation: any SqlxViewRepository automatically implements ViewRepository for its Entity
@@ -1011,6 +2528,15 @@ where
         SqlxViewRepository::count(self, filters).await
     }
 
+    async fn find_suffixed(
+        &self,
+        column: &str,
+        base: &str,
+        filters: Vec<Filter>,
+    ) -> Result<Vec<String>, CoreError> {
+        SqlxViewRepository::find_suffixed(self, column, base, filters).await
+    }
+
     async fn find_many(
         &self,
         sort_criteria: Vec<SortCriterion>,
@@ -1021,6 +2547,72 @@ where
         SqlxViewRepository::find_many(self, sort_criteria, first_result, max_results, filters).await
     }
 
+    async fn find_many_with_attributes(
+        &self,
+        sort_criteria: Vec<SortCriterion>,
+        first_result: Option<i32>,
+        max_results: Option<i32>,
+        filters: Vec<Filter>,
+    ) -> Result<Vec<WithAttributes<<R as SqlxViewRepository>::Entity>>, CoreError> {
+        SqlxViewRepository::find_many_with_attributes(
+            self,
+            sort_criteria,
+            first_result,
+            max_results,
+            filters,
+        )
+        .await
+    }
+
+    async fn facets(
+        &self,
+        fields: Vec<FacetField>,
+        filters: Vec<Filter>,
+    ) -> Result<Vec<Facet>, CoreError> {
+        SqlxViewRepository::facets(self, fields, filters).await
+    }
+
+    async fn search(
+        &self,
+        query: String,
+        first_result: Option<i32>,
+        max_results: Option<i32>,
+        filters: Vec<Filter>,
+    ) -> Result<Vec<SearchHit<<R as SqlxViewRepository>::Entity>>, CoreError> {
+        SqlxViewRepository::search(self, query, first_result, max_results, filters).await
+    }
+
+    async fn suggest(
+        &self,
+        query: String,
+        max_results: Option<i32>,
+        filters: Vec<Filter>,
+    ) -> Result<Vec<Suggestion<<R as SqlxViewRepository>::Entity>>, CoreError> {
+        SqlxViewRepository::suggest(self, query, max_results, filters).await
+    }
+
+    async fn find_page(
+        &self,
+        sort_criteria: Vec<SortCriterion>,
+        position: CursorPosition,
+        max_results: i32,
+        filters: Vec<Filter>,
+    ) -> Result<CursorPage<<R as SqlxViewRepository>::Entity>, CoreError> {
+        SqlxViewRepository::find_page(self, sort_criteria, position, max_results, filters).await
+    }
+
+    async fn aggregate(
+        &self,
+        group_by: Vec<String>,
+        aggregates: Vec<Aggregate>,
+        first_result: Option<i32>,
+        max_results: Option<i32>,
+        filters: Vec<Filter>,
+    ) -> Result<Vec<AggregateGroup>, CoreError> {
+        SqlxViewRepository::aggregate(self, group_by, aggregates, first_result, max_results, filters)
+            .await
+    }
+
     async fn find_by_id(
         &self,
         id: i32,
@@ -1089,6 +2681,22 @@ where
         SqlxRepository::delete_by_uids(self, parsed).await
     }
 
+    async fn restore_by_id(&self, id: i32) -> Result<u64, CoreError> {
+        SqlxRepository::restore_by_ids(self, vec![id]).await
+    }
+
+    async fn restore_by_ids(&self, ids: Vec<i32>) -> Result<u64, CoreError> {
+        SqlxRepository::restore_by_ids(self, ids).await
+    }
+
+    async fn purge_by_id(&self, id: i32) -> Result<u64, CoreError> {
+        SqlxRepository::purge_by_ids(self, vec![id]).await
+    }
+
+    async fn purge_by_ids(&self, ids: Vec<i32>) -> Result<u64, CoreError> {
+        SqlxRepository::purge_by_ids(self, ids).await
+    }
+
     async fn create(
         &self,
         entity_create: &<R as SqlxRepository>::EntityCreate,
@@ -1103,7 +2711,122 @@ where
         <Self as SqlxRepository>::update(self, entity).await
     }
 
+    async fn create_many(
+        &self,
+        creates: &[<R as SqlxRepository>::EntityCreate],
+    ) -> Result<Vec<<R as SqlxViewRepository>::Entity>, CoreError> {
+        if creates.is_empty() {
+            return Ok(vec![]);
+        }
+        SqlxRepository::generic_create_many(self, creates).await
+    }
+
+    async fn update_many(
+        &self,
+        entities: &[<R as SqlxViewRepository>::Entity],
+    ) -> Result<Vec<<R as SqlxViewRepository>::Entity>, CoreError> {
+        if entities.is_empty() {
+            return Ok(vec![]);
+        }
+        SqlxRepository::generic_update_many(self, entities).await
+    }
+
+    async fn upsert_by(
+        &self,
+        key: &[&str],
+        entities: &[<R as SqlxViewRepository>::Entity],
+    ) -> Result<Vec<<R as SqlxViewRepository>::Entity>, CoreError> {
+        if entities.is_empty() {
+            return self.validate_upsert_key(key).map(|_| vec![]);
+        }
+        SqlxRepository::generic_upsert_by(self, key, entities).await
+    }
+
     async fn get_attribute_type_map(&self) -> Result<HashMap<String, ScalarValue>, CoreError> {
         SqlxRepository::get_attribute_type_map(self).await
     }
+
+    async fn snapshot_by_ids(
+        &self,
+        ids: Vec<i32>,
+        scope: SoftDeleteScope,
+    ) -> Result<Vec<Snapshot>, CoreError> {
+        SqlxRepository::snapshot_by_ids(self, ids, scope).await
+    }
+
+    async fn snapshot_by_uids(
+        &self,
+        uids: Vec<String>,
+        scope: SoftDeleteScope,
+    ) -> Result<Vec<Snapshot>, CoreError> {
+        let mut parsed = Vec::with_capacity(uids.len());
+        for s in uids.into_iter() {
+            match Uuid::parse_str(&s) {
+                Ok(u) => parsed.push(u),
+                Err(_) => return Err(CoreError::bad_request("error.invalid.uid")),
+            }
+        }
+        SqlxRepository::snapshot_by_uids(self, parsed, scope).await
+    }
+
+    async fn snapshot_by_key(
+        &self,
+        key: &[&str],
+        entities: &[<R as SqlxViewRepository>::Entity],
+        scope: SoftDeleteScope,
+    ) -> Result<Vec<Snapshot>, CoreError> {
+        SqlxRepository::snapshot_by_key(self, key, entities, scope).await
+    }
+
+    async fn record_audit(&self, entries: Vec<AuditEntry>) -> Result<(), CoreError> {
+        SqlxRepository::record_audit(self, entries).await
+    }
+}
+
+// Blanket implementation: any SqlxViewRepository can be bound to a shared transaction
+impl<R> Transactional for R
+where
+    R: SqlxViewRepository,
+{
+    type Tx = SqlxTransaction;
+
+    async fn begin(&self) -> Result<SqlxTransaction, CoreError> {
+        self.get_executor().begin().await
+    }
+
+    fn in_transaction(&self, tx: &SqlxTransaction) -> Self {
+        self.with_executor(SqlxExecutor::Transaction(tx.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::macros::{date, datetime, time};
+
+    #[test]
+    fn test_attributes_from_json() {
+        let raw = r#"{
+            "reading_time": [12, null, null, null, null, null, null],
+            "difficulty": [null, null, "hard", null, null, null, null],
+            "featured": [null, null, null, true, null, null, null],
+            "released": [null, null, null, null, "2025-07-16", null, null],
+            "reviewed_at": [null, null, null, null, null, "2025-07-16T10:20:30.5+07:00", null],
+            "slot": [null, null, null, null, null, null, "08:30:00"],
+            "unset": [null, null, null, null, null, null, null]
+        }"#;
+        let attributes = attributes_from_json(raw).unwrap();
+        assert_eq!(attributes.len(), 6);
+        assert_eq!(attributes["reading_time"], ScalarValue::Int(12));
+        assert_eq!(attributes["difficulty"], ScalarValue::String("hard".into()));
+        assert_eq!(attributes["featured"], ScalarValue::Bool(true));
+        assert_eq!(attributes["released"], ScalarValue::Date(date!(2025 - 07 - 16)));
+        assert_eq!(
+            attributes["reviewed_at"],
+            ScalarValue::DateTime(datetime!(2025-07-16 10:20:30.5 +07:00))
+        );
+        assert_eq!(attributes["slot"], ScalarValue::Time(time!(08:30)));
+
+        assert!(attributes_from_json(r#"{"x": [null, null, null, null, "soon", null, null]}"#).is_err());
+    }
 }
@@ -0,0 +1,299 @@
+#![cfg(feature = "ssr")]
+
+use crate::common::error::CoreError;
+use crate::common::repository::UnitOfWork;
+use crate::db::DbPools;
+use futures::lock::{Mutex, OwnedMutexGuard};
+use leptos::prelude::use_context;
+use sqlx::pool::PoolConnection;
+use sqlx::{PgConnection, PgPool, Postgres, Transaction};
+use std::future::Future;
+use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+type SharedTransaction = Arc<Mutex<Option<Transaction<'static, Postgres>>>>;
+
+/// An open transaction shared by every repository bound to it.
+/// Dropping the last handle without committing rolls it back.
+#[derive(Clone)]
+pub struct SqlxTransaction {
+    inner: SharedTransaction,
+    // Handles obtained by beginning inside an existing transaction only join it;
+    // the outermost handle decides whether it commits.
+    owner: bool,
+}
+
+impl UnitOfWork for SqlxTransaction {
+    async fn commit(&self) -> Result<(), CoreError> {
+        if !self.owner {
+            return Ok(());
+        }
+        match self.inner.lock().await.take() {
+            Some(tx) => Ok(tx.commit().await?),
+            None => Err(CoreError::internal_server_error("error.transaction.closed")),
+        }
+    }
+
+    async fn rollback(&self) -> Result<(), CoreError> {
+        if !self.owner {
+            return Ok(());
+        }
+        match self.inner.lock().await.take() {
+            Some(tx) => Ok(tx.rollback().await?),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Where a repository sends its queries: straight to the pool, or into a transaction.
+/// A `Routed` executor writes to `primary` and reads from `replica`.
+#[derive(Clone)]
+pub enum SqlxExecutor {
+    Pool(PgPool),
+    Routed { primary: PgPool, replica: PgPool },
+    Transaction(SqlxTransaction),
+}
+
+impl From<PgPool> for SqlxExecutor {
+    fn from(pool: PgPool) -> Self {
+        SqlxExecutor::Pool(pool)
+    }
+}
+
+impl From<DbPools> for SqlxExecutor {
+    fn from(pools: DbPools) -> Self {
+        match pools.read {
+            Some(replica) => SqlxExecutor::Routed {
+                primary: pools.primary,
+                replica,
+            },
+            None => SqlxExecutor::Pool(pools.primary),
+        }
+    }
+}
+
+/// Where one request sends its reads, shared by every task working on it.
+#[derive(Clone)]
+pub struct ReadRouting {
+    read_primary: bool,
+    wrote: Arc<AtomicBool>,
+}
+
+impl ReadRouting {
+    pub fn new(read_primary: bool) -> Self {
+        Self {
+            read_primary,
+            wrote: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Whether work under this routing began a transaction.
+    pub fn wrote(&self) -> bool {
+        self.wrote.load(Ordering::Relaxed)
+    }
+}
+
+tokio::task_local! {
+    static READ_ROUTING: ReadRouting;
+}
+
+/// Runs `work` with its reads sent to the primary from the start if
+/// `routing` asks to, or else from its first transaction on, so a request
+/// reads its own writes despite replication lag.
+///
+/// Tasks spawned by `work` lose this scope; Leptos resources find `routing`
+/// in their reactive context instead, where it must be provided as well.
+pub async fn with_read_routing<F: Future>(routing: &ReadRouting, work: F) -> F::Output {
+    READ_ROUTING.scope(routing.clone(), work).await
+}
+
+fn current_routing() -> Option<ReadRouting> {
+    READ_ROUTING
+        .try_with(ReadRouting::clone)
+        .ok()
+        .or_else(use_context::<ReadRouting>)
+}
+
+fn reads_from_primary() -> bool {
+    current_routing().is_some_and(|r| r.read_primary || r.wrote())
+}
+
+fn mark_written() {
+    if let Some(routing) = current_routing() {
+        routing.wrote.store(true, Ordering::Relaxed);
+    }
+}
+
+impl SqlxExecutor {
+    /// Connection for a write, or for a read that must see the latest writes.
+    pub async fn acquire(&self) -> Result<SqlxConnection, CoreError> {
+        match self {
+            SqlxExecutor::Pool(pool) | SqlxExecutor::Routed { primary: pool, .. } => {
+                Ok(SqlxConnection::Pool(pool.acquire().await?))
+            }
+            SqlxExecutor::Transaction(tx) => {
+                let guard = tx.inner.clone().lock_owned().await;
+                if guard.is_none() {
+                    return Err(CoreError::internal_server_error("error.transaction.closed"));
+                }
+                Ok(SqlxConnection::Transaction(guard))
+            }
+        }
+    }
+
+    /// Connection for a read, from the replica unless the current request has
+    /// written or asked to read from the primary.
+    pub async fn acquire_read(&self) -> Result<SqlxConnection, CoreError> {
+        match self {
+            SqlxExecutor::Routed { replica, .. } if !reads_from_primary() => {
+                Ok(SqlxConnection::Pool(replica.acquire().await?))
+            }
+            _ => self.acquire().await,
+        }
+    }
+
+    pub async fn begin(&self) -> Result<SqlxTransaction, CoreError> {
+        match self {
+            SqlxExecutor::Pool(pool) | SqlxExecutor::Routed { primary: pool, .. } => {
+                mark_written();
+                Ok(SqlxTransaction {
+                    inner: Arc::new(Mutex::new(Some(pool.begin().await?))),
+                    owner: true,
+                })
+            }
+            SqlxExecutor::Transaction(tx) => Ok(SqlxTransaction {
+                inner: tx.inner.clone(),
+                owner: false,
+            }),
+        }
+    }
+}
+
+/// A connection checked out for a single query, either from the pool or from
+/// the shared transaction (held exclusively until dropped).
+pub enum SqlxConnection {
+    Pool(PoolConnection<Postgres>),
+    Transaction(OwnedMutexGuard<Option<Transaction<'static, Postgres>>>),
+}
+
+impl Deref for SqlxConnection {
+    type Target = PgConnection;
+
+    fn deref(&self) -> &Self::Target {
+        match self {
+            SqlxConnection::Pool(conn) => conn,
+            SqlxConnection::Transaction(guard) => guard.as_ref().expect("transaction is open"),
+        }
+    }
+}
+
+impl DerefMut for SqlxConnection {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        match self {
+            SqlxConnection::Pool(conn) => conn,
+            SqlxConnection::Transaction(guard) => guard.as_mut().expect("transaction is open"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{connect_with, PoolSettings};
+
+    async fn application_name(executor: &SqlxExecutor, read: bool) -> String {
+        let mut conn = if read {
+            executor.acquire_read().await.unwrap()
+        } else {
+            executor.acquire().await.unwrap()
+        };
+        sqlx::query_scalar("SELECT current_setting('application_name')")
+            .fetch_one(&mut *conn)
+            .await
+            .unwrap()
+    }
+
+    async fn routed_executor() -> Option<SqlxExecutor> {
+        let db_url = std::env::var("TEST_DATABASE_URL").ok()?;
+        let pool = |name: &str| {
+            let settings = PoolSettings {
+                application_name: name.to_string(),
+                ..PoolSettings::default()
+            };
+            let db_url = db_url.clone();
+            async move { connect_with(&db_url, &settings).await.unwrap() }
+        };
+        Some(SqlxExecutor::from(DbPools {
+            primary: pool("primary").await,
+            read: Some(pool("replica").await),
+        }))
+    }
+
+    #[tokio::test]
+    async fn test_routed_reads_use_the_replica_until_the_request_writes() {
+        let Some(executor) = routed_executor().await else {
+            return;
+        };
+
+        assert_eq!(application_name(&executor, true).await, "replica");
+        assert_eq!(application_name(&executor, false).await, "primary");
+
+        let routing = ReadRouting::new(false);
+        let names = with_read_routing(&routing, async {
+            let before = application_name(&executor, true).await;
+            let tx = executor.begin().await.unwrap();
+            tx.rollback().await.unwrap();
+            (before, application_name(&executor, true).await)
+        })
+        .await;
+        assert_eq!(names, ("replica".to_string(), "primary".to_string()));
+        assert!(routing.wrote());
+
+        let routing = ReadRouting::new(true);
+        let name = with_read_routing(&routing, application_name(&executor, true)).await;
+        assert_eq!(name, "primary");
+        assert!(!routing.wrote());
+    }
+
+    #[tokio::test]
+    async fn test_spawned_resources_keep_the_request_routing() {
+        use leptos::prelude::{provide_context, Owner};
+        use leptos::reactive::computed::ScopedFuture;
+
+        let Some(executor) = routed_executor().await else {
+            return;
+        };
+
+        // Like a resource rendered by leptos_actix: spawned, so outside the
+        // task-local scope, but polled with the request's reactive owner
+        let routing = ReadRouting::new(true);
+        let owner = Owner::new();
+        let spawned = with_read_routing(&routing, async {
+            owner.with(|| {
+                provide_context(routing.clone());
+                let executor = executor.clone();
+                tokio::spawn(ScopedFuture::new(async move {
+                    application_name(&executor, true).await
+                }))
+            })
+        })
+        .await;
+        assert_eq!(spawned.await.unwrap(), "primary");
+
+        let routing = ReadRouting::new(false);
+        let owner = Owner::new();
+        let spawned = owner.with(|| {
+            provide_context(routing.clone());
+            let executor = executor.clone();
+            tokio::spawn(ScopedFuture::new(async move {
+                let before = application_name(&executor, true).await;
+                executor.begin().await.unwrap().rollback().await.unwrap();
+                (before, application_name(&executor, true).await)
+            }))
+        });
+        let names = spawned.await.unwrap();
+        assert_eq!(names, ("replica".to_string(), "primary".to_string()));
+        assert!(routing.wrote());
+    }
+}
@@ -1,13 +1,18 @@
 #![cfg(feature = "ssr")]
 
+use crate::business::slug_redirect_service::{
+    SlugRedirect, SlugRedirectCreate, SlugRedirectRecorder,
+};
 use crate::business::term_service::{
     Term, TermCreate, TermInfo, TermInfoRepository, TermRepository,
 };
+use crate::common::error::CoreError;
+use crate::infras::slug_redirect_sqlx_repository::SlugRedirectSqlxRepository;
 use crate::infras::sqlx_repository::{
     SqlxEntityMapper, SqlxRepository, SqlxViewMeta, SqlxViewRepository,
 };
 use crate::{define_orm_with_common_fields, define_readonly_orm_with_common_fields};
-use sqlx::PgPool;
+use crate::infras::sqlx_transaction::SqlxExecutor;
 use uuid::Uuid;
 
 // Table: terms
@@ -57,16 +62,18 @@ impl From<TermOrm> for Term {
 
 #[derive(Clone)]
 pub struct TermSqlxRepository {
-    pool: PgPool,
+    executor: SqlxExecutor,
 }
 #[derive(Clone)]
 pub struct TermInfoSqlxRepository {
-    pool: PgPool,
+    executor: SqlxExecutor,
 }
 
 impl TermSqlxRepository {
-    pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+    pub fn new(executor: impl Into<SqlxExecutor>) -> Self {
+        Self {
+            executor: executor.into(),
+        }
     }
 }
 
@@ -80,25 +87,30 @@ impl SqlxViewMeta for TermSqlxRepository {
     fn get_searchable_columns(&self) -> Vec<&str> {
         TermOrm::searchable_columns()
     }
+    fn get_search_vector_column(&self) -> Option<&str> {
+        Some("search_vector")
+    }
+    fn get_headline_columns(&self) -> Vec<&str> {
+        vec!["name", "description"]
+    }
 }
 
 impl SqlxViewRepository for TermSqlxRepository {
     type Entity = Term;
     type Orm = TermOrm;
-    fn get_pool(&self) -> &PgPool {
-        &self.pool
+    fn get_executor(&self) -> &SqlxExecutor {
+        &self.executor
+    }
+    fn with_executor(&self, executor: SqlxExecutor) -> Self {
+        Self { executor }
     }
     fn from_orm(orm: Self::Orm) -> Self::Entity {
         Term::from(orm)
     }
 }
 
-impl SqlxEntityMapper for TermSqlxRepository {
-    type Entity = Term;
-    type EntityCreate = TermCreate;
-    type Orm = TermOrm;
-
-    fn to_orm_from_create(&self, create: &Self::EntityCreate) -> Self::Orm {
+impl From<&TermCreate> for TermOrm {
+    fn from(create: &TermCreate) -> Self {
         let now = time::OffsetDateTime::now_utc();
         TermOrm {
             id: 0,
@@ -113,8 +125,10 @@ impl SqlxEntityMapper for TermSqlxRepository {
             description: None,
         }
     }
+}
 
-    fn to_orm_from_entity(&self, entity: &Self::Entity) -> Self::Orm {
+impl From<&Term> for TermOrm {
+    fn from(entity: &Term) -> Self {
         TermOrm {
             id: entity.id,
             uid: Uuid::parse_str(&entity.uid).unwrap_or_else(|_| Uuid::nil()),
@@ -130,11 +144,36 @@ impl SqlxEntityMapper for TermSqlxRepository {
     }
 }
 
+impl SqlxEntityMapper for TermSqlxRepository {
+    type Entity = Term;
+    type EntityCreate = TermCreate;
+    type Orm = TermOrm;
+
+    fn to_orm_from_create(&self, create: &Self::EntityCreate) -> Self::Orm {
+        TermOrm::from(create)
+    }
+
+    fn to_orm_from_entity(&self, entity: &Self::Entity) -> Self::Orm {
+        TermOrm::from(entity)
+    }
+}
+
 impl SqlxRepository for TermSqlxRepository {
     type EntityCreate = TermCreate;
 }
 impl TermRepository for TermSqlxRepository {}
 
+impl SlugRedirectRecorder for TermSqlxRepository {
+    async fn record_slug_redirect(
+        &self,
+        redirect: &SlugRedirectCreate,
+    ) -> Result<SlugRedirect, CoreError> {
+        SlugRedirectSqlxRepository::new(self.executor.clone())
+            .record_slug_redirect(redirect)
+            .await
+    }
+}
+
 impl TermInfoOrm {
     pub fn searchable_columns() -> Vec<&'static str> {
         vec!["slug", "name", "taxonomy_code", "taxonomy_name"]
@@ -163,8 +202,10 @@ impl From<TermInfoOrm> for TermInfo {
 }
 
 impl TermInfoSqlxRepository {
-    pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+    pub fn new(executor: impl Into<SqlxExecutor>) -> Self {
+        Self {
+            executor: executor.into(),
+        }
     }
 }
 
@@ -183,8 +224,11 @@ impl SqlxViewMeta for TermInfoSqlxRepository {
 impl SqlxViewRepository for TermInfoSqlxRepository {
     type Entity = TermInfo;
     type Orm = TermInfoOrm;
-    fn get_pool(&self) -> &PgPool {
-        &self.pool
+    fn get_executor(&self) -> &SqlxExecutor {
+        &self.executor
+    }
+    fn with_executor(&self, executor: SqlxExecutor) -> Self {
+        Self { executor }
     }
     fn from_orm(orm: Self::Orm) -> Self::Entity {
         TermInfo::from(orm)
@@ -8,7 +8,7 @@ use crate::infras::sqlx_repository::{
     SqlxEntityMapper, SqlxRepository, SqlxViewMeta, SqlxViewRepository,
 };
 use crate::{define_orm_with_common_fields, define_readonly_orm_with_common_fields};
-use sqlx::PgPool;
+use crate::infras::sqlx_transaction::SqlxExecutor;
 use uuid::Uuid;
 
 define_orm_with_common_fields!(User {
@@ -57,16 +57,18 @@ impl From<UserInfoOrm> for UserInfo {
 
 #[derive(Clone)]
 pub struct UserSqlxRepository {
-    pool: PgPool,
+    executor: SqlxExecutor,
 }
 #[derive(Clone)]
 pub struct UserInfoSqlxRepository {
-    pool: PgPool,
+    executor: SqlxExecutor,
 }
 
 impl UserInfoSqlxRepository {
-    pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+    pub fn new(executor: impl Into<SqlxExecutor>) -> Self {
+        Self {
+            executor: executor.into(),
+        }
     }
 }
 
@@ -76,8 +78,10 @@ impl UserOrm {
     }
 }
 impl UserSqlxRepository {
-    pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+    pub fn new(executor: impl Into<SqlxExecutor>) -> Self {
+        Self {
+            executor: executor.into(),
+        }
     }
 }
 
@@ -91,25 +95,27 @@ impl SqlxViewMeta for UserSqlxRepository {
     fn get_searchable_columns(&self) -> Vec<&str> {
         UserOrm::searchable_columns()
     }
+    fn get_unaudited_columns(&self) -> Vec<&str> {
+        vec!["password"]
+    }
 }
 
 impl SqlxViewRepository for UserSqlxRepository {
     type Entity = User;
     type Orm = UserOrm;
-    fn get_pool(&self) -> &PgPool {
-        &self.pool
+    fn get_executor(&self) -> &SqlxExecutor {
+        &self.executor
+    }
+    fn with_executor(&self, executor: SqlxExecutor) -> Self {
+        Self { executor }
     }
     fn from_orm(orm: Self::Orm) -> Self::Entity {
         User::from(orm)
     }
 }
 
-impl SqlxEntityMapper for UserSqlxRepository {
-    type Entity = User;
-    type EntityCreate = UserCreate;
-    type Orm = UserOrm;
-
-    fn to_orm_from_create(&self, create: &Self::EntityCreate) -> Self::Orm {
+impl From<&UserCreate> for UserOrm {
+    fn from(create: &UserCreate) -> Self {
         let now = time::OffsetDateTime::now_utc();
         UserOrm {
             id: 0,
@@ -123,8 +129,10 @@ impl SqlxEntityMapper for UserSqlxRepository {
             role: UserRole::USER.as_i32(),
         }
     }
+}
 
-    fn to_orm_from_entity(&self, entity: &Self::Entity) -> Self::Orm {
+impl From<&User> for UserOrm {
+    fn from(entity: &User) -> Self {
         UserOrm {
             id: entity.id,
             uid: Uuid::parse_str(&entity.uid).unwrap_or_else(|_| Uuid::nil()),
@@ -139,6 +147,20 @@ impl SqlxEntityMapper for UserSqlxRepository {
     }
 }
 
+impl SqlxEntityMapper for UserSqlxRepository {
+    type Entity = User;
+    type EntityCreate = UserCreate;
+    type Orm = UserOrm;
+
+    fn to_orm_from_create(&self, create: &Self::EntityCreate) -> Self::Orm {
+        UserOrm::from(create)
+    }
+
+    fn to_orm_from_entity(&self, entity: &Self::Entity) -> Self::Orm {
+        UserOrm::from(entity)
+    }
+}
+
 impl SqlxRepository for UserSqlxRepository {
     type EntityCreate = UserCreate;
 }
@@ -158,8 +180,11 @@ impl SqlxViewMeta for UserInfoSqlxRepository {
 impl SqlxViewRepository for UserInfoSqlxRepository {
     type Entity = UserInfo;
     type Orm = UserInfoOrm;
-    fn get_pool(&self) -> &PgPool {
-        &self.pool
+    fn get_executor(&self) -> &SqlxExecutor {
+        &self.executor
+    }
+    fn with_executor(&self, executor: SqlxExecutor) -> Self {
+        Self { executor }
     }
     fn from_orm(orm: Self::Orm) -> Self::Entity {
         UserInfo::from(orm)
@@ -168,18 +193,22 @@ impl SqlxViewRepository for UserInfoSqlxRepository {
 
 impl UserRepository for UserSqlxRepository {
     async fn find_by_username(&self, name: &str) -> Result<Option<User>, CoreError> {
-        let result = sqlx::query_as::<_, UserOrm>("SELECT * FROM users WHERE username = $1")
-            .bind(name)
-            .fetch_optional(self.get_pool())
-            .await?;
+        let result = sqlx::query_as::<_, UserOrm>(
+            "SELECT * FROM users WHERE username = $1 AND deleted_at IS NULL",
+        )
+        .bind(name)
+        .fetch_optional(&mut *self.acquire().await?)
+        .await?;
         Ok(result.map(Self::from_orm))
     }
 
     async fn find_by_email(&self, email: &str) -> Result<Option<User>, CoreError> {
-        let result = sqlx::query_as::<_, UserOrm>("SELECT * FROM users WHERE email = $1")
-            .bind(email)
-            .fetch_optional(self.get_pool())
-            .await?;
+        let result = sqlx::query_as::<_, UserOrm>(
+            "SELECT * FROM users WHERE email = $1 AND deleted_at IS NULL",
+        )
+        .bind(email)
+        .fetch_optional(&mut *self.acquire().await?)
+        .await?;
         Ok(result.map(Self::from_orm))
     }
 
@@ -187,11 +216,12 @@ impl UserRepository for UserSqlxRepository {
         &self,
         email_or_username: &str,
     ) -> Result<Option<User>, CoreError> {
-        let result =
-            sqlx::query_as::<_, UserOrm>("SELECT * FROM users WHERE email = $1 OR username = $1")
-                .bind(email_or_username)
-                .fetch_optional(self.get_pool())
-                .await?;
+        let result = sqlx::query_as::<_, UserOrm>(
+            "SELECT * FROM users WHERE (email = $1 OR username = $1) AND deleted_at IS NULL",
+        )
+        .bind(email_or_username)
+        .fetch_optional(&mut *self.acquire().await?)
+        .await?;
         Ok(result.map(Self::from_orm))
     }
 }
@@ -0,0 +1,256 @@
+#![cfg(feature = "ssr")]
+
+//! Periodic jobs run inside the server. Every instance runs the same jobs;
+//! a Postgres advisory lock per job makes the instances take turns, so a tick
+//! is skipped where another instance is already running the job.
+
+use crate::common::error::CoreError;
+use crate::state::AppState;
+use actix_web::web::Data;
+use futures::future::LocalBoxFuture;
+use futures::FutureExt;
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use std::env;
+use std::future::Future;
+use std::panic::AssertUnwindSafe;
+use std::time::Duration;
+
+type JobFn = Box<dyn Fn() -> LocalBoxFuture<'static, Result<(), CoreError>>>;
+
+struct Job {
+    name: &'static str,
+    interval: Duration,
+    lock_key: i64,
+    work: JobFn,
+}
+
+/// Named jobs, each run every `interval` once started.
+pub struct JobRunner {
+    pool: PgPool,
+    jobs: Vec<Job>,
+}
+
+impl JobRunner {
+    /// Jobs lock on `pool`, which should be the primary.
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool, jobs: vec![] }
+    }
+
+    /// Adds a job. `work` may be cut short by a restart at any point and
+    /// picked up by another instance on its next tick, so it must be safe to
+    /// run again.
+    pub fn every<F, Fut>(mut self, name: &'static str, interval: Duration, work: F) -> Self
+    where
+        F: Fn() -> Fut + 'static,
+        Fut: Future<Output = Result<(), CoreError>> + 'static,
+    {
+        self.jobs.push(Job {
+            name,
+            interval,
+            lock_key: lock_key(name),
+            work: Box::new(move || work().boxed_local()),
+        });
+        self
+    }
+
+    /// Runs each job on its own task: once right away, then `interval` after
+    /// the previous run ends.
+    pub fn start(self) {
+        for job in self.jobs {
+            let pool = self.pool.clone();
+            actix_web::rt::spawn(async move {
+                loop {
+                    match run_once(&pool, &job).await {
+                        Ok(true) => log::debug!("job {} done", job.name),
+                        Ok(false) => log::debug!("job {} running elsewhere, skipped", job.name),
+                        Err(e) => log::error!("job {} failed: {:?}", job.name, e),
+                    }
+                    actix_web::rt::time::sleep(job.interval).await;
+                }
+            });
+        }
+    }
+}
+
+/// Runs `job` unless another session holds its lock; `Ok(false)` if it was
+/// skipped. The lock is released even if the job fails or panics: the
+/// connection holding it goes back to the pool, where a lock left behind
+/// would block every other instance.
+async fn run_once(pool: &PgPool, job: &Job) -> Result<bool, CoreError> {
+    let mut conn = pool.acquire().await?;
+    let locked: bool = sqlx::query_scalar("SELECT pg_try_advisory_lock($1)")
+        .bind(job.lock_key)
+        .fetch_one(&mut *conn)
+        .await?;
+    if !locked {
+        return Ok(false);
+    }
+    let result = AssertUnwindSafe((job.work)())
+        .catch_unwind()
+        .await
+        .unwrap_or_else(|_| Err(CoreError::internal_server_error("error.job.panicked")));
+    let unlocked = sqlx::query("SELECT pg_advisory_unlock($1)")
+        .bind(job.lock_key)
+        .execute(&mut *conn)
+        .await;
+    if let Err(e) = unlocked {
+        // Closing the session is the only other way to release the lock
+        drop(conn.detach());
+        return Err(e.into());
+    }
+    result.map(|_| true)
+}
+
+/// Advisory lock key of the job called `name`, stable across builds and
+/// instances.
+fn lock_key(name: &str) -> i64 {
+    let digest = Sha256::digest(format!("lazycoder.job.{name}").as_bytes());
+    i64::from_be_bytes(
+        digest[..8]
+            .try_into()
+            .expect("a SHA-256 digest has 8 bytes"),
+    )
+}
+
+/// Job settings, read from the environment by `JobConfig::from_env`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct JobConfig {
+    /// `JOBS_ENABLED`: `false` keeps this instance from running any job,
+    /// e.g. to leave them to a dedicated instance. `true` by default.
+    pub enabled: bool,
+    /// `POST_SCHEDULE_INTERVAL_SECS`: how often due posts are published or
+    /// archived, 60 by default.
+    pub post_schedule_interval: Duration,
+}
+
+impl JobConfig {
+    pub fn from_env() -> Self {
+        Self::from_lookup(|name| env::var(name).ok())
+    }
+
+    /// Reads the settings through `lookup`; missing or unparsable values keep
+    /// their defaults.
+    pub fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Self {
+        JobConfig {
+            enabled: lookup("JOBS_ENABLED")
+                .and_then(|v| v.trim().parse::<bool>().ok())
+                .unwrap_or(true),
+            post_schedule_interval: Duration::from_secs(
+                lookup("POST_SCHEDULE_INTERVAL_SECS")
+                    .and_then(|v| v.trim().parse::<u64>().ok())
+                    .filter(|secs| *secs > 0)
+                    .unwrap_or(60),
+            ),
+        }
+    }
+}
+
+/// Starts the server's jobs, unless `config` disables them.
+pub fn start(pool: PgPool, state: Data<AppState>, config: &JobConfig) {
+    if !config.enabled {
+        log::info!("background jobs disabled");
+        return;
+    }
+    JobRunner::new(pool)
+        .every("post_schedule", config.post_schedule_interval, move || {
+            let state = state.clone();
+            async move {
+                let run = state
+                    .post_service
+                    .run_schedule(time::OffsetDateTime::now_utc())
+                    .await?;
+                for id in &run.published {
+                    log::info!("published scheduled post {id}");
+                }
+                for id in &run.archived {
+                    log::info!("archived expired post {id}");
+                }
+                for (id, e) in &run.failed {
+                    log::warn!("post {id} is due but could not be moved: {e:?}");
+                }
+                Ok(())
+            }
+        })
+        .start();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::connect;
+    use std::collections::HashMap;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_config_defaults_and_overrides() {
+        let config = |vars: &[(&str, &str)]| {
+            let vars: HashMap<String, String> = vars
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect();
+            JobConfig::from_lookup(|name| vars.get(name).cloned())
+        };
+        assert_eq!(
+            config(&[]),
+            JobConfig {
+                enabled: true,
+                post_schedule_interval: Duration::from_secs(60),
+            }
+        );
+        assert_eq!(
+            config(&[
+                ("JOBS_ENABLED", "false"),
+                ("POST_SCHEDULE_INTERVAL_SECS", "15")
+            ]),
+            JobConfig {
+                enabled: false,
+                post_schedule_interval: Duration::from_secs(15),
+            }
+        );
+        assert_eq!(
+            config(&[("POST_SCHEDULE_INTERVAL_SECS", "0")]).post_schedule_interval,
+            Duration::from_secs(60)
+        );
+        assert_ne!(lock_key("post_schedule"), lock_key("other"));
+    }
+
+    #[tokio::test]
+    async fn test_job_is_skipped_while_another_session_holds_its_lock() {
+        let Ok(db_url) = std::env::var("TEST_DATABASE_URL") else {
+            return;
+        };
+        let pool = connect(&db_url).await.unwrap();
+        let runs = Arc::new(AtomicUsize::new(0));
+        let counter = runs.clone();
+        let runner =
+            JobRunner::new(pool.clone()).every("test_lock", Duration::from_secs(60), move || {
+                let counter = counter.clone();
+                async move {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                    Ok(())
+                }
+            });
+        let job = &runner.jobs[0];
+
+        // Another instance in the middle of the job
+        let mut other = pool.acquire().await.unwrap();
+        sqlx::query("SELECT pg_advisory_lock($1)")
+            .bind(job.lock_key)
+            .execute(&mut *other)
+            .await
+            .unwrap();
+        assert!(!run_once(&pool, job).await.unwrap());
+        assert_eq!(runs.load(Ordering::SeqCst), 0);
+
+        sqlx::query("SELECT pg_advisory_unlock($1)")
+            .bind(job.lock_key)
+            .execute(&mut *other)
+            .await
+            .unwrap();
+        assert!(run_once(&pool, job).await.unwrap());
+        assert!(run_once(&pool, job).await.unwrap());
+        assert_eq!(runs.load(Ordering::SeqCst), 2);
+    }
+}
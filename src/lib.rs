@@ -1,3 +1,4 @@
+#![recursion_limit = "256"]
 #[cfg(feature = "ssr")]
 pub mod app;
 pub mod business;
@@ -8,6 +9,10 @@ pub mod config;
 pub mod db;
 #[cfg(feature = "ssr")]
 pub mod infras;
+#[cfg(feature = "ssr")]
+pub mod jobs;
+#[cfg(feature = "ssr")]
+pub mod migrations;
 pub mod pages;
 #[cfg(feature = "ssr")]
 pub mod presentation;
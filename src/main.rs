@@ -1,19 +1,60 @@
 #![recursion_limit = "256"]
 #[cfg(feature = "ssr")]
-use lazycoder_leptos::{app, config, db};
+use lazycoder_leptos::{app, config, db, migrations};
 
 #[cfg(feature = "ssr")]
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
+    use config::StartupMode;
+    use migrations::Migrator;
+
     config::init_env();
-    let pool = db::init_pool().await.expect("Failed to connect DB");
-    db::run_migrations(&pool)
-        .await
-        .expect("Failed to run migrations");
-    db::run_view_migrations(&pool)
-        .await
-        .expect("Failed to run view migrations");
-    app::run(pool).await
+    let mode = match StartupMode::from_args(std::env::args().skip(1)) {
+        Ok(mode) => mode,
+        Err(e) => {
+            eprintln!("error: {e}\n{}", config::USAGE);
+            std::process::exit(2);
+        }
+    };
+    let db_config = db::DbConfig::from_env();
+    let pools = db::init_pools(&db_config).await.expect("Failed to connect DB");
+    let migrator = Migrator::new(pools.primary.clone());
+
+    match mode {
+        StartupMode::DryRun => {
+            let pending = migrator
+                .pending()
+                .await
+                .expect("Failed to inspect migrations");
+            if pending.is_empty() {
+                println!("No pending migrations");
+            }
+            for m in pending {
+                println!("pending: {:04} {} ({})", m.version, m.name, m.checksum());
+            }
+            Ok(())
+        }
+        StartupMode::RollbackTo(version) => {
+            for m in migrator
+                .rollback_to(version)
+                .await
+                .expect("Failed to roll back migrations")
+            {
+                println!("reverted: {:04} {}", m.version, m.name);
+            }
+            Ok(())
+        }
+        StartupMode::MigrateOnly => {
+            for m in migrator.run().await.expect("Failed to run migrations") {
+                println!("applied: {:04} {}", m.version, m.name);
+            }
+            Ok(())
+        }
+        StartupMode::Serve => {
+            migrator.run().await.expect("Failed to run migrations");
+            app::run(pools, db_config.read_your_writes).await
+        }
+    }
 }
 
 #[cfg(not(any(feature = "ssr", feature = "csr")))]
@@ -0,0 +1,11 @@
+DROP TABLE IF EXISTS post_relations;
+DROP TABLE IF EXISTS post_terms;
+DROP TABLE IF EXISTS post_collection_items;
+DROP TABLE IF EXISTS terms;
+DROP TABLE IF EXISTS post_taxonomies;
+DROP TABLE IF EXISTS post_collections;
+DROP TABLE IF EXISTS posts;
+DROP TABLE IF EXISTS post_types;
+DROP TABLE IF EXISTS users;
+DROP TABLE IF EXISTS attribute_values;
+DROP TABLE IF EXISTS attributes;
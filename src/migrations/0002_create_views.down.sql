@@ -0,0 +1,8 @@
+DROP VIEW IF EXISTS attribute_values_info;
+DROP VIEW IF EXISTS attributes_info;
+DROP VIEW IF EXISTS terms_info;
+DROP VIEW IF EXISTS post_taxonomies_info;
+DROP VIEW IF EXISTS post_collections_info;
+DROP VIEW IF EXISTS posts_info;
+DROP VIEW IF EXISTS post_types_info;
+DROP VIEW IF EXISTS users_info;
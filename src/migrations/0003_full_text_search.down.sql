@@ -0,0 +1,6 @@
+DROP INDEX IF EXISTS IDX_terms_search_vector;
+ALTER TABLE terms DROP COLUMN IF EXISTS search_vector;
+DROP INDEX IF EXISTS IDX_posts_search_vector;
+ALTER TABLE posts DROP COLUMN IF EXISTS search_vector;
+DROP TEXT SEARCH CONFIGURATION IF EXISTS simple_unaccent;
+-- unaccent stays installed: Filter::Search on tables without a search vector uses it.
@@ -0,0 +1,28 @@
+-- Full-text search: accent-insensitive text search configuration, stored
+-- weighted tsvector columns and GIN indexes for posts and terms.
+CREATE EXTENSION IF NOT EXISTS unaccent;
+
+-- 'simple' tokenisation with accents folded, so "viet" matches "Việt" while
+-- ts_headline can still highlight the original, accented text.
+DROP TEXT SEARCH CONFIGURATION IF EXISTS simple_unaccent;
+CREATE TEXT SEARCH CONFIGURATION simple_unaccent (COPY = simple);
+ALTER TEXT SEARCH CONFIGURATION simple_unaccent
+    ALTER MAPPING FOR asciiword, asciihword, hword_asciipart, word, hword, hword_part
+    WITH unaccent, simple;
+
+ALTER TABLE posts
+    ADD COLUMN IF NOT EXISTS search_vector TSVECTOR GENERATED ALWAYS AS (
+        setweight(to_tsvector('simple_unaccent'::regconfig, coalesce(title, '')), 'A') ||
+        setweight(to_tsvector('simple_unaccent'::regconfig, coalesce(summary, '')), 'B') ||
+        setweight(to_tsvector('simple_unaccent'::regconfig, coalesce(content, '')), 'C')
+        ) STORED;
+
+CREATE INDEX IF NOT EXISTS IDX_posts_search_vector ON posts USING GIN (search_vector);
+
+ALTER TABLE terms
+    ADD COLUMN IF NOT EXISTS search_vector TSVECTOR GENERATED ALWAYS AS (
+        setweight(to_tsvector('simple_unaccent'::regconfig, coalesce(name, '')), 'A') ||
+        setweight(to_tsvector('simple_unaccent'::regconfig, coalesce(description, '')), 'C')
+        ) STORED;
+
+CREATE INDEX IF NOT EXISTS IDX_terms_search_vector ON terms USING GIN (search_vector);
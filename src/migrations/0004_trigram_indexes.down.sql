@@ -0,0 +1,13 @@
+DROP INDEX IF EXISTS IDX_attributes_name_trgm;
+DROP INDEX IF EXISTS IDX_post_collections_title_trgm;
+DROP INDEX IF EXISTS IDX_post_collections_slug_trgm;
+DROP INDEX IF EXISTS IDX_post_taxonomies_name_trgm;
+DROP INDEX IF EXISTS IDX_post_taxonomies_code_trgm;
+DROP INDEX IF EXISTS IDX_post_types_name_trgm;
+DROP INDEX IF EXISTS IDX_post_types_code_trgm;
+DROP INDEX IF EXISTS IDX_terms_name_trgm;
+DROP INDEX IF EXISTS IDX_terms_slug_trgm;
+DROP INDEX IF EXISTS IDX_posts_summary_trgm;
+DROP INDEX IF EXISTS IDX_posts_title_trgm;
+DROP INDEX IF EXISTS IDX_posts_slug_trgm;
+-- pg_trgm stays installed: it was created by the initial schema.
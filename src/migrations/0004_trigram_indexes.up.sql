@@ -0,0 +1,21 @@
+-- Fuzzy search: trigram indexes for the word-similarity operators (<%, %>)
+-- on the short text columns that fuzzy search and suggestions compare.
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+CREATE INDEX IF NOT EXISTS IDX_posts_slug_trgm ON posts USING GIN (slug gin_trgm_ops);
+CREATE INDEX IF NOT EXISTS IDX_posts_title_trgm ON posts USING GIN (title gin_trgm_ops);
+CREATE INDEX IF NOT EXISTS IDX_posts_summary_trgm ON posts USING GIN (summary gin_trgm_ops);
+
+CREATE INDEX IF NOT EXISTS IDX_terms_slug_trgm ON terms USING GIN (slug gin_trgm_ops);
+CREATE INDEX IF NOT EXISTS IDX_terms_name_trgm ON terms USING GIN (name gin_trgm_ops);
+
+CREATE INDEX IF NOT EXISTS IDX_post_types_code_trgm ON post_types USING GIN (code gin_trgm_ops);
+CREATE INDEX IF NOT EXISTS IDX_post_types_name_trgm ON post_types USING GIN (name gin_trgm_ops);
+
+CREATE INDEX IF NOT EXISTS IDX_post_taxonomies_code_trgm ON post_taxonomies USING GIN (code gin_trgm_ops);
+CREATE INDEX IF NOT EXISTS IDX_post_taxonomies_name_trgm ON post_taxonomies USING GIN (name gin_trgm_ops);
+
+CREATE INDEX IF NOT EXISTS IDX_post_collections_slug_trgm ON post_collections USING GIN (slug gin_trgm_ops);
+CREATE INDEX IF NOT EXISTS IDX_post_collections_title_trgm ON post_collections USING GIN (title gin_trgm_ops);
+
+CREATE INDEX IF NOT EXISTS IDX_attributes_name_trgm ON attributes USING GIN (name gin_trgm_ops);
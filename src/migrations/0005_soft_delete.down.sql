@@ -0,0 +1,138 @@
+-- Rows still in the trash become live again; purge them first to get rid of them.
+-- Views must be recreated: OR REPLACE cannot drop the deleted_at column.
+DROP VIEW IF EXISTS users_info;
+CREATE VIEW users_info AS
+SELECT u.id,
+       u.uid,
+       u.version,
+       u.created_at,
+       u.updated_at,
+       u.username,
+       u.email,
+       u.role
+FROM users u;
+
+DROP VIEW IF EXISTS post_types_info;
+CREATE VIEW post_types_info AS
+SELECT pt.id,
+       pt.uid,
+       pt.version,
+       pt.created_at,
+       pt.updated_at,
+       pt.code,
+       pt.name
+FROM post_types pt;
+
+DROP VIEW IF EXISTS posts_info;
+CREATE VIEW posts_info AS
+SELECT p.id,
+       p.uid,
+       p.version,
+       p.created_at,
+       p.updated_at,
+       p.slug,
+       p.title,
+       p.summary,
+       p.content,
+       p.status,
+       p.user_id,
+       u.username,
+       u.email
+FROM posts AS p
+         LEFT JOIN users AS u ON p.user_id = u.id;
+
+DROP VIEW IF EXISTS post_collections_info;
+CREATE VIEW post_collections_info AS
+SELECT pc.id,
+       pc.uid,
+       pc.version,
+       pc.created_at,
+       pc.updated_at,
+       pc.slug,
+       pc.title,
+       pc.description,
+       pc.visibility
+FROM post_collections pc;
+
+DROP VIEW IF EXISTS post_taxonomies_info;
+CREATE VIEW post_taxonomies_info AS
+SELECT t.id,
+       t.uid,
+       t.version,
+       t.created_at,
+       t.updated_at,
+       t.code,
+       t.name
+FROM post_taxonomies t;
+
+DROP VIEW IF EXISTS terms_info;
+CREATE VIEW terms_info AS
+SELECT te.id,
+       te.uid,
+       te.version,
+       te.created_at,
+       te.updated_at,
+       te.taxonomy_id,
+       tx.code AS taxonomy_code,
+       tx.name AS taxonomy_name,
+       te.parent_id,
+       tp.slug AS parent_slug,
+       tp.name AS parent_name,
+       te.slug,
+       te.name,
+       te.description
+FROM terms te
+         LEFT JOIN post_taxonomies tx ON tx.id = te.taxonomy_id
+         LEFT JOIN terms tp ON tp.id = te.parent_id;
+
+DROP VIEW IF EXISTS attributes_info;
+CREATE VIEW attributes_info AS
+SELECT a.id,
+       a.uid,
+       a.version,
+       a.created_at,
+       a.updated_at,
+       a.name,
+       a.entity_type,
+       a.data_type
+FROM attributes a;
+
+DROP VIEW IF EXISTS attribute_values_info;
+CREATE VIEW attribute_values_info AS
+SELECT av.id,
+       av.uid,
+       av.version,
+       av.created_at,
+       av.updated_at,
+       av.int_value,
+       av.double_value,
+       av.string_value,
+       av.boolean_value,
+       av.date_value,
+       av.datetime_value,
+       av.time_value,
+       av.attribute_id,
+       a.name        AS attribute_name,
+       a.entity_type AS attribute_entity_type,
+       a.data_type   AS attribute_data_type,
+       av.entity_id,
+       av.entity_type
+FROM attribute_values av
+         LEFT JOIN attributes a ON a.id = av.attribute_id;
+
+DROP INDEX IF EXISTS IDX_attribute_values_deleted_at;
+DROP INDEX IF EXISTS IDX_attributes_deleted_at;
+DROP INDEX IF EXISTS IDX_terms_deleted_at;
+DROP INDEX IF EXISTS IDX_post_taxonomies_deleted_at;
+DROP INDEX IF EXISTS IDX_post_collections_deleted_at;
+DROP INDEX IF EXISTS IDX_posts_deleted_at;
+DROP INDEX IF EXISTS IDX_post_types_deleted_at;
+DROP INDEX IF EXISTS IDX_users_deleted_at;
+ALTER TABLE attribute_values DROP COLUMN IF EXISTS deleted_at;
+ALTER TABLE attributes DROP COLUMN IF EXISTS deleted_at;
+ALTER TABLE terms DROP COLUMN IF EXISTS deleted_at;
+ALTER TABLE post_taxonomies DROP COLUMN IF EXISTS deleted_at;
+ALTER TABLE post_collections DROP COLUMN IF EXISTS deleted_at;
+ALTER TABLE posts DROP COLUMN IF EXISTS deleted_at;
+ALTER TABLE post_types DROP COLUMN IF EXISTS deleted_at;
+ALTER TABLE users DROP COLUMN IF EXISTS deleted_at;
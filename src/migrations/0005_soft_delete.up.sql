@@ -0,0 +1,142 @@
+-- Soft delete: deleting a row sets deleted_at instead of removing it, so it can
+-- be restored from the trash until it is purged. Unique constraints still
+-- cover trashed rows, which keeps a restore from clashing with newer rows.
+ALTER TABLE users ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMPTZ;
+ALTER TABLE post_types ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMPTZ;
+ALTER TABLE posts ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMPTZ;
+ALTER TABLE post_collections ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMPTZ;
+ALTER TABLE post_taxonomies ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMPTZ;
+ALTER TABLE terms ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMPTZ;
+ALTER TABLE attributes ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMPTZ;
+ALTER TABLE attribute_values ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMPTZ;
+
+-- Partial indexes for listing the trash, which stays small next to the live rows.
+CREATE INDEX IF NOT EXISTS IDX_users_deleted_at ON users (deleted_at) WHERE deleted_at IS NOT NULL;
+CREATE INDEX IF NOT EXISTS IDX_post_types_deleted_at ON post_types (deleted_at) WHERE deleted_at IS NOT NULL;
+CREATE INDEX IF NOT EXISTS IDX_posts_deleted_at ON posts (deleted_at) WHERE deleted_at IS NOT NULL;
+CREATE INDEX IF NOT EXISTS IDX_post_collections_deleted_at ON post_collections (deleted_at) WHERE deleted_at IS NOT NULL;
+CREATE INDEX IF NOT EXISTS IDX_post_taxonomies_deleted_at ON post_taxonomies (deleted_at) WHERE deleted_at IS NOT NULL;
+CREATE INDEX IF NOT EXISTS IDX_terms_deleted_at ON terms (deleted_at) WHERE deleted_at IS NOT NULL;
+CREATE INDEX IF NOT EXISTS IDX_attributes_deleted_at ON attributes (deleted_at) WHERE deleted_at IS NOT NULL;
+CREATE INDEX IF NOT EXISTS IDX_attribute_values_deleted_at ON attribute_values (deleted_at) WHERE deleted_at IS NOT NULL;
+
+-- Expose deleted_at on the _info views, appended so the views can be replaced in place.
+CREATE OR REPLACE VIEW users_info AS
+SELECT u.id,
+       u.uid,
+       u.version,
+       u.created_at,
+       u.updated_at,
+       u.username,
+       u.email,
+       u.role,
+       u.deleted_at
+FROM users u;
+
+CREATE OR REPLACE VIEW post_types_info AS
+SELECT pt.id,
+       pt.uid,
+       pt.version,
+       pt.created_at,
+       pt.updated_at,
+       pt.code,
+       pt.name,
+       pt.deleted_at
+FROM post_types pt;
+
+CREATE OR REPLACE VIEW posts_info AS
+SELECT p.id,
+       p.uid,
+       p.version,
+       p.created_at,
+       p.updated_at,
+       p.slug,
+       p.title,
+       p.summary,
+       p.content,
+       p.status,
+       p.user_id,
+       u.username,
+       u.email,
+       p.deleted_at
+FROM posts AS p
+         LEFT JOIN users AS u ON p.user_id = u.id;
+
+CREATE OR REPLACE VIEW post_collections_info AS
+SELECT pc.id,
+       pc.uid,
+       pc.version,
+       pc.created_at,
+       pc.updated_at,
+       pc.slug,
+       pc.title,
+       pc.description,
+       pc.visibility,
+       pc.deleted_at
+FROM post_collections pc;
+
+CREATE OR REPLACE VIEW post_taxonomies_info AS
+SELECT t.id,
+       t.uid,
+       t.version,
+       t.created_at,
+       t.updated_at,
+       t.code,
+       t.name,
+       t.deleted_at
+FROM post_taxonomies t;
+
+CREATE OR REPLACE VIEW terms_info AS
+SELECT te.id,
+       te.uid,
+       te.version,
+       te.created_at,
+       te.updated_at,
+       te.taxonomy_id,
+       tx.code AS taxonomy_code,
+       tx.name AS taxonomy_name,
+       te.parent_id,
+       tp.slug AS parent_slug,
+       tp.name AS parent_name,
+       te.slug,
+       te.name,
+       te.description,
+       te.deleted_at
+FROM terms te
+         LEFT JOIN post_taxonomies tx ON tx.id = te.taxonomy_id
+         LEFT JOIN terms tp ON tp.id = te.parent_id;
+
+CREATE OR REPLACE VIEW attributes_info AS
+SELECT a.id,
+       a.uid,
+       a.version,
+       a.created_at,
+       a.updated_at,
+       a.name,
+       a.entity_type,
+       a.data_type,
+       a.deleted_at
+FROM attributes a;
+
+CREATE OR REPLACE VIEW attribute_values_info AS
+SELECT av.id,
+       av.uid,
+       av.version,
+       av.created_at,
+       av.updated_at,
+       av.int_value,
+       av.double_value,
+       av.string_value,
+       av.boolean_value,
+       av.date_value,
+       av.datetime_value,
+       av.time_value,
+       av.attribute_id,
+       a.name        AS attribute_name,
+       a.entity_type AS attribute_entity_type,
+       a.data_type   AS attribute_data_type,
+       av.entity_id,
+       av.entity_type,
+       av.deleted_at
+FROM attribute_values av
+         LEFT JOIN attributes a ON a.id = av.attribute_id;
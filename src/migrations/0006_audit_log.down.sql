@@ -0,0 +1,2 @@
+DROP VIEW IF EXISTS audit_log_info;
+DROP TABLE IF EXISTS audit_log;
@@ -0,0 +1,39 @@
+-- Append-only history of every write made through the services: who did what
+-- to which row, with the changed columns as a JSON object of
+-- {"column": {"old": ..., "new": ...}}.
+CREATE TABLE IF NOT EXISTS audit_log
+(
+    id          SERIAL
+        CONSTRAINT PK_audit_log PRIMARY KEY,
+    uid         UUID         NOT NULL
+        CONSTRAINT UN_audit_log_uid UNIQUE,
+    version     INTEGER      NOT NULL DEFAULT 0,
+    created_at  TIMESTAMPTZ  NOT NULL DEFAULT NOW(),
+    updated_at  TIMESTAMPTZ  NOT NULL DEFAULT NOW(),
+    entity_type VARCHAR(255) NOT NULL,
+    entity_id   INTEGER      NOT NULL,
+    entity_uid  UUID         NOT NULL,
+    operation   VARCHAR(32)  NOT NULL,
+    user_id     INTEGER,
+    changes     TEXT         NOT NULL DEFAULT '{}',
+    CONSTRAINT FK_audit_log_users_id FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS IDX_audit_log_entity ON audit_log (entity_type, entity_id, created_at DESC);
+CREATE INDEX IF NOT EXISTS IDX_audit_log_user_id ON audit_log (user_id);
+
+CREATE OR REPLACE VIEW audit_log_info AS
+SELECT al.id,
+       al.uid,
+       al.version,
+       al.created_at,
+       al.updated_at,
+       al.entity_type,
+       al.entity_id,
+       al.entity_uid,
+       al.operation,
+       al.user_id,
+       u.username,
+       al.changes
+FROM audit_log al
+         LEFT JOIN users u ON u.id = al.user_id;
@@ -0,0 +1,2 @@
+DROP VIEW IF EXISTS post_revisions_info;
+DROP TABLE IF EXISTS post_revisions;
@@ -0,0 +1,41 @@
+-- Copies of the editable fields of a post, taken on every change (AUTOSAVE)
+-- or on request (CHECKPOINT). Each kind is pruned to its own retention limit.
+CREATE TABLE IF NOT EXISTS post_revisions
+(
+    id         SERIAL
+        CONSTRAINT PK_post_revisions PRIMARY KEY,
+    uid        UUID        NOT NULL
+        CONSTRAINT UN_post_revisions_uid UNIQUE,
+    version    INTEGER     NOT NULL DEFAULT 0,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    post_id    INTEGER     NOT NULL,
+    kind       VARCHAR(16) NOT NULL,
+    title      TEXT        NOT NULL,
+    summary    TEXT        NOT NULL DEFAULT '',
+    content    TEXT        NOT NULL DEFAULT '',
+    status     INTEGER     NOT NULL DEFAULT 0,
+    user_id    INTEGER,
+    CONSTRAINT FK_post_revisions_posts_id FOREIGN KEY (post_id) REFERENCES posts (id) ON DELETE CASCADE,
+    CONSTRAINT FK_post_revisions_users_id FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE SET NULL,
+    CONSTRAINT CK_post_revisions_kind CHECK (kind IN ('AUTOSAVE', 'CHECKPOINT'))
+);
+
+CREATE INDEX IF NOT EXISTS IDX_post_revisions_post_kind ON post_revisions (post_id, kind, created_at DESC);
+
+CREATE OR REPLACE VIEW post_revisions_info AS
+SELECT pr.id,
+       pr.uid,
+       pr.version,
+       pr.created_at,
+       pr.updated_at,
+       pr.post_id,
+       pr.kind,
+       pr.title,
+       pr.summary,
+       pr.content,
+       pr.status,
+       pr.user_id,
+       u.username
+FROM post_revisions pr
+         LEFT JOIN users u ON u.id = pr.user_id;
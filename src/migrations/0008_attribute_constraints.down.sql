@@ -0,0 +1,8 @@
+ALTER TABLE attributes
+    DROP CONSTRAINT IF EXISTS CK_attributes_data_type,
+    DROP COLUMN IF EXISTS default_value,
+    DROP COLUMN IF EXISTS allowed_values,
+    DROP COLUMN IF EXISTS pattern,
+    DROP COLUMN IF EXISTS max_value,
+    DROP COLUMN IF EXISTS min_value,
+    DROP COLUMN IF EXISTS required;
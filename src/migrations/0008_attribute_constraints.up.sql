@@ -0,0 +1,33 @@
+-- Optional constraints on the values of an attribute, enforced by the attribute
+-- value service. allowed_values holds a JSON array of values in text form.
+ALTER TABLE attributes
+    ADD COLUMN IF NOT EXISTS required       BOOLEAN NOT NULL DEFAULT FALSE,
+    ADD COLUMN IF NOT EXISTS min_value      DOUBLE PRECISION,
+    ADD COLUMN IF NOT EXISTS max_value      DOUBLE PRECISION,
+    ADD COLUMN IF NOT EXISTS pattern        TEXT,
+    ADD COLUMN IF NOT EXISTS allowed_values TEXT,
+    ADD COLUMN IF NOT EXISTS default_value  TEXT;
+
+-- data_type used to be free text: map the SQL type names in use onto the
+-- canonical names, and reject anything else from now on.
+UPDATE attributes
+SET data_type = CASE lower(trim(data_type))
+                    WHEN 'integer' THEN 'int'
+                    WHEN 'double' THEN 'float'
+                    WHEN 'double precision' THEN 'float'
+                    WHEN 'real' THEN 'float'
+                    WHEN 'numeric' THEN 'float'
+                    WHEN 'text' THEN 'string'
+                    WHEN 'character varying' THEN 'string'
+                    WHEN 'character' THEN 'string'
+                    WHEN 'bool' THEN 'boolean'
+                    WHEN 'timestamp' THEN 'datetime'
+                    WHEN 'timestamp with time zone' THEN 'datetime'
+                    WHEN 'timestamp without time zone' THEN 'datetime'
+                    ELSE lower(trim(data_type))
+    END
+WHERE data_type NOT IN ('int', 'float', 'string', 'boolean', 'date', 'datetime', 'time');
+
+ALTER TABLE attributes
+    ADD CONSTRAINT CK_attributes_data_type
+        CHECK (data_type IN ('int', 'float', 'string', 'boolean', 'date', 'datetime', 'time')) NOT VALID;
@@ -0,0 +1,5 @@
+DROP TABLE IF EXISTS post_transitions;
+ALTER TABLE posts
+    DROP CONSTRAINT IF EXISTS CK_posts_status;
+ALTER TABLE posts
+    ADD CONSTRAINT CK_posts_status CHECK (status IN (0, 1, 2)) NOT VALID;
@@ -0,0 +1,33 @@
+-- The status check predates ARCHIVED (3) and DELETED (4).
+ALTER TABLE posts
+    DROP CONSTRAINT IF EXISTS CK_posts_status;
+ALTER TABLE posts
+    ADD CONSTRAINT CK_posts_status CHECK (status IN (0, 1, 2, 3, 4));
+
+-- published_at was never set; the last update is the best guess we have.
+UPDATE posts
+SET published_at = updated_at
+WHERE status = 2
+  AND published_at IS NULL;
+
+-- Every status change made through the editorial workflow, with who made it
+-- and why.
+CREATE TABLE IF NOT EXISTS post_transitions
+(
+    id          SERIAL
+        CONSTRAINT PK_post_transitions PRIMARY KEY,
+    uid         UUID        NOT NULL
+        CONSTRAINT UN_post_transitions_uid UNIQUE,
+    version     INTEGER     NOT NULL DEFAULT 0,
+    created_at  TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at  TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    post_id     INTEGER     NOT NULL,
+    from_status INTEGER     NOT NULL,
+    to_status   INTEGER     NOT NULL,
+    user_id     INTEGER,
+    comment     TEXT,
+    CONSTRAINT FK_post_transitions_posts_id FOREIGN KEY (post_id) REFERENCES posts (id) ON DELETE CASCADE,
+    CONSTRAINT FK_post_transitions_users_id FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS IDX_post_transitions_post ON post_transitions (post_id, created_at DESC);
@@ -0,0 +1,5 @@
+DROP INDEX IF EXISTS IDX_posts_expires_at;
+DROP INDEX IF EXISTS IDX_posts_scheduled_at;
+ALTER TABLE posts
+    DROP COLUMN IF EXISTS expires_at,
+    DROP COLUMN IF EXISTS scheduled_at;
@@ -0,0 +1,9 @@
+-- When a post in review goes live on its own, and when a published post is
+-- archived on its own. Both are cleared once the job has acted on them.
+ALTER TABLE posts
+    ADD COLUMN IF NOT EXISTS scheduled_at TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS expires_at   TIMESTAMPTZ;
+
+-- The scheduler only ever looks at posts with a date set.
+CREATE INDEX IF NOT EXISTS IDX_posts_scheduled_at ON posts (scheduled_at) WHERE scheduled_at IS NOT NULL;
+CREATE INDEX IF NOT EXISTS IDX_posts_expires_at ON posts (expires_at) WHERE expires_at IS NOT NULL;
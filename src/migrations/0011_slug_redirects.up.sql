@@ -0,0 +1,20 @@
+-- Slugs a published entity had before, so links using them can still find it.
+-- Rows point at the entity rather than at its new slug, so renaming it again
+-- never leaves a chain of redirects behind.
+CREATE TABLE IF NOT EXISTS slug_redirects
+(
+    id          SERIAL
+        CONSTRAINT PK_slug_redirects PRIMARY KEY,
+    uid         UUID        NOT NULL
+        CONSTRAINT UN_slug_redirects_uid UNIQUE,
+    version     INTEGER     NOT NULL DEFAULT 0,
+    created_at  TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at  TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    entity_type TEXT        NOT NULL, /*'posts', 'terms', 'post_collections'*/
+    scope_id    INTEGER     NOT NULL DEFAULT 0,
+    old_slug    TEXT        NOT NULL,
+    entity_id   INTEGER     NOT NULL,
+    CONSTRAINT UN_slug_redirects_old_slug UNIQUE (entity_type, scope_id, old_slug)
+);
+
+CREATE INDEX IF NOT EXISTS IDX_slug_redirects_entity ON slug_redirects (entity_type, entity_id);
@@ -0,0 +1,7 @@
+DROP INDEX IF EXISTS IDX_slug_redirects_prefix;
+DELETE FROM slug_redirects WHERE entity_id IS NULL;
+ALTER TABLE slug_redirects
+    DROP CONSTRAINT IF EXISTS CK_slug_redirects_target,
+    DROP COLUMN IF EXISTS prefix,
+    DROP COLUMN IF EXISTS target,
+    ALTER COLUMN entity_id SET NOT NULL;
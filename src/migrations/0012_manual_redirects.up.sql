@@ -0,0 +1,10 @@
+-- Redirects entered by hand (entity_type 'paths'): old_slug holds the path
+-- they answer, and target where it leads, another path or an external URL.
+-- A prefix redirect answers every path below old_slug too.
+ALTER TABLE slug_redirects
+    ALTER COLUMN entity_id DROP NOT NULL,
+    ADD COLUMN IF NOT EXISTS target TEXT,
+    ADD COLUMN IF NOT EXISTS prefix BOOLEAN NOT NULL DEFAULT FALSE,
+    ADD CONSTRAINT CK_slug_redirects_target CHECK ((entity_id IS NULL) <> (target IS NULL));
+
+CREATE INDEX IF NOT EXISTS IDX_slug_redirects_prefix ON slug_redirects (old_slug) WHERE prefix;
@@ -0,0 +1,391 @@
+#![cfg(feature = "ssr")]
+
+use crate::common::cache::CACHE;
+use crate::common::service::{ATTRIBUTE_TYPE_MAP, FIELD_TYPE_MAP};
+use sha2::{Digest, Sha256};
+use sqlx::pool::PoolConnection;
+use sqlx::{Acquire, PgConnection, PgPool, Postgres, Row};
+use std::collections::HashMap;
+use std::time::Instant;
+
+/// Key of the session-level advisory lock held while migrating, so that several
+/// app instances starting at the same time apply each migration exactly once.
+const MIGRATION_LOCK_KEY: i64 = 7_212_345_871_009_341_001;
+
+/// A numbered schema migration. Files live next to this module as
+/// `NNNN_<name>.up.sql` / `NNNN_<name>.down.sql` and are embedded in the binary.
+pub struct Migration {
+    pub version: i64,
+    pub name: &'static str,
+    pub up: &'static str,
+    pub down: &'static str,
+}
+
+impl Migration {
+    pub fn checksum(&self) -> String {
+        format!("{:x}", Sha256::digest(self.up.as_bytes()))
+    }
+}
+
+/// All known migrations, ordered by version. Append new entries at the end;
+/// never edit a migration that has already been applied somewhere.
+pub static MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "create_tables",
+        up: include_str!("0001_create_tables.up.sql"),
+        down: include_str!("0001_create_tables.down.sql"),
+    },
+    Migration {
+        version: 2,
+        name: "create_views",
+        up: include_str!("0002_create_views.up.sql"),
+        down: include_str!("0002_create_views.down.sql"),
+    },
+    Migration {
+        version: 3,
+        name: "full_text_search",
+        up: include_str!("0003_full_text_search.up.sql"),
+        down: include_str!("0003_full_text_search.down.sql"),
+    },
+    Migration {
+        version: 4,
+        name: "trigram_indexes",
+        up: include_str!("0004_trigram_indexes.up.sql"),
+        down: include_str!("0004_trigram_indexes.down.sql"),
+    },
+    Migration {
+        version: 5,
+        name: "soft_delete",
+        up: include_str!("0005_soft_delete.up.sql"),
+        down: include_str!("0005_soft_delete.down.sql"),
+    },
+    Migration {
+        version: 6,
+        name: "audit_log",
+        up: include_str!("0006_audit_log.up.sql"),
+        down: include_str!("0006_audit_log.down.sql"),
+    },
+    Migration {
+        version: 7,
+        name: "post_revisions",
+        up: include_str!("0007_post_revisions.up.sql"),
+        down: include_str!("0007_post_revisions.down.sql"),
+    },
+    Migration {
+        version: 8,
+        name: "attribute_constraints",
+        up: include_str!("0008_attribute_constraints.up.sql"),
+        down: include_str!("0008_attribute_constraints.down.sql"),
+    },
+    Migration {
+        version: 9,
+        name: "post_workflow",
+        up: include_str!("0009_post_workflow.up.sql"),
+        down: include_str!("0009_post_workflow.down.sql"),
+    },
+    Migration {
+        version: 10,
+        name: "post_schedule",
+        up: include_str!("0010_post_schedule.up.sql"),
+        down: include_str!("0010_post_schedule.down.sql"),
+    },
+    Migration {
+        version: 11,
+        name: "slug_redirects",
+        up: include_str!("0011_slug_redirects.up.sql"),
+        down: include_str!("0011_slug_redirects.down.sql"),
+    },
+    Migration {
+        version: 12,
+        name: "manual_redirects",
+        up: include_str!("0012_manual_redirects.up.sql"),
+        down: include_str!("0012_manual_redirects.down.sql"),
+    },
+];
+
+#[derive(Debug, thiserror::Error)]
+pub enum MigrationError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error("migration {version} ({name}) changed after it was applied: recorded checksum {recorded}, current {current}")]
+    ChecksumMismatch {
+        version: i64,
+        name: String,
+        recorded: String,
+        current: String,
+    },
+    #[error("migration {0} is recorded in schema_migrations but unknown to this build")]
+    UnknownApplied(i64),
+}
+
+pub struct Migrator {
+    pool: PgPool,
+    migrations: &'static [Migration],
+}
+
+impl Migrator {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            pool,
+            migrations: MIGRATIONS,
+        }
+    }
+
+    /// Migrations that `run` would apply, without touching the database.
+    pub async fn pending(&self) -> Result<Vec<&'static Migration>, MigrationError> {
+        let mut conn = self.pool.acquire().await?;
+        let exists: bool =
+            sqlx::query_scalar("SELECT to_regclass('public.schema_migrations') IS NOT NULL")
+                .fetch_one(&mut *conn)
+                .await?;
+        let applied = if exists {
+            Self::applied(&mut conn).await?
+        } else {
+            HashMap::new()
+        };
+        self.validate(&applied)?;
+        Ok(self
+            .migrations
+            .iter()
+            .filter(|m| !applied.contains_key(&m.version))
+            .collect())
+    }
+
+    /// Apply every pending migration in version order, each in its own transaction.
+    pub async fn run(&self) -> Result<Vec<&'static Migration>, MigrationError> {
+        let mut conn = self.pool.acquire().await?;
+        Self::lock(&mut conn).await?;
+        let result = self.run_locked(&mut conn).await;
+        Self::forget_schema();
+        Self::release(conn).await;
+        result
+    }
+
+    /// Revert applied migrations newer than `target_version`, newest first.
+    pub async fn rollback_to(
+        &self,
+        target_version: i64,
+    ) -> Result<Vec<&'static Migration>, MigrationError> {
+        let mut conn = self.pool.acquire().await?;
+        Self::lock(&mut conn).await?;
+        let result = self.rollback_locked(&mut conn, target_version).await;
+        Self::forget_schema();
+        Self::release(conn).await;
+        result
+    }
+
+    /// Column and attribute types cached before the schema changed are stale.
+    fn forget_schema() {
+        CACHE.invalidate_namespace(FIELD_TYPE_MAP);
+        CACHE.invalidate_namespace(ATTRIBUTE_TYPE_MAP);
+    }
+
+    async fn run_locked(
+        &self,
+        conn: &mut PgConnection,
+    ) -> Result<Vec<&'static Migration>, MigrationError> {
+        Self::ensure_table(conn).await?;
+        let applied = Self::applied(conn).await?;
+        self.validate(&applied)?;
+
+        let mut done = vec![];
+        for migration in self
+            .migrations
+            .iter()
+            .filter(|m| !applied.contains_key(&m.version))
+        {
+            let started = Instant::now();
+            let mut tx = conn.begin().await?;
+            sqlx::raw_sql(migration.up).execute(&mut *tx).await?;
+            sqlx::query(
+                "INSERT INTO schema_migrations (version, name, checksum, execution_ms) VALUES ($1, $2, $3, $4)",
+            )
+            .bind(migration.version)
+            .bind(migration.name)
+            .bind(migration.checksum())
+            .bind(started.elapsed().as_millis() as i64)
+            .execute(&mut *tx)
+            .await?;
+            tx.commit().await?;
+            log::info!(
+                "Applied migration {} ({}) in {} ms",
+                migration.version,
+                migration.name,
+                started.elapsed().as_millis()
+            );
+            done.push(migration);
+        }
+        Ok(done)
+    }
+
+    async fn rollback_locked(
+        &self,
+        conn: &mut PgConnection,
+        target_version: i64,
+    ) -> Result<Vec<&'static Migration>, MigrationError> {
+        Self::ensure_table(conn).await?;
+        let applied = Self::applied(conn).await?;
+        self.validate(&applied)?;
+
+        let mut done = vec![];
+        for migration in self
+            .migrations
+            .iter()
+            .rev()
+            .filter(|m| m.version > target_version && applied.contains_key(&m.version))
+        {
+            let mut tx = conn.begin().await?;
+            sqlx::raw_sql(migration.down).execute(&mut *tx).await?;
+            sqlx::query("DELETE FROM schema_migrations WHERE version = $1")
+                .bind(migration.version)
+                .execute(&mut *tx)
+                .await?;
+            tx.commit().await?;
+            log::info!(
+                "Reverted migration {} ({})",
+                migration.version,
+                migration.name
+            );
+            done.push(migration);
+        }
+        Ok(done)
+    }
+
+    fn validate(&self, applied: &HashMap<i64, String>) -> Result<(), MigrationError> {
+        for (version, recorded) in applied.iter() {
+            let Some(migration) = self.migrations.iter().find(|m| m.version == *version) else {
+                return Err(MigrationError::UnknownApplied(*version));
+            };
+            let current = migration.checksum();
+            if *recorded != current {
+                return Err(MigrationError::ChecksumMismatch {
+                    version: *version,
+                    name: migration.name.to_string(),
+                    recorded: recorded.clone(),
+                    current,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    async fn applied(conn: &mut PgConnection) -> Result<HashMap<i64, String>, sqlx::Error> {
+        let rows = sqlx::query("SELECT version, checksum FROM schema_migrations")
+            .fetch_all(&mut *conn)
+            .await?;
+        Ok(rows
+            .into_iter()
+            .map(|row| (row.get("version"), row.get("checksum")))
+            .collect())
+    }
+
+    async fn ensure_table(conn: &mut PgConnection) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS schema_migrations
+            (
+                version      BIGINT      NOT NULL
+                    CONSTRAINT PK_schema_migrations PRIMARY KEY,
+                name         TEXT        NOT NULL,
+                checksum     TEXT        NOT NULL,
+                applied_at   TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                execution_ms BIGINT      NOT NULL
+            )",
+        )
+        .execute(&mut *conn)
+        .await?;
+        Ok(())
+    }
+
+    async fn lock(conn: &mut PgConnection) -> Result<(), sqlx::Error> {
+        sqlx::query("SELECT pg_advisory_lock($1)")
+            .bind(MIGRATION_LOCK_KEY)
+            .execute(&mut *conn)
+            .await?;
+        Ok(())
+    }
+
+    /// Unlocks whether migrating succeeded or not. A failed unlock is only
+    /// logged, so that it cannot hide why migrating failed; the connection is
+    /// closed instead of going back to the pool, which ends the lock with it.
+    async fn release(mut conn: PoolConnection<Postgres>) {
+        if let Err(e) = Self::unlock(&mut conn).await {
+            log::error!("Could not release the migration lock: {e}");
+            conn.close_on_drop();
+        }
+    }
+
+    async fn unlock(conn: &mut PgConnection) -> Result<(), sqlx::Error> {
+        sqlx::query("SELECT pg_advisory_unlock($1)")
+            .bind(MIGRATION_LOCK_KEY)
+            .execute(&mut *conn)
+            .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_migrations_are_strictly_ordered() {
+        for pair in MIGRATIONS.windows(2) {
+            assert!(
+                pair[0].version < pair[1].version,
+                "migration {} must come before {}",
+                pair[0].version,
+                pair[1].version
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_failed_migration_releases_the_lock() {
+        let Ok(db_url) = std::env::var("TEST_DATABASE_URL") else {
+            return;
+        };
+        let pool = crate::db::connect(&db_url).await.unwrap();
+        let mut migrations = MIGRATIONS
+            .iter()
+            .map(|m| Migration {
+                version: m.version,
+                name: m.name,
+                up: m.up,
+                down: m.down,
+            })
+            .collect::<Vec<_>>();
+        migrations.push(Migration {
+            version: i64::MAX,
+            name: "broken",
+            up: "SELECT no_such_column FROM schema_migrations",
+            down: "",
+        });
+        let migrator = Migrator {
+            pool: pool.clone(),
+            migrations: Vec::leak(migrations),
+        };
+        assert!(matches!(
+            migrator.run().await,
+            Err(MigrationError::Database(_))
+        ));
+
+        // Another session gets the lock, once other tests migrating are done
+        let other = crate::db::connect(&db_url).await.unwrap();
+        let mut other = other.acquire().await.unwrap();
+        let mut locked = false;
+        for _ in 0..50 {
+            locked = sqlx::query_scalar("SELECT pg_try_advisory_lock($1)")
+                .bind(MIGRATION_LOCK_KEY)
+                .fetch_one(&mut *other)
+                .await
+                .unwrap();
+            if locked {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        }
+        assert!(locked);
+        Migrator::unlock(&mut other).await.unwrap();
+    }
+}
@@ -0,0 +1,118 @@
+use crate::pages::components::{Button, Input, Select};
+use crate::pages::rest::attribute_value_api::{
+    load_custom_fields, save_custom_fields, CustomFieldTO, CustomFieldValueTO,
+};
+use crate::pages::rest::error::ErrorTO;
+use leptos::prelude::*;
+use leptos::{component, view, IntoView};
+use std::collections::HashMap;
+
+/// "Custom fields" section of an admin edit page: one input per attribute
+/// defined for `entity_type`, saved together. Hidden when there are none.
+#[component]
+pub fn CustomFields(
+    entity_type: &'static str,
+    #[prop(into)] entity_id: Signal<i32>,
+) -> impl IntoView {
+    let fields_res = Resource::new(
+        move || entity_id.get(),
+        move |id| async move { load_custom_fields(entity_type.to_string(), id).await },
+    );
+    let values = RwSignal::new(HashMap::<String, String>::new());
+
+    let fill = move |fields: &[CustomFieldTO]| {
+        values.set(fields.iter().map(|f| (f.name.clone(), f.value.clone())).collect());
+    };
+    Effect::new(move |_| {
+        if let Some(Ok(fields)) = fields_res.get() {
+            fill(&fields);
+        }
+    });
+
+    let save_action = Action::new(move |values: &HashMap<String, String>| {
+        let values = values
+            .iter()
+            .map(|(name, value)| CustomFieldValueTO { name: name.clone(), value: value.clone() })
+            .collect();
+        async move { save_custom_fields(entity_type.to_string(), entity_id.get_untracked(), values).await }
+    });
+    Effect::new(move |_| {
+        if let Some(Ok(fields)) = save_action.value().get() {
+            fill(&fields);
+        }
+    });
+
+    // A rejected value names its attribute; show the message next to that field
+    let save_error = move || save_action.value().get().and_then(|r| r.err());
+    let field_error = move |name: &str| {
+        save_error()
+            .and_then(|e| ErrorTO::from_server_fn_error(&e))
+            .filter(|e| e.details.get("attribute").map(String::as_str) == Some(name))
+            .map(|e| e.message)
+    };
+    let form_error = move || {
+        save_error()
+            .filter(|e| !ErrorTO::from_server_fn_error(e).is_some_and(|err| err.details.contains_key("attribute")))
+            .map(|e| ErrorTO::describe(&e))
+    };
+
+    let field_input = move |field: CustomFieldTO| {
+        let name = field.name.clone();
+        let value = Signal::derive({ let name = name.clone(); move || values.with(|v| v.get(&name).cloned().unwrap_or_default()) });
+        let on_change = Callback::new({ let name = name.clone(); move |ev| values.update(|v| { v.insert(name.clone(), event_target_value(&ev)); }) });
+        let options = if !field.allowed_values.is_empty() {
+            Some(field.allowed_values.clone())
+        } else if field.data_type == "boolean" {
+            Some(vec!["true".to_string(), "false".to_string()])
+        } else {
+            None
+        };
+        let input = match options {
+            Some(options) => view!{
+                <Select value=value on_change=on_change>
+                    <option value="">"—"</option>
+                    {options.into_iter().map(|o| view!{ <option value={o.clone()}>{o.clone()}</option> }).collect_view()}
+                </Select>
+            }.into_any(),
+            None => {
+                let (input_type, placeholder) = match field.data_type.as_str() {
+                    "int" | "float" => ("number", ""),
+                    "date" => ("date", ""),
+                    "time" => ("time", ""),
+                    "datetime" => ("text", "2024-01-31T09:30:00Z"),
+                    _ => ("text", ""),
+                };
+                view!{ <Input r#type=input_type placeholder=placeholder value=value on_input=on_change /> }.into_any()
+            }
+        };
+        view!{
+            <label class="grid gap-1">
+                <span class="text-sm font-semibold">{field.name.clone()}{field.required.then_some(" *")}</span>
+                {input}
+                {move || field_error(&name).map(|m| view!{<span class="text-sm text-red-600">{m}</span>})}
+            </label>
+        }
+    };
+
+    view! {
+        <Suspense fallback=move || view!{<div/>}>
+            {move || match fields_res.get() {
+                Some(Ok(fields)) if fields.is_empty() => ().into_any(),
+                Some(Ok(fields)) => view!{
+                    <section class="grid gap-3 max-w-xl mt-8">
+                        <h2 class="text-xl font-bold">"Custom fields"</h2>
+                        {fields.into_iter().map(field_input).collect_view()}
+                        <div>
+                            <Button on_click=Callback::new(move |_| { let _ = save_action.dispatch(values.get_untracked()); }) loading_signal=Signal::derive(move || save_action.pending().get())>
+                                "Save custom fields"
+                            </Button>
+                        </div>
+                        {move || form_error().map(|m| view!{<div class="text-sm text-red-600">{m}</div>})}
+                    </section>
+                }.into_any(),
+                Some(Err(e)) => view!{<div class="text-red-600">{"Error: "}{e.to_string()}</div>}.into_any(),
+                None => view!{<div/>}.into_any(),
+            }}
+        </Suspense>
+    }
+}
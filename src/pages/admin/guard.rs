@@ -18,7 +18,7 @@ pub fn AdminGuard(children: leptos::children::ChildrenFn) -> impl IntoView {
                         UserRole::ADMIN => {
                             allowed.set(true);
                         }
-                        UserRole::USER => {
+                        UserRole::USER | UserRole::EDITOR => {
                             let _ = navigate("/403", Default::default());
                         }
                     },
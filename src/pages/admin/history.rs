@@ -0,0 +1,114 @@
+use crate::pages::rest::audit_log_api::{load_entity_history, AuditLogInfoTO};
+use leptos::prelude::*;
+use leptos::{component, view, IntoView};
+use serde_json::{Map, Value};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum EditTab {
+    Details,
+    History,
+}
+
+/// "Details | History" tabs for an admin edit page; `children` is the form.
+#[component]
+pub fn EditTabs(
+    entity_type: &'static str,
+    #[prop(into)] entity_id: Signal<i32>,
+    children: ChildrenFn,
+) -> impl IntoView {
+    let tab = RwSignal::new(EditTab::Details);
+    let tab_button = move |t: EditTab, label: &'static str| {
+        view! {
+            <button
+                type="button"
+                class=move || if tab.get() == t {
+                    "px-3 py-2 text-sm font-semibold border-b-2 border-stone-900"
+                } else {
+                    "px-3 py-2 text-sm text-stone-600 hover:text-stone-900"
+                }
+                on:click=move |_| tab.set(t)
+            >
+                {label}
+            </button>
+        }
+    };
+
+    view! {
+        <div class="flex gap-2 mb-6 border-b border-stone-200">
+            {tab_button(EditTab::Details, "Details")}
+            {tab_button(EditTab::History, "History")}
+        </div>
+        {move || match tab.get() {
+            EditTab::Details => children().into_any(),
+            EditTab::History => view!{ <EntityHistory entity_type=entity_type entity_id=entity_id /> }.into_any(),
+        }}
+    }
+}
+
+/// Audit log of one row, newest first, with the old and new value of every
+/// changed column.
+#[component]
+pub fn EntityHistory(
+    entity_type: &'static str,
+    #[prop(into)] entity_id: Signal<i32>,
+) -> impl IntoView {
+    let entries = Resource::new(
+        move || entity_id.get(),
+        move |id| async move { load_entity_history(entity_type.to_string(), id, Some(0), Some(100)).await },
+    );
+
+    view! {
+        <Suspense fallback=move || view!{<div>"Loading..."</div>}>
+            {move || match entries.get() {
+                Some(Ok(items)) if items.is_empty() => view!{
+                    <div class="text-stone-600">"No changes recorded yet."</div>
+                }.into_any(),
+                Some(Ok(items)) => view!{
+                    <ol class="grid gap-4 max-w-3xl">
+                        {items.into_iter().map(|it: AuditLogInfoTO| view!{
+                            <li class="bg-white border border-stone-200 rounded-lg p-4">
+                                <div class="flex justify-between text-sm text-stone-600 mb-2">
+                                    <span class="font-semibold text-stone-900 capitalize">{it.operation.clone()}</span>
+                                    <span>{it.username.clone().unwrap_or_else(|| "system".to_string())}{" · "}{format_timestamp(it.created_at)}</span>
+                                </div>
+                                {change_rows(&it.changes)}
+                            </li>
+                        }).collect_view()}
+                    </ol>
+                }.into_any(),
+                Some(Err(e)) => view!{<div class="text-red-600">{"Error: "}{e.to_string()}</div>}.into_any(),
+                None => view!{<div/>}.into_any(),
+            }}
+        </Suspense>
+    }
+}
+
+fn change_rows(changes: &str) -> impl IntoView {
+    let changes: Map<String, Value> = serde_json::from_str(changes).unwrap_or_default();
+    (!changes.is_empty()).then(|| view! {
+        <table class="text-sm w-full">
+            <tbody>
+                {changes.into_iter().map(|(column, change)| view!{
+                    <tr class="border-t">
+                        <td class="pr-4 py-1 font-mono text-stone-700 align-top">{column}</td>
+                        <td class="pr-4 py-1 text-red-700 line-through align-top break-all">{display_value(change.get("old"))}</td>
+                        <td class="py-1 text-green-700 align-top break-all">{display_value(change.get("new"))}</td>
+                    </tr>
+                }).collect_view()}
+            </tbody>
+        </table>
+    })
+}
+
+fn display_value(value: Option<&Value>) -> String {
+    match value {
+        None | Some(Value::Null) => "—".to_string(),
+        Some(Value::String(s)) => s.clone(),
+        Some(v) => v.to_string(),
+    }
+}
+
+fn format_timestamp(at: time::OffsetDateTime) -> String {
+    let fmt = time::macros::format_description!("[year]-[month]-[day] [hour]:[minute]:[second]");
+    at.format(&fmt).unwrap_or_default()
+}
@@ -15,7 +15,7 @@ pub fn AdminHomePage() -> impl IntoView {
                 match user_ctx.get() {
                     Some(user) => match user.role {
                         UserRole::ADMIN => {}
-                        UserRole::USER => {
+                        UserRole::USER | UserRole::EDITOR => {
                             let _ = navigate("/403", Default::default());
                         }
                     },
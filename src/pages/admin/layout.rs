@@ -39,6 +39,8 @@ pub fn AdminSidebar() -> impl IntoView {
                     <A href="/admin/taxonomies" attr:class="rounded px-2 py-1 hover:bg-accent hover:text-accent-foreground">Taxonomies</A>
                     <A href="/admin/terms" attr:class="rounded px-2 py-1 hover:bg-accent hover:text-accent-foreground">Terms</A>
                     <A href="/admin/users" attr:class="rounded px-2 py-1 hover:bg-accent hover:text-accent-foreground">Users</A>
+                    <A href="/admin/redirects" attr:class="rounded px-2 py-1 hover:bg-accent hover:text-accent-foreground">Redirects</A>
+                    <A href="/admin/trash" attr:class="rounded px-2 py-1 hover:bg-accent hover:text-accent-foreground">Trash</A>
                     <A href="/settings" attr:class="rounded px-2 py-1 hover:bg-accent hover:text-accent-foreground">Settings</A>
                     <Separator class="my-2" />
                     <button class="text-left rounded px-2 py-1 hover:bg-accent hover:text-accent-foreground" on:click=move |_| do_logout(())>Logout</button>
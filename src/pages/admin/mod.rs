@@ -1,9 +1,14 @@
+pub mod custom_fields;
 pub mod guard;
+pub mod history;
 pub mod home;
 pub mod layout;
 
 pub mod post_dashboard;
 pub mod post_edit;
+pub mod post_revisions;
+pub mod post_terms;
+pub mod post_workflow;
 
 pub mod post_type_dashboard;
 pub mod post_type_edit;
@@ -14,8 +19,12 @@ pub mod post_collection_edit;
 pub mod post_taxonomy_dashboard;
 pub mod post_taxonomy_edit;
 
+pub mod redirects;
+
 pub mod term_dashboard;
 pub mod term_edit;
 
+pub mod trash;
+
 pub mod user_dashboard;
 pub mod user_edit;
@@ -1,8 +1,11 @@
 use crate::pages::admin::guard::AdminGuard;
+use crate::pages::admin::custom_fields::CustomFields;
+use crate::pages::admin::history::EditTabs;
 use crate::pages::admin::layout::AdminSidebar;
 use crate::pages::components::sidebar::SidebarProvider;
 use crate::pages::components::{Input, Button, Select};
 use crate::pages::rest::post_collection_api::{load_post_collection_by_id, update_post_collection, PostCollectionTO};
+use crate::pages::rest::error::ErrorTO;
 use leptos::prelude::*;
 use leptos::{component, view, IntoView};
 use leptos_router::hooks::use_params_map;
@@ -47,6 +50,13 @@ pub fn AdminPostCollectionEditPage() -> impl IntoView {
         async move { update_post_collection(current).await }
     });
 
+    // Saved rows come back with a bumped version; refetch so the next save is based on it
+    Effect::new(move |_| {
+        if let Some(Ok(_)) = save_action.value().get() {
+            entity_res.refetch();
+        }
+    });
+
     view! {
         <AdminGuard>
             <SidebarProvider default_open=true>
@@ -55,27 +65,31 @@ pub fn AdminPostCollectionEditPage() -> impl IntoView {
                     <main class="flex-1 min-h-screen">
                         <div class="container-page py-10 font-serif">
                             <h1 class="text-3xl font-bold mb-6">"Edit Post Collection"</h1>
-                            <Suspense fallback=move || view!{<div>"Loading..."</div>}>
-                                {move || match entity_res.get() {
-                                    Some(Ok(_e)) => view!{
-                                        <div class="grid gap-3 max-w-xl">
-                                            <Input placeholder="Slug" value=slug on_input=Callback::new(move |ev| slug.set(event_target_value(&ev))) />
-                                            <Input placeholder="Title" value=title on_input=Callback::new(move |ev| title.set(event_target_value(&ev))) />
-                                            <Select value=visibility on_change=Callback::new(move |ev| visibility.set(event_target_value(&ev)))>
-                                                <option value="PUBLIC">PUBLIC</option>
-                                                <option value="PRIVATE">PRIVATE</option>
-                                            </Select>
-                                            <div>
-                                                <Button on_click=Callback::new({ let slug=slug.clone(); let title=title.clone(); let visibility=visibility.clone(); let save=save_action.clone(); move |_| { let _= save.dispatch((slug.get_untracked(), title.get_untracked(), visibility.get_untracked())); } }) loading_signal=Signal::derive(move || save_action.pending().get())>
-                                                    "Save"
-                                                </Button>
+                            <EditTabs entity_type="post_collections" entity_id=Signal::derive(id)>
+                                <Suspense fallback=move || view!{<div>"Loading..."</div>}>
+                                    {move || match entity_res.get() {
+                                        Some(Ok(_e)) => view!{
+                                            <div class="grid gap-3 max-w-xl">
+                                                <Input placeholder="Slug" value=slug on_input=Callback::new(move |ev| slug.set(event_target_value(&ev))) />
+                                                <Input placeholder="Title" value=title on_input=Callback::new(move |ev| title.set(event_target_value(&ev))) />
+                                                <Select value=visibility on_change=Callback::new(move |ev| visibility.set(event_target_value(&ev)))>
+                                                    <option value="PUBLIC">PUBLIC</option>
+                                                    <option value="PRIVATE">PRIVATE</option>
+                                                </Select>
+                                                <div>
+                                                    <Button on_click=Callback::new({ let slug=slug.clone(); let title=title.clone(); let visibility=visibility.clone(); let save=save_action.clone(); move |_| { let _= save.dispatch((slug.get_untracked(), title.get_untracked(), visibility.get_untracked())); } }) loading_signal=Signal::derive(move || save_action.pending().get())>
+                                                        "Save"
+                                                    </Button>
+                                                </div>
+                                                {move || save_action.value().get().and_then(|r| r.err()).map(|e| view!{<div class="text-sm text-red-600">{ErrorTO::describe(&e)}</div>})}
                                             </div>
-                                        </div>
-                                    }.into_any(),
-                                    Some(Err(e)) => view!{<div class="text-red-600">{"Error: "}{e.to_string()}</div>}.into_any(),
-                                    None => view!{<div/>}.into_any(),
-                                }}
-                            </Suspense>
+                                        }.into_any(),
+                                        Some(Err(e)) => view!{<div class="text-red-600">{"Error: "}{e.to_string()}</div>}.into_any(),
+                                        None => view!{<div/>}.into_any(),
+                                    }}
+                                </Suspense>
+                                <CustomFields entity_type="post_collections" entity_id=Signal::derive(id) />
+                            </EditTabs>
                         </div>
                     </main>
                 </div>
@@ -6,7 +6,7 @@ use crate::pages::components::datatable::core::query_sync::{sync_table_query_to_
 use crate::pages::components::datatable::core::render_value::Value;
 use crate::pages::components::datatable::core::row::RowNode;
 use crate::pages::components::datatable::core::state::TableState;
-use crate::pages::components::select::{Select, SelectOption, SelectSize};
+use crate::pages::components::select::{SearchOptions, Select, SelectOption, SelectSize};
 use crate::pages::components::sidebar::SidebarProvider;
 use crate::pages::components::{
     Button, Dialog, DialogClose, DialogContent, DialogDescription, DialogFooter, DialogHeader,
@@ -17,7 +17,9 @@ use crate::pages::rest::auth_api::UserTO;
 use crate::pages::rest::post_api::{
     count_post_infos, create_post, delete_post, load_post_infos, update_post, PostInfoTO, PostTO,
 };
-use crate::pages::rest::post_type_api::{load_post_type_infos, PostTypeInfoTO};
+use crate::pages::rest::post_type_api::{
+    load_post_type_infos, suggest_post_type_infos, PostTypeInfoTO,
+};
 use leptos::prelude::*;
 use leptos::{component, view, IntoView};
 use leptos_router::hooks::{use_navigate, use_query_map};
@@ -55,17 +57,10 @@ fn NewPostDialog() -> impl IntoView {
         },
     );
 
-    // Unified select loader for post types
-    let load_post_type_options: Arc<
-        dyn Fn() -> std::pin::Pin<
-                Box<dyn std::future::Future<Output = Result<Vec<SelectOption>, String>> + Send>,
-            > + Send
-            + Sync,
-    > = Arc::new(|| {
+    // Autocomplete for post types: fuzzy-matches what was typed on the server
+    let search_post_type_options: SearchOptions = Arc::new(|query| {
         Box::pin(async move {
-            match load_post_type_infos(Some(0), Some(100), Some("name".to_string()), None, None)
-                .await
-            {
+            match suggest_post_type_infos(query, 20).await {
                 Ok(items) => Ok(items
                     .into_iter()
                     .map(|pt| SelectOption {
@@ -161,7 +156,8 @@ fn NewPostDialog() -> impl IntoView {
                                     name="post_type"
                                     required=true
                                     placeholder="Select a Post Type".to_string()
-                                    load_options=load_post_type_options.clone()
+                                    search_options=search_post_type_options.clone()
+                                    search_placeholder="Search post types...".to_string()
                                     on_focus=Callback::new({
                                         let post_types_trigger = post_types_trigger.clone();
                                         move |_| {
@@ -1,10 +1,16 @@
 use crate::pages::admin::guard::AdminGuard;
+use crate::pages::admin::custom_fields::CustomFields;
+use crate::pages::admin::history::EditTabs;
 use crate::pages::admin::layout::AdminSidebar;
+use crate::pages::admin::post_revisions::PostRevisions;
+use crate::pages::admin::post_terms::PostTerms;
+use crate::pages::admin::post_workflow::PostWorkflow;
 use crate::pages::components::sidebar::SidebarProvider;
-use crate::pages::components::Input;
+use crate::pages::components::button::{ButtonSize, ButtonVariant};
+use crate::pages::components::{Button, Input};
 use crate::pages::components::MarkdownEditor;
-use crate::pages::components::Select;
-use crate::pages::rest::post_api::{load_post_by_id, update_post, PostTO};
+use crate::pages::rest::error::ErrorTO;
+use crate::pages::rest::post_api::{load_post_by_id, schedule_post, update_post, PostTO};
 use leptos::prelude::*;
 use leptos::{component, view, IntoView};
 use leptos_router::components::A;
@@ -17,7 +23,21 @@ pub struct PostFormValues {
     pub title: String,
     pub summary: String,
     pub content: String,
-    pub status: String,
+}
+
+/// When a post goes live and is archived on its own, as entered in the
+/// form's `datetime-local` inputs (UTC); empty for no date.
+#[derive(Clone, Debug)]
+pub struct PostScheduleValues {
+    pub scheduled_at: String,
+    pub expires_at: String,
+}
+
+/// Value of a `datetime-local` input showing `at` in UTC.
+fn schedule_input_value(at: Option<time::OffsetDateTime>) -> String {
+    let format = time::macros::format_description!("[year]-[month]-[day]T[hour]:[minute]");
+    at.and_then(|at| at.to_offset(time::UtcOffset::UTC).format(&format).ok())
+        .unwrap_or_default()
 }
 
 /// Form used on the AdminPostEditPage to edit an existing post
@@ -29,16 +49,21 @@ pub fn AdminPostEditForm(
     initial_title: String,
     initial_summary: String,
     initial_content: String,
-    initial_status: String,
+    initial_scheduled_at: Option<time::OffsetDateTime>,
+    initial_expires_at: Option<time::OffsetDateTime>,
     meta: Option<String>,
     saved_post: Signal<Option<PostTO>>,
     on_submit: Callback<PostFormValues, ()>,
+    on_schedule: Callback<PostScheduleValues, ()>,
+    #[prop(into)] schedule_pending: Signal<bool>,
+    #[prop(into)] schedule_error: Signal<Option<String>>,
 ) -> impl IntoView {
     let slug = RwSignal::new(initial_slug);
     let title = RwSignal::new(initial_title);
     let summary = RwSignal::new(initial_summary);
     let content = RwSignal::new(initial_content);
-    let status = RwSignal::new(initial_status);
+    let scheduled_at = RwSignal::new(schedule_input_value(initial_scheduled_at));
+    let expires_at = RwSignal::new(schedule_input_value(initial_expires_at));
 
     let submit = move || {
         on_submit.run(PostFormValues {
@@ -46,7 +71,6 @@ pub fn AdminPostEditForm(
             title: title.get_untracked(),
             summary: summary.get_untracked(),
             content: content.get_untracked(),
-            status: status.get_untracked(),
         })
     };
 
@@ -57,7 +81,8 @@ pub fn AdminPostEditForm(
             title.set(p.title.clone());
             summary.set(p.summary.clone());
             content.set(p.content.clone());
-            status.set(p.status.clone());
+            scheduled_at.set(schedule_input_value(p.scheduled_at));
+            expires_at.set(schedule_input_value(p.expires_at));
         }
     });
 
@@ -77,13 +102,6 @@ pub fn AdminPostEditForm(
                     }.into_any() } else { view!{}.into_any() }}
                     <Input placeholder="Title" value=title on_input=Callback::new(move |ev: leptos::ev::Event| title.set(event_target_value(&ev))) on_blur=Callback::new(move |_| submit()) />
                     <Input class="md:col-span-2" placeholder="Summary" value=summary on_input=Callback::new(move |ev: leptos::ev::Event| summary.set(event_target_value(&ev))) on_blur=Callback::new(move |_| submit()) />
-                    <Select value=status on_change=Callback::new(move |ev: leptos::ev::Event| { status.set(event_target_value(&ev)); submit(); })>
-                        <option value="DRAFT">DRAFT</option>
-                        <option value="REVIEW">REVIEW</option>
-                        <option value="PUBLISHED">PUBLISHED</option>
-                        <option value="ARCHIVED">ARCHIVED</option>
-                        <option value="DELETED">DELETED</option>
-                    </Select>
                     <div class="md:col-span-2">
                         <MarkdownEditor
                             initial_content=content.get()
@@ -98,11 +116,42 @@ pub fn AdminPostEditForm(
                         />
                     </div>
                 </div>
+                <div class="mt-4 pt-4 border-t border-stone-200 grid grid-cols-1 md:grid-cols-2 gap-3">
+                    <label class="grid gap-1">
+                        <span class="text-sm font-semibold">"Publish at (UTC)"</span>
+                        <Input r#type="datetime-local" value=scheduled_at on_input=Callback::new(move |ev: leptos::ev::Event| scheduled_at.set(event_target_value(&ev))) />
+                        <span class="text-xs text-stone-500">"Goes live then if it is in review."</span>
+                    </label>
+                    <label class="grid gap-1">
+                        <span class="text-sm font-semibold">"Archive at (UTC)"</span>
+                        <Input r#type="datetime-local" value=expires_at on_input=Callback::new(move |ev: leptos::ev::Event| expires_at.set(event_target_value(&ev))) />
+                        <span class="text-xs text-stone-500">"Archived then if it is published."</span>
+                    </label>
+                    <div class="md:col-span-2 flex items-center gap-3">
+                        <Button variant=ButtonVariant::Outline size=ButtonSize::Sm
+                            loading_signal=schedule_pending
+                            on_click=Callback::new(move |_| on_schedule.run(PostScheduleValues {
+                                scheduled_at: scheduled_at.get_untracked(),
+                                expires_at: expires_at.get_untracked(),
+                            }))>
+                            "Save schedule"
+                        </Button>
+                        {move || schedule_error.get().map(|m| view!{<span class="text-sm text-red-600">{m}</span>})}
+                    </div>
+                </div>
             </div>
         </div>
     }
 }
 
+impl PostFormValues {
+    fn apply_to(&self, to: &mut PostTO) {
+        to.title = self.title.clone();
+        to.summary = self.summary.clone();
+        to.content = self.content.clone();
+    }
+}
+
 #[component]
 pub fn AdminPostEditPage() -> impl IntoView {
     let params = use_params_map();
@@ -115,73 +164,171 @@ pub fn AdminPostEditPage() -> impl IntoView {
 
     let post_res = Resource::new(move || id(), |id| async move { load_post_by_id(id).await });
 
+    // Latest copy known to be on the server; each autosave is based on its version
+    let latest = RwSignal::new(None::<PostTO>);
+    let last_values = RwSignal::new(None::<PostFormValues>);
+    // Server version that rejected the last save, if any
+    let conflict = RwSignal::new(None::<String>);
+
+    Effect::new(move |_| {
+        if let Some(Ok(p)) = post_res.get() {
+            latest.set(Some(p));
+            conflict.set(None);
+        }
+    });
+
     let update_action = Action::new(move |vals: &PostFormValues| {
-        let mut to = match post_res.get() {
-            Some(Ok(p)) => p.clone(),
-            _ => PostTO {
-                id: id(),
-                uid: String::new(),
-                version: 0,
-                created_at: time::OffsetDateTime::UNIX_EPOCH,
-                updated_at: time::OffsetDateTime::UNIX_EPOCH,
-                slug: String::new(),
-                title: String::new(),
-                summary: String::new(),
-                content: String::new(),
-                status: String::new(),
-            },
-        };
-        to.title = vals.title.clone();
-        to.summary = vals.summary.clone();
-        to.content = vals.content.clone();
-        to.status = vals.status.clone();
+        let mut to = latest.get_untracked().unwrap_or_else(|| PostTO {
+            id: id(),
+            uid: String::new(),
+            version: 0,
+            created_at: time::OffsetDateTime::UNIX_EPOCH,
+            updated_at: time::OffsetDateTime::UNIX_EPOCH,
+            slug: String::new(),
+            title: String::new(),
+            summary: String::new(),
+            content: String::new(),
+            status: String::new(),
+            user_id: 0,
+            type_id: 0,
+            published_at: None,
+            scheduled_at: None,
+            expires_at: None,
+        });
+        vals.apply_to(&mut to);
         async move { update_post(to).await }
     });
 
+    // Re-apply the rejected edits on top of whatever is on the server now
+    let overwrite_action = Action::new(move |vals: &PostFormValues| {
+        let vals = vals.clone();
+        let id = id();
+        async move {
+            let mut to = load_post_by_id(id).await?;
+            vals.apply_to(&mut to);
+            update_post(to).await
+        }
+    });
+
+    let schedule_action = Action::new(move |vals: &PostScheduleValues| {
+        let vals = vals.clone();
+        let id = id();
+        async move { schedule_post(id, vals.scheduled_at, vals.expires_at).await }
+    });
+
+    let saved = RwSignal::new(None::<PostTO>);
+    Effect::new(move |_| {
+        if let Some(Ok(p)) = schedule_action.value().get() {
+            latest.set(Some(p.clone()));
+            saved.set(Some(p));
+        }
+    });
+    for action in [update_action, overwrite_action] {
+        Effect::new(move |_| match action.value().get() {
+            Some(Ok(p)) => {
+                latest.set(Some(p.clone()));
+                saved.set(Some(p));
+                conflict.set(None);
+            }
+            Some(Err(e)) => {
+                if let Some(err) = ErrorTO::from_server_fn_error(&e).filter(ErrorTO::is_conflict) {
+                    conflict.set(Some(
+                        err.details.get("current_version").cloned().unwrap_or_default(),
+                    ));
+                }
+            }
+            None => {}
+        });
+    }
+
     view! {
         <AdminGuard>
             <SidebarProvider default_open=true>
                 <div class="flex gap-0">
                     <AdminSidebar />
                     <main class="flex-1 min-h-screen">
-                        <Suspense fallback=move || view!{<div class="text-center py-8">Loading post...</div>}>
-                            {move || match post_res.get() {
-                    Some(Ok(post)) => {
-                        let format = format_description::parse("[year]-[month]-[day] [hour]:[minute]:[second]").unwrap();
-                        let meta = format!(
-                            "Slug: {} - Status: {} - Created: {} - Updated: {}",
-                            post.slug,
-                            post.status,
-                            post.created_at.format(&format).unwrap_or_default(),
-                            post.updated_at.format(&format).unwrap_or_default()
-                        );
-                        view!{
-                            <AdminPostEditForm
-                                heading="Edit Post".to_string()
-                                show_slug=false
-                                initial_slug=post.slug.clone()
-                                initial_title=post.title.clone()
-                                initial_summary=post.summary.clone()
-                                initial_content=post.content.clone()
-                                initial_status=post.status.clone()
-                                meta=Some(meta)
-                                saved_post=Signal::derive({
-                                    let v = update_action.value();
-                                    move || v.get().and_then(|r| r.ok())
-                                })
-                                on_submit=Callback::new({
-                                    let update_action = update_action.clone();
-                                    move |vals: PostFormValues| {
-                                        let _ = update_action.dispatch(vals);
-                                    }
-                                })
-                            />
-                        }.into_any()
-                    }
-                    Some(Err(e)) => view!{<div class="text-red-600">Error: {e.to_string()}</div>}.into_any(),
-                    None => view!{<div/>}.into_any()
-                }}
-                        </Suspense>
+                        {move || conflict.get().map(|current_version| view!{
+                            <div class="container-page pt-6 font-serif">
+                                <div class="p-3 rounded-lg border border-amber-300 bg-amber-50 text-sm text-amber-900 flex items-center justify-between gap-3">
+                                    <span>{format!("This post was changed elsewhere (now at version {}). Your last edit was not saved.", current_version)}</span>
+                                    <div class="flex gap-2">
+                                        <Button variant=ButtonVariant::Secondary size=ButtonSize::Sm on_click=Callback::new(move |_| post_res.refetch())>
+                                            "Reload"
+                                        </Button>
+                                        <Button variant=ButtonVariant::Destructive size=ButtonSize::Sm
+                                            loading_signal=Signal::derive(move || overwrite_action.pending().get())
+                                            on_click=Callback::new(move |_| {
+                                                if let Some(vals) = last_values.get_untracked() {
+                                                    let _ = overwrite_action.dispatch(vals);
+                                                }
+                                            })>
+                                            "Overwrite with my changes"
+                                        </Button>
+                                    </div>
+                                </div>
+                            </div>
+                        })}
+                        <div class="container-page pt-6 font-serif">
+                            <EditTabs entity_type="posts" entity_id=Signal::derive(id)>
+                                <Suspense fallback=move || view!{<div class="text-center py-8">Loading post...</div>}>
+                                    {move || match post_res.get() {
+                            Some(Ok(post)) => {
+                                let format = format_description::parse("[year]-[month]-[day] [hour]:[minute]:[second]").unwrap();
+                                let meta = format!(
+                                    "Slug: {} - Created: {} - Updated: {}",
+                                    post.slug,
+                                    post.created_at.format(&format).unwrap_or_default(),
+                                    post.updated_at.format(&format).unwrap_or_default()
+                                );
+                                view!{
+                                    <AdminPostEditForm
+                                        heading="Edit Post".to_string()
+                                        show_slug=false
+                                        initial_slug=post.slug.clone()
+                                        initial_title=post.title.clone()
+                                        initial_summary=post.summary.clone()
+                                        initial_content=post.content.clone()
+                                        initial_scheduled_at=post.scheduled_at
+                                        initial_expires_at=post.expires_at
+                                        meta=Some(meta)
+                                        saved_post=saved.into()
+                                        on_submit=Callback::new({
+                                            let update_action = update_action.clone();
+                                            move |vals: PostFormValues| {
+                                                last_values.set(Some(vals.clone()));
+                                                let _ = update_action.dispatch(vals);
+                                            }
+                                        })
+                                        on_schedule=Callback::new(move |vals: PostScheduleValues| {
+                                            let _ = schedule_action.dispatch(vals);
+                                        })
+                                        schedule_pending=schedule_action.pending()
+                                        schedule_error=Signal::derive(move || {
+                                            schedule_action.value().get().and_then(|r| r.err()).map(|e| ErrorTO::describe(&e))
+                                        })
+                                    />
+                                }.into_any()
+                            }
+                            Some(Err(e)) => view!{<div class="text-red-600">Error: {e.to_string()}</div>}.into_any(),
+                            None => view!{<div/>}.into_any()
+                        }}
+                                </Suspense>
+                                <PostWorkflow
+                                    post_id=Signal::derive(id)
+                                    on_transitioned=Callback::new(move |p: PostTO| {
+                                        latest.set(Some(p.clone()));
+                                        saved.set(Some(p));
+                                    })
+                                />
+                                <PostTerms post_id=Signal::derive(id) />
+                                <CustomFields entity_type="posts" entity_id=Signal::derive(id) />
+                                <PostRevisions
+                                    post_id=Signal::derive(id)
+                                    saved_post=saved.into()
+                                    on_restored=Callback::new(move |_| post_res.refetch())
+                                />
+                            </EditTabs>
+                        </div>
                     </main>
                 </div>
             </SidebarProvider>
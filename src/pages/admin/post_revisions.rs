@@ -0,0 +1,176 @@
+use crate::common::diff::DiffLine;
+use crate::pages::components::button::{ButtonSize, ButtonVariant};
+use crate::pages::components::Button;
+use crate::pages::rest::error::ErrorTO;
+use crate::pages::rest::post_api::PostTO;
+use crate::pages::rest::post_revision_api::{
+    diff_post_revisions, load_post_revisions, restore_post_revision, save_post_revision,
+    PostRevisionInfoTO,
+};
+use leptos::prelude::*;
+use leptos::{component, view, IntoView};
+
+/// Revisions of a post: save a checkpoint, compare any two, restore one.
+/// Refreshes whenever `saved_post` changes, i.e. after every autosave.
+#[component]
+pub fn PostRevisions(
+    #[prop(into)] post_id: Signal<i32>,
+    saved_post: Signal<Option<PostTO>>,
+    on_restored: Callback<PostTO, ()>,
+) -> impl IntoView {
+    let reload = RwSignal::new(0u32);
+    let revisions = Resource::new(
+        move || {
+            (
+                post_id.get(),
+                saved_post.with(|p| p.as_ref().map(|p| p.version)),
+                reload.get(),
+            )
+        },
+        |(id, _, _)| async move { load_post_revisions(id, Some(0), Some(100)).await },
+    );
+
+    // Revisions picked for the diff
+    let from = RwSignal::new(None::<i32>);
+    let to = RwSignal::new(None::<i32>);
+    let diff = Resource::new(
+        move || (from.get(), to.get()),
+        |pair| async move {
+            match pair {
+                (Some(from), Some(to)) if from != to => Some(diff_post_revisions(from, to).await),
+                _ => None,
+            }
+        },
+    );
+
+    let save_action = Action::new(move |_: &()| {
+        let id = post_id.get_untracked();
+        async move { save_post_revision(id).await }
+    });
+    let restore_action = Action::new(|revision_id: &i32| {
+        let revision_id = *revision_id;
+        async move { restore_post_revision(revision_id).await }
+    });
+
+    Effect::new(move |_| {
+        if let Some(Ok(_)) = save_action.value().get() {
+            reload.update(|n| *n += 1);
+        }
+    });
+    Effect::new(move |_| {
+        if let Some(Ok(post)) = restore_action.value().get() {
+            reload.update(|n| *n += 1);
+            on_restored.run(post);
+        }
+    });
+
+    view! {
+        <section class="mt-8 p-4 bg-white rounded-lg border border-stone-200">
+            <div class="flex items-center justify-between mb-3">
+                <h2 class="text-xl font-semibold">"Revisions"</h2>
+                <Button
+                    variant=ButtonVariant::Outline
+                    size=ButtonSize::Sm
+                    loading_signal=Signal::derive(move || save_action.pending().get())
+                    on_click=Callback::new(move |_| { save_action.dispatch(()); })
+                >
+                    "Save revision"
+                </Button>
+            </div>
+            {move || save_action.value().get().and_then(|r| r.err()).map(|e| view!{<div class="text-sm text-red-600 mb-2">{ErrorTO::describe(&e)}</div>})}
+            {move || restore_action.value().get().and_then(|r| r.err()).map(|e| view!{<div class="text-sm text-red-600 mb-2">{ErrorTO::describe(&e)}</div>})}
+            <Suspense fallback=move || view!{<div>"Loading..."</div>}>
+                {move || match revisions.get() {
+                    Some(Ok(items)) if items.is_empty() => view!{
+                        <div class="text-sm text-stone-600">"No revisions yet."</div>
+                    }.into_any(),
+                    Some(Ok(items)) => view!{
+                        <table class="min-w-full text-sm">
+                            <thead class="bg-stone-100">
+                                <tr>
+                                    <th class="text-left px-2 py-1">"From"</th>
+                                    <th class="text-left px-2 py-1">"To"</th>
+                                    <th class="text-left px-2 py-1">"Saved"</th>
+                                    <th class="text-left px-2 py-1">"Kind"</th>
+                                    <th class="text-left px-2 py-1">"By"</th>
+                                    <th class="text-left px-2 py-1">"Title"</th>
+                                    <th class="text-right px-2 py-1"></th>
+                                </tr>
+                            </thead>
+                            <tbody>
+                                {items.into_iter().map(|it: PostRevisionInfoTO| {
+                                    let id = it.id;
+                                    view!{
+                                        <tr class="border-t">
+                                            <td class="px-2 py-1">
+                                                <input type="radio" name="revision-from" prop:checked=move || from.get() == Some(id) on:change=move |_| from.set(Some(id)) />
+                                            </td>
+                                            <td class="px-2 py-1">
+                                                <input type="radio" name="revision-to" prop:checked=move || to.get() == Some(id) on:change=move |_| to.set(Some(id)) />
+                                            </td>
+                                            <td class="px-2 py-1 whitespace-nowrap">{format_timestamp(it.created_at)}</td>
+                                            <td class="px-2 py-1">
+                                                <span class=if it.kind == "CHECKPOINT" { "font-semibold text-stone-900" } else { "text-stone-500" }>{it.kind.clone()}</span>
+                                            </td>
+                                            <td class="px-2 py-1">{it.username.clone().unwrap_or_else(|| "system".to_string())}</td>
+                                            <td class="px-2 py-1">{it.title.clone()}</td>
+                                            <td class="px-2 py-1 text-right">
+                                                <Button
+                                                    variant=ButtonVariant::Ghost
+                                                    size=ButtonSize::Sm
+                                                    on_click=Callback::new(move |_| { restore_action.dispatch(id); })
+                                                >
+                                                    "Restore"
+                                                </Button>
+                                            </td>
+                                        </tr>
+                                    }
+                                }).collect_view()}
+                            </tbody>
+                        </table>
+                    }.into_any(),
+                    Some(Err(e)) => view!{<div class="text-red-600">{"Error: "}{e.to_string()}</div>}.into_any(),
+                    None => view!{<div/>}.into_any(),
+                }}
+            </Suspense>
+            <Suspense fallback=move || view!{<div>"Comparing..."</div>}>
+                {move || match diff.get().flatten() {
+                    Some(Ok(d)) => view!{
+                        <div class="mt-4 grid gap-3">
+                            {diff_block("Title", d.title)}
+                            {diff_block("Status", d.status)}
+                            {diff_block("Summary", d.summary)}
+                            {diff_block("Content", d.content)}
+                        </div>
+                    }.into_any(),
+                    Some(Err(e)) => view!{<div class="text-red-600">{"Error: "}{e.to_string()}</div>}.into_any(),
+                    None => view!{<div/>}.into_any(),
+                }}
+            </Suspense>
+        </section>
+    }
+}
+
+fn diff_block(label: &'static str, lines: Vec<DiffLine>) -> impl IntoView {
+    let changed = lines.iter().any(|l| !matches!(l, DiffLine::Same(_)));
+    changed.then(|| view! {
+        <div>
+            <div class="text-sm font-semibold mb-1">{label}</div>
+            <pre class="text-xs border border-stone-200 rounded overflow-x-auto">
+                {lines.into_iter().map(|line| {
+                    let (class, text) = match line {
+                        DiffLine::Same(text) => ("px-2 text-stone-700", format!("  {text}")),
+                        DiffLine::Added(text) => ("px-2 bg-green-50 text-green-800", format!("+ {text}")),
+                        DiffLine::Removed(text) => ("px-2 bg-red-50 text-red-800", format!("- {text}")),
+                    };
+                    view!{<div class=class>{text}</div>}
+                }).collect_view()}
+            </pre>
+        </div>
+    })
+}
+
+fn format_timestamp(at: time::OffsetDateTime) -> String {
+    let fmt = time::macros::format_description!("[year]-[month]-[day] [hour]:[minute]:[second]");
+    at.format(&fmt).unwrap_or_default()
+}
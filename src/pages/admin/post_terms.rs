@@ -0,0 +1,113 @@
+use crate::pages::components::Checkbox;
+use crate::pages::rest::error::ErrorTO;
+use crate::pages::rest::post_api::{load_post_terms, replace_post_terms};
+use crate::pages::rest::term_api::{load_term_infos, TermInfoTO};
+use leptos::prelude::*;
+use leptos::{component, view, IntoView};
+
+/// Tags, categories and other terms of a post, one group of checkboxes per
+/// taxonomy. Each change replaces the post's terms of that taxonomy.
+#[component]
+pub fn PostTerms(#[prop(into)] post_id: Signal<i32>) -> impl IntoView {
+    let terms_res = Resource::new(
+        || (),
+        |_| async move {
+            load_term_infos(None, None, Some("taxonomy_code|name".to_string()), None, None).await
+        },
+    );
+    let assigned_res = Resource::new(
+        move || post_id.get(),
+        |id| async move { load_post_terms(id).await },
+    );
+    // Ids of the post's terms, from loading or from the last change
+    let assigned = RwSignal::new(Vec::<i32>::new());
+
+    Effect::new(move |_| {
+        if let Some(Ok(terms)) = assigned_res.get() {
+            assigned.set(terms.iter().map(|t| t.id).collect());
+        }
+    });
+
+    let replace_action = Action::new(move |(taxonomy, term_ids): &(String, Vec<i32>)| {
+        let id = post_id.get_untracked();
+        let (taxonomy, term_ids) = (taxonomy.clone(), term_ids.clone());
+        async move { replace_post_terms(id, taxonomy, term_ids).await }
+    });
+    Effect::new(move |_| {
+        if let Some(Ok(terms)) = replace_action.value().get() {
+            assigned.set(terms.iter().map(|t| t.id).collect());
+        }
+    });
+
+    let toggle = move |all: &[TermInfoTO], term: &TermInfoTO, checked: bool| {
+        let current = assigned.get_untracked();
+        let term_ids = all
+            .iter()
+            .filter(|t| t.taxonomy_code == term.taxonomy_code)
+            .map(|t| t.id)
+            .filter(|id| if *id == term.id { checked } else { current.contains(id) })
+            .collect();
+        replace_action.dispatch((term.taxonomy_code.clone(), term_ids));
+    };
+
+    view! {
+        <section class="mt-8 p-4 bg-white rounded-lg border border-stone-200">
+            <h2 class="text-xl font-semibold mb-3">"Terms"</h2>
+            <Suspense fallback=move || view!{<div>"Loading..."</div>}>
+                {move || match terms_res.get() {
+                    Some(Err(e)) => view!{<div class="text-red-600">{"Error: "}{e.to_string()}</div>}.into_any(),
+                    None => view!{<div/>}.into_any(),
+                    Some(Ok(all)) if all.is_empty() => view!{
+                        <div class="text-sm text-stone-600">"No terms yet."</div>
+                    }.into_any(),
+                    Some(Ok(all)) => {
+                        let groups = by_taxonomy(&all);
+                        view!{
+                            <div class="grid gap-4">
+                                {groups.into_iter().map(|(name, terms)| {
+                                    let all = all.clone();
+                                    view!{
+                                        <div>
+                                            <div class="text-sm font-semibold mb-1">{name}</div>
+                                            <div class="flex flex-wrap gap-x-4 gap-y-1">
+                                                {terms.into_iter().map(|term| {
+                                                    let all = all.clone();
+                                                    let id = term.id;
+                                                    let name = term.name.clone();
+                                                    view!{
+                                                        <label class="flex items-center gap-2 text-sm">
+                                                            <Checkbox
+                                                                checked=Signal::derive(move || assigned.with(|a| a.contains(&id)))
+                                                                on_change=Callback::new(move |checked: bool| toggle(&all, &term, checked))
+                                                            />
+                                                            {name}
+                                                        </label>
+                                                    }
+                                                }).collect_view()}
+                                            </div>
+                                        </div>
+                                    }
+                                }).collect_view()}
+                            </div>
+                        }.into_any()
+                    }
+                }}
+            </Suspense>
+            {move || replace_action.value().get().and_then(|r| r.err()).map(|e| view!{<div class="text-sm text-red-600 mt-2">{ErrorTO::describe(&e)}</div>})}
+        </section>
+    }
+}
+
+/// Terms sorted by taxonomy, grouped under each taxonomy's name.
+fn by_taxonomy(terms: &[TermInfoTO]) -> Vec<(String, Vec<TermInfoTO>)> {
+    let mut groups: Vec<(String, Vec<TermInfoTO>)> = vec![];
+    for term in terms {
+        match groups.last_mut() {
+            Some((_, group)) if group[0].taxonomy_code == term.taxonomy_code => {
+                group.push(term.clone())
+            }
+            _ => groups.push((term.taxonomy_name.clone(), vec![term.clone()])),
+        }
+    }
+    groups
+}
@@ -1,8 +1,10 @@
 use crate::pages::admin::guard::AdminGuard;
+use crate::pages::admin::history::EditTabs;
 use crate::pages::admin::layout::AdminSidebar;
 use crate::pages::components::sidebar::SidebarProvider;
 use crate::pages::components::{Input, Button};
 use crate::pages::rest::post_type_api::{load_post_type_by_id, update_post_type, PostTypeTO};
+use crate::pages::rest::error::ErrorTO;
 use leptos::prelude::*;
 use leptos::{component, view, IntoView};
 use leptos_router::hooks::use_params_map;
@@ -42,6 +44,13 @@ pub fn AdminPostTypeEditPage() -> impl IntoView {
         async move { update_post_type(current).await }
     });
 
+    // Saved rows come back with a bumped version; refetch so the next save is based on it
+    Effect::new(move |_| {
+        if let Some(Ok(_)) = save_action.value().get() {
+            entity_res.refetch();
+        }
+    });
+
     view! {
         <AdminGuard>
             <SidebarProvider default_open=true>
@@ -50,23 +59,26 @@ pub fn AdminPostTypeEditPage() -> impl IntoView {
                     <main class="flex-1 min-h-screen">
                         <div class="container-page py-10 font-serif">
                             <h1 class="text-3xl font-bold mb-6">"Edit Post Type"</h1>
-                            <Suspense fallback=move || view!{<div>"Loading..."</div>}>
-                                {move || match entity_res.get() {
-                                    Some(Ok(_e)) => view!{
-                                        <div class="grid gap-3 max-w-xl">
-                                            <Input placeholder="Code" value=code on_input=Callback::new(move |ev| code.set(event_target_value(&ev))) />
-                                            <Input placeholder="Name" value=name on_input=Callback::new(move |ev| name.set(event_target_value(&ev))) />
-                                            <div>
-                                                <Button on_click=Callback::new({ let code=code.clone(); let name=name.clone(); let save=save_action.clone(); move |_| { let _= save.dispatch((code.get_untracked(), name.get_untracked())); } }) loading_signal=Signal::derive(move || save_action.pending().get())>
-                                                    "Save"
-                                                </Button>
+                            <EditTabs entity_type="post_types" entity_id=Signal::derive(id)>
+                                <Suspense fallback=move || view!{<div>"Loading..."</div>}>
+                                    {move || match entity_res.get() {
+                                        Some(Ok(_e)) => view!{
+                                            <div class="grid gap-3 max-w-xl">
+                                                <Input placeholder="Code" value=code on_input=Callback::new(move |ev| code.set(event_target_value(&ev))) />
+                                                <Input placeholder="Name" value=name on_input=Callback::new(move |ev| name.set(event_target_value(&ev))) />
+                                                <div>
+                                                    <Button on_click=Callback::new({ let code=code.clone(); let name=name.clone(); let save=save_action.clone(); move |_| { let _= save.dispatch((code.get_untracked(), name.get_untracked())); } }) loading_signal=Signal::derive(move || save_action.pending().get())>
+                                                        "Save"
+                                                    </Button>
+                                                </div>
+                                                {move || save_action.value().get().and_then(|r| r.err()).map(|e| view!{<div class="text-sm text-red-600">{ErrorTO::describe(&e)}</div>})}
                                             </div>
-                                        </div>
-                                    }.into_any(),
-                                    Some(Err(e)) => view!{<div class="text-red-600">{"Error: "}{e.to_string()}</div>}.into_any(),
-                                    None => view!{<div/>}.into_any(),
-                                }}
-                            </Suspense>
+                                        }.into_any(),
+                                        Some(Err(e)) => view!{<div class="text-red-600">{"Error: "}{e.to_string()}</div>}.into_any(),
+                                        None => view!{<div/>}.into_any(),
+                                    }}
+                                </Suspense>
+                            </EditTabs>
                         </div>
                     </main>
                 </div>
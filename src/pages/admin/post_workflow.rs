@@ -0,0 +1,124 @@
+use crate::pages::components::button::{ButtonSize, ButtonVariant};
+use crate::pages::components::{Button, Input};
+use crate::pages::rest::error::ErrorTO;
+use crate::pages::rest::post_api::{
+    load_post_workflow, transition_post, PostTO, PostTransitionTO, PostWorkflowTO,
+};
+use leptos::prelude::*;
+use leptos::{component, view, IntoView};
+
+/// Workflow of a post: its status, the actions the signed-in user may take
+/// from it, each with an optional comment, and the history of past changes.
+#[component]
+pub fn PostWorkflow(
+    #[prop(into)] post_id: Signal<i32>,
+    on_transitioned: Callback<PostTO, ()>,
+) -> impl IntoView {
+    let workflow_res = Resource::new(
+        move || post_id.get(),
+        |id| async move { load_post_workflow(id).await },
+    );
+    // The latest state, from loading or from the last action
+    let workflow = RwSignal::new(None::<PostWorkflowTO>);
+    let comment = RwSignal::new(String::new());
+
+    Effect::new(move |_| {
+        if let Some(Ok(w)) = workflow_res.get() {
+            workflow.set(Some(w));
+        }
+    });
+
+    let transition_action = Action::new(move |to: &String| {
+        let to = to.clone();
+        let id = post_id.get_untracked();
+        let comment = comment.get_untracked();
+        async move { transition_post(id, to, comment).await }
+    });
+    Effect::new(move |_| {
+        if let Some(Ok(w)) = transition_action.value().get() {
+            comment.set(String::new());
+            on_transitioned.run(w.post.clone());
+            workflow.set(Some(w));
+        }
+    });
+
+    view! {
+        <section class="mt-8 p-4 bg-white rounded-lg border border-stone-200">
+            <Suspense fallback=move || view!{<div>"Loading..."</div>}>
+                {move || match workflow_res.get() {
+                    Some(Err(e)) => view!{<div class="text-red-600">{"Error: "}{e.to_string()}</div>}.into_any(),
+                    None => view!{<div/>}.into_any(),
+                    Some(Ok(_)) => view!{
+                        <div class="flex items-center justify-between mb-3">
+                            <h2 class="text-xl font-semibold">"Workflow"</h2>
+                            <span class="text-sm">
+                                {move || workflow.with(|w| w.as_ref().map(|w| status_line(&w.post)))}
+                            </span>
+                        </div>
+                        {move || {
+                            let actions = workflow.with(|w| w.as_ref().map(|w| w.actions.clone()).unwrap_or_default());
+                            if actions.is_empty() {
+                                view!{<div class="text-sm text-stone-600">"No actions available to you."</div>}.into_any()
+                            } else {
+                                view!{
+                                    <div class="grid gap-2">
+                                        <Input placeholder="Comment (optional)" value=comment on_input=Callback::new(move |ev| comment.set(event_target_value(&ev))) />
+                                        <div class="flex flex-wrap gap-2">
+                                            {actions.into_iter().map(|a| {
+                                                let to = a.to.clone();
+                                                view!{
+                                                    <Button
+                                                        variant=ButtonVariant::Outline
+                                                        size=ButtonSize::Sm
+                                                        loading_signal=Signal::derive(move || transition_action.pending().get())
+                                                        on_click=Callback::new(move |_| { transition_action.dispatch(to.clone()); })
+                                                    >
+                                                        {a.action.clone()}
+                                                    </Button>
+                                                }
+                                            }).collect_view()}
+                                        </div>
+                                    </div>
+                                }.into_any()
+                            }
+                        }}
+                        {move || transition_action.value().get().and_then(|r| r.err()).map(|e| view!{<div class="text-sm text-red-600 mt-2">{ErrorTO::describe(&e)}</div>})}
+                        {move || {
+                            let history = workflow.with(|w| w.as_ref().map(|w| w.history.clone()).unwrap_or_default());
+                            (!history.is_empty()).then(|| view!{
+                                <ul class="mt-4 grid gap-1 text-sm">
+                                    {history.into_iter().map(history_entry).collect_view()}
+                                </ul>
+                            })
+                        }}
+                    }.into_any(),
+                }}
+            </Suspense>
+        </section>
+    }
+}
+
+fn status_line(post: &PostTO) -> String {
+    match post.published_at {
+        Some(at) => format!("{} - first published {}", post.status, format_timestamp(at)),
+        None => post.status.clone(),
+    }
+}
+
+fn history_entry(t: PostTransitionTO) -> impl IntoView {
+    view! {
+        <li class="border-t pt-1">
+            <span class="text-stone-500 whitespace-nowrap">{format_timestamp(t.created_at)}</span>
+            " "
+            <span class="font-semibold">{format!("{} → {}", t.from_status, t.to_status)}</span>
+            " by "
+            {t.username.unwrap_or_else(|| "system".to_string())}
+            {t.comment.map(|c| view!{<div class="text-stone-700">{c}</div>})}
+        </li>
+    }
+}
+
+fn format_timestamp(at: time::OffsetDateTime) -> String {
+    let fmt = time::macros::format_description!("[year]-[month]-[day] [hour]:[minute]:[second]");
+    at.format(&fmt).unwrap_or_default()
+}
@@ -0,0 +1,185 @@
+use crate::pages::admin::guard::AdminGuard;
+use crate::pages::admin::layout::AdminSidebar;
+use crate::pages::components::button::{ButtonIntent, ButtonSize};
+use crate::pages::components::sidebar::SidebarProvider;
+use crate::pages::components::{Button, Input};
+use crate::pages::rest::error::ErrorTO;
+use crate::pages::rest::slug_redirect_api::{
+    create_slug_redirect, delete_slug_redirect_by_id, load_slug_redirects, SlugRedirectTO,
+};
+use leptos::prelude::*;
+use leptos::{component, view, IntoView};
+
+/// What a redirect leads to, as listed: the target of one entered by hand,
+/// the entity of an old slug.
+fn destination(redirect: &SlugRedirectTO) -> String {
+    match (&redirect.target, redirect.entity_id) {
+        (Some(target), _) if redirect.prefix => format!("{}/…", target.trim_end_matches('/')),
+        (Some(target), _) => target.clone(),
+        (None, Some(id)) => format!("{} #{}", redirect.entity_type, id),
+        (None, None) => String::new(),
+    }
+}
+
+#[component]
+pub fn AdminRedirectsPage() -> impl IntoView {
+    let manual = RwSignal::new(true);
+    let search = RwSignal::new(String::new());
+    let items = Resource::new(
+        move || (manual.get(), search.get()),
+        |(manual, search)| async move {
+            load_slug_redirects(manual, Some(search), Some(0), Some(100)).await
+        },
+    );
+
+    let old_path = RwSignal::new(String::new());
+    let target = RwSignal::new(String::new());
+    let prefix = RwSignal::new(false);
+    let create_action = Action::new(move |_: &()| {
+        let (old_path, target, prefix) = (
+            old_path.get_untracked(),
+            target.get_untracked(),
+            prefix.get_untracked(),
+        );
+        async move { create_slug_redirect(old_path, target, prefix).await }
+    });
+    let delete_action = Action::new(move |id: &i32| {
+        let id = *id;
+        async move { delete_slug_redirect_by_id(id).await }
+    });
+
+    // Clear the form and refresh the list after a change
+    Effect::new(move |_| {
+        if let Some(Ok(_)) = create_action.value().get() {
+            old_path.set(String::new());
+            target.set(String::new());
+            prefix.set(false);
+            items.refetch();
+        }
+    });
+    Effect::new(move |_| {
+        if let Some(Ok(_)) = delete_action.value().get() {
+            items.refetch();
+        }
+    });
+
+    view! {
+        <AdminGuard>
+            <SidebarProvider default_open=true>
+                <div class="flex gap-0">
+                    <AdminSidebar />
+                    <main class="flex-1 min-h-screen">
+                        <div class="container-page py-10 font-serif">
+                            <div class="flex items-center justify-between mb-6 gap-3">
+                                <h1 class="text-3xl font-bold">"Redirects"</h1>
+                                <div class="flex gap-2">
+                                    <Input
+                                        placeholder="Search paths..."
+                                        value=search
+                                        on_input=Callback::new(move |ev: leptos::ev::Event| search.set(event_target_value(&ev)))
+                                    />
+                                    <select
+                                        class="rounded border border-stone-300 px-3 py-2 text-sm"
+                                        on:change=move |ev| manual.set(event_target_value(&ev) == "manual")
+                                        prop:value=move || if manual.get() { "manual" } else { "slugs" }
+                                    >
+                                        <option value="manual">"Entered by hand"</option>
+                                        <option value="slugs">"Old slugs"</option>
+                                    </select>
+                                </div>
+                            </div>
+
+                            <Show when=move || manual.get()>
+                                <div class="mb-6 p-4 bg-white border border-stone-200 rounded-lg grid grid-cols-1 md:grid-cols-2 gap-3">
+                                    <label class="grid gap-1">
+                                        <span class="text-sm font-semibold">"From path"</span>
+                                        <Input
+                                            placeholder="/blog/2019/hello"
+                                            value=old_path
+                                            on_input=Callback::new(move |ev: leptos::ev::Event| old_path.set(event_target_value(&ev)))
+                                        />
+                                    </label>
+                                    <label class="grid gap-1">
+                                        <span class="text-sm font-semibold">"To"</span>
+                                        <Input
+                                            placeholder="/articles/hello or https://..."
+                                            value=target
+                                            on_input=Callback::new(move |ev: leptos::ev::Event| target.set(event_target_value(&ev)))
+                                        />
+                                    </label>
+                                    <label class="md:col-span-2 flex items-center gap-2 text-sm">
+                                        <input
+                                            type="checkbox"
+                                            prop:checked=move || prefix.get()
+                                            on:change=move |ev| prefix.set(event_target_checked(&ev))
+                                        />
+                                        "Also redirect every path below it, keeping the rest of the path"
+                                    </label>
+                                    <div class="md:col-span-2 flex items-center gap-3">
+                                        <Button
+                                            size=ButtonSize::Sm
+                                            loading_signal=Signal::derive(move || create_action.pending().get())
+                                            on_click=Callback::new(move |_| { create_action.dispatch(()); })
+                                        >
+                                            "Add redirect"
+                                        </Button>
+                                        {move || create_action.value().get().and_then(|r| r.err()).map(|e| view!{
+                                            <span class="text-sm text-red-600">{ErrorTO::describe(&e)}</span>
+                                        })}
+                                    </div>
+                                </div>
+                            </Show>
+
+                            <Suspense fallback=move || view!{<div>"Loading..."</div>}>
+                                {move || match items.get() {
+                                    Some(Ok(items)) if items.is_empty() => view!{
+                                        <div class="text-stone-600">"No redirects."</div>
+                                    }.into_any(),
+                                    Some(Ok(items)) => view!{
+                                        <div class="overflow-x-auto bg-white border border-stone-200 rounded-lg">
+                                            <table class="min-w-full text-sm">
+                                                <thead class="bg-stone-100">
+                                                    <tr>
+                                                        <th class="text-left px-4 py-2">"From"</th>
+                                                        <th class="text-left px-4 py-2">"To"</th>
+                                                        <th class="text-right px-4 py-2">"Actions"</th>
+                                                    </tr>
+                                                </thead>
+                                                <tbody>
+                                                    {items.into_iter().map(|it: SlugRedirectTO| {
+                                                        let id = it.id;
+                                                        let from = if it.prefix {
+                                                            format!("{}/…", it.old_slug)
+                                                        } else {
+                                                            it.old_slug.clone()
+                                                        };
+                                                        view!{
+                                                            <tr class="border-t">
+                                                                <td class="px-4 py-2 font-mono">{from}</td>
+                                                                <td class="px-4 py-2 font-mono">{destination(&it)}</td>
+                                                                <td class="px-4 py-2 flex justify-end">
+                                                                    <Button
+                                                                        intent=ButtonIntent::Destructive
+                                                                        on_click=Callback::new(move |_| { delete_action.dispatch(id); })
+                                                                    >
+                                                                        "Delete"
+                                                                    </Button>
+                                                                </td>
+                                                            </tr>
+                                                        }
+                                                    }).collect_view()}
+                                                </tbody>
+                                            </table>
+                                        </div>
+                                    }.into_any(),
+                                    Some(Err(e)) => view!{<div class="text-red-600">{"Error: "}{e.to_string()}</div>}.into_any(),
+                                    None => view!{<div/>}.into_any()
+                                }}
+                            </Suspense>
+                        </div>
+                    </main>
+                </div>
+            </SidebarProvider>
+        </AdminGuard>
+    }
+}
@@ -1,9 +1,12 @@
 use crate::pages::admin::guard::AdminGuard;
+use crate::pages::admin::custom_fields::CustomFields;
+use crate::pages::admin::history::EditTabs;
 use crate::pages::admin::layout::AdminSidebar;
 use crate::pages::components::sidebar::SidebarProvider;
 use crate::pages::components::{Input, Button, Select};
 use crate::pages::rest::term_api::{load_term_by_id, update_term, TermTO};
 use crate::pages::rest::post_taxonomy_api::{load_post_taxonomy_infos, PostTaxonomyInfoTO};
+use crate::pages::rest::error::ErrorTO;
 use leptos::prelude::*;
 use leptos::{component, view, IntoView};
 use leptos_router::hooks::use_params_map;
@@ -52,6 +55,13 @@ pub fn AdminTermEditPage() -> impl IntoView {
         async move { update_term(current).await }
     });
 
+    // Saved rows come back with a bumped version; refetch so the next save is based on it
+    Effect::new(move |_| {
+        if let Some(Ok(_)) = save_action.value().get() {
+            entity_res.refetch();
+        }
+    });
+
     view! {
         <AdminGuard>
             <SidebarProvider default_open=true>
@@ -60,31 +70,35 @@ pub fn AdminTermEditPage() -> impl IntoView {
                     <main class="flex-1 min-h-screen">
                         <div class="container-page py-10 font-serif">
                             <h1 class="text-3xl font-bold mb-6">"Edit Term"</h1>
-                            <Suspense fallback=move || view!{<div>"Loading..."</div>}>
-                                {move || match entity_res.get() {
-                                    Some(Ok(_e)) => view!{
-                                        <div class="grid gap-3 max-w-xl">
-                                            <Select value=Signal::derive({ let taxonomy_id=taxonomy_id.clone(); move || taxonomy_id.get().to_string() }) on_change=Callback::new(move |ev| taxonomy_id.set(event_target_value(&ev).parse().unwrap_or_default()))>
-                                                <Suspense fallback=move || view!{<option value="0">"Loading..."</option>}>
-                                                    {move || match taxonomies_res.get() {
-                                                        Some(Ok(items)) => items.into_iter().map(|it: PostTaxonomyInfoTO| view!{ <option value={it.id.to_string()}>{format!("{} ({})", it.name, it.code)}</option> }).collect_view().into_any(),
-                                                        _ => view!{<></>}.into_any(),
-                                                    }}
-                                                </Suspense>
-                                            </Select>
-                                            <Input placeholder="Slug" value=slug on_input=Callback::new(move |ev| slug.set(event_target_value(&ev))) />
-                                            <Input placeholder="Name" value=name on_input=Callback::new(move |ev| name.set(event_target_value(&ev))) />
-                                            <div>
-                                                <Button on_click=Callback::new({ let taxonomy_id=taxonomy_id.clone(); let slug=slug.clone(); let name=name.clone(); let save=save_action.clone(); move |_| { let _= save.dispatch((taxonomy_id.get_untracked(), slug.get_untracked(), name.get_untracked())); } }) loading_signal=Signal::derive(move || save_action.pending().get())>
-                                                    "Save"
-                                                </Button>
+                            <EditTabs entity_type="terms" entity_id=Signal::derive(id)>
+                                <Suspense fallback=move || view!{<div>"Loading..."</div>}>
+                                    {move || match entity_res.get() {
+                                        Some(Ok(_e)) => view!{
+                                            <div class="grid gap-3 max-w-xl">
+                                                <Select value=Signal::derive({ let taxonomy_id=taxonomy_id.clone(); move || taxonomy_id.get().to_string() }) on_change=Callback::new(move |ev| taxonomy_id.set(event_target_value(&ev).parse().unwrap_or_default()))>
+                                                    <Suspense fallback=move || view!{<option value="0">"Loading..."</option>}>
+                                                        {move || match taxonomies_res.get() {
+                                                            Some(Ok(items)) => items.into_iter().map(|it: PostTaxonomyInfoTO| view!{ <option value={it.id.to_string()}>{format!("{} ({})", it.name, it.code)}</option> }).collect_view().into_any(),
+                                                            _ => view!{<></>}.into_any(),
+                                                        }}
+                                                    </Suspense>
+                                                </Select>
+                                                <Input placeholder="Slug" value=slug on_input=Callback::new(move |ev| slug.set(event_target_value(&ev))) />
+                                                <Input placeholder="Name" value=name on_input=Callback::new(move |ev| name.set(event_target_value(&ev))) />
+                                                <div>
+                                                    <Button on_click=Callback::new({ let taxonomy_id=taxonomy_id.clone(); let slug=slug.clone(); let name=name.clone(); let save=save_action.clone(); move |_| { let _= save.dispatch((taxonomy_id.get_untracked(), slug.get_untracked(), name.get_untracked())); } }) loading_signal=Signal::derive(move || save_action.pending().get())>
+                                                        "Save"
+                                                    </Button>
+                                                </div>
+                                                {move || save_action.value().get().and_then(|r| r.err()).map(|e| view!{<div class="text-sm text-red-600">{ErrorTO::describe(&e)}</div>})}
                                             </div>
-                                        </div>
-                                    }.into_any(),
-                                    Some(Err(e)) => view!{<div class="text-red-600">{"Error: "}{e.to_string()}</div>}.into_any(),
-                                    None => view!{<div/>}.into_any(),
-                                }}
-                            </Suspense>
+                                        }.into_any(),
+                                        Some(Err(e)) => view!{<div class="text-red-600">{"Error: "}{e.to_string()}</div>}.into_any(),
+                                        None => view!{<div/>}.into_any(),
+                                    }}
+                                </Suspense>
+                                <CustomFields entity_type="terms" entity_id=Signal::derive(id) />
+                            </EditTabs>
                         </div>
                     </main>
                 </div>
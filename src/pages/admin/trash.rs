@@ -0,0 +1,114 @@
+use crate::pages::admin::guard::AdminGuard;
+use crate::pages::admin::layout::AdminSidebar;
+use crate::pages::components::button::{ButtonIntent, ButtonVariant};
+use crate::pages::components::sidebar::SidebarProvider;
+use crate::pages::components::Button;
+use crate::pages::rest::trash_api::{
+    load_trash, purge_from_trash, restore_from_trash, TrashItemTO, TRASH_ENTITIES,
+};
+use leptos::prelude::*;
+use leptos::{component, view, IntoView};
+
+#[component]
+pub fn AdminTrashPage() -> impl IntoView {
+    let entity = RwSignal::new(TRASH_ENTITIES[0].0.to_string());
+    let items = Resource::new(
+        move || entity.get(),
+        |entity| async move { load_trash(entity, Some(0), Some(100)).await },
+    );
+
+    let restore_action = Action::new(move |id: &i32| {
+        let id = *id;
+        async move { restore_from_trash(entity.get_untracked(), id).await }
+    });
+    let purge_action = Action::new(move |id: &i32| {
+        let id = *id;
+        async move { purge_from_trash(entity.get_untracked(), id).await }
+    });
+
+    // Refresh list after restore/purge
+    Effect::new(move |_| {
+        if let Some(Ok(_)) = restore_action.value().get() {
+            items.refetch();
+        }
+    });
+    Effect::new(move |_| {
+        if let Some(Ok(_)) = purge_action.value().get() {
+            items.refetch();
+        }
+    });
+
+    view! {
+        <AdminGuard>
+            <SidebarProvider default_open=true>
+                <div class="flex gap-0">
+                    <AdminSidebar />
+                    <main class="flex-1 min-h-screen">
+                        <div class="container-page py-10 font-serif">
+                            <div class="flex items-center justify-between mb-6">
+                                <h1 class="text-3xl font-bold">"Trash"</h1>
+                                <select
+                                    class="rounded border border-stone-300 px-3 py-2 text-sm"
+                                    on:change=move |ev| entity.set(event_target_value(&ev))
+                                    prop:value=move || entity.get()
+                                >
+                                    {TRASH_ENTITIES.iter().map(|(key, label)| view! {
+                                        <option value=*key>{*label}</option>
+                                    }).collect_view()}
+                                </select>
+                            </div>
+
+                            <Suspense fallback=move || view!{<div>"Loading..."</div>}>
+                                {move || match items.get() {
+                                    Some(Ok(items)) if items.is_empty() => view!{
+                                        <div class="text-stone-600">"The trash is empty."</div>
+                                    }.into_any(),
+                                    Some(Ok(items)) => view!{
+                                        <div class="overflow-x-auto bg-white border border-stone-200 rounded-lg">
+                                            <table class="min-w-full text-sm">
+                                                <thead class="bg-stone-100">
+                                                    <tr>
+                                                        <th class="text-left px-4 py-2">"ID"</th>
+                                                        <th class="text-left px-4 py-2">"Name"</th>
+                                                        <th class="text-right px-4 py-2">"Actions"</th>
+                                                    </tr>
+                                                </thead>
+                                                <tbody>
+                                                    {items.into_iter().map(|it: TrashItemTO| {
+                                                        let id = it.id;
+                                                        view!{
+                                                            <tr class="border-t">
+                                                                <td class="px-4 py-2">{it.id}</td>
+                                                                <td class="px-4 py-2">{it.label}</td>
+                                                                <td class="px-4 py-2 flex justify-end gap-2">
+                                                                    <Button
+                                                                        variant=ButtonVariant::Outline
+                                                                        on_click=Callback::new(move |_| { restore_action.dispatch(id); })
+                                                                    >
+                                                                        "Restore"
+                                                                    </Button>
+                                                                    <Button
+                                                                        intent=ButtonIntent::Destructive
+                                                                        on_click=Callback::new(move |_| { purge_action.dispatch(id); })
+                                                                    >
+                                                                        "Delete forever"
+                                                                    </Button>
+                                                                </td>
+                                                            </tr>
+                                                        }
+                                                    }).collect_view()}
+                                                </tbody>
+                                            </table>
+                                        </div>
+                                    }.into_any(),
+                                    Some(Err(e)) => view!{<div class="text-red-600">{"Error: "}{e.to_string()}</div>}.into_any(),
+                                    None => view!{<div/>}.into_any()
+                                }}
+                            </Suspense>
+                        </div>
+                    </main>
+                </div>
+            </SidebarProvider>
+        </AdminGuard>
+    }
+}
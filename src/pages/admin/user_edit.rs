@@ -1,8 +1,11 @@
 use crate::pages::admin::guard::AdminGuard;
+use crate::pages::admin::custom_fields::CustomFields;
+use crate::pages::admin::history::EditTabs;
 use crate::pages::admin::layout::AdminSidebar;
 use crate::pages::components::sidebar::SidebarProvider;
 use crate::pages::components::{Input, Button, Select};
 use crate::pages::rest::user_api::{load_user_by_id, update_user, UserTO};
+use crate::pages::rest::error::ErrorTO;
 use leptos::prelude::*;
 use leptos::{component, view, IntoView};
 use leptos_router::hooks::use_params_map;
@@ -49,6 +52,13 @@ pub fn AdminUserEditPage() -> impl IntoView {
         async move { update_user(current).await }
     });
 
+    // Saved rows come back with a bumped version; refetch so the next save is based on it
+    Effect::new(move |_| {
+        if let Some(Ok(_)) = save_action.value().get() {
+            entity_res.refetch();
+        }
+    });
+
     view! {
         <AdminGuard>
             <SidebarProvider default_open=true>
@@ -57,28 +67,33 @@ pub fn AdminUserEditPage() -> impl IntoView {
                     <main class="flex-1 min-h-screen">
                         <div class="container-page py-10 font-serif">
                             <h1 class="text-3xl font-bold mb-6">"Edit User"</h1>
-                            <Suspense fallback=move || view!{<div>"Loading..."</div>}>
-                                {move || match entity_res.get() {
-                                    Some(Ok(_e)) => view!{
-                                        <div class="grid gap-3 max-w-xl">
-                                            <Input placeholder="Username" value=username on_input=Callback::new(move |ev| username.set(event_target_value(&ev))) />
-                                            <Input placeholder="Email" value=email on_input=Callback::new(move |ev| email.set(event_target_value(&ev))) />
-                                            <Input r#type="password" placeholder="New Password (optional)" value=password on_input=Callback::new(move |ev| password.set(event_target_value(&ev))) />
-                                            <Select value=role on_change=Callback::new(move |ev| role.set(event_target_value(&ev)))>
-                                                <option value="USER">USER</option>
-                                                <option value="ADMIN">ADMIN</option>
-                                            </Select>
-                                            <div>
-                                                <Button on_click=Callback::new({ let username=username.clone(); let email=email.clone(); let password=password.clone(); let role=role.clone(); let save=save_action.clone(); move |_| { let _= save.dispatch((username.get_untracked(), email.get_untracked(), password.get_untracked(), role.get_untracked())); } }) loading_signal=Signal::derive(move || save_action.pending().get())>
-                                                    "Save"
-                                                </Button>
+                            <EditTabs entity_type="users" entity_id=Signal::derive(id)>
+                                <Suspense fallback=move || view!{<div>"Loading..."</div>}>
+                                    {move || match entity_res.get() {
+                                        Some(Ok(_e)) => view!{
+                                            <div class="grid gap-3 max-w-xl">
+                                                <Input placeholder="Username" value=username on_input=Callback::new(move |ev| username.set(event_target_value(&ev))) />
+                                                <Input placeholder="Email" value=email on_input=Callback::new(move |ev| email.set(event_target_value(&ev))) />
+                                                <Input r#type="password" placeholder="New Password (optional)" value=password on_input=Callback::new(move |ev| password.set(event_target_value(&ev))) />
+                                                <Select value=role on_change=Callback::new(move |ev| role.set(event_target_value(&ev)))>
+                                                    <option value="USER">USER</option>
+                                                    <option value="ADMIN">ADMIN</option>
+                                                    <option value="EDITOR">EDITOR</option>
+                                                </Select>
+                                                <div>
+                                                    <Button on_click=Callback::new({ let username=username.clone(); let email=email.clone(); let password=password.clone(); let role=role.clone(); let save=save_action.clone(); move |_| { let _= save.dispatch((username.get_untracked(), email.get_untracked(), password.get_untracked(), role.get_untracked())); } }) loading_signal=Signal::derive(move || save_action.pending().get())>
+                                                        "Save"
+                                                    </Button>
+                                                </div>
+                                                {move || save_action.value().get().and_then(|r| r.err()).map(|e| view!{<div class="text-sm text-red-600">{ErrorTO::describe(&e)}</div>})}
                                             </div>
-                                        </div>
-                                    }.into_any(),
-                                    Some(Err(e)) => view!{<div class="text-red-600">{"Error: "}{e.to_string()}</div>}.into_any(),
-                                    None => view!{<div/>}.into_any(),
-                                }}
-                            </Suspense>
+                                        }.into_any(),
+                                        Some(Err(e)) => view!{<div class="text-red-600">{"Error: "}{e.to_string()}</div>}.into_any(),
+                                        None => view!{<div/>}.into_any(),
+                                    }}
+                                </Suspense>
+                                <CustomFields entity_type="users" entity_id=Signal::derive(id) />
+                            </EditTabs>
                         </div>
                     </main>
                 </div>
@@ -2,13 +2,17 @@ use crate::pages::about::AboutMePage;
 use crate::pages::admin::home::AdminHomePage;
 use crate::pages::admin::post_edit::AdminPostEditPage;
 use crate::pages::admin::post_dashboard::AdminPostsDashboardPage;
+use crate::pages::article::ArticlePage;
 use crate::pages::articles::ArticlesPage;
+use crate::pages::collection::CollectionPage;
 use crate::pages::components::{Footer, Navigation};
 use crate::pages::home::HomePage;
 use crate::pages::login::LoginPage;
 use crate::pages::newsletter::NewsletterPage;
 use crate::pages::not_found::NotFoundPage;
 use crate::pages::register::RegisterPage;
+use crate::pages::search::SearchPage;
+use crate::pages::term::TermPage;
 use crate::pages::rest::auth_api::{current_user, UserTO};
 use leptos::prelude::*;
 use leptos::{component, view, IntoView};
@@ -23,8 +27,10 @@ use crate::pages::admin::post_taxonomy_dashboard::AdminPostTaxonomiesDashboardPa
 use crate::pages::admin::post_taxonomy_edit::AdminPostTaxonomyEditPage;
 use crate::pages::admin::post_type_dashboard::AdminPostTypesDashboardPage;
 use crate::pages::admin::post_type_edit::AdminPostTypeEditPage;
+use crate::pages::admin::redirects::AdminRedirectsPage;
 use crate::pages::admin::term_dashboard::AdminTermsDashboardPage;
 use crate::pages::admin::term_edit::AdminTermEditPage;
+use crate::pages::admin::trash::AdminTrashPage;
 use crate::pages::admin::user_dashboard::AdminUsersDashboardPage;
 use crate::pages::admin::user_edit::AdminUserEditPage;
 
@@ -74,6 +80,12 @@ pub fn App() -> impl IntoView {
                     <Route path=path!("/register") view=RegisterPage/>
                     <Route path=path!("/about") view=AboutMePage/>
                     <Route path=path!("/articles") view=ArticlesPage ssr=SsrMode::OutOfOrder/>
+                    // Rendered before responding, so that an unknown slug is a 404
+                    // the server can still turn into a redirect to where it moved
+                    <Route path=path!("/articles/:slug") view=ArticlePage ssr=SsrMode::Async/>
+                    <Route path=path!("/collections/:slug") view=CollectionPage ssr=SsrMode::Async/>
+                    <Route path=path!("/terms/:code/:slug") view=TermPage ssr=SsrMode::Async/>
+                    <Route path=path!("/search") view=SearchPage ssr=SsrMode::OutOfOrder/>
                     <Route path=path!("/newsletter") view=NewsletterPage/>
                     <Route path=path!("/admin") view=AdminHomePage/>
                     <Route path=path!("/admin/home") view=AdminHomePage/>
@@ -89,6 +101,8 @@ pub fn App() -> impl IntoView {
                     <Route path=path!("/admin/terms/:id") view=AdminTermEditPage/>
                     <Route path=path!("/admin/users") view=AdminUsersDashboardPage/>
                     <Route path=path!("/admin/users/:id") view=AdminUserEditPage/>
+                    <Route path=path!("/admin/redirects") view=AdminRedirectsPage/>
+                    <Route path=path!("/admin/trash") view=AdminTrashPage/>
                     <Route path=WildcardSegment("any") view=NotFoundPage/>
                 </Routes>
             </main>
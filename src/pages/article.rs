@@ -0,0 +1,32 @@
+use crate::pages::components::MarkdownViewer;
+use crate::pages::not_found::NotFoundPage;
+use crate::pages::rest::post_api::load_article;
+use leptos::prelude::*;
+use leptos_router::hooks::use_params_map;
+
+/// A published post, at `/articles/:slug`.
+#[component]
+pub fn ArticlePage() -> impl IntoView {
+    let params = use_params_map();
+    let slug = move || params.with(|p| p.get("slug").unwrap_or_default());
+
+    let article_resource = Resource::new(slug, |slug| async move { load_article(slug).await });
+
+    view! {
+        <div class="container-page py-10">
+            <Suspense fallback=move || view! {<div class="text-center py-8">Loading...</div>}>
+                {move || match article_resource.get() {
+                    Some(Ok(post)) => view! {
+                        <article>
+                            <MarkdownViewer content=post.content title=post.title/>
+                        </article>
+                    }.into_any(),
+                    Some(Err(_)) => view! { <NotFoundPage/> }.into_any(),
+                    None => view! {
+                        <div class="text-center py-8">Loading...</div>
+                    }.into_any()
+                }}
+            </Suspense>
+        </div>
+    }
+}
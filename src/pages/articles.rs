@@ -1,9 +1,9 @@
 use crate::pages::components::button::ButtonVariant;
 use crate::pages::components::Button;
-use crate::pages::components::Paginator;
+use crate::pages::components::CursorPaginator;
 use crate::pages::rest::auth_api::UserTO;
 use crate::pages::rest::post_api::PostInfoTO;
-use crate::pages::rest::post_api::{count_posts, load_posts};
+use crate::pages::rest::post_api::load_posts;
 use leptos::control_flow::Show;
 use leptos::prelude::*;
 use leptos_router::components::A;
@@ -13,13 +13,7 @@ use time::format_description;
 #[component]
 pub fn ArticlesPage() -> impl IntoView {
     let query = use_query_map();
-    let first_result = move || {
-        query.with(|q| {
-            q.get("first_result")
-                .and_then(|p| p.parse::<i64>().ok())
-                .unwrap_or(0)
-        })
-    };
+    let cursor = move |name: &str| query.with(|q| q.get(name).filter(|c| !c.is_empty()));
     let max_results = move || {
         query.with(|q| {
             q.get("max_results")
@@ -29,13 +23,8 @@ pub fn ArticlesPage() -> impl IntoView {
     };
 
     let posts_resource = Resource::new(
-        move || (first_result(), max_results()),
-        |(first_result, max_results)| async move { load_posts(first_result, max_results).await },
-    );
-
-    let total_posts_resource = Resource::new(
-        move || (first_result(), max_results()),
-        |(_, _)| async { count_posts().await },
+        move || (cursor("after"), cursor("before"), max_results()),
+        |(after, before, max_results)| async move { load_posts(after, before, max_results).await },
     );
 
     view! {
@@ -44,14 +33,19 @@ pub fn ArticlesPage() -> impl IntoView {
 
             <Suspense fallback=move || view! {<div class="text-center py-8">Loading posts...</div>}>
                 {move || match posts_resource.get() {
-                    Some(Ok(posts)) => view! {
+                    Some(Ok(page)) => view! {
                         <div class="space-y-6">
-                            {posts.into_iter()
+                            {page.items.into_iter()
                                 .map(|post| view! { <Article post=post/> })
                                 .collect_view()
                             }
 
                         </div>
+                        <CursorPaginator
+                            prev_cursor=page.prev_cursor
+                            next_cursor=page.next_cursor
+                            max_results=max_results() as i64
+                        />
                     }.into_any(),
                     Some(Err(e)) => view! {
                         <div class="text-red-600">
@@ -63,23 +57,6 @@ pub fn ArticlesPage() -> impl IntoView {
                     }.into_any()
                 }}
             </Suspense>
-
-            <div>
-            <Suspense fallback=move || view! {<div>Loading total...</div>}>
-                {move || match total_posts_resource.get() {
-                        Some(Ok(total_posts)) => view! {
-                        <Paginator
-                            first_result=first_result()
-                            total_entities=total_posts as i64
-                            max_results=max_results() as i64
-                            max_visible_pages=7
-                        />
-                    }.into_any(),
-                        Some(Err(_e)) => view!{<div>Error loading total</div>}.into_any(),
-                        None => view!{<div>Loading...</div>}.into_any()}
-                }
-            </Suspense>
-            </div>
         </div>
     }
 }
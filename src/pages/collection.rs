@@ -0,0 +1,34 @@
+use crate::pages::not_found::NotFoundPage;
+use crate::pages::rest::post_collection_api::load_post_collection_by_slug;
+use leptos::prelude::*;
+use leptos_router::hooks::use_params_map;
+
+/// A collection that is not private, at `/collections/:slug`.
+#[component]
+pub fn CollectionPage() -> impl IntoView {
+    let params = use_params_map();
+    let slug = move || params.with(|p| p.get("slug").unwrap_or_default());
+
+    let collection_resource = Resource::new(slug, |slug| async move {
+        load_post_collection_by_slug(slug).await
+    });
+
+    view! {
+        <div class="container-page py-10">
+            <Suspense fallback=move || view! {<div class="text-center py-8">Loading...</div>}>
+                {move || match collection_resource.get() {
+                    Some(Ok(collection)) => view! {
+                        <h1 class="text-3xl font-serif font-semibold mb-4">{collection.title}</h1>
+                        <p class="text-stone-700 leading-7">
+                            {collection.description.unwrap_or_default()}
+                        </p>
+                    }.into_any(),
+                    Some(Err(_)) => view! { <NotFoundPage/> }.into_any(),
+                    None => view! {
+                        <div class="text-center py-8">Loading...</div>
+                    }.into_any()
+                }}
+            </Suspense>
+        </div>
+    }
+}
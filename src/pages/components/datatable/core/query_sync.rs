@@ -129,6 +129,7 @@ pub fn build_p_filters<T: Send + Sync + 'static>(state: &Arc<TableState<T>>) ->
             FilterOperator::LessThanOrEqual => "<=",
             FilterOperator::Like => "~",
             FilterOperator::NotLike => "!~",
+            FilterOperator::Similar => "%",
             FilterOperator::In => "[]",
             FilterOperator::NotIn => "![]",
             FilterOperator::IsNull => "=null",
@@ -50,7 +50,7 @@ pub use pagination::{
     Pagination, PaginationContent, PaginationEllipsis, PaginationItem, PaginationLink,
     PaginationNext, PaginationPrevious,
 };
-pub use paginator::Paginator;
+pub use paginator::{CursorPaginator, Paginator};
 pub use popover::{Popover, PopoverContent, PopoverTrigger};
 pub use select::Select;
 pub use separator::Separator;
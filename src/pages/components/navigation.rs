@@ -34,6 +34,7 @@ pub fn Navigation() -> impl IntoView {
                         <div class="flex flex-wrap items-center gap-4 sm:gap-6 text-xl [&_a]:inline-flex [&_a]:items-center [&_a]:font-bold [&_a]:underline [&_a]:decoration-current [&_a]:underline-offset-4 [&_a]:decoration-1 [&_a:hover]:no-underline [&_a:hover]:text-black [&_a]:transition-colors [&_a[aria-current=page]]:decoration-4">
                             <A href="/" exact=true attr:class="text-rose-600">HOME</A>
                             <A href="/articles" attr:class="text-emerald-600">ARTICLES</A>
+                            <A href="/search" attr:class="text-sky-600">SEARCH</A>
                             <A href="/about" attr:class="text-indigo-600">ABOUT ME</A>
                             <A href="/newsletter" attr:class="text-amber-600">NEWSLETTER</A>
                         </div>
@@ -161,3 +161,37 @@ pub fn Paginator(
         </Pagination>
     }
 }
+
+/// Previous/next paginator for keyset (cursor) paginated listings, where the
+/// server hands out opaque cursors instead of page offsets.
+#[component]
+pub fn CursorPaginator(
+    prev_cursor: Option<String>,
+    next_cursor: Option<String>,
+    #[prop(optional, default = 5)] max_results: i64,
+    #[prop(into, optional)] class: Option<String>,
+) -> impl IntoView {
+    let class = class.unwrap_or_default();
+    view! {
+        <Pagination class=class>
+            <PaginationContent>
+                <PaginationItem>
+                    {
+                        match prev_cursor {
+                            Some(c) => view! { <PaginationPrevious href=format!("?before={}&max_results={}", c, max_results) /> }.into_any(),
+                            None => view! { <PaginationPrevious /> }.into_any(),
+                        }
+                    }
+                </PaginationItem>
+                <PaginationItem>
+                    {
+                        match next_cursor {
+                            Some(c) => view! { <PaginationNext href=format!("?after={}&max_results={}", c, max_results) /> }.into_any(),
+                            None => view! { <PaginationNext /> }.into_any(),
+                        }
+                    }
+                </PaginationItem>
+            </PaginationContent>
+        </Pagination>
+    }
+}
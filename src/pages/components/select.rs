@@ -28,6 +28,13 @@ pub struct SelectOption {
     pub disabled: bool,
 }
 
+/// Loads the options matching the text typed in the autocomplete search box.
+pub type SearchOptions = Arc<
+    dyn Fn(String) -> Pin<Box<dyn Future<Output = Result<Vec<SelectOption>, String>> + Send>>
+        + Send
+        + Sync,
+>;
+
 impl SelectOption {
     pub fn new(value: impl Into<String>, label: impl Into<String>) -> Self {
         Self {
@@ -87,6 +94,10 @@ pub fn Select(
                 + Sync,
         >,
     >,
+    // Autocomplete: when set, a search box above the select reloads the options
+    // for what was typed; takes precedence over `load_options`
+    #[prop(optional)] search_options: Option<SearchOptions>,
+    #[prop(optional)] search_placeholder: Option<String>,
     #[prop(optional)] size: SelectSize,
     children: Children,
 ) -> impl IntoView {
@@ -115,21 +126,27 @@ pub fn Select(
 
     // Internal lazy-load trigger if a loader is provided
     // Changed: use a tick counter so options are reloaded on every interaction
-    let has_loader = load_options.is_some();
+    let has_search = search_options.is_some();
+    let has_loader = load_options.is_some() || has_search;
     let tick = RwSignal::new(0u32);
+    let search_text = RwSignal::new(String::new());
 
     let options_res = Resource::new(
         {
             let tick = tick.clone();
-            move || tick.get()
+            move || (tick.get(), search_text.get())
         },
         {
             let load_options = load_options.clone();
-            move |t| {
+            let search_options = search_options.clone();
+            move |(t, text)| {
                 let load_options = load_options.clone();
+                let search_options = search_options.clone();
                 async move {
                     if t > 0 {
-                        if let Some(loader) = load_options {
+                        if let Some(search) = search_options {
+                            search(text).await
+                        } else if let Some(loader) = load_options {
                             loader().await
                         } else {
                             Ok(vec![])
@@ -148,7 +165,7 @@ pub fn Select(
     let children_any = children().into_any();
 
     if has_loader {
-        view! {
+        let select_view = view! {
             <select
                 class=classes
                 id=id
@@ -209,7 +226,32 @@ pub fn Select(
                     }
                 }}
             </select>
-        }.into_any()
+        };
+        if has_search {
+            let search_placeholder =
+                search_placeholder.unwrap_or_else(|| "Type to search...".to_string());
+            view! {
+                <div class="grid gap-1">
+                    <input
+                        type="search"
+                        class="w-full rounded-md border border-input bg-background px-3 h-8 text-sm shadow-sm focus-visible:outline-none focus-visible:ring-1"
+                        placeholder=search_placeholder
+                        disabled=disabled
+                        prop:value=search_text
+                        on:input=move |ev| {
+                            search_text.set(event_target_value(&ev));
+                            if tick.get_untracked() == 0 {
+                                tick.set(1);
+                            }
+                        }
+                    />
+                    {select_view}
+                </div>
+            }
+            .into_any()
+        } else {
+            select_view.into_any()
+        }
     } else {
         view! {
             <select
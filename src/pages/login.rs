@@ -33,7 +33,7 @@ pub fn LoginPage() -> impl IntoView {
                         UserRole::ADMIN => {
                             let _ = navigate("/admin/home", Default::default());
                         }
-                        UserRole::USER => {
+                        UserRole::USER | UserRole::EDITOR => {
                             let _ = navigate("/home", Default::default());
                         }
                     }
@@ -1,11 +1,15 @@
 mod about;
 pub mod app;
+mod article;
 mod articles;
+mod collection;
 pub mod components;
 pub mod home;
 mod newsletter;
 pub mod not_found;
 mod rest;
+mod search;
+mod term;
 
 pub mod admin;
 pub mod forbidden;
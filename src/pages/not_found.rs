@@ -1,5 +1,7 @@
+use crate::pages::rest::slug_redirect_api::resolve_redirect;
 use leptos::prelude::*;
 use leptos::{component, IntoView};
+use leptos_router::hooks::use_location;
 
 /// 404 - Not Found
 #[component]
@@ -18,6 +20,18 @@ pub fn NotFoundPage() -> impl IntoView {
         resp.set_status(actix_web::http::StatusCode::NOT_FOUND);
     }
 
+    // Likewise, a page that moved is only answered with a 301 when it is
+    // loaded from the server; after navigating here, ask where it went
+    let location = use_location();
+    Effect::new(move |_| {
+        let path = location.pathname.get();
+        leptos::task::spawn_local(async move {
+            if let Ok(Some(target)) = resolve_redirect(path).await {
+                let _ = window().location().replace(&target);
+            }
+        });
+    });
+
     view! {
         <h1>"Not Found"</h1>
     }
@@ -0,0 +1,122 @@
+use leptos::prelude::ServerFnError;
+use leptos::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// One group of a server-side aggregation, shaped for the datatable.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AggregateGroupTO {
+    /// Group-by values in request order, as text; `None` for rows without one.
+    pub key: Vec<Option<String>>,
+    pub count: i64,
+    /// Numeric aggregate values by label, e.g. `sum(views)`, ready for
+    /// `RowsResult::aggregates`. Non-numeric ones and NULLs are left out.
+    pub aggregates: HashMap<String, f64>,
+}
+
+#[cfg(feature = "ssr")]
+async fn aggregate_with<S>(
+    service: &S,
+    query_options: &crate::presentation::query_options::QueryOptions,
+) -> Result<Vec<AggregateGroupTO>, ServerFnError>
+where
+    S: crate::common::service::ViewService,
+{
+    use crate::common::filter::ScalarValue;
+    use crate::presentation::query_options::serialize_column_value;
+
+    let aggregates = match query_options.to_aggregates() {
+        Ok(aggregates) => aggregates,
+        Err(e) => return Err(ServerFnError::ServerError(e.to_json())),
+    };
+    let filters = match query_options.to_filters() {
+        Ok(filters) => filters,
+        Err(e) => return Err(ServerFnError::ServerError(e.to_json())),
+    };
+    service
+        .aggregate(
+            query_options.to_group_by(),
+            aggregates,
+            query_options.first_result,
+            query_options.max_results,
+            filters,
+        )
+        .await
+        .map(|groups| {
+            groups
+                .into_iter()
+                .map(|group| AggregateGroupTO {
+                    key: group
+                        .key
+                        .into_iter()
+                        .map(|(_, value)| value.map(|v| serialize_column_value(&v).v))
+                        .collect(),
+                    count: group.count,
+                    aggregates: group
+                        .values
+                        .into_iter()
+                        .filter_map(|(label, value)| match value {
+                            Some(ScalarValue::Float(v)) => Some((label, v)),
+                            Some(ScalarValue::Int(v)) => Some((label, v as f64)),
+                            _ => None,
+                        })
+                        .collect(),
+                })
+                .collect()
+        })
+        .map_err(|e| ServerFnError::ServerError(e.to_json()))
+}
+
+/// Rows of `entity` matching `p_filters` and `q`, grouped by the `group_by`
+/// columns with `aggregates` such as `sum(views)` computed in SQL.
+#[server(name=LoadAggregates, prefix="/load", endpoint="/aggregate")]
+pub async fn load_aggregates(
+    entity: String,
+    group_by: Vec<String>,
+    aggregates: Vec<String>,
+    p_filters: Option<Vec<String>>,
+    q: Option<String>,
+    first_result: Option<i32>,
+    max_results: Option<i32>,
+) -> Result<Vec<AggregateGroupTO>, ServerFnError> {
+    use crate::common::error::CoreError;
+    use crate::presentation::query_options::QueryOptions;
+    use crate::state::AppState;
+    use actix_web::web::Data;
+    use leptos_actix::extract;
+
+    crate::pages::rest::auth_api::require_admin().await?;
+    let state: Data<AppState> = extract().await?;
+    let query_options = QueryOptions {
+        first_result,
+        max_results,
+        sort: None,
+        p_filters,
+        a_filters: None,
+        search: None,
+        after: None,
+        before: None,
+        q,
+        facets: None,
+        deleted: None,
+        group_by: Some(group_by.join("|")),
+        aggregates: Some(aggregates.join("|")),
+    };
+    match entity.as_str() {
+        "posts" => aggregate_with(&state.post_service, &query_options).await,
+        "post_types" => aggregate_with(&state.post_type_service, &query_options).await,
+        "post_collections" => aggregate_with(&state.post_collection_service, &query_options).await,
+        "post_taxonomies" => aggregate_with(&state.post_taxonomy_service, &query_options).await,
+        "terms" => aggregate_with(&state.term_service, &query_options).await,
+        "attributes" => aggregate_with(&state.attribute_service, &query_options).await,
+        "attribute_values" => aggregate_with(&state.attribute_value_service, &query_options).await,
+        "users" => aggregate_with(&state.user_service, &query_options).await,
+        _ => Err(ServerFnError::ServerError(
+            CoreError::BadRequest(
+                "error.aggregate.unknown_entity",
+                HashMap::from([("entity".to_string(), entity.clone())]),
+            )
+            .to_json(),
+        )),
+    }
+}
@@ -1,12 +1,25 @@
 use crate::business::attribute_service::{Attribute, AttributeCreate, AttributeInfo};
 use crate::common::error::CoreError;
+#[cfg(feature = "ssr")]
 use crate::common::service::{Service, ViewService};
 use crate::{define_readonly_to_with_common_fields_fe, define_to_with_common_fields_fe};
 use leptos::prelude::ServerFnError;
 use leptos::*;
 
 // Table TO
-define_to_with_common_fields_fe!(Attribute { pub name: String, pub entity_type: String, pub data_type: String, });
+define_to_with_common_fields_fe!(Attribute {
+    pub name: String,
+    pub entity_type: String,
+    pub data_type: String,
+    #[serde(default)]
+    pub required: bool,
+    pub min_value: Option<f64>,
+    pub max_value: Option<f64>,
+    pub pattern: Option<String>,
+    #[serde(default)]
+    pub allowed_values: Vec<String>,
+    pub default_value: Option<String>,
+});
 // View TO
 define_readonly_to_with_common_fields_fe!(AttributeInfo { pub name: String, pub entity_type: String, pub data_type: String, });
 
@@ -21,6 +34,12 @@ impl From<Attribute> for AttributeTO {
             name: e.name,
             entity_type: e.entity_type,
             data_type: e.data_type,
+            required: e.required,
+            min_value: e.min_value,
+            max_value: e.max_value,
+            pattern: e.pattern,
+            allowed_values: e.allowed_values,
+            default_value: e.default_value,
         }
     }
 }
@@ -35,6 +54,12 @@ impl From<AttributeTO> for Attribute {
             name: t.name,
             entity_type: t.entity_type,
             data_type: t.data_type,
+            required: t.required,
+            min_value: t.min_value,
+            max_value: t.max_value,
+            pattern: t.pattern,
+            allowed_values: t.allowed_values,
+            default_value: t.default_value,
         }
     }
 }
@@ -73,6 +98,17 @@ pub async fn load_attributes(
         search,
         p_filters,
         a_filters: None,
+        after: None,
+        before: None,
+        q: None,
+        facets: None,
+        deleted: None,
+        group_by: None,
+        aggregates: None,
+    };
+    let filters = match q.to_filters() {
+        Ok(filters) => filters,
+        Err(e) => return Err(ServerFnError::ServerError(e.to_json())),
     };
     state
         .attribute_service
@@ -80,7 +116,7 @@ pub async fn load_attributes(
             q.to_sort_criteria(),
             q.first_result,
             q.max_results,
-            q.to_filters(),
+            filters,
         )
         .await
         .map(|v| v.into_iter().map(AttributeTO::from).collect())
@@ -104,10 +140,21 @@ pub async fn count_attributes(
         search,
         p_filters,
         a_filters: None,
+        after: None,
+        before: None,
+        q: None,
+        facets: None,
+        deleted: None,
+        group_by: None,
+        aggregates: None,
+    };
+    let filters = match q.to_filters() {
+        Ok(filters) => filters,
+        Err(e) => return Err(ServerFnError::ServerError(e.to_json())),
     };
     state
         .attribute_service
-        .count(q.to_filters())
+        .count(filters)
         .await
         .map_err(|e| ServerFnError::ServerError(e.to_json()))
 }
@@ -227,6 +274,17 @@ pub async fn load_attribute_infos(
         search,
         p_filters,
         a_filters: None,
+        after: None,
+        before: None,
+        q: None,
+        facets: None,
+        deleted: None,
+        group_by: None,
+        aggregates: None,
+    };
+    let filters = match q.to_filters() {
+        Ok(filters) => filters,
+        Err(e) => return Err(ServerFnError::ServerError(e.to_json())),
     };
     state
         .attribute_info_service
@@ -234,7 +292,7 @@ pub async fn load_attribute_infos(
             q.to_sort_criteria(),
             q.first_result,
             q.max_results,
-            q.to_filters(),
+            filters,
         )
         .await
         .map(|v| v.into_iter().map(AttributeInfoTO::from).collect())
@@ -258,10 +316,21 @@ pub async fn count_attribute_infos(
         search,
         p_filters,
         a_filters: None,
+        after: None,
+        before: None,
+        q: None,
+        facets: None,
+        deleted: None,
+        group_by: None,
+        aggregates: None,
+    };
+    let filters = match q.to_filters() {
+        Ok(filters) => filters,
+        Err(e) => return Err(ServerFnError::ServerError(e.to_json())),
     };
     state
         .attribute_info_service
-        .count(q.to_filters())
+        .count(filters)
         .await
         .map_err(|e| ServerFnError::ServerError(e.to_json()))
 }
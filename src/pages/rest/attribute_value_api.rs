@@ -2,6 +2,7 @@ use crate::business::attribute_value_service::{
     AttributeValue, AttributeValueCreate, AttributeValueInfo,
 };
 use crate::common::error::CoreError;
+#[cfg(feature = "ssr")]
 use crate::common::service::{Service, ViewService};
 use crate::{define_readonly_to_with_common_fields_fe, define_to_with_common_fields_fe};
 use leptos::prelude::ServerFnError;
@@ -125,6 +126,17 @@ pub async fn load_attribute_values(
         search,
         p_filters,
         a_filters,
+        after: None,
+        before: None,
+        q: None,
+        facets: None,
+        deleted: None,
+        group_by: None,
+        aggregates: None,
+    };
+    let filters = match q.to_filters() {
+        Ok(filters) => filters,
+        Err(e) => return Err(ServerFnError::ServerError(e.to_json())),
     };
     state
         .attribute_value_service
@@ -132,7 +144,7 @@ pub async fn load_attribute_values(
             q.to_sort_criteria(),
             q.first_result,
             q.max_results,
-            q.to_filters(),
+            filters,
         )
         .await
         .map(|v| v.into_iter().map(AttributeValueTO::from).collect())
@@ -157,10 +169,21 @@ pub async fn count_attribute_values(
         search,
         p_filters,
         a_filters,
+        after: None,
+        before: None,
+        q: None,
+        facets: None,
+        deleted: None,
+        group_by: None,
+        aggregates: None,
+    };
+    let filters = match q.to_filters() {
+        Ok(filters) => filters,
+        Err(e) => return Err(ServerFnError::ServerError(e.to_json())),
     };
     state
         .attribute_value_service
-        .count(q.to_filters())
+        .count(filters)
         .await
         .map_err(|e| ServerFnError::ServerError(e.to_json()))
 }
@@ -196,10 +219,18 @@ pub async fn load_attribute_value_by_uid(uid: String) -> Result<AttributeValueTO
 }
 
 #[server(name=CreateAttributeValue, prefix="/load", endpoint="/attribute_values/create")]
+#[allow(clippy::too_many_arguments)]
 pub async fn create_attribute_value(
     attribute_id: i32,
     entity_id: i32,
     entity_type: String,
+    int_value: Option<i32>,
+    double_value: Option<f64>,
+    string_value: Option<String>,
+    boolean_value: Option<bool>,
+    date_value: Option<time::Date>,
+    datetime_value: Option<time::OffsetDateTime>,
+    time_value: Option<time::Time>,
 ) -> Result<AttributeValueTO, ServerFnError> {
     use crate::state::AppState;
     use actix_web::web::Data;
@@ -209,6 +240,13 @@ pub async fn create_attribute_value(
         attribute_id,
         entity_id,
         entity_type,
+        int_value,
+        double_value,
+        string_value,
+        boolean_value,
+        date_value,
+        datetime_value,
+        time_value,
     };
     state
         .attribute_value_service
@@ -283,6 +321,17 @@ pub async fn load_attribute_value_infos(
         search,
         p_filters,
         a_filters,
+        after: None,
+        before: None,
+        q: None,
+        facets: None,
+        deleted: None,
+        group_by: None,
+        aggregates: None,
+    };
+    let filters = match q.to_filters() {
+        Ok(filters) => filters,
+        Err(e) => return Err(ServerFnError::ServerError(e.to_json())),
     };
     state
         .attribute_value_info_service
@@ -290,7 +339,7 @@ pub async fn load_attribute_value_infos(
             q.to_sort_criteria(),
             q.first_result,
             q.max_results,
-            q.to_filters(),
+            filters,
         )
         .await
         .map(|v| v.into_iter().map(AttributeValueInfoTO::from).collect())
@@ -315,10 +364,21 @@ pub async fn count_attribute_value_infos(
         search,
         p_filters,
         a_filters,
+        after: None,
+        before: None,
+        q: None,
+        facets: None,
+        deleted: None,
+        group_by: None,
+        aggregates: None,
+    };
+    let filters = match q.to_filters() {
+        Ok(filters) => filters,
+        Err(e) => return Err(ServerFnError::ServerError(e.to_json())),
     };
     state
         .attribute_value_info_service
-        .count(q.to_filters())
+        .count(filters)
         .await
         .map_err(|e| ServerFnError::ServerError(e.to_json()))
 }
@@ -356,3 +416,118 @@ pub async fn load_attribute_value_info_by_uid(
         .map(AttributeValueInfoTO::from)
         .map_err(|e| ServerFnError::ServerError(e.to_json()))
 }
+
+/// A custom field of an entity: an attribute defined for its entity type and
+/// the entity's value in text form, empty when unset.
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct CustomFieldTO {
+    pub name: String,
+    pub data_type: String,
+    pub required: bool,
+    pub allowed_values: Vec<String>,
+    pub value: String,
+}
+
+/// A custom field value to save; an empty value clears the field.
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct CustomFieldValueTO {
+    pub name: String,
+    pub value: String,
+}
+
+#[cfg(feature = "ssr")]
+async fn custom_fields(
+    state: &crate::state::AppState,
+    entity_type: &str,
+    entity_id: i32,
+) -> Result<Vec<CustomFieldTO>, CoreError> {
+    use crate::business::attribute_service::AttributeDataType;
+
+    let definitions = state
+        .attribute_value_service
+        .get_definitions(entity_type)
+        .await?;
+    let values = state
+        .attribute_value_service
+        .get_attributes(entity_type, entity_id)
+        .await?;
+    Ok(definitions
+        .into_iter()
+        .map(|a| CustomFieldTO {
+            value: values
+                .get(&a.name)
+                .map(AttributeDataType::format_value)
+                .unwrap_or_default(),
+            name: a.name,
+            data_type: a.data_type,
+            required: a.required,
+            allowed_values: a.allowed_values,
+        })
+        .collect())
+}
+
+#[server(name=LoadCustomFields, prefix="/load", endpoint="/attribute_values/custom_fields")]
+pub async fn load_custom_fields(
+    entity_type: String,
+    entity_id: i32,
+) -> Result<Vec<CustomFieldTO>, ServerFnError> {
+    use crate::state::AppState;
+    use actix_web::web::Data;
+    use leptos_actix::extract;
+    crate::pages::rest::auth_api::require_admin().await?;
+    let state: Data<AppState> = extract().await?;
+    custom_fields(&state, &entity_type, entity_id)
+        .await
+        .map_err(|e| ServerFnError::ServerError(e.to_json()))
+}
+
+#[server(name=SaveCustomFields, prefix="/load", endpoint="/attribute_values/custom_fields/save")]
+pub async fn save_custom_fields(
+    entity_type: String,
+    entity_id: i32,
+    values: Vec<CustomFieldValueTO>,
+) -> Result<Vec<CustomFieldTO>, ServerFnError> {
+    use crate::state::AppState;
+    use actix_web::web::Data;
+    use leptos_actix::extract;
+    use std::collections::HashMap;
+    crate::pages::rest::auth_api::require_admin().await?;
+    let state: Data<AppState> = extract().await?;
+    let result = async {
+        let definitions = state
+            .attribute_value_service
+            .get_definitions(&entity_type)
+            .await?;
+        let mut parsed = HashMap::new();
+        for field in values {
+            if field.value.trim().is_empty() {
+                parsed.insert(field.name, None);
+                continue;
+            }
+            // Unknown names are passed through for the service to reject.
+            let Some(attribute) = definitions.iter().find(|a| a.name == field.name) else {
+                parsed.insert(field.name, None);
+                continue;
+            };
+            let data_type = attribute.parsed_data_type()?;
+            let value = data_type.parse_value(&field.value).ok_or_else(|| {
+                CoreError::UnprocessableEntity(
+                    "error.attribute_value.invalid.value",
+                    HashMap::from([
+                        ("field".to_string(), data_type.value_column().to_string()),
+                        ("attribute".to_string(), field.name.clone()),
+                    ]),
+                )
+            })?;
+            parsed.insert(field.name, Some(value));
+        }
+        state
+            .attribute_value_service
+            .set_attributes(&entity_type, entity_id, parsed)
+            .await?;
+        custom_fields(&state, &entity_type, entity_id).await
+    };
+    result
+        .await
+        .map_err(|e| ServerFnError::ServerError(e.to_json()))
+}
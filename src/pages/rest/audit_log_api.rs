@@ -0,0 +1,84 @@
+use crate::business::audit_log_service::AuditLogInfo;
+use crate::define_readonly_to_with_common_fields_fe;
+use leptos::prelude::ServerFnError;
+use leptos::*;
+
+// View
+define_readonly_to_with_common_fields_fe!(AuditLogInfo {
+    pub entity_type: String,
+    pub entity_id: i32,
+    pub entity_uid: String,
+    pub operation: String,
+    pub user_id: Option<i32>,
+    pub username: Option<String>,
+    pub changes: String,
+});
+
+impl From<AuditLogInfo> for AuditLogInfoTO {
+    fn from(e: AuditLogInfo) -> Self {
+        Self {
+            id: e.id,
+            uid: e.uid,
+            version: e.version,
+            created_at: e.created_at,
+            updated_at: e.updated_at,
+            entity_type: e.entity_type,
+            entity_id: e.entity_id,
+            entity_uid: e.entity_uid,
+            operation: e.operation,
+            user_id: e.user_id,
+            username: e.username,
+            changes: e.changes,
+        }
+    }
+}
+
+/// Change history of one row, newest first. `entity_type` is the table name,
+/// e.g. `posts` or `terms`.
+#[server(name=LoadEntityHistory, prefix="/load", endpoint="/audit_log/history")]
+pub async fn load_entity_history(
+    entity_type: String,
+    entity_id: i32,
+    first_result: Option<i32>,
+    max_results: Option<i32>,
+) -> Result<Vec<AuditLogInfoTO>, ServerFnError> {
+    use crate::common::filter::{Filter, FilterOperator, FilterValue, ScalarValue};
+    use crate::common::service::ViewService;
+    use crate::common::sort::SortCriterion;
+    use crate::state::AppState;
+    use actix_web::web::Data;
+    use leptos_actix::extract;
+
+    crate::pages::rest::auth_api::require_admin().await?;
+    let state: Data<AppState> = extract().await?;
+    let filters = vec![
+        Filter::Property {
+            property_name: "entity_type".to_string(),
+            operator: FilterOperator::Equal,
+            value: FilterValue::Single(ScalarValue::String(entity_type)),
+        },
+        Filter::Property {
+            property_name: "entity_id".to_string(),
+            operator: FilterOperator::Equal,
+            value: FilterValue::Single(ScalarValue::Int(entity_id)),
+        },
+    ];
+    let sort = vec![
+        SortCriterion {
+            field: "created_at".to_string(),
+            ascending: false,
+            is_attribute: false,
+        },
+        SortCriterion {
+            field: "id".to_string(),
+            ascending: false,
+            is_attribute: false,
+        },
+    ];
+    state
+        .audit_log_info_service
+        .get_many(sort, first_result, max_results, filters)
+        .await
+        .map(|items| items.into_iter().map(AuditLogInfoTO::from).collect())
+        .map_err(|e| ServerFnError::ServerError(e.to_json()))
+}
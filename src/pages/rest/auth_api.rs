@@ -25,9 +25,11 @@ impl From<User> for UserTO {
     }
 }
 #[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[allow(clippy::upper_case_acronyms)]
 pub enum UserRole {
     USER,
     ADMIN,
+    EDITOR,
 }
 
 impl From<crate::business::user_service::UserRole> for UserRole {
@@ -35,6 +37,7 @@ impl From<crate::business::user_service::UserRole> for UserRole {
         match value {
             crate::business::user_service::UserRole::ADMIN => UserRole::ADMIN,
             crate::business::user_service::UserRole::USER => UserRole::USER,
+            crate::business::user_service::UserRole::EDITOR => UserRole::EDITOR,
         }
     }
 }
@@ -90,6 +93,7 @@ pub async fn login(
         match role {
             UserRole::ADMIN => "ADMIN",
             UserRole::USER => "USER",
+            UserRole::EDITOR => "EDITOR",
         },
     ) {
         return Err(ServerFnError::ServerError(
@@ -118,6 +122,7 @@ pub async fn me() -> Result<UserRole, ServerFnError> {
     match role.as_deref() {
         Some("ADMIN") => Ok(UserRole::ADMIN),
         Some("USER") => Ok(UserRole::USER),
+        Some("EDITOR") => Ok(UserRole::EDITOR),
         _ => Err(ServerFnError::ServerError(
             CoreError::unauthorized("error.unauthorized").to_json(),
         )),
@@ -152,3 +157,28 @@ pub async fn logout() -> Result<(), ServerFnError> {
     session.purge();
     Ok(())
 }
+
+#[cfg(feature = "ssr")]
+/// Fails unless the session belongs to an admin.
+pub(crate) async fn require_admin() -> Result<(), ServerFnError> {
+    use crate::common::error::CoreError;
+    use actix_session::SessionExt as _;
+    use actix_web::HttpRequest;
+    use leptos_actix::extract;
+
+    let req: HttpRequest = extract().await?;
+    let role: Option<String> = match req.get_session().get("role") {
+        Ok(v) => v,
+        Err(_) => {
+            return Err(ServerFnError::ServerError(
+                CoreError::unauthorized("error.missing_session").to_json(),
+            ))
+        }
+    };
+    match role.as_deref() {
+        Some("ADMIN") => Ok(()),
+        _ => Err(ServerFnError::ServerError(
+            CoreError::forbidden("error.forbidden").to_json(),
+        )),
+    }
+}
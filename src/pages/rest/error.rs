@@ -1,5 +1,6 @@
 use crate::common::error::CoreError;
-use serde::Serialize;
+use leptos::prelude::ServerFnError;
+use serde::{Deserialize, Serialize};
 use serde_json::to_string;
 use std::collections::HashMap;
 
@@ -52,3 +53,36 @@ impl CoreError {
         to_string(&dto).unwrap_or_else(|_| "{}".into())
     }
 }
+
+/// Client-side view of the JSON produced by `CoreError::to_json`.
+#[derive(Deserialize, Debug, Clone)]
+pub struct ErrorTO {
+    pub code: String,
+    pub message: String,
+    #[serde(default)]
+    pub details: HashMap<String, String>,
+}
+
+impl ErrorTO {
+    pub fn from_server_fn_error(error: &ServerFnError) -> Option<Self> {
+        match error {
+            ServerFnError::ServerError(json) => serde_json::from_str(json).ok(),
+            _ => None,
+        }
+    }
+
+    pub fn is_conflict(&self) -> bool {
+        self.code == "CONFLICT"
+    }
+
+    /// Human readable text for a failed save.
+    pub fn describe(error: &ServerFnError) -> String {
+        match Self::from_server_fn_error(error) {
+            Some(err) if err.is_conflict() => {
+                "This record was changed by someone else. Reload the page to get the latest version.".to_string()
+            }
+            Some(err) => err.message,
+            None => error.to_string(),
+        }
+    }
+}
@@ -83,6 +83,8 @@ macro_rules! define_readonly_to_with_common_fields_fe {
                 $( pub $fname : $fty, )*
             }
 
+            // Generic table helpers; not every read-only TO is shown in a table
+            #[allow(dead_code)]
             impl [<$name TO>] {
                 pub fn columns() -> Vec<&'static str> {
                     vec!["id", "uid", "version", "created_at", "updated_at", $(stringify!($fname),)*]
@@ -1,11 +1,16 @@
+pub mod aggregate_api;
 pub mod attribute_api;
 pub mod attribute_value_api;
+pub mod audit_log_api;
 pub mod auth_api;
-mod error;
+pub mod error;
 mod macros;
 pub mod post_api;
 pub mod post_collection_api;
+pub mod post_revision_api;
 pub mod post_taxonomy_api;
 pub mod post_type_api;
+pub mod slug_redirect_api;
 pub mod term_api;
+pub mod trash_api;
 pub mod user_api;
@@ -1,6 +1,10 @@
 use crate::business::post_service::{Post, PostInfo, PostStatus};
+use crate::common::cursor::CursorPage;
 use crate::common::error::CoreError;
+use crate::common::search::{SearchHit, Suggestion};
+#[cfg(feature = "ssr")]
 use crate::common::service::{Service, ViewService};
+use crate::pages::rest::term_api::TermInfoTO;
 use crate::{define_readonly_to_with_common_fields_fe, define_to_with_common_fields_fe};
 use leptos::prelude::ServerFnError;
 use leptos::*;
@@ -12,6 +16,11 @@ define_to_with_common_fields_fe!(Post {
     pub summary: String,
     pub content: String,
     pub status: String,
+    pub user_id: i32,
+    pub type_id: i32,
+    pub published_at: Option<time::OffsetDateTime>,
+    pub scheduled_at: Option<time::OffsetDateTime>,
+    pub expires_at: Option<time::OffsetDateTime>,
 });
 
 define_readonly_to_with_common_fields_fe!(PostInfo {
@@ -38,8 +47,11 @@ impl From<PostTO> for Post {
             summary: to.summary,
             content: to.content,
             status: PostStatus::from_str(&to.status).unwrap_or(PostStatus::DRAFT),
-            user_id: -1,
-            type_id: -1,
+            user_id: to.user_id,
+            type_id: to.type_id,
+            published_at: to.published_at,
+            scheduled_at: to.scheduled_at,
+            expires_at: to.expires_at,
         }
     }
 }
@@ -57,6 +69,11 @@ impl From<Post> for PostTO {
             summary: entity.summary,
             content: entity.content,
             status: entity.status.as_str().to_string(),
+            user_id: entity.user_id,
+            type_id: entity.type_id,
+            published_at: entity.published_at,
+            scheduled_at: entity.scheduled_at,
+            expires_at: entity.expires_at,
         }
     }
 }
@@ -80,49 +97,115 @@ impl From<PostInfo> for PostInfoTO {
     }
 }
 
+/// A page of the public feed: published posts, most recently updated first.
 #[server(name=LoadPosts,prefix="/load", endpoint="/posts")]
 pub async fn load_posts(
-    first_result: i64,
+    after: Option<String>,
+    before: Option<String>,
     max_results: i32,
-) -> Result<Vec<PostInfoTO>, ServerFnError> {
-    use crate::common::sort::SortCriterion;
+) -> Result<CursorPage<PostInfoTO>, ServerFnError> {
+    use crate::common::filter::{Filter, FilterOperator, FilterValue, ScalarValue};
+    use crate::presentation::query_options::QueryOptions;
     use crate::state::AppState;
     use actix_web::web::Data;
     use leptos_actix::extract;
 
     let state: Data<AppState> = extract().await?;
-    let result = state
+    let query_options = QueryOptions {
+        first_result: None,
+        max_results: Some(max_results),
+        sort: Some("-updated_at".to_string()),
+        p_filters: None,
+        a_filters: None,
+        search: None,
+        after,
+        before,
+        q: None,
+        facets: None,
+        deleted: None,
+        group_by: None,
+        aggregates: None,
+    };
+    let position = match query_options.to_cursor_position() {
+        Ok(position) => position,
+        Err(e) => return Err(ServerFnError::ServerError(e.to_json())),
+    };
+    let mut filters = match query_options.to_filters() {
+        Ok(filters) => filters,
+        Err(e) => return Err(ServerFnError::ServerError(e.to_json())),
+    };
+    filters.push(Filter::Property {
+        property_name: "status".to_string(),
+        operator: FilterOperator::Equal,
+        value: FilterValue::Single(ScalarValue::Int(PostStatus::PUBLISHED.as_i32())),
+    });
+    state
         .post_info_service
-        .get_many(
-            vec![SortCriterion {
-                field: "updated_at".to_owned(),
-                ascending: false,
-            }],
-            Some(first_result as i32),
-            Some(max_results),
-            vec![],
+        .get_page(
+            query_options.to_sort_criteria(),
+            position,
+            max_results,
+            filters,
         )
         .await
-        .map(|posts| {
-            posts
-                .into_iter()
-                .map(PostInfoTO::from)
-                .collect::<Vec<PostInfoTO>>()
-        })
-        .map_err(|e| ServerFnError::ServerError(e.to_json()));
-    result
+        .map(|page| page.map(PostInfoTO::from))
+        .map_err(|e| ServerFnError::ServerError(e.to_json()))
 }
-#[server(name=CountPosts,prefix="/load", endpoint="/posts/count")]
-pub async fn count_posts() -> Result<i64, ServerFnError> {
+
+/// Full-text search over published posts, best match first.
+#[server(name=SearchPosts, prefix="/load", endpoint="/posts/search")]
+pub async fn search_posts(
+    query: String,
+    first_result: i32,
+    max_results: i32,
+) -> Result<Vec<SearchHit<PostTO>>, ServerFnError> {
+    use crate::common::filter::{Filter, FilterOperator, FilterValue, ScalarValue};
     use crate::state::AppState;
     use actix_web::web::Data;
     use leptos_actix::extract;
 
     let state: Data<AppState> = extract().await?;
+    let published = Filter::Property {
+        property_name: "status".to_string(),
+        operator: FilterOperator::Equal,
+        value: FilterValue::Single(ScalarValue::Int(PostStatus::PUBLISHED.as_i32())),
+    };
     state
-        .post_info_service
-        .count(vec![])
+        .post_service
+        .search(
+            query,
+            Some(first_result.max(0)),
+            Some(max_results.clamp(1, 50)),
+            vec![published],
+        )
+        .await
+        .map(|hits| hits.into_iter().map(|hit| hit.map(PostTO::from)).collect())
+        .map_err(|e| ServerFnError::ServerError(e.to_json()))
+}
+
+/// Published posts whose title, slug or summary is similar to `query`, for
+/// "did you mean" when a search finds nothing.
+#[server(name=SuggestPosts, prefix="/load", endpoint="/posts/suggest")]
+pub async fn suggest_posts(
+    query: String,
+    max_results: i32,
+) -> Result<Vec<Suggestion<PostTO>>, ServerFnError> {
+    use crate::common::filter::{Filter, FilterOperator, FilterValue, ScalarValue};
+    use crate::state::AppState;
+    use actix_web::web::Data;
+    use leptos_actix::extract;
+
+    let state: Data<AppState> = extract().await?;
+    let published = Filter::Property {
+        property_name: "status".to_string(),
+        operator: FilterOperator::Equal,
+        value: FilterValue::Single(ScalarValue::Int(PostStatus::PUBLISHED.as_i32())),
+    };
+    state
+        .post_service
+        .suggest(query, Some(max_results.clamp(1, 20)), vec![published])
         .await
+        .map(|suggestions| suggestions.into_iter().map(|s| s.map(PostTO::from)).collect())
         .map_err(|e| ServerFnError::ServerError(e.to_json()))
 }
 
@@ -207,6 +290,7 @@ pub async fn create_post(title: String, type_id: i32) -> Result<PostTO, ServerFn
     let state: Data<AppState> = extract().await?;
     let create = PostCreate {
         title,
+        slug: String::new(),
         type_id,
         user_id,
     };
@@ -249,8 +333,8 @@ pub async fn update_post(post: PostTO) -> Result<PostTO, ServerFnError> {
     let state: Data<AppState> = extract().await?;
     let entity: Post = post.into();
     state
-        .post_service
-        .update(&entity)
+        .post_revision_service
+        .update_post(&state.post_service, &entity)
         .await
         .map(PostTO::from)
         .map_err(|e| e.to_json())
@@ -311,6 +395,25 @@ pub async fn load_post_by_id(id: i32) -> Result<PostTO, ServerFnError> {
     }
 }
 
+/// The published post with slug `slug`, for its article page. Posts not
+/// published are not found.
+#[server(name=LoadArticle, prefix="/load", endpoint="/posts/article")]
+pub async fn load_article(slug: String) -> Result<PostTO, ServerFnError> {
+    use crate::state::AppState;
+    use actix_web::web::Data;
+    use leptos_actix::extract;
+
+    let state: Data<AppState> = extract().await?;
+    let result = state.post_service.get_by_slug(&slug).await;
+    match result {
+        Ok(Some(p)) if p.status == PostStatus::PUBLISHED => Ok(PostTO::from(p)),
+        Ok(_) => Err(ServerFnError::ServerError(
+            CoreError::not_found("error.post_not_found").to_json(),
+        )),
+        Err(e) => Err(ServerFnError::ServerError(e.to_json())),
+    }
+}
+
 #[server(name=LoadPostInfos,prefix="/load", endpoint="/posts/info")]
 pub async fn load_post_infos(
     first_result: i64,
@@ -333,15 +436,26 @@ pub async fn load_post_infos(
         p_filters,
         a_filters,
         search,
+        after: None,
+        before: None,
+        q: None,
+        facets: None,
+        deleted: None,
+        group_by: None,
+        aggregates: None,
     };
 
+    let filters = match query_options.to_filters() {
+        Ok(filters) => filters,
+        Err(e) => return Err(ServerFnError::ServerError(e.to_json())),
+    };
     let result = state
         .post_info_service
         .get_many(
             query_options.to_sort_criteria(),
             query_options.first_result,
             query_options.max_results,
-            query_options.to_filters(),
+            filters,
         )
         .await
         .map(|items| {
@@ -373,10 +487,21 @@ pub async fn count_post_infos(
         p_filters,
         a_filters,
         search,
+        after: None,
+        before: None,
+        q: None,
+        facets: None,
+        deleted: None,
+        group_by: None,
+        aggregates: None,
+    };
+    let filters = match query_options.to_filters() {
+        Ok(filters) => filters,
+        Err(e) => return Err(ServerFnError::ServerError(e.to_json())),
     };
     state
         .post_info_service
-        .count(query_options.to_filters())
+        .count(filters)
         .await
         .map_err(|e| ServerFnError::ServerError(e.to_json()))
 }
@@ -398,3 +523,244 @@ pub async fn load_post_info_by_id(id: i32) -> Result<PostInfoTO, ServerFnError>
         Err(e) => Err(ServerFnError::ServerError(e.to_json())),
     }
 }
+
+/// A workflow step the signed-in user may take from the post's status.
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct WorkflowActionTO {
+    pub action: String,
+    pub to: String,
+}
+
+/// One entry of a post's workflow history.
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct PostTransitionTO {
+    pub created_at: time::OffsetDateTime,
+    pub from_status: String,
+    pub to_status: String,
+    pub username: Option<String>,
+    pub comment: Option<String>,
+}
+
+/// Where a post stands in the workflow, what can be done next, and how it
+/// got there (newest first).
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct PostWorkflowTO {
+    pub post: PostTO,
+    pub actions: Vec<WorkflowActionTO>,
+    pub history: Vec<PostTransitionTO>,
+}
+
+#[cfg(feature = "ssr")]
+/// The signed-in user, as the workflow sees them.
+async fn workflow_actor() -> Result<crate::business::post_service::WorkflowActor, ServerFnError> {
+    use crate::business::post_service::WorkflowActor;
+    use crate::business::user_service::UserRole;
+    use actix_session::SessionExt as _;
+    use actix_web::HttpRequest;
+    use leptos_actix::extract;
+
+    let req: HttpRequest = extract().await?;
+    let session = req.get_session();
+    let user: Option<crate::pages::rest::auth_api::UserTO> = session.get("user").ok().flatten();
+    let role: Option<String> = session.get("role").ok().flatten();
+    match user {
+        Some(user) => Ok(WorkflowActor {
+            user_id: Some(user.id),
+            role: role
+                .and_then(|role| UserRole::from_str(&role).ok())
+                .unwrap_or(UserRole::USER),
+        }),
+        None => Err(ServerFnError::ServerError(
+            CoreError::unauthorized("error.unauthorized").to_json(),
+        )),
+    }
+}
+
+#[cfg(feature = "ssr")]
+async fn post_workflow(
+    state: &crate::state::AppState,
+    post: Post,
+    actor: &crate::business::post_service::WorkflowActor,
+) -> Result<PostWorkflowTO, CoreError> {
+    use std::collections::hash_map::Entry;
+    use std::collections::HashMap;
+
+    let transitions = state.post_service.get_transitions(post.id).await?;
+    let mut usernames = HashMap::new();
+    for user_id in transitions.iter().filter_map(|t| t.user_id) {
+        if let Entry::Vacant(entry) = usernames.entry(user_id) {
+            let user = state.user_service.get_by_id(user_id).await?;
+            entry.insert(user.map(|u| u.username));
+        }
+    }
+    Ok(PostWorkflowTO {
+        actions: state
+            .post_service
+            .available_transitions(&post, actor)
+            .into_iter()
+            .map(|rule| WorkflowActionTO {
+                action: rule.action.to_string(),
+                to: rule.to.as_str().to_string(),
+            })
+            .collect(),
+        history: transitions
+            .into_iter()
+            .map(|t| PostTransitionTO {
+                created_at: t.created_at,
+                from_status: t.from_status.as_str().to_string(),
+                to_status: t.to_status.as_str().to_string(),
+                username: t.user_id.and_then(|id| usernames.get(&id).cloned().flatten()),
+                comment: t.comment,
+            })
+            .collect(),
+        post: PostTO::from(post),
+    })
+}
+
+#[server(name=LoadPostWorkflow, prefix="/load", endpoint="/posts/workflow")]
+pub async fn load_post_workflow(id: i32) -> Result<PostWorkflowTO, ServerFnError> {
+    use crate::state::AppState;
+    use actix_web::web::Data;
+    use leptos_actix::extract;
+
+    let actor = workflow_actor().await?;
+    let state: Data<AppState> = extract().await?;
+    let result = async {
+        let post = state
+            .post_service
+            .get_by_id(id)
+            .await?
+            .ok_or(CoreError::not_found("error.post_not_found"))?;
+        post_workflow(&state, post, &actor).await
+    };
+    result
+        .await
+        .map_err(|e| ServerFnError::ServerError(e.to_json()))
+}
+
+/// Moves the post to status `to`; the workflow decides whether the signed-in
+/// user may.
+#[server(name=TransitionPost, prefix="/load", endpoint="/posts/workflow/transition")]
+pub async fn transition_post(
+    id: i32,
+    to: String,
+    comment: String,
+) -> Result<PostWorkflowTO, ServerFnError> {
+    use crate::state::AppState;
+    use actix_web::web::Data;
+    use leptos_actix::extract;
+
+    let actor = workflow_actor().await?;
+    let state: Data<AppState> = extract().await?;
+    let result = async {
+        let to = PostStatus::from_str(&to)
+            .map_err(|_| CoreError::unprocessable_entity("error.post.status.invalid"))?;
+        let post = state
+            .post_service
+            .transition(id, to, Some(comment), &actor)
+            .await?;
+        post_workflow(&state, post, &actor).await
+    };
+    result
+        .await
+        .map_err(|e| ServerFnError::ServerError(e.to_json()))
+}
+
+/// Sets when the post is published and archived on its own. Dates come from
+/// `datetime-local` inputs (`2024-01-31T09:30`) and are taken as UTC; an empty
+/// one clears that date.
+#[server(name=SchedulePost, prefix="/load", endpoint="/posts/workflow/schedule")]
+pub async fn schedule_post(
+    id: i32,
+    scheduled_at: String,
+    expires_at: String,
+) -> Result<PostTO, ServerFnError> {
+    use crate::state::AppState;
+    use actix_web::web::Data;
+    use leptos_actix::extract;
+
+    let actor = workflow_actor().await?;
+    let state: Data<AppState> = extract().await?;
+    let result = async {
+        let scheduled_at = parse_schedule_date(&scheduled_at)?;
+        let expires_at = parse_schedule_date(&expires_at)?;
+        state
+            .post_service
+            .schedule(id, scheduled_at, expires_at, &actor)
+            .await
+    };
+    result
+        .await
+        .map(PostTO::from)
+        .map_err(|e| ServerFnError::ServerError(e.to_json()))
+}
+
+#[cfg(feature = "ssr")]
+fn parse_schedule_date(value: &str) -> Result<Option<time::OffsetDateTime>, CoreError> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Ok(None);
+    }
+    let format = time::macros::format_description!("[year]-[month]-[day]T[hour]:[minute][optional [:[second]]]");
+    time::PrimitiveDateTime::parse(value, &format)
+        .map(|at| Some(at.assume_utc()))
+        .map_err(|_| CoreError::unprocessable_entity("error.post.schedule.invalid_date"))
+}
+
+/// Terms of a post by taxonomy, then name.
+#[server(name=LoadPostTerms, prefix="/load", endpoint="/posts/terms")]
+pub async fn load_post_terms(id: i32) -> Result<Vec<TermInfoTO>, ServerFnError> {
+    use crate::state::AppState;
+    use actix_web::web::Data;
+    use leptos_actix::extract;
+    let state: Data<AppState> = extract().await?;
+    state
+        .post_term_service
+        .get_terms(id, None)
+        .await
+        .map(|v| v.into_iter().map(TermInfoTO::from).collect())
+        .map_err(|e| ServerFnError::ServerError(e.to_json()))
+}
+
+/// Makes `term_ids` the post's terms of the taxonomy with code `taxonomy`,
+/// leaving its other terms alone. Returns all its terms.
+#[server(name=ReplacePostTerms, prefix="/load", endpoint="/posts/terms/replace")]
+pub async fn replace_post_terms(
+    id: i32,
+    taxonomy: String,
+    term_ids: Vec<i32>,
+) -> Result<Vec<TermInfoTO>, ServerFnError> {
+    use crate::state::AppState;
+    use actix_session::SessionExt as _;
+    use actix_web::web::Data;
+    use actix_web::HttpRequest;
+    use leptos_actix::extract;
+
+    // Guard: require ADMIN role from server session
+    let req: HttpRequest = extract().await?;
+    let session = req.get_session();
+    let role: Option<String> = match session.get("role") {
+        Ok(v) => v,
+        Err(_) => {
+            return Err(ServerFnError::ServerError(
+                CoreError::unauthorized("error.missing_session").to_json(),
+            ))
+        }
+    };
+    match role.as_deref() {
+        Some("ADMIN") => {}
+        _ => {
+            return Err(ServerFnError::ServerError(
+                CoreError::forbidden("error.forbidden").to_json(),
+            ))
+        }
+    }
+
+    let state: Data<AppState> = extract().await?;
+    state
+        .post_term_service
+        .replace(id, Some(&taxonomy), &term_ids)
+        .await
+        .map(|v| v.into_iter().map(TermInfoTO::from).collect())
+        .map_err(|e| ServerFnError::ServerError(e.to_json()))
+}
@@ -2,6 +2,7 @@ use crate::business::post_collection_service::{
     PostCollection, PostCollectionCreate, PostCollectionInfo,
 };
 use crate::common::error::CoreError;
+#[cfg(feature = "ssr")]
 use crate::common::service::{Service, ViewService};
 use crate::{define_readonly_to_with_common_fields_fe, define_to_with_common_fields_fe};
 use leptos::prelude::ServerFnError;
@@ -89,6 +90,17 @@ pub async fn load_post_collections(
         search,
         p_filters,
         a_filters: None,
+        after: None,
+        before: None,
+        q: None,
+        facets: None,
+        deleted: None,
+        group_by: None,
+        aggregates: None,
+    };
+    let filters = match q.to_filters() {
+        Ok(filters) => filters,
+        Err(e) => return Err(ServerFnError::ServerError(e.to_json())),
     };
     state
         .post_collection_service
@@ -96,7 +108,7 @@ pub async fn load_post_collections(
             q.to_sort_criteria(),
             q.first_result,
             q.max_results,
-            q.to_filters(),
+            filters,
         )
         .await
         .map(|v| v.into_iter().map(PostCollectionTO::from).collect())
@@ -120,10 +132,21 @@ pub async fn count_post_collections(
         search,
         p_filters,
         a_filters: None,
+        after: None,
+        before: None,
+        q: None,
+        facets: None,
+        deleted: None,
+        group_by: None,
+        aggregates: None,
+    };
+    let filters = match q.to_filters() {
+        Ok(filters) => filters,
+        Err(e) => return Err(ServerFnError::ServerError(e.to_json())),
     };
     state
         .post_collection_service
-        .count(q.to_filters())
+        .count(filters)
         .await
         .map_err(|e| ServerFnError::ServerError(e.to_json()))
 }
@@ -158,6 +181,33 @@ pub async fn load_post_collection_by_uid(uid: String) -> Result<PostCollectionTO
         .map_err(|e| ServerFnError::ServerError(e.to_json()))
 }
 
+/// The collection with slug `slug`, for its public page. Private collections
+/// are not found.
+#[server(name=LoadPostCollectionBySlug, prefix="/load", endpoint="/post_collections/get-slug")]
+pub async fn load_post_collection_by_slug(slug: String) -> Result<PostCollectionTO, ServerFnError> {
+    use crate::common::filter::{Filter, FilterOperator, FilterValue, ScalarValue};
+    use crate::state::AppState;
+    use actix_web::web::Data;
+    use leptos_actix::extract;
+    let state: Data<AppState> = extract().await?;
+    let with_slug = Filter::Property {
+        property_name: "slug".to_string(),
+        operator: FilterOperator::Equal,
+        value: FilterValue::Single(ScalarValue::String(slug)),
+    };
+    state
+        .post_collection_service
+        .get_all(vec![with_slug])
+        .await
+        .and_then(|v| {
+            v.into_iter()
+                .find(|c| c.visibility != "private")
+                .ok_or(CoreError::not_found("error.not_found"))
+        })
+        .map(PostCollectionTO::from)
+        .map_err(|e| ServerFnError::ServerError(e.to_json()))
+}
+
 #[server(name=CreatePostCollection, prefix="/load", endpoint="/post_collections/create")]
 pub async fn create_post_collection(
     slug: String,
@@ -245,6 +295,17 @@ pub async fn load_post_collection_infos(
         search,
         p_filters,
         a_filters: None,
+        after: None,
+        before: None,
+        q: None,
+        facets: None,
+        deleted: None,
+        group_by: None,
+        aggregates: None,
+    };
+    let filters = match q.to_filters() {
+        Ok(filters) => filters,
+        Err(e) => return Err(ServerFnError::ServerError(e.to_json())),
     };
     state
         .post_collection_info_service
@@ -252,7 +313,7 @@ pub async fn load_post_collection_infos(
             q.to_sort_criteria(),
             q.first_result,
             q.max_results,
-            q.to_filters(),
+            filters,
         )
         .await
         .map(|v| v.into_iter().map(PostCollectionInfoTO::from).collect())
@@ -276,10 +337,21 @@ pub async fn count_post_collection_infos(
         search,
         p_filters,
         a_filters: None,
+        after: None,
+        before: None,
+        q: None,
+        facets: None,
+        deleted: None,
+        group_by: None,
+        aggregates: None,
+    };
+    let filters = match q.to_filters() {
+        Ok(filters) => filters,
+        Err(e) => return Err(ServerFnError::ServerError(e.to_json())),
     };
     state
         .post_collection_info_service
-        .count(q.to_filters())
+        .count(filters)
         .await
         .map_err(|e| ServerFnError::ServerError(e.to_json()))
 }
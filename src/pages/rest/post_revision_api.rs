@@ -0,0 +1,169 @@
+use crate::business::post_revision_service::PostRevisionInfo;
+use crate::common::diff::DiffLine;
+use crate::define_readonly_to_with_common_fields_fe;
+use crate::pages::rest::post_api::PostTO;
+use leptos::prelude::ServerFnError;
+use leptos::*;
+use serde::{Deserialize, Serialize};
+
+// View
+define_readonly_to_with_common_fields_fe!(PostRevisionInfo {
+    pub post_id: i32,
+    pub kind: String,
+    pub title: String,
+    pub summary: String,
+    pub content: String,
+    pub status: String,
+    pub user_id: Option<i32>,
+    pub username: Option<String>,
+});
+
+impl From<PostRevisionInfo> for PostRevisionInfoTO {
+    fn from(e: PostRevisionInfo) -> Self {
+        Self {
+            id: e.id,
+            uid: e.uid,
+            version: e.version,
+            created_at: e.created_at,
+            updated_at: e.updated_at,
+            post_id: e.post_id,
+            kind: e.kind.as_str().to_string(),
+            title: e.title,
+            summary: e.summary,
+            content: e.content,
+            status: e.status.as_str().to_string(),
+            user_id: e.user_id,
+            username: e.username,
+        }
+    }
+}
+
+/// Line-level changes from one revision to another, field by field.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RevisionDiffTO {
+    pub title: Vec<DiffLine>,
+    pub summary: Vec<DiffLine>,
+    pub content: Vec<DiffLine>,
+    pub status: Vec<DiffLine>,
+}
+
+/// Revisions of a post, newest first.
+#[server(name=LoadPostRevisions, prefix="/load", endpoint="/posts/revisions")]
+pub async fn load_post_revisions(
+    post_id: i32,
+    first_result: Option<i32>,
+    max_results: Option<i32>,
+) -> Result<Vec<PostRevisionInfoTO>, ServerFnError> {
+    use crate::common::filter::{Filter, FilterOperator, FilterValue, ScalarValue};
+    use crate::common::service::ViewService;
+    use crate::common::sort::SortCriterion;
+    use crate::state::AppState;
+    use actix_web::web::Data;
+    use leptos_actix::extract;
+
+    crate::pages::rest::auth_api::require_admin().await?;
+    let state: Data<AppState> = extract().await?;
+    let filters = vec![Filter::Property {
+        property_name: "post_id".to_string(),
+        operator: FilterOperator::Equal,
+        value: FilterValue::Single(ScalarValue::Int(post_id)),
+    }];
+    let sort = vec![
+        SortCriterion {
+            field: "created_at".to_string(),
+            ascending: false,
+            is_attribute: false,
+        },
+        SortCriterion {
+            field: "id".to_string(),
+            ascending: false,
+            is_attribute: false,
+        },
+    ];
+    state
+        .post_revision_info_service
+        .get_many(sort, first_result, max_results, filters)
+        .await
+        .map(|items| items.into_iter().map(PostRevisionInfoTO::from).collect())
+        .map_err(|e| ServerFnError::ServerError(e.to_json()))
+}
+
+/// Saves the post as it is now as a checkpoint revision.
+#[server(name=SavePostRevision, prefix="/load", endpoint="/posts/revisions/save")]
+pub async fn save_post_revision(post_id: i32) -> Result<(), ServerFnError> {
+    use crate::business::post_revision_service::RevisionKind;
+    use crate::common::error::CoreError;
+    use crate::common::service::ViewService;
+    use crate::state::AppState;
+    use actix_web::web::Data;
+    use leptos_actix::extract;
+
+    crate::pages::rest::auth_api::require_admin().await?;
+    let state: Data<AppState> = extract().await?;
+    let post = match state.post_service.get_by_id(post_id).await {
+        Ok(Some(post)) => post,
+        Ok(None) => {
+            return Err(ServerFnError::ServerError(
+                CoreError::not_found("error.not_found").to_json(),
+            ))
+        }
+        Err(e) => return Err(ServerFnError::ServerError(e.to_json())),
+    };
+    state
+        .post_revision_service
+        .record(&post, RevisionKind::CHECKPOINT)
+        .await
+        .map(|_| ())
+        .map_err(|e| ServerFnError::ServerError(e.to_json()))
+}
+
+/// Diff from revision `from_id` to revision `to_id`.
+#[server(name=DiffPostRevisions, prefix="/load", endpoint="/posts/revisions/diff")]
+pub async fn diff_post_revisions(from_id: i32, to_id: i32) -> Result<RevisionDiffTO, ServerFnError> {
+    use crate::common::diff::diff_lines;
+    use crate::common::error::CoreError;
+    use crate::common::service::ViewService;
+    use crate::state::AppState;
+    use actix_web::web::Data;
+    use leptos_actix::extract;
+
+    crate::pages::rest::auth_api::require_admin().await?;
+    let state: Data<AppState> = extract().await?;
+    let mut revisions = Vec::with_capacity(2);
+    for id in [from_id, to_id] {
+        match state.post_revision_service.get_by_id(id).await {
+            Ok(Some(revision)) => revisions.push(revision),
+            Ok(None) => {
+                return Err(ServerFnError::ServerError(
+                    CoreError::not_found("error.not_found").to_json(),
+                ))
+            }
+            Err(e) => return Err(ServerFnError::ServerError(e.to_json())),
+        }
+    }
+    let (from, to) = (&revisions[0], &revisions[1]);
+    Ok(RevisionDiffTO {
+        title: diff_lines(&from.title, &to.title),
+        summary: diff_lines(&from.summary, &to.summary),
+        content: diff_lines(&from.content, &to.content),
+        status: diff_lines(from.status.as_str(), to.status.as_str()),
+    })
+}
+
+/// Puts the post back the way it was at `revision_id`, recording the result
+/// as a new revision.
+#[server(name=RestorePostRevision, prefix="/load", endpoint="/posts/revisions/restore")]
+pub async fn restore_post_revision(revision_id: i32) -> Result<PostTO, ServerFnError> {
+    use crate::state::AppState;
+    use actix_web::web::Data;
+    use leptos_actix::extract;
+
+    crate::pages::rest::auth_api::require_admin().await?;
+    let state: Data<AppState> = extract().await?;
+    state
+        .post_revision_service
+        .restore(&state.post_service, revision_id)
+        .await
+        .map(PostTO::from)
+        .map_err(|e| ServerFnError::ServerError(e.to_json()))
+}
@@ -1,5 +1,6 @@
 use crate::business::post_type_service::{PostType, PostTypeCreate, PostTypeInfo};
 use crate::common::error::CoreError;
+#[cfg(feature = "ssr")]
 use crate::common::service::{Service, ViewService};
 use crate::{define_readonly_to_with_common_fields_fe, define_to_with_common_fields_fe};
 use leptos::prelude::ServerFnError;
@@ -57,6 +58,17 @@ pub async fn load_post_types(
         search,
         p_filters,
         a_filters: None,
+        after: None,
+        before: None,
+        q: None,
+        facets: None,
+        deleted: None,
+        group_by: None,
+        aggregates: None,
+    };
+    let filters = match q.to_filters() {
+        Ok(filters) => filters,
+        Err(e) => return Err(ServerFnError::ServerError(e.to_json())),
     };
     state
         .post_type_service
@@ -64,7 +76,7 @@ pub async fn load_post_types(
             q.to_sort_criteria(),
             q.first_result,
             q.max_results,
-            q.to_filters(),
+            filters,
         )
         .await
         .map(|v| v.into_iter().map(PostTypeTO::from).collect())
@@ -88,10 +100,21 @@ pub async fn count_post_types(
         search,
         p_filters,
         a_filters: None,
+        after: None,
+        before: None,
+        q: None,
+        facets: None,
+        deleted: None,
+        group_by: None,
+        aggregates: None,
+    };
+    let filters = match q.to_filters() {
+        Ok(filters) => filters,
+        Err(e) => return Err(ServerFnError::ServerError(e.to_json())),
     };
     state
         .post_type_service
-        .count(q.to_filters())
+        .count(filters)
         .await
         .map_err(|e| ServerFnError::ServerError(e.to_json()))
 }
@@ -211,6 +234,17 @@ pub async fn load_post_type_infos(
         search,
         p_filters,
         a_filters: None,
+        after: None,
+        before: None,
+        q: None,
+        facets: None,
+        deleted: None,
+        group_by: None,
+        aggregates: None,
+    };
+    let filters = match q.to_filters() {
+        Ok(filters) => filters,
+        Err(e) => return Err(ServerFnError::ServerError(e.to_json())),
     };
     state
         .post_type_info_service
@@ -218,13 +252,52 @@ pub async fn load_post_type_infos(
             q.to_sort_criteria(),
             q.first_result,
             q.max_results,
-            q.to_filters(),
+            filters,
         )
         .await
         .map(|v| v.into_iter().map(PostTypeInfoTO::from).collect())
         .map_err(|e| ServerFnError::ServerError(e.to_json()))
 }
 
+/// Autocomplete for post type pickers: post types whose code or name is similar to
+/// `query`, typos included. An empty query lists the first ones by name.
+#[server(name=SuggestPostTypeInfos, prefix="/load", endpoint="/post_types/info/suggest")]
+pub async fn suggest_post_type_infos(
+    query: String,
+    max_results: i32,
+) -> Result<Vec<PostTypeInfoTO>, ServerFnError> {
+    use crate::common::sort::SortCriterion;
+    use crate::state::AppState;
+    use actix_web::web::Data;
+    use leptos_actix::extract;
+
+    let state: Data<AppState> = extract().await?;
+    let max_results = Some(max_results.clamp(1, 50));
+    let service = &state.post_type_info_service;
+    let result = if query.trim().is_empty() {
+        service
+            .get_many(
+                vec![SortCriterion {
+                    field: "name".to_string(),
+                    ascending: true,
+                    is_attribute: false,
+                }],
+                Some(0),
+                max_results,
+                vec![],
+            )
+            .await
+    } else {
+        service
+            .suggest(query, max_results, vec![])
+            .await
+            .map(|suggestions| suggestions.into_iter().map(|s| s.entity).collect())
+    };
+    result
+        .map(|v| v.into_iter().map(PostTypeInfoTO::from).collect())
+        .map_err(|e| ServerFnError::ServerError(e.to_json()))
+}
+
 #[server(name=CountPostTypeInfos, prefix="/load", endpoint="/post_types/info/count")]
 pub async fn count_post_type_infos(
     search: Option<String>,
@@ -242,10 +315,21 @@ pub async fn count_post_type_infos(
         search,
         p_filters,
         a_filters: None,
+        after: None,
+        before: None,
+        q: None,
+        facets: None,
+        deleted: None,
+        group_by: None,
+        aggregates: None,
+    };
+    let filters = match q.to_filters() {
+        Ok(filters) => filters,
+        Err(e) => return Err(ServerFnError::ServerError(e.to_json())),
     };
     state
         .post_type_info_service
-        .count(q.to_filters())
+        .count(filters)
         .await
         .map_err(|e| ServerFnError::ServerError(e.to_json()))
 }
@@ -0,0 +1,150 @@
+use crate::business::slug_redirect_service::SlugRedirect;
+use crate::define_to_with_common_fields_fe;
+use leptos::prelude::ServerFnError;
+use leptos::*;
+
+define_to_with_common_fields_fe!(SlugRedirect {
+    pub entity_type: String,
+    pub scope_id: i32,
+    pub old_slug: String,
+    pub entity_id: Option<i32>,
+    pub target: Option<String>,
+    pub prefix: bool,
+});
+
+impl From<SlugRedirect> for SlugRedirectTO {
+    fn from(e: SlugRedirect) -> Self {
+        Self {
+            id: e.id,
+            uid: e.uid,
+            version: e.version,
+            created_at: e.created_at,
+            updated_at: e.updated_at,
+            entity_type: e.entity_type,
+            scope_id: e.scope_id,
+            old_slug: e.old_slug,
+            entity_id: e.entity_id,
+            target: e.target,
+            prefix: e.prefix,
+        }
+    }
+}
+
+/// Redirects entered by hand when `manual`, otherwise the old slugs of
+/// posts, terms and collections; newest first. `search` matches the
+/// redirected path or slug.
+#[server(name=LoadSlugRedirects, prefix="/load", endpoint="/slug_redirects")]
+pub async fn load_slug_redirects(
+    manual: bool,
+    search: Option<String>,
+    first_result: Option<i32>,
+    max_results: Option<i32>,
+) -> Result<Vec<SlugRedirectTO>, ServerFnError> {
+    use crate::business::slug_redirect_service::PATHS;
+    use crate::common::filter::{Filter, FilterOperator, FilterValue, ScalarValue};
+    use crate::common::service::ViewService;
+    use crate::common::sort::SortCriterion;
+    use crate::state::AppState;
+    use actix_web::web::Data;
+    use leptos_actix::extract;
+
+    crate::pages::rest::auth_api::require_admin().await?;
+    let state: Data<AppState> = extract().await?;
+    let mut filters = vec![Filter::Property {
+        property_name: "entity_type".to_string(),
+        operator: if manual {
+            FilterOperator::Equal
+        } else {
+            FilterOperator::NotEqual
+        },
+        value: FilterValue::Single(ScalarValue::String(PATHS.to_string())),
+    }];
+    if let Some(search) = search.filter(|s| !s.trim().is_empty()) {
+        filters.push(Filter::Property {
+            property_name: "old_slug".to_string(),
+            operator: FilterOperator::Like,
+            value: FilterValue::Single(ScalarValue::String(search.trim().to_string())),
+        });
+    }
+    state
+        .slug_redirect_service
+        .get_many(
+            vec![SortCriterion {
+                field: "updated_at".to_string(),
+                ascending: false,
+                is_attribute: false,
+            }],
+            first_result,
+            max_results,
+            filters,
+        )
+        .await
+        .map(|v| v.into_iter().map(SlugRedirectTO::from).collect())
+        .map_err(|e| ServerFnError::ServerError(e.to_json()))
+}
+
+/// Redirects `old_path` to `target`, a path of this site or an `http(s)`
+/// URL. With `prefix`, the paths below `old_path` go to the same place below
+/// `target`.
+#[server(name=CreateSlugRedirect, prefix="/load", endpoint="/slug_redirects/create")]
+pub async fn create_slug_redirect(
+    old_path: String,
+    target: String,
+    prefix: bool,
+) -> Result<SlugRedirectTO, ServerFnError> {
+    use crate::business::slug_redirect_service::{SlugRedirectCreate, PATHS};
+    use crate::common::service::Service;
+    use crate::state::AppState;
+    use actix_web::web::Data;
+    use leptos_actix::extract;
+
+    crate::pages::rest::auth_api::require_admin().await?;
+    let state: Data<AppState> = extract().await?;
+    let c = SlugRedirectCreate {
+        entity_type: PATHS.to_string(),
+        scope_id: 0,
+        old_slug: old_path,
+        entity_id: None,
+        target: Some(target),
+        prefix,
+    };
+    state
+        .slug_redirect_service
+        .create(&c)
+        .await
+        .map(SlugRedirectTO::from)
+        .map_err(|e| ServerFnError::ServerError(e.to_json()))
+}
+
+/// Removes a redirect; for an old slug, links using it stop working.
+#[server(name=DeleteSlugRedirectById, prefix="/load", endpoint="/slug_redirects/delete")]
+pub async fn delete_slug_redirect_by_id(id: i32) -> Result<u64, ServerFnError> {
+    use crate::common::service::Service;
+    use crate::state::AppState;
+    use actix_web::web::Data;
+    use leptos_actix::extract;
+
+    crate::pages::rest::auth_api::require_admin().await?;
+    let state: Data<AppState> = extract().await?;
+    state
+        .slug_redirect_service
+        .delete_by_id(id)
+        .await
+        .map_err(|e| ServerFnError::ServerError(e.to_json()))
+}
+
+/// Where `path` has moved, for pages not found after navigating in the
+/// browser; loading them from the server gets a 301 instead.
+#[server(name=ResolveRedirect, prefix="/load", endpoint="/slug_redirects/resolve")]
+pub async fn resolve_redirect(path: String) -> Result<Option<String>, ServerFnError> {
+    use crate::state::AppState;
+    use actix_web::web::Data;
+    use leptos_actix::extract;
+
+    let state: Data<AppState> = extract().await?;
+    state
+        .slug_redirect_service
+        .resolve(&path)
+        .await
+        .map_err(|e| ServerFnError::ServerError(e.to_json()))
+}
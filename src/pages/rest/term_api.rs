@@ -1,6 +1,8 @@
 use crate::business::term_service::{Term, TermCreate, TermInfo};
 use crate::common::error::CoreError;
+#[cfg(feature = "ssr")]
 use crate::common::service::{Service, ViewService};
+use crate::pages::rest::post_api::PostTO;
 use crate::{define_readonly_to_with_common_fields_fe, define_to_with_common_fields_fe};
 use leptos::prelude::ServerFnError;
 use leptos::*;
@@ -99,6 +101,17 @@ pub async fn load_terms(
         search,
         p_filters,
         a_filters: None,
+        after: None,
+        before: None,
+        q: None,
+        facets: None,
+        deleted: None,
+        group_by: None,
+        aggregates: None,
+    };
+    let filters = match q.to_filters() {
+        Ok(filters) => filters,
+        Err(e) => return Err(ServerFnError::ServerError(e.to_json())),
     };
     state
         .term_service
@@ -106,7 +119,7 @@ pub async fn load_terms(
             q.to_sort_criteria(),
             q.first_result,
             q.max_results,
-            q.to_filters(),
+            filters,
         )
         .await
         .map(|v| v.into_iter().map(TermTO::from).collect())
@@ -129,10 +142,21 @@ pub async fn count_terms(
         search,
         p_filters,
         a_filters: None,
+        after: None,
+        before: None,
+        q: None,
+        facets: None,
+        deleted: None,
+        group_by: None,
+        aggregates: None,
+    };
+    let filters = match q.to_filters() {
+        Ok(filters) => filters,
+        Err(e) => return Err(ServerFnError::ServerError(e.to_json())),
     };
     state
         .term_service
-        .count(q.to_filters())
+        .count(filters)
         .await
         .map_err(|e| ServerFnError::ServerError(e.to_json()))
 }
@@ -253,6 +277,17 @@ pub async fn load_term_infos(
         search,
         p_filters,
         a_filters: None,
+        after: None,
+        before: None,
+        q: None,
+        facets: None,
+        deleted: None,
+        group_by: None,
+        aggregates: None,
+    };
+    let filters = match q.to_filters() {
+        Ok(filters) => filters,
+        Err(e) => return Err(ServerFnError::ServerError(e.to_json())),
     };
     state
         .term_info_service
@@ -260,7 +295,7 @@ pub async fn load_term_infos(
             q.to_sort_criteria(),
             q.first_result,
             q.max_results,
-            q.to_filters(),
+            filters,
         )
         .await
         .map(|v| v.into_iter().map(TermInfoTO::from).collect())
@@ -284,10 +319,21 @@ pub async fn count_term_infos(
         search,
         p_filters,
         a_filters: None,
+        after: None,
+        before: None,
+        q: None,
+        facets: None,
+        deleted: None,
+        group_by: None,
+        aggregates: None,
+    };
+    let filters = match q.to_filters() {
+        Ok(filters) => filters,
+        Err(e) => return Err(ServerFnError::ServerError(e.to_json())),
     };
     state
         .term_info_service
-        .count(q.to_filters())
+        .count(filters)
         .await
         .map_err(|e| ServerFnError::ServerError(e.to_json()))
 }
@@ -321,3 +367,71 @@ pub async fn load_term_info_by_uid(uid: String) -> Result<TermInfoTO, ServerFnEr
         .map(TermInfoTO::from)
         .map_err(|e| ServerFnError::ServerError(e.to_json()))
 }
+
+/// A term and its published posts, newest first, as the term's page shows
+/// them.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct TermPageTO {
+    pub term: TermInfoTO,
+    pub posts: Vec<PostTO>,
+}
+
+/// The term with slug `slug` in the taxonomy with code `taxonomy`, and a
+/// page of its published posts.
+#[server(name=LoadTermPage, prefix="/load", endpoint="/terms/page")]
+pub async fn load_term_page(
+    taxonomy: String,
+    slug: String,
+    first_result: i32,
+    max_results: i32,
+) -> Result<TermPageTO, ServerFnError> {
+    use crate::business::post_service::PostStatus;
+    use crate::common::filter::{Filter, FilterOperator, FilterValue, ScalarValue};
+    use crate::common::sort::SortCriterion;
+    use crate::state::AppState;
+    use actix_web::web::Data;
+    use leptos_actix::extract;
+    let state: Data<AppState> = extract().await?;
+    let equals = |name: &str, value: ScalarValue| Filter::Property {
+        property_name: name.to_string(),
+        operator: FilterOperator::Equal,
+        value: FilterValue::Single(value),
+    };
+    let found = state
+        .term_info_service
+        .get_all(vec![
+            equals("taxonomy_code", ScalarValue::String(taxonomy)),
+            equals("slug", ScalarValue::String(slug)),
+        ])
+        .await;
+    let term = match found.map(|v| v.into_iter().next()) {
+        Ok(Some(term)) => term,
+        Ok(None) => {
+            return Err(ServerFnError::ServerError(
+                CoreError::not_found("error.not_found").to_json(),
+            ))
+        }
+        Err(e) => return Err(ServerFnError::ServerError(e.to_json())),
+    };
+    let newest_first = vec![SortCriterion {
+        field: "published_at".to_string(),
+        ascending: false,
+        is_attribute: false,
+    }];
+    let published = equals("status", ScalarValue::Int(PostStatus::PUBLISHED.as_i32()));
+    state
+        .post_term_service
+        .get_posts(
+            term.id,
+            newest_first,
+            Some(first_result.max(0)),
+            Some(max_results.clamp(1, 50)),
+            vec![published],
+        )
+        .await
+        .map(|posts| TermPageTO {
+            term: TermInfoTO::from(term),
+            posts: posts.into_iter().map(PostTO::from).collect(),
+        })
+        .map_err(|e| ServerFnError::ServerError(e.to_json()))
+}
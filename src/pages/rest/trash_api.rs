@@ -0,0 +1,145 @@
+use leptos::prelude::ServerFnError;
+use leptos::*;
+use serde::{Deserialize, Serialize};
+
+/// Entities with a trash, as `(key, label)`; the key is what the trash server
+/// functions take as `entity`.
+pub const TRASH_ENTITIES: [(&str, &str); 7] = [
+    ("posts", "Posts"),
+    ("post_types", "Post Types"),
+    ("post_collections", "Post Collections"),
+    ("post_taxonomies", "Taxonomies"),
+    ("terms", "Terms"),
+    ("attributes", "Attributes"),
+    ("users", "Users"),
+];
+
+/// A soft-deleted row, labelled with its title, name or username. Listed most
+/// recently deleted first.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TrashItemTO {
+    pub id: i32,
+    pub label: String,
+}
+
+#[cfg(feature = "ssr")]
+fn unknown_entity(entity: &str) -> ServerFnError {
+    use crate::common::error::CoreError;
+    use std::collections::HashMap;
+
+    ServerFnError::ServerError(
+        CoreError::BadRequest(
+            "error.trash.unknown_entity",
+            HashMap::from([("entity".to_string(), entity.to_string())]),
+        )
+        .to_json(),
+    )
+}
+
+#[cfg(feature = "ssr")]
+async fn load_trashed<S, F>(
+    service: &S,
+    first_result: Option<i32>,
+    max_results: Option<i32>,
+    label: F,
+) -> Result<Vec<TrashItemTO>, ServerFnError>
+where
+    S: crate::common::service::ViewService,
+    S::Entity: crate::common::service::Entity,
+    F: Fn(&S::Entity) -> String,
+{
+    use crate::common::filter::{Filter, SoftDeleteScope};
+    use crate::common::service::Entity;
+    use crate::common::sort::SortCriterion;
+
+    service
+        .get_many(
+            vec![SortCriterion {
+                field: "deleted_at".to_string(),
+                ascending: false,
+                is_attribute: false,
+            }],
+            first_result,
+            max_results,
+            vec![Filter::SoftDeleted(SoftDeleteScope::Only)],
+        )
+        .await
+        .map(|items| {
+            items
+                .iter()
+                .map(|e| TrashItemTO {
+                    id: e.id(),
+                    label: label(e),
+                })
+                .collect()
+        })
+        .map_err(|e| ServerFnError::ServerError(e.to_json()))
+}
+
+#[server(name=LoadTrash, prefix="/load", endpoint="/trash")]
+pub async fn load_trash(
+    entity: String,
+    first_result: Option<i32>,
+    max_results: Option<i32>,
+) -> Result<Vec<TrashItemTO>, ServerFnError> {
+    use crate::state::AppState;
+    use actix_web::web::Data;
+    use leptos_actix::extract;
+
+    crate::pages::rest::auth_api::require_admin().await?;
+    let state: Data<AppState> = extract().await?;
+    match entity.as_str() {
+        "posts" => load_trashed(&state.post_service, first_result, max_results, |e| e.title.clone()).await,
+        "post_types" => load_trashed(&state.post_type_service, first_result, max_results, |e| e.name.clone()).await,
+        "post_collections" => load_trashed(&state.post_collection_service, first_result, max_results, |e| e.title.clone()).await,
+        "post_taxonomies" => load_trashed(&state.post_taxonomy_service, first_result, max_results, |e| e.name.clone()).await,
+        "terms" => load_trashed(&state.term_service, first_result, max_results, |e| e.name.clone()).await,
+        "attributes" => load_trashed(&state.attribute_service, first_result, max_results, |e| e.name.clone()).await,
+        "users" => load_trashed(&state.user_service, first_result, max_results, |e| e.username.clone()).await,
+        _ => Err(unknown_entity(&entity)),
+    }
+}
+
+#[server(name=RestoreFromTrash, prefix="/load", endpoint="/trash/restore")]
+pub async fn restore_from_trash(entity: String, id: i32) -> Result<u64, ServerFnError> {
+    use crate::common::service::Service;
+    use crate::state::AppState;
+    use actix_web::web::Data;
+    use leptos_actix::extract;
+
+    crate::pages::rest::auth_api::require_admin().await?;
+    let state: Data<AppState> = extract().await?;
+    let result = match entity.as_str() {
+        "posts" => state.post_service.restore_by_id(id).await,
+        "post_types" => state.post_type_service.restore_by_id(id).await,
+        "post_collections" => state.post_collection_service.restore_by_id(id).await,
+        "post_taxonomies" => state.post_taxonomy_service.restore_by_id(id).await,
+        "terms" => state.term_service.restore_by_id(id).await,
+        "attributes" => state.attribute_service.restore_by_id(id).await,
+        "users" => state.user_service.restore_by_id(id).await,
+        _ => return Err(unknown_entity(&entity)),
+    };
+    result.map_err(|e| ServerFnError::ServerError(e.to_json()))
+}
+
+#[server(name=PurgeFromTrash, prefix="/load", endpoint="/trash/purge")]
+pub async fn purge_from_trash(entity: String, id: i32) -> Result<u64, ServerFnError> {
+    use crate::common::service::Service;
+    use crate::state::AppState;
+    use actix_web::web::Data;
+    use leptos_actix::extract;
+
+    crate::pages::rest::auth_api::require_admin().await?;
+    let state: Data<AppState> = extract().await?;
+    let result = match entity.as_str() {
+        "posts" => state.post_service.purge_by_id(id).await,
+        "post_types" => state.post_type_service.purge_by_id(id).await,
+        "post_collections" => state.post_collection_service.purge_by_id(id).await,
+        "post_taxonomies" => state.post_taxonomy_service.purge_by_id(id).await,
+        "terms" => state.term_service.purge_by_id(id).await,
+        "attributes" => state.attribute_service.purge_by_id(id).await,
+        "users" => state.user_service.purge_by_id(id).await,
+        _ => return Err(unknown_entity(&entity)),
+    };
+    result.map_err(|e| ServerFnError::ServerError(e.to_json()))
+}
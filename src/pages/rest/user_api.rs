@@ -1,5 +1,6 @@
 use crate::business::user_service::{User, UserCreate, UserInfo, UserRole};
 use crate::common::error::CoreError;
+#[cfg(feature = "ssr")]
 use crate::common::service::{Service, ViewService};
 use crate::{define_readonly_to_with_common_fields_fe, define_to_with_common_fields_fe};
 use leptos::prelude::ServerFnError;
@@ -39,6 +40,7 @@ impl From<UserTO> for User {
     fn from(to: UserTO) -> Self {
         let role = match to.role.as_str() {
             "ADMIN" => UserRole::ADMIN,
+            "EDITOR" => UserRole::EDITOR,
             _ => UserRole::USER,
         };
         Self {
@@ -90,6 +92,17 @@ pub async fn load_users(
         search,
         p_filters,
         a_filters: None,
+        after: None,
+        before: None,
+        q: None,
+        facets: None,
+        deleted: None,
+        group_by: None,
+        aggregates: None,
+    };
+    let filters = match query.to_filters() {
+        Ok(filters) => filters,
+        Err(e) => return Err(ServerFnError::ServerError(e.to_json())),
     };
     state
         .user_service
@@ -97,7 +110,7 @@ pub async fn load_users(
             query.to_sort_criteria(),
             query.first_result,
             query.max_results,
-            query.to_filters(),
+            filters,
         )
         .await
         .map(|v| v.into_iter().map(UserTO::from).collect())
@@ -121,10 +134,21 @@ pub async fn count_users(
         search,
         p_filters,
         a_filters: None,
+        after: None,
+        before: None,
+        q: None,
+        facets: None,
+        deleted: None,
+        group_by: None,
+        aggregates: None,
+    };
+    let filters = match query.to_filters() {
+        Ok(filters) => filters,
+        Err(e) => return Err(ServerFnError::ServerError(e.to_json())),
     };
     state
         .user_service
-        .count(query.to_filters())
+        .count(filters)
         .await
         .map_err(|e| ServerFnError::ServerError(e.to_json()))
 }
@@ -245,6 +269,17 @@ pub async fn load_user_infos(
         search,
         p_filters,
         a_filters: None,
+        after: None,
+        before: None,
+        q: None,
+        facets: None,
+        deleted: None,
+        group_by: None,
+        aggregates: None,
+    };
+    let filters = match query.to_filters() {
+        Ok(filters) => filters,
+        Err(e) => return Err(ServerFnError::ServerError(e.to_json())),
     };
     state
         .user_info_service
@@ -252,7 +287,7 @@ pub async fn load_user_infos(
             query.to_sort_criteria(),
             query.first_result,
             query.max_results,
-            query.to_filters(),
+            filters,
         )
         .await
         .map(|v| v.into_iter().map(UserInfoTO::from).collect())
@@ -276,10 +311,21 @@ pub async fn count_user_infos(
         search,
         p_filters,
         a_filters: None,
+        after: None,
+        before: None,
+        q: None,
+        facets: None,
+        deleted: None,
+        group_by: None,
+        aggregates: None,
+    };
+    let filters = match query.to_filters() {
+        Ok(filters) => filters,
+        Err(e) => return Err(ServerFnError::ServerError(e.to_json())),
     };
     state
         .user_info_service
-        .count(query.to_filters())
+        .count(filters)
         .await
         .map_err(|e| ServerFnError::ServerError(e.to_json()))
 }
@@ -0,0 +1,182 @@
+use crate::common::search::{split_headline, SearchHit};
+use crate::pages::components::{
+    Pagination, PaginationContent, PaginationItem, PaginationNext, PaginationPrevious,
+};
+use crate::pages::rest::post_api::{search_posts, suggest_posts, PostTO};
+use leptos::prelude::*;
+use leptos_router::components::A;
+use leptos_router::hooks::use_query_map;
+
+const PAGE_SIZE: i32 = 10;
+
+#[component]
+pub fn SearchPage() -> impl IntoView {
+    let query = use_query_map();
+    let search_text = move || query.with(|q| q.get("q").unwrap_or_default());
+    let first_result = move || {
+        query.with(|q| {
+            q.get("first_result")
+                .and_then(|p| p.parse::<i32>().ok())
+                .unwrap_or(0)
+                .max(0)
+        })
+    };
+
+    let hits_resource = Resource::new(
+        move || (search_text(), first_result()),
+        |(text, first_result)| async move {
+            if text.trim().is_empty() {
+                Ok(vec![])
+            } else {
+                search_posts(text, first_result, PAGE_SIZE).await
+            }
+        },
+    );
+
+    let page_href = move |first: i32| {
+        format!(
+            "/search?q={}&first_result={}",
+            encode_query_value(&search_text()),
+            first
+        )
+    };
+
+    view! {
+        <div class="container-page py-10">
+            <h1 class="text-3xl font-serif font-semibold mb-6">Tìm kiếm</h1>
+
+            <form method="get" action="/search" class="flex gap-2 mb-8">
+                <input
+                    type="search"
+                    name="q"
+                    prop:value=search_text
+                    placeholder="Tìm bài viết… (\"cụm từ chính xác\")"
+                    class="flex-1 rounded-lg border border-stone-300 px-3 py-2"
+                />
+                <button type="submit" class="px-4 py-2 rounded-lg bg-stone-900 text-white">Tìm</button>
+            </form>
+
+            <Suspense fallback=move || view! { <div class="text-center py-8">Đang tìm...</div> }>
+                {move || match hits_resource.get() {
+                    Some(Ok(hits)) if hits.is_empty() => {
+                        if search_text().trim().is_empty() {
+                            ().into_any()
+                        } else {
+                            view! {
+                                <p class="text-stone-600">Không tìm thấy bài viết nào.</p>
+                                <DidYouMean query=search_text() />
+                            }.into_any()
+                        }
+                    }
+                    Some(Ok(hits)) => {
+                        let first = first_result();
+                        let has_next = hits.len() as i32 == PAGE_SIZE;
+                        view! {
+                            <div class="space-y-6">
+                                {hits.into_iter().map(|hit| view! { <SearchResult hit=hit/> }).collect_view()}
+                            </div>
+                            <Pagination class="mt-8">
+                                <PaginationContent>
+                                    <PaginationItem>
+                                        {if first > 0 {
+                                            view! { <PaginationPrevious href=page_href((first - PAGE_SIZE).max(0)) /> }.into_any()
+                                        } else {
+                                            view! { <PaginationPrevious /> }.into_any()
+                                        }}
+                                    </PaginationItem>
+                                    <PaginationItem>
+                                        {if has_next {
+                                            view! { <PaginationNext href=page_href(first + PAGE_SIZE) /> }.into_any()
+                                        } else {
+                                            view! { <PaginationNext /> }.into_any()
+                                        }}
+                                    </PaginationItem>
+                                </PaginationContent>
+                            </Pagination>
+                        }.into_any()
+                    }
+                    Some(Err(e)) => view! {
+                        <div class="text-red-600">Error searching posts: {e.to_string()}</div>
+                    }.into_any(),
+                    None => view! { <div class="text-center py-8">Đang tìm...</div> }.into_any(),
+                }}
+            </Suspense>
+        </div>
+    }
+}
+
+#[component]
+fn SearchResult(hit: SearchHit<PostTO>) -> impl IntoView {
+    let post = hit.entity;
+    view! {
+        <article class="p-6 bg-white rounded-2xl border border-stone-200 shadow-sm/20">
+            <A
+                href=format!("/articles/{}", post.slug)
+                attr:class="text-xl font-semibold hover:text-stone-900 transition-colors"
+            >
+                {post.title}
+            </A>
+            <p class="mt-2 text-stone-700 leading-7">
+                {split_headline(&hit.headline)
+                    .into_iter()
+                    .map(|(text, highlighted)| {
+                        if highlighted {
+                            view! { <mark class="bg-amber-100 rounded px-0.5">{text}</mark> }.into_any()
+                        } else {
+                            view! { <span>{text}</span> }.into_any()
+                        }
+                    })
+                    .collect_view()}
+            </p>
+        </article>
+    }
+}
+
+/// Posts with a title close to a query that found nothing, e.g. a misspelling.
+#[component]
+fn DidYouMean(query: String) -> impl IntoView {
+    let suggestions_res = Resource::new(
+        move || query.clone(),
+        |query| async move { suggest_posts(query, 5).await },
+    );
+
+    view! {
+        <Suspense fallback=|| ()>
+            {move || match suggestions_res.get() {
+                Some(Ok(suggestions)) if !suggestions.is_empty() => view! {
+                    <div class="mt-4 text-stone-700">
+                        <span>Có phải bạn muốn tìm: </span>
+                        <ul class="mt-2 space-y-1">
+                            {suggestions.into_iter().map(|suggestion| {
+                                let post = suggestion.entity;
+                                view! {
+                                    <li>
+                                        <A
+                                            href=format!("/articles/{}", post.slug)
+                                            attr:class="font-medium underline hover:text-stone-900"
+                                        >
+                                            {post.title}
+                                        </A>
+                                    </li>
+                                }
+                            }).collect_view()}
+                        </ul>
+                    </div>
+                }.into_any(),
+                _ => ().into_any(),
+            }}
+        </Suspense>
+    }
+}
+
+fn encode_query_value(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
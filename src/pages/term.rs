@@ -0,0 +1,53 @@
+use crate::pages::not_found::NotFoundPage;
+use crate::pages::rest::term_api::load_term_page;
+use leptos::prelude::*;
+use leptos_router::components::A;
+use leptos_router::hooks::use_params_map;
+
+/// A term and its newest published posts, at `/terms/:code/:slug`.
+#[component]
+pub fn TermPage() -> impl IntoView {
+    let params = use_params_map();
+    let param = move |name: &str| params.with(|p| p.get(name).unwrap_or_default());
+
+    let term_resource = Resource::new(
+        move || (param("code"), param("slug")),
+        |(code, slug)| async move { load_term_page(code, slug, 0, 50).await },
+    );
+
+    view! {
+        <div class="container-page py-10">
+            <Suspense fallback=move || view! {<div class="text-center py-8">Loading...</div>}>
+                {move || match term_resource.get() {
+                    Some(Ok(page)) => view! {
+                        <p class="text-sm text-stone-500 mb-1">{page.term.taxonomy_name}</p>
+                        <h1 class="text-3xl font-serif font-semibold mb-4">{page.term.name}</h1>
+                        <p class="text-stone-700 mb-8 leading-7">
+                            {page.term.description.unwrap_or_default()}
+                        </p>
+                        <ul class="space-y-4">
+                            {page.posts.into_iter()
+                                .map(|post| view! {
+                                    <li>
+                                        <A
+                                            href=format!("/articles/{}", post.slug)
+                                            attr:class="text-xl font-semibold hover:text-stone-900"
+                                        >
+                                            {post.title}
+                                        </A>
+                                        <p class="text-stone-700 leading-7">{post.summary}</p>
+                                    </li>
+                                })
+                                .collect_view()
+                            }
+                        </ul>
+                    }.into_any(),
+                    Some(Err(_)) => view! { <NotFoundPage/> }.into_any(),
+                    None => view! {
+                        <div class="text-center py-8">Loading...</div>
+                    }.into_any()
+                }}
+            </Suspense>
+        </div>
+    }
+}
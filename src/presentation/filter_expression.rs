@@ -0,0 +1,347 @@
+use crate::common::error::CoreError;
+use crate::common::filter::Filter;
+use crate::presentation::query_options::QueryOptions;
+use std::collections::HashMap;
+
+/// Parses a boolean filter expression, as passed in the `q` query parameter.
+///
+/// ```text
+/// expr  := and ("OR" and)*
+/// and   := unary ("AND" unary)*
+/// unary := "NOT" unary | "(" expr ")" | term
+/// term  := "p:" filter | "a:" filter | "s:" keywords | "f:" keywords
+///        | "t:" taxonomy [":" slug]
+/// ```
+///
+/// `filter` uses the same `key:op:value:dtype` form as `p_filters` / `a_filters`;
+/// `f:` is a fuzzy search, which tolerates misspelled keywords. `t:` matches
+/// posts by their terms: `t:tag:rust`, any tag with `t:tag`, or any taxonomy
+/// with `t::rust`.
+/// Keywords are case-insensitive; a term containing spaces or parentheses must be
+/// double-quoted (`\"` and `\\` escape inside quotes), e.g.
+/// `p:status:=:published:0 AND (a:rating:>=:4:1 OR NOT "s:rust async")`.
+/// Groups and `NOT`s nest at most [`MAX_DEPTH`] deep.
+pub fn parse_filter_expression(raw: &str) -> Result<Filter, CoreError> {
+    let tokens = tokenize(raw)?;
+    let mut parser = Parser {
+        raw,
+        tokens,
+        pos: 0,
+        depth: 0,
+    };
+    let filter = parser.parse_or()?;
+    if parser.pos != parser.tokens.len() {
+        return Err(invalid(raw));
+    }
+    Ok(filter)
+}
+
+/// Deepest nesting of groups and `NOT`s an expression may have, so that
+/// parsing it and building its SQL cannot overflow the stack.
+pub const MAX_DEPTH: usize = 32;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    LParen,
+    RParen,
+    And,
+    Or,
+    Not,
+    Term(String),
+}
+
+fn invalid(raw: &str) -> CoreError {
+    CoreError::UnprocessableEntity(
+        "error.filters.invalid.expression",
+        HashMap::from([("expression".to_string(), raw.to_string())]),
+    )
+}
+
+fn tokenize(raw: &str) -> Result<Vec<Token>, CoreError> {
+    let mut tokens = vec![];
+    let mut chars = raw.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '(' => {
+                chars.next();
+                tokens.push(Token::LParen);
+            }
+            ')' => {
+                chars.next();
+                tokens.push(Token::RParen);
+            }
+            '"' => {
+                chars.next();
+                let mut term = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(escaped) => term.push(escaped),
+                            None => return Err(invalid(raw)),
+                        },
+                        Some(other) => term.push(other),
+                        None => return Err(invalid(raw)),
+                    }
+                }
+                tokens.push(Token::Term(term));
+            }
+            _ => {
+                let mut word = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || c == '(' || c == ')' {
+                        break;
+                    }
+                    word.push(c);
+                    chars.next();
+                }
+                tokens.push(match word.to_ascii_uppercase().as_str() {
+                    "AND" => Token::And,
+                    "OR" => Token::Or,
+                    "NOT" => Token::Not,
+                    _ => Token::Term(word),
+                });
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser<'r> {
+    raw: &'r str,
+    tokens: Vec<Token>,
+    pos: usize,
+    depth: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn parse_or(&mut self) -> Result<Filter, CoreError> {
+        let mut operands = vec![self.parse_and()?];
+        while self.peek() == Some(&Token::Or) {
+            self.pos += 1;
+            operands.push(self.parse_and()?);
+        }
+        Ok(if operands.len() == 1 {
+            operands.remove(0)
+        } else {
+            Filter::Or(operands)
+        })
+    }
+
+    fn parse_and(&mut self) -> Result<Filter, CoreError> {
+        let mut operands = vec![self.parse_unary()?];
+        while self.peek() == Some(&Token::And) {
+            self.pos += 1;
+            operands.push(self.parse_unary()?);
+        }
+        Ok(if operands.len() == 1 {
+            operands.remove(0)
+        } else {
+            Filter::And(operands)
+        })
+    }
+
+    fn parse_unary(&mut self) -> Result<Filter, CoreError> {
+        match self.next() {
+            Some(Token::Not) => {
+                let filter = self.nested(Self::parse_unary)?;
+                Ok(Filter::Not(Box::new(filter)))
+            }
+            Some(Token::LParen) => {
+                let filter = self.nested(Self::parse_or)?;
+                match self.next() {
+                    Some(Token::RParen) => Ok(filter),
+                    _ => Err(invalid(self.raw)),
+                }
+            }
+            Some(Token::Term(term)) => parse_term(&term),
+            _ => Err(invalid(self.raw)),
+        }
+    }
+
+    fn nested(
+        &mut self,
+        parse: fn(&mut Self) -> Result<Filter, CoreError>,
+    ) -> Result<Filter, CoreError> {
+        if self.depth == MAX_DEPTH {
+            return Err(CoreError::UnprocessableEntity(
+                "error.filters.too.deep",
+                HashMap::from([("max_depth".to_string(), MAX_DEPTH.to_string())]),
+            ));
+        }
+        self.depth += 1;
+        let filter = parse(self);
+        self.depth -= 1;
+        filter
+    }
+}
+
+fn parse_term(term: &str) -> Result<Filter, CoreError> {
+    if let Some(filter) = term.strip_prefix("p:") {
+        QueryOptions::parse_single_filter(filter, true)
+    } else if let Some(filter) = term.strip_prefix("a:") {
+        QueryOptions::parse_single_filter(filter, false)
+    } else if let Some(keywords) = term.strip_prefix("s:") {
+        Ok(Filter::Search {
+            value: keywords.trim().to_string(),
+            fuzzy: false,
+        })
+    } else if let Some(keywords) = term.strip_prefix("f:") {
+        Ok(Filter::Search {
+            value: keywords.trim().to_string(),
+            fuzzy: true,
+        })
+    } else if let Some(post_term) = term.strip_prefix("t:") {
+        let (taxonomy, slug) = post_term.split_once(':').unwrap_or((post_term, ""));
+        let part = |s: &str| Some(s.trim().to_string()).filter(|s| !s.is_empty());
+        Ok(Filter::Term {
+            taxonomy: part(taxonomy),
+            slug: part(slug),
+        })
+    } else {
+        Err(CoreError::UnprocessableEntity(
+            "error.filters.invalid.term",
+            HashMap::from([("term".to_string(), term.to_string())]),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::filter::{FilterOperator, FilterValue, ScalarValue};
+
+    fn property(name: &str, operator: FilterOperator, value: ScalarValue) -> Filter {
+        Filter::Property {
+            property_name: name.to_string(),
+            operator,
+            value: FilterValue::Single(value),
+        }
+    }
+
+    #[test]
+    fn test_parse_filter_expression_precedence() {
+        let filter =
+            parse_filter_expression("p:id:=:1:1 or p:id:=:2:1 AND not p:title:~:rust:0").unwrap();
+        assert_eq!(
+            filter,
+            Filter::Or(vec![
+                property("id", FilterOperator::Equal, ScalarValue::Int(1)),
+                Filter::And(vec![
+                    property("id", FilterOperator::Equal, ScalarValue::Int(2)),
+                    Filter::Not(Box::new(property(
+                        "title",
+                        FilterOperator::Like,
+                        ScalarValue::String("rust".into())
+                    ))),
+                ]),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_parse_filter_expression_groups_and_quotes() {
+        let filter = parse_filter_expression(
+            r#"(p:id:>:1:1 OR "a:tag:=:a (b):0") AND "s:rust \"async\"" AND f:leptso"#,
+        )
+        .unwrap();
+        assert_eq!(
+            filter,
+            Filter::And(vec![
+                Filter::Or(vec![
+                    property("id", FilterOperator::GreaterThan, ScalarValue::Int(1)),
+                    Filter::Attribute {
+                        attr_name: "tag".into(),
+                        operator: FilterOperator::Equal,
+                        value: FilterValue::Single(ScalarValue::String("a (b)".into())),
+                    },
+                ]),
+                Filter::Search {
+                    value: "rust \"async\"".into(),
+                    fuzzy: false,
+                },
+                Filter::Search {
+                    value: "leptso".into(),
+                    fuzzy: true,
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn test_parse_filter_expression_rejects_malformed_input() {
+        assert!(parse_filter_expression("").is_err());
+        assert!(parse_filter_expression("(p:id:=:1:1").is_err());
+        assert!(parse_filter_expression("p:id:=:1:1 OR").is_err());
+        assert!(parse_filter_expression("p:id:=:1:1 p:id:=:2:1").is_err());
+        assert!(parse_filter_expression("x:id:=:1:1").is_err());
+        assert!(parse_filter_expression("\"p:id:=:1:1").is_err());
+    }
+
+    #[test]
+    fn test_parse_filter_expression_terms() {
+        let term = |taxonomy: Option<&str>, slug: Option<&str>| Filter::Term {
+            taxonomy: taxonomy.map(str::to_string),
+            slug: slug.map(str::to_string),
+        };
+        assert_eq!(
+            parse_filter_expression("t:tag:rust OR t:category").unwrap(),
+            Filter::Or(vec![
+                term(Some("tag"), Some("rust")),
+                term(Some("category"), None)
+            ])
+        );
+        assert_eq!(
+            parse_filter_expression("NOT t::rust").unwrap(),
+            Filter::Not(Box::new(term(None, Some("rust"))))
+        );
+    }
+
+    #[test]
+    fn test_parse_filter_expression_caps_nesting() {
+        let nested = |depth: usize| {
+            format!("{}p:id:=:1:1{}", "(NOT ".repeat(depth), ")".repeat(depth))
+        };
+        assert!(parse_filter_expression(&nested(MAX_DEPTH / 2)).is_ok());
+        assert!(matches!(
+            parse_filter_expression(&nested(MAX_DEPTH)),
+            Err(CoreError::UnprocessableEntity("error.filters.too.deep", _))
+        ));
+        assert!(parse_filter_expression(&"NOT ".repeat(100_000)).is_err());
+    }
+
+    #[test]
+    fn test_to_filters_rejects_malformed_filters() {
+        let query = |p_filter: &str, q: &str| QueryOptions {
+            p_filters: Some(vec![p_filter.to_string()]),
+            q: Some(q.to_string()),
+            first_result: None,
+            max_results: None,
+            sort: None,
+            a_filters: None,
+            search: None,
+            after: None,
+            before: None,
+            facets: None,
+            deleted: None,
+            group_by: None,
+            aggregates: None,
+        };
+        assert_eq!(query("id:=:1:1", "p:id:=:2:1").to_filters().unwrap().len(), 2);
+        assert!(query("id", "p:id:=:2:1").to_filters().is_err());
+        assert!(query("id:=:1:1", "p:id:=:2:1 AND").to_filters().is_err());
+    }
+}
@@ -1,4 +1,5 @@
 mod error;
+pub mod filter_expression;
 pub mod macros;
 pub mod query_options;
 pub mod rest;
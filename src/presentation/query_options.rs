@@ -1,6 +1,11 @@
+use crate::common::aggregate::{Aggregate, AggregateGroup};
+use crate::common::cursor::{Cursor, CursorPosition};
 use crate::common::error::CoreError;
-use crate::common::filter::{Filter, FilterOperator, FilterValue, ScalarValue};
+use crate::common::facet::{Facet, FacetField};
+use crate::common::filter::{Filter, FilterOperator, FilterValue, ScalarValue, SoftDeleteScope};
+use crate::common::repository::WithAttributes;
 use crate::common::sort::SortCriterion;
+use crate::presentation::filter_expression::parse_filter_expression;
 use crate::value_data_type::ValueDataType;
 use serde::de::{MapAccess, Visitor};
 use serde::Deserializer as SerdeDeserializer;
@@ -62,6 +67,20 @@ pub struct QueryOptions {
     pub p_filters: Option<Vec<String>>,
     pub a_filters: Option<Vec<String>>,
     pub search: Option<String>,
+    /// Opaque keyset cursors; see `to_cursor_position`.
+    pub after: Option<String>,
+    pub before: Option<String>,
+    /// Boolean filter expression; see `parse_filter_expression`.
+    pub q: Option<String>,
+    /// `|`-separated facet fields, attributes prefixed like attribute sorts.
+    pub facets: Option<String>,
+    /// Soft-deleted rows to see: `only` (the trash) or `include`; live rows
+    /// only when unset.
+    pub deleted: Option<String>,
+    /// `|`-separated columns to group by for `aggregate`.
+    pub group_by: Option<String>,
+    /// `|`-separated aggregates such as `count|sum(views)|max(updated_at)`.
+    pub aggregates: Option<String>,
 }
 
 impl<'de> Deserialize<'de> for QueryOptions {
@@ -85,6 +104,13 @@ impl<'de> Deserialize<'de> for QueryOptions {
                 let mut search: Option<String> = None;
                 let mut p_filters: Option<Vec<String>> = None;
                 let mut a_filters: Option<Vec<String>> = None;
+                let mut after: Option<String> = None;
+                let mut before: Option<String> = None;
+                let mut q: Option<String> = None;
+                let mut facets: Option<String> = None;
+                let mut deleted: Option<String> = None;
+                let mut group_by: Option<String> = None;
+                let mut aggregates: Option<String> = None;
 
                 while let Some(key) = map.next_key::<String>()? {
                     match key.as_str() {
@@ -101,6 +127,27 @@ impl<'de> Deserialize<'de> for QueryOptions {
                         "search" => {
                             search = Some(map.next_value()?);
                         }
+                        "after" => {
+                            after = Some(map.next_value()?);
+                        }
+                        "before" => {
+                            before = Some(map.next_value()?);
+                        }
+                        "q" => {
+                            q = Some(map.next_value()?);
+                        }
+                        "facets" => {
+                            facets = Some(map.next_value()?);
+                        }
+                        "deleted" => {
+                            deleted = Some(map.next_value()?);
+                        }
+                        "group_by" => {
+                            group_by = Some(map.next_value()?);
+                        }
+                        "aggregates" => {
+                            aggregates = Some(map.next_value()?);
+                        }
                         "p_filters" => {
                             let v: StringOrVec = map.next_value()?;
                             let entry = p_filters.get_or_insert_with(Vec::new);
@@ -142,6 +189,13 @@ impl<'de> Deserialize<'de> for QueryOptions {
                     p_filters,
                     a_filters,
                     search,
+                    after,
+                    before,
+                    q,
+                    facets,
+                    deleted,
+                    group_by,
+                    aggregates,
                 })
             }
         }
@@ -156,30 +210,85 @@ impl QueryOptions {
             .map(|s| {
                 s.split('|')
                     .filter_map(|item| SortCriterion::from_str(item).ok())
+                    .filter(|criterion| !criterion.field.is_empty())
                     .collect::<Vec<_>>()
             })
             .unwrap_or(vec![SortCriterion {
                 field: "id".to_string(),
                 ascending: true,
+                is_attribute: false,
             }])
     }
 
-    pub fn to_filters(&self) -> Vec<Filter> {
+    pub fn to_facet_fields(&self) -> Vec<FacetField> {
+        self.facets
+            .as_deref()
+            .map(|s| {
+                s.split('|')
+                    .filter_map(|item| FacetField::from_str(item).ok())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn to_group_by(&self) -> Vec<String> {
+        self.group_by
+            .as_deref()
+            .map(|s| {
+                s.split('|')
+                    .map(str::trim)
+                    .filter(|field| !field.is_empty())
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Requested aggregates; a misspelled one is an error rather than dropped,
+    /// since its result would silently be missing.
+    pub fn to_aggregates(&self) -> Result<Vec<Aggregate>, CoreError> {
+        self.aggregates
+            .as_deref()
+            .map(|s| {
+                s.split('|')
+                    .filter(|item| !item.trim().is_empty())
+                    .map(|item| {
+                        Aggregate::from_str(item).map_err(|_| {
+                            CoreError::UnprocessableEntity(
+                                "error.aggregate.invalid",
+                                HashMap::from([("aggregate".into(), item.trim().to_string())]),
+                            )
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or(Ok(vec![]))
+    }
+
+    /// Keyset page requested by `after` / `before`; the first page when neither is set.
+    pub fn to_cursor_position(&self) -> Result<CursorPosition, CoreError> {
+        match (self.after.as_deref(), self.before.as_deref()) {
+            (None, None) => Ok(CursorPosition::First),
+            (Some(after), None) => Cursor::decode(after).map(CursorPosition::After),
+            (None, Some(before)) => Cursor::decode(before).map(CursorPosition::Before),
+            (Some(_), Some(_)) => Err(CoreError::bad_request("error.cursor.ambiguous")),
+        }
+    }
+
+    /// Requested filters; a malformed one is an error rather than dropped,
+    /// since the result would silently match more than asked for.
+    pub fn to_filters(&self) -> Result<Vec<Filter>, CoreError> {
         let mut filters = vec![];
 
         if let Some(p_filters) = &self.p_filters {
             for raw in p_filters {
-                if let Ok(f) = QueryOptions::parse_single_filter(raw, true) {
-                    filters.push(f);
-                }
+                filters.push(QueryOptions::parse_single_filter(raw, true)?);
             }
         }
 
         if let Some(a_filters) = &self.a_filters {
             for raw in a_filters {
-                if let Ok(f) = QueryOptions::parse_single_filter(raw, false) {
-                    filters.push(f);
-                }
+                filters.push(QueryOptions::parse_single_filter(raw, false)?);
             }
         }
 
@@ -188,12 +297,23 @@ impl QueryOptions {
             if !keyword.is_empty() {
                 filters.push(Filter::Search {
                     value: keyword.to_string(),
+                    fuzzy: false,
                 });
             }
         }
-        filters
+
+        if let Some(expression) = self.q.as_deref().filter(|q| !q.trim().is_empty()) {
+            filters.push(parse_filter_expression(expression)?);
+        }
+
+        match self.deleted.as_deref() {
+            Some("only") => filters.push(Filter::SoftDeleted(SoftDeleteScope::Only)),
+            Some("include") => filters.push(Filter::SoftDeleted(SoftDeleteScope::Include)),
+            _ => {}
+        }
+        Ok(filters)
     }
-    fn parse_single_filter(raw: &str, is_property: bool) -> Result<Filter, CoreError> {
+    pub(crate) fn parse_single_filter(raw: &str, is_property: bool) -> Result<Filter, CoreError> {
         // Implement split-based parsing per specification:
         // - If there are exactly 2 parts, it must be a null/not-null operator: key:operator
         // - Otherwise, take last as datatype, first two as key/operator, and the middle joined by ':' as value
@@ -255,6 +375,7 @@ impl QueryOptions {
             "<=" | "lte" => FilterOperator::LessThanOrEqual,
             "~" | "like" => FilterOperator::Like,
             "!~" | "not_like" | "!like" | "nlike" => FilterOperator::NotLike,
+            "%" | "similar" => FilterOperator::Similar,
             "[]" | "in" => FilterOperator::In,
             "![]" | "not_in" | "nin" => FilterOperator::NotIn,
             "=null" | "is_null" => FilterOperator::IsNull,
@@ -659,29 +780,104 @@ pub fn parse_column_values(
 pub fn serialize_column_values(
     map: &std::collections::HashMap<String, ScalarValue>,
 ) -> ColumnValuesDto {
+    map.iter()
+        .map(|(k, v)| (k.clone(), serialize_column_value(v)))
+        .collect()
+}
+
+pub fn serialize_column_value(value: &ScalarValue) -> ColumnValueDto {
     use ScalarValue as SV;
-    let mut out: ColumnValuesDto = std::collections::HashMap::new();
-    for (k, v) in map.iter() {
-        let (t, vstr) = match v {
-            SV::String(s) => (0u8, s.clone()),
-            SV::Int(i) => (1u8, i.to_string()),
-            SV::Float(f) => (2u8, f.to_string()),
-            SV::Bool(b) => (3u8, b.to_string()),
-            SV::Date(d) => (
-                4u8,
-                d.format(&format_description!("[year]-[month]-[day]"))
-                    .unwrap_or_default(),
-            ),
-            SV::DateTime(dt) => (5u8, dt.format(&Rfc3339).unwrap_or_default()),
-            SV::Time(ti) => (
-                6u8,
-                ti.format(&format_description!("[hour]:[minute]:[second]"))
-                    .unwrap_or_default(),
-            ),
+    let (t, v) = match value {
+        SV::String(s) => (0u8, s.clone()),
+        SV::Int(i) => (1u8, i.to_string()),
+        SV::Float(f) => (2u8, f.to_string()),
+        SV::Bool(b) => (3u8, b.to_string()),
+        SV::Date(d) => (
+            4u8,
+            d.format(&format_description!("[year]-[month]-[day]"))
+                .unwrap_or_default(),
+        ),
+        SV::DateTime(dt) => (5u8, dt.format(&Rfc3339).unwrap_or_default()),
+        SV::Time(ti) => (
+            6u8,
+            ti.format(&format_description!("[hour]:[minute]:[second]"))
+                .unwrap_or_default(),
+        ),
+    };
+    ColumnValueDto { t, v }
+}
+
+/// A transfer object with its attribute values alongside, in `ColumnValuesDto` form.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WithAttributesTO<T> {
+    #[serde(flatten)]
+    pub entity: T,
+    pub attributes: ColumnValuesDto,
+}
+
+impl<T> WithAttributesTO<T> {
+    pub fn from_entity<E, F: FnOnce(E) -> T>(value: WithAttributes<E>, mapper: F) -> Self {
+        Self {
+            attributes: serialize_column_values(&value.attributes),
+            entity: mapper(value.entity),
+        }
+    }
+}
+
+/// One group of an aggregation: group-by values by field and aggregate values
+/// by label, e.g. `sum(views)`; a value is absent where SQL gave NULL.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AggregateGroupTO {
+    pub key: HashMap<String, Option<ColumnValueDto>>,
+    pub count: i64,
+    pub values: HashMap<String, Option<ColumnValueDto>>,
+}
+
+impl From<AggregateGroup> for AggregateGroupTO {
+    fn from(group: AggregateGroup) -> Self {
+        let to_map = |pairs: Vec<(String, Option<ScalarValue>)>| {
+            pairs
+                .into_iter()
+                .map(|(name, value)| (name, value.as_ref().map(serialize_column_value)))
+                .collect()
         };
-        out.insert(k.clone(), ColumnValueDto { t, v: vstr });
+        Self {
+            key: to_map(group.key),
+            count: group.count,
+            values: to_map(group.values),
+        }
+    }
+}
+
+/// Value counts of one facet; `value` is absent for rows without one.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FacetTO {
+    pub field: String,
+    pub is_attribute: bool,
+    pub buckets: Vec<FacetBucketTO>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FacetBucketTO {
+    pub value: Option<ColumnValueDto>,
+    pub count: i64,
+}
+
+impl From<Facet> for FacetTO {
+    fn from(facet: Facet) -> Self {
+        Self {
+            field: facet.field.field,
+            is_attribute: facet.field.is_attribute,
+            buckets: facet
+                .buckets
+                .into_iter()
+                .map(|bucket| FacetBucketTO {
+                    value: bucket.value.as_ref().map(serialize_column_value),
+                    count: bucket.count,
+                })
+                .collect(),
+        }
     }
-    out
 }
 
 #[cfg(any())]
@@ -938,7 +1134,8 @@ mod tests {
                     value: FilterValue::String("giang".to_owned())
                 },
                 Filter::Search {
-                    value: "abc xyz".to_owned()
+                    value: "abc xyz".to_owned(),
+                    fuzzy: false
                 }
             ]
         );
@@ -1,7 +1,7 @@
 use crate::business::attribute_service::{Attribute, AttributeCreate, AttributeInfo};
 use crate::common::error::CoreError;
 use crate::common::service::{Service, ViewService};
-use crate::presentation::query_options::QueryOptions;
+use crate::presentation::query_options::{AggregateGroupTO, FacetTO, QueryOptions};
 use crate::presentation::rest::response_result::{respond_result, respond_results};
 use crate::state::AppState;
 use crate::{define_readonly_to_with_common_fields_be, define_to_with_common_fields_be};
@@ -11,9 +11,21 @@ use actix_web::{delete, get, post, put, Responder};
 
 // Table TO (CRUD)
 define_to_with_common_fields_be!(Attribute {
-    pub name: String,
-    pub entity_type: String,
-    pub data_type: String,
+    req {
+        pub name: String,
+        pub entity_type: String,
+        pub data_type: String,
+    }
+    opt {
+        #[serde(default)]
+        pub required: bool,
+        pub min_value: Option<f64>,
+        pub max_value: Option<f64>,
+        pub pattern: Option<String>,
+        #[serde(default)]
+        pub allowed_values: Vec<String>,
+        pub default_value: Option<String>,
+    }
 });
 
 // View TO (info)
@@ -34,6 +46,12 @@ impl From<AttributeTO> for Attribute {
             name: to.name,
             entity_type: to.entity_type,
             data_type: to.data_type,
+            required: to.required,
+            min_value: to.min_value,
+            max_value: to.max_value,
+            pattern: to.pattern,
+            allowed_values: to.allowed_values,
+            default_value: to.default_value,
         }
     }
 }
@@ -48,6 +66,12 @@ impl From<Attribute> for AttributeTO {
             name: entity.name,
             entity_type: entity.entity_type,
             data_type: entity.data_type,
+            required: entity.required,
+            min_value: entity.min_value,
+            max_value: entity.max_value,
+            pattern: entity.pattern,
+            allowed_values: entity.allowed_values,
+            default_value: entity.default_value,
         }
     }
 }
@@ -77,23 +101,64 @@ impl From<AttributeCreateTO> for AttributeCreate {
 
 #[get("")]
 pub async fn get_many(state: Data<AppState>, query: Query<QueryOptions>) -> impl Responder {
-    respond_results(
-        state
-            .attribute_service
-            .get_many(
-                query.to_sort_criteria(),
-                query.first_result,
-                query.max_results,
-                query.to_filters(),
-            )
-            .await,
-        AttributeTO::from,
-    )
+    let result = match query.to_filters() {
+        Ok(filters) => {
+            state
+                .attribute_service
+                .get_many(
+                    query.to_sort_criteria(),
+                    query.first_result,
+                    query.max_results,
+                    filters,
+                )
+                .await
+        }
+        Err(e) => Err(e),
+    };
+    respond_results(result, AttributeTO::from)
 }
 
 #[get("/count")]
 pub async fn count(state: Data<AppState>, query: Query<QueryOptions>) -> impl Responder {
-    respond_result(state.attribute_service.count(query.to_filters()).await)
+    let result = match query.to_filters() {
+        Ok(filters) => state.attribute_service.count(filters).await,
+        Err(e) => Err(e),
+    };
+    respond_result(result)
+}
+
+#[get("/facets")]
+pub async fn facets(state: Data<AppState>, query: Query<QueryOptions>) -> impl Responder {
+    let result = match query.to_filters() {
+        Ok(filters) => {
+            state
+                .attribute_service
+                .get_facets(query.to_facet_fields(), filters)
+                .await
+        }
+        Err(e) => Err(e),
+    };
+    respond_results(result, FacetTO::from)
+}
+
+#[get("/aggregate")]
+pub async fn aggregate(state: Data<AppState>, query: Query<QueryOptions>) -> impl Responder {
+    let result = match (query.to_aggregates(), query.to_filters()) {
+        (Ok(aggregates), Ok(filters)) => {
+            state
+                .attribute_service
+                .aggregate(
+                    query.to_group_by(),
+                    aggregates,
+                    query.first_result,
+                    query.max_results,
+                    filters,
+                )
+                .await
+        }
+        (Err(e), _) | (_, Err(e)) => Err(e),
+    };
+    respond_results(result, AggregateGroupTO::from)
 }
 
 #[get("/{id}")]
@@ -163,26 +228,43 @@ pub async fn delete_by_uid(state: Data<AppState>, uid: Path<String>) -> impl Res
     )
 }
 
+#[post("/{id}/restore")]
+pub async fn restore_by_id(state: Data<AppState>, id: Path<i32>) -> impl Responder {
+    respond_result(state.attribute_service.restore_by_id(id.into_inner()).await)
+}
+
+#[delete("/{id}/purge")]
+pub async fn purge_by_id(state: Data<AppState>, id: Path<i32>) -> impl Responder {
+    respond_result(state.attribute_service.purge_by_id(id.into_inner()).await)
+}
+
 // Info endpoints
 #[get("/info")]
 pub async fn get_many_info(state: Data<AppState>, query: Query<QueryOptions>) -> impl Responder {
-    respond_results(
-        state
-            .attribute_info_service
-            .get_many(
-                query.to_sort_criteria(),
-                query.first_result,
-                query.max_results,
-                query.to_filters(),
-            )
-            .await,
-        AttributeInfoTO::from,
-    )
+    let result = match query.to_filters() {
+        Ok(filters) => {
+            state
+                .attribute_info_service
+                .get_many(
+                    query.to_sort_criteria(),
+                    query.first_result,
+                    query.max_results,
+                    filters,
+                )
+                .await
+        }
+        Err(e) => Err(e),
+    };
+    respond_results(result, AttributeInfoTO::from)
 }
 
 #[get("/info/count")]
 pub async fn count_info(state: Data<AppState>, query: Query<QueryOptions>) -> impl Responder {
-    respond_result(state.attribute_info_service.count(query.to_filters()).await)
+    let result = match query.to_filters() {
+        Ok(filters) => state.attribute_info_service.count(filters).await,
+        Err(e) => Err(e),
+    };
+    respond_result(result)
 }
 
 #[get("/{id}/info")]
@@ -214,12 +296,16 @@ pub fn routes_attributes(cfg: &mut ServiceConfig) {
         scope("/api/attributes")
             .service(get_many)
             .service(count)
+            .service(facets)
+            .service(aggregate)
             .service(get_by_id)
             .service(get_by_uid)
             .service(create)
             .service(update)
             .service(delete_by_id)
             .service(delete_by_uid)
+            .service(restore_by_id)
+            .service(purge_by_id)
             .service(get_many_info)
             .service(count_info)
             .service(get_info_by_id)
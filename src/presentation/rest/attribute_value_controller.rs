@@ -5,12 +5,16 @@ use crate::business::attribute_value_service::{
 };
 use crate::common::error::CoreError;
 use crate::common::service::{Service, ViewService};
-use crate::presentation::query_options::QueryOptions;
+use crate::presentation::query_options::{
+    parse_column_values, serialize_column_values, AggregateGroupTO, ColumnValueDto,
+    ColumnValuesDto, FacetTO, QueryOptions,
+};
 use crate::presentation::rest::response_result::{respond_result, respond_results};
 use crate::state::AppState;
 use crate::{define_readonly_to_with_common_fields_be, define_to_with_common_fields_be};
 use actix_web::web::{scope, Data, Json, Path, Query, ServiceConfig};
 use actix_web::{delete, get, post, put, Responder};
+use std::collections::HashMap;
 
 // Table TO for CRUD
 define_to_with_common_fields_be!(AttributeValue {
@@ -18,8 +22,6 @@ define_to_with_common_fields_be!(AttributeValue {
         pub attribute_id: i32,
         pub entity_id: i32,
         pub entity_type: String,
-    }
-    opt {
         pub int_value: Option<i32>,
         pub double_value: Option<f64>,
         pub string_value: Option<String>,
@@ -28,6 +30,7 @@ define_to_with_common_fields_be!(AttributeValue {
         pub datetime_value: Option<time::OffsetDateTime>,
         pub time_value: Option<time::Time>,
     }
+    opt {}
 });
 
 // View TO for info
@@ -117,30 +120,122 @@ impl From<AttributeValueInfo> for AttributeValueInfoTO {
 // ===== Table endpoints =====
 #[get("")]
 pub async fn get_many(state: Data<AppState>, query: Query<QueryOptions>) -> impl Responder {
-    respond_results(
-        state
-            .attribute_value_service
-            .get_many(
-                query.to_sort_criteria(),
-                query.first_result,
-                query.max_results,
-                query.to_filters(),
-            )
-            .await,
-        AttributeValueTO::from,
-    )
+    let result = match query.to_filters() {
+        Ok(filters) => {
+            state
+                .attribute_value_service
+                .get_many(
+                    query.to_sort_criteria(),
+                    query.first_result,
+                    query.max_results,
+                    filters,
+                )
+                .await
+        }
+        Err(e) => Err(e),
+    };
+    respond_results(result, AttributeValueTO::from)
 }
 
 #[get("/count")]
 pub async fn count(state: Data<AppState>, query: Query<QueryOptions>) -> impl Responder {
+    let result = match query.to_filters() {
+        Ok(filters) => {
+            state
+                .attribute_value_service
+                .count(filters)
+                .await
+        }
+        Err(e) => Err(e),
+    };
+    respond_result(result, )
+}
+
+#[get("/facets")]
+pub async fn facets(state: Data<AppState>, query: Query<QueryOptions>) -> impl Responder {
+    let result = match query.to_filters() {
+        Ok(filters) => {
+            state
+                .attribute_value_service
+                .get_facets(query.to_facet_fields(), filters)
+                .await
+        }
+        Err(e) => Err(e),
+    };
+    respond_results(result, FacetTO::from)
+}
+
+#[get("/aggregate")]
+pub async fn aggregate(state: Data<AppState>, query: Query<QueryOptions>) -> impl Responder {
+    let result = match (query.to_aggregates(), query.to_filters()) {
+        (Ok(aggregates), Ok(filters)) => {
+            state
+                .attribute_value_service
+                .aggregate(
+                    query.to_group_by(),
+                    aggregates,
+                    query.first_result,
+                    query.max_results,
+                    filters,
+                )
+                .await
+        }
+        (Err(e), _) | (_, Err(e)) => Err(e),
+    };
+    respond_results(result, AggregateGroupTO::from)
+}
+
+// ===== Custom field endpoints =====
+
+#[get("/entity/{entity_type}/{entity_id}")]
+pub async fn get_entity_attributes(
+    state: Data<AppState>,
+    path: Path<(String, i32)>,
+) -> impl Responder {
+    let (entity_type, entity_id) = path.into_inner();
     respond_result(
         state
             .attribute_value_service
-            .count(query.to_filters())
-            .await,
+            .get_attributes(&entity_type, entity_id)
+            .await
+            .map(|values| serialize_column_values(&values)),
     )
 }
 
+/// Sets custom fields by attribute name; a `null` value clears the field.
+#[put("/entity/{entity_type}/{entity_id}")]
+pub async fn set_entity_attributes(
+    state: Data<AppState>,
+    path: Path<(String, i32)>,
+    data: Json<HashMap<String, Option<ColumnValueDto>>>,
+) -> impl Responder {
+    let (entity_type, entity_id) = path.into_inner();
+    let mut set = ColumnValuesDto::new();
+    let mut values = HashMap::new();
+    for (name, value) in data.into_inner() {
+        match value {
+            Some(value) => {
+                set.insert(name, value);
+            }
+            None => {
+                values.insert(name, None);
+            }
+        }
+    }
+    let result = match parse_column_values(set) {
+        Ok(parsed) => {
+            values.extend(parsed.into_iter().map(|(name, v)| (name, Some(v))));
+            state
+                .attribute_value_service
+                .set_attributes(&entity_type, entity_id, values)
+                .await
+                .map(|values| serialize_column_values(&values))
+        }
+        Err(e) => Err(e),
+    };
+    respond_result(result)
+}
+
 #[get("/{id}")]
 pub async fn get_by_id(state: Data<AppState>, id: Path<i32>) -> impl Responder {
     respond_result(
@@ -207,31 +302,48 @@ pub async fn delete_by_uid(state: Data<AppState>, uid: Path<String>) -> impl Res
     )
 }
 
+#[post("/{id}/restore")]
+pub async fn restore_by_id(state: Data<AppState>, id: Path<i32>) -> impl Responder {
+    respond_result(state.attribute_value_service.restore_by_id(id.into_inner()).await)
+}
+
+#[delete("/{id}/purge")]
+pub async fn purge_by_id(state: Data<AppState>, id: Path<i32>) -> impl Responder {
+    respond_result(state.attribute_value_service.purge_by_id(id.into_inner()).await)
+}
+
 // ===== Info endpoints =====
 #[get("/info")]
 pub async fn get_many_info(state: Data<AppState>, query: Query<QueryOptions>) -> impl Responder {
-    respond_results(
-        state
-            .attribute_value_info_service
-            .get_many(
-                query.to_sort_criteria(),
-                query.first_result,
-                query.max_results,
-                query.to_filters(),
-            )
-            .await,
-        AttributeValueInfoTO::from,
-    )
+    let result = match query.to_filters() {
+        Ok(filters) => {
+            state
+                .attribute_value_info_service
+                .get_many(
+                    query.to_sort_criteria(),
+                    query.first_result,
+                    query.max_results,
+                    filters,
+                )
+                .await
+        }
+        Err(e) => Err(e),
+    };
+    respond_results(result, AttributeValueInfoTO::from)
 }
 
 #[get("/info/count")]
 pub async fn count_info(state: Data<AppState>, query: Query<QueryOptions>) -> impl Responder {
-    respond_result(
-        state
-            .attribute_value_info_service
-            .count(query.to_filters())
-            .await,
-    )
+    let result = match query.to_filters() {
+        Ok(filters) => {
+            state
+                .attribute_value_info_service
+                .count(filters)
+                .await
+        }
+        Err(e) => Err(e),
+    };
+    respond_result(result, )
 }
 
 #[get("/{id}/info")]
@@ -263,12 +375,18 @@ pub fn routes(cfg: &mut ServiceConfig) {
         scope("/api/attribute_values")
             .service(get_many)
             .service(count)
+            .service(facets)
+            .service(aggregate)
+            .service(get_entity_attributes)
+            .service(set_entity_attributes)
             .service(get_by_id)
             .service(get_by_uid)
             .service(create)
             .service(update)
             .service(delete_by_id)
             .service(delete_by_uid)
+            .service(restore_by_id)
+            .service(purge_by_id)
             .service(get_many_info)
             .service(count_info)
             .service(get_info_by_id)
@@ -282,6 +400,13 @@ impl From<AttributeValueCreateTO> for AttributeValueCreate {
             attribute_id: to.attribute_id,
             entity_id: to.entity_id,
             entity_type: to.entity_type,
+            int_value: to.int_value,
+            double_value: to.double_value,
+            string_value: to.string_value,
+            boolean_value: to.boolean_value,
+            date_value: to.date_value,
+            datetime_value: to.datetime_value,
+            time_value: to.time_value,
         }
     }
 }
@@ -0,0 +1,91 @@
+#![cfg(feature = "ssr")]
+
+use crate::business::audit_log_service::AuditLogInfo;
+use crate::common::error::CoreError;
+use crate::common::service::ViewService;
+use crate::define_readonly_to_with_common_fields_be;
+use crate::presentation::query_options::QueryOptions;
+use crate::presentation::rest::response_result::{respond_result, respond_results};
+use crate::state::AppState;
+use actix_web::web::{scope, Data, Path, Query, ServiceConfig};
+use actix_web::{get, Responder};
+
+// View
+define_readonly_to_with_common_fields_be!(AuditLogInfo {
+    pub entity_type: String,
+    pub entity_id: i32,
+    pub entity_uid: String,
+    pub operation: String,
+    pub user_id: Option<i32>,
+    pub username: Option<String>,
+    pub changes: String,
+});
+
+impl From<AuditLogInfo> for AuditLogInfoTO {
+    fn from(e: AuditLogInfo) -> Self {
+        Self {
+            id: e.id,
+            uid: e.uid,
+            version: e.version,
+            created_at: e.created_at,
+            updated_at: e.updated_at,
+            entity_type: e.entity_type,
+            entity_id: e.entity_id,
+            entity_uid: e.entity_uid,
+            operation: e.operation,
+            user_id: e.user_id,
+            username: e.username,
+            changes: e.changes,
+        }
+    }
+}
+
+// The log is written by the other services; it is only read here
+#[get("")]
+pub async fn get_many(state: Data<AppState>, query: Query<QueryOptions>) -> impl Responder {
+    let result = match query.to_filters() {
+        Ok(filters) => {
+            state
+                .audit_log_info_service
+                .get_many(
+                    query.to_sort_criteria(),
+                    query.first_result,
+                    query.max_results,
+                    filters,
+                )
+                .await
+        }
+        Err(e) => Err(e),
+    };
+    respond_results(result, AuditLogInfoTO::from)
+}
+
+#[get("/count")]
+pub async fn count(state: Data<AppState>, query: Query<QueryOptions>) -> impl Responder {
+    let result = match query.to_filters() {
+        Ok(filters) => state.audit_log_info_service.count(filters).await,
+        Err(e) => Err(e),
+    };
+    respond_result(result)
+}
+
+#[get("/{id}")]
+pub async fn get_by_id(state: Data<AppState>, id: Path<i32>) -> impl Responder {
+    respond_result(
+        state
+            .audit_log_info_service
+            .get_by_id(id.into_inner())
+            .await
+            .and_then(|opt| opt.ok_or(CoreError::not_found("error.not_found")))
+            .map(AuditLogInfoTO::from),
+    )
+}
+
+pub fn routes(cfg: &mut ServiceConfig) {
+    cfg.service(
+        scope("/api/audit_log")
+            .service(get_many)
+            .service(count)
+            .service(get_by_id),
+    );
+}
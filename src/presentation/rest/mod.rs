@@ -1,6 +1,7 @@
 #![cfg(feature = "ssr")]
 pub mod attribute_controller;
 pub mod attribute_value_controller;
+pub mod audit_log_controller;
 pub mod post_collection_controller;
 pub mod post_controller;
 pub mod post_taxonomy_controller;
@@ -1,13 +1,17 @@
-use crate::business::post_service::{Post, PostCreate, PostInfo, PostStatus};
+use crate::business::post_service::{
+    Post, PostCreate, PostInfo, PostStatus, PostTransition, WorkflowActor, UPSERT_KEY,
+};
+use crate::business::user_service::UserRole;
 use crate::common::error::CoreError;
 use crate::common::service::{Service, ViewService};
-use crate::presentation::query_options::QueryOptions;
-use crate::presentation::rest::response_result::{respond_result, respond_results};
-use crate::presentation::rest::user_controller::UserTO;
+use crate::presentation::query_options::{AggregateGroupTO, FacetTO, QueryOptions, WithAttributesTO};
+use crate::presentation::rest::response_result::{respond_batch, respond_result, respond_results};
+use crate::presentation::rest::term_controller::TermInfoTO;
 use crate::state::AppState;
 use crate::{define_readonly_to_with_common_fields_be, define_to_with_common_fields_be};
 use actix_web::web::{scope, Data, Json, Path, Query, ServiceConfig};
-use actix_web::{delete, get, post, put, Responder};
+use actix_web::{delete, get, post, put, Either, Responder};
+use std::collections::HashMap;
 use std::str::FromStr;
 
 define_to_with_common_fields_be!(Post {
@@ -21,9 +25,20 @@ define_to_with_common_fields_be!(Post {
         pub content: String,
         pub status: String,
         pub user_id: i32,
+        pub published_at: Option<time::OffsetDateTime>,
+        pub scheduled_at: Option<time::OffsetDateTime>,
+        pub expires_at: Option<time::OffsetDateTime>,
     }
 });
 
+define_readonly_to_with_common_fields_be!(PostTransition {
+    pub post_id: i32,
+    pub from_status: String,
+    pub to_status: String,
+    pub user_id: Option<i32>,
+    pub comment: Option<String>,
+});
+
 define_readonly_to_with_common_fields_be!(PostInfo {
     pub slug: String,
     pub title: String,
@@ -50,6 +65,9 @@ impl From<PostTO> for Post {
             status: PostStatus::from_str(&to.status).unwrap_or(PostStatus::DRAFT),
             user_id: to.user_id,
             type_id: to.type_id,
+            published_at: to.published_at,
+            scheduled_at: to.scheduled_at,
+            expires_at: to.expires_at,
         }
     }
 }
@@ -74,9 +92,59 @@ impl From<PostInfo> for PostInfoTO {
     }
 }
 
+impl From<PostTransition> for PostTransitionTO {
+    fn from(entity: PostTransition) -> Self {
+        Self {
+            id: entity.id,
+            uid: entity.uid,
+            version: entity.version,
+            created_at: entity.created_at,
+            updated_at: entity.updated_at,
+            post_id: entity.post_id,
+            from_status: entity.from_status.as_str().to_string(),
+            to_status: entity.to_status.as_str().to_string(),
+            user_id: entity.user_id,
+            comment: entity.comment,
+        }
+    }
+}
+
+/// A requested workflow step: the status to move to, and why.
+#[derive(serde::Deserialize)]
+pub struct TransitionTO {
+    pub to: String,
+    pub comment: Option<String>,
+}
+
+/// When the post goes live and when it is archived, as RFC 3339 timestamps;
+/// a missing or null date is cleared.
+#[derive(serde::Deserialize)]
+pub struct ScheduleTO {
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub scheduled_at: Option<time::OffsetDateTime>,
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub expires_at: Option<time::OffsetDateTime>,
+}
+
+/// Terms to give a post. With `taxonomy` (a code), replacing only touches the
+/// post's terms of that taxonomy.
+#[derive(serde::Deserialize)]
+pub struct PostTermsTO {
+    pub term_ids: Vec<i32>,
+    pub taxonomy: Option<String>,
+}
+
+#[derive(serde::Deserialize)]
+pub struct TaxonomyQuery {
+    pub taxonomy: Option<String>,
+}
+
 #[derive(serde::Deserialize)]
 pub struct NewPostTO {
     pub title: String,
+    /// Made from the title when left out.
+    #[serde(default)]
+    pub slug: String,
     pub type_id: i32,
 }
 
@@ -95,29 +163,160 @@ impl From<Post> for PostTO {
             status: entity.status.as_str().to_string(),
             user_id: entity.user_id,
             type_id: entity.type_id,
+            published_at: entity.published_at,
+            scheduled_at: entity.scheduled_at,
+            expires_at: entity.expires_at,
         }
     }
 }
 
+const DEFAULT_PAGE_SIZE: i32 = 20;
+
 #[get("")]
 pub async fn get_many(state: Data<AppState>, query: Query<QueryOptions>) -> impl Responder {
-    respond_results(
-        state
-            .post_service
-            .get_many(
-                query.to_sort_criteria(),
-                query.first_result,
-                query.max_results,
-                query.to_filters(),
-            )
-            .await,
-        PostTO::from,
-    )
+    let result = match query.to_filters() {
+        Ok(filters) => {
+            state
+                .post_service
+                .get_many(
+                    query.to_sort_criteria(),
+                    query.first_result,
+                    query.max_results,
+                    filters,
+                )
+                .await
+        }
+        Err(e) => Err(e),
+    };
+    respond_results(result, PostTO::from)
+}
+
+#[get("/with-attributes")]
+pub async fn get_many_with_attributes(
+    state: Data<AppState>,
+    query: Query<QueryOptions>,
+) -> impl Responder {
+    let result = match query.to_filters() {
+        Ok(filters) => {
+            state
+                .post_service
+                .get_many_with_attributes(
+                    query.to_sort_criteria(),
+                    query.first_result,
+                    query.max_results,
+                    filters,
+                )
+                .await
+        }
+        Err(e) => Err(e),
+    };
+    respond_results(result, |post| WithAttributesTO::from_entity(post, PostTO::from))
+}
+
+#[get("/page")]
+pub async fn get_page(state: Data<AppState>, query: Query<QueryOptions>) -> impl Responder {
+    let result = match (query.to_cursor_position(), query.to_filters()) {
+        (Ok(position), Ok(filters)) => {
+            state
+                .post_service
+                .get_page(
+                    query.to_sort_criteria(),
+                    position,
+                    query.max_results.unwrap_or(DEFAULT_PAGE_SIZE),
+                    filters,
+                )
+                .await
+        }
+        (Err(e), _) | (_, Err(e)) => Err(e),
+    };
+    respond_result(result.map(|page| page.map(PostTO::from)))
 }
 
 #[get("/count")]
 pub async fn count(state: Data<AppState>, query: Query<QueryOptions>) -> impl Responder {
-    respond_result(state.post_service.count(query.to_filters()).await)
+    let result = match query.to_filters() {
+        Ok(filters) => state.post_service.count(filters).await,
+        Err(e) => Err(e),
+    };
+    respond_result(result)
+}
+
+#[get("/facets")]
+pub async fn facets(state: Data<AppState>, query: Query<QueryOptions>) -> impl Responder {
+    let result = match query.to_filters() {
+        Ok(filters) => {
+            state
+                .post_service
+                .get_facets(query.to_facet_fields(), filters)
+                .await
+        }
+        Err(e) => Err(e),
+    };
+    respond_results(result, FacetTO::from)
+}
+
+#[get("/aggregate")]
+pub async fn aggregate(state: Data<AppState>, query: Query<QueryOptions>) -> impl Responder {
+    let result = match (query.to_aggregates(), query.to_filters()) {
+        (Ok(aggregates), Ok(filters)) => {
+            state
+                .post_service
+                .aggregate(
+                    query.to_group_by(),
+                    aggregates,
+                    query.first_result,
+                    query.max_results,
+                    filters,
+                )
+                .await
+        }
+        (Err(e), _) | (_, Err(e)) => Err(e),
+    };
+    respond_results(result, AggregateGroupTO::from)
+}
+
+#[get("/search")]
+pub async fn search(state: Data<AppState>, query: Query<QueryOptions>) -> impl Responder {
+    let query = query.into_inner();
+    let text = query.search.clone().unwrap_or_default();
+    // The search text is the ranked query itself, not one more filter
+    let filters = QueryOptions {
+        search: None,
+        ..query.clone()
+    }
+    .to_filters();
+    let result = match filters {
+        Ok(filters) => {
+            state
+                .post_service
+                .search(text, query.first_result, query.max_results, filters)
+                .await
+        }
+        Err(e) => Err(e),
+    };
+    respond_results(result, |hit| hit.map(PostTO::from))
+}
+
+#[get("/suggest")]
+pub async fn suggest(state: Data<AppState>, query: Query<QueryOptions>) -> impl Responder {
+    let query = query.into_inner();
+    let text = query.search.clone().unwrap_or_default();
+    // The text is matched fuzzily, not as a full-text filter
+    let filters = QueryOptions {
+        search: None,
+        ..query.clone()
+    }
+    .to_filters();
+    let result = match filters {
+        Ok(filters) => {
+            state
+                .post_service
+                .suggest(text, query.max_results, filters)
+                .await
+        }
+        Err(e) => Err(e),
+    };
+    respond_results(result, |suggestion| suggestion.map(PostTO::from))
 }
 
 #[get("/{id}")]
@@ -132,6 +331,21 @@ pub async fn get_by_id(state: Data<AppState>, id: Path<i32>) -> impl Responder {
     )
 }
 
+/// The post's content rendered to HTML, as a JSON string.
+#[get("/{id}/html")]
+pub async fn get_html_by_id(state: Data<AppState>, id: Path<i32>) -> impl Responder {
+    let post = state
+        .post_service
+        .get_by_id(id.into_inner())
+        .await
+        .and_then(|opt| opt.ok_or(CoreError::not_found("error.not_found")));
+    let html = match post {
+        Ok(post) => state.post_service.render_html(&post).await,
+        Err(e) => Err(e),
+    };
+    respond_result(html)
+}
+
 #[get("/uid/{uid}")]
 pub async fn get_by_uid(state: Data<AppState>, uid: Path<String>) -> impl Responder {
     respond_result(
@@ -144,26 +358,165 @@ pub async fn get_by_uid(state: Data<AppState>, uid: Path<String>) -> impl Respon
     )
 }
 
+/// The part of the session's "user" entry the API needs.
+#[derive(serde::Deserialize)]
+struct SessionUser {
+    id: i32,
+}
+
+/// Id of the signed-in user, who authors the posts created by a request.
+fn session_user_id(req: &actix_web::HttpRequest) -> Result<i32, CoreError> {
+    use actix_session::SessionExt as _;
+
+    req.get_session()
+        .get::<SessionUser>("user")
+        .ok()
+        .flatten()
+        .map(|u| u.id)
+        .ok_or(CoreError::unauthorized("error.missing_session"))
+}
+
+/// The signed-in user, as the workflow sees them.
+fn session_actor(req: &actix_web::HttpRequest) -> Result<WorkflowActor, CoreError> {
+    use actix_session::SessionExt as _;
+
+    let user_id = session_user_id(req)?;
+    let role = req
+        .get_session()
+        .get::<String>("role")
+        .ok()
+        .flatten()
+        .and_then(|role| UserRole::from_str(&role).ok())
+        .unwrap_or(UserRole::USER);
+    Ok(WorkflowActor {
+        user_id: Some(user_id),
+        role,
+    })
+}
+
+/// The post's workflow history, newest first.
+#[get("/{id}/transitions")]
+pub async fn get_transitions(state: Data<AppState>, id: Path<i32>) -> impl Responder {
+    respond_results(
+        state.post_service.get_transitions(id.into_inner()).await,
+        PostTransitionTO::from,
+    )
+}
+
+/// Moves the post along the workflow as the signed-in user.
+#[post("/{id}/transitions")]
+pub async fn transition(
+    state: Data<AppState>,
+    req: actix_web::HttpRequest,
+    id: Path<i32>,
+    body: Json<TransitionTO>,
+) -> impl Responder {
+    let body = body.into_inner();
+    let result = match (session_actor(&req), PostStatus::from_str(&body.to)) {
+        (Err(e), _) => Err(e),
+        (Ok(_), Err(_)) => Err(CoreError::unprocessable_entity("error.post.status.invalid")),
+        (Ok(actor), Ok(to)) => {
+            state
+                .post_service
+                .transition(id.into_inner(), to, body.comment, &actor)
+                .await
+        }
+    };
+    respond_result(result.map(PostTO::from))
+}
+
+/// The post's terms by taxonomy, then name; `?taxonomy=` keeps one taxonomy.
+#[get("/{id}/terms")]
+pub async fn get_terms(
+    state: Data<AppState>,
+    id: Path<i32>,
+    query: Query<TaxonomyQuery>,
+) -> impl Responder {
+    respond_results(
+        state
+            .post_term_service
+            .get_terms(id.into_inner(), query.taxonomy.as_deref())
+            .await,
+        TermInfoTO::from,
+    )
+}
+
+/// Adds terms to the post, keeping the ones it has.
+#[post("/{id}/terms")]
+pub async fn assign_terms(
+    state: Data<AppState>,
+    id: Path<i32>,
+    body: Json<PostTermsTO>,
+) -> impl Responder {
+    respond_results(
+        state
+            .post_term_service
+            .assign(id.into_inner(), &body.term_ids)
+            .await,
+        TermInfoTO::from,
+    )
+}
+
+/// Makes the given terms the post's terms, of one taxonomy or of all.
+#[put("/{id}/terms")]
+pub async fn replace_terms(
+    state: Data<AppState>,
+    id: Path<i32>,
+    body: Json<PostTermsTO>,
+) -> impl Responder {
+    respond_results(
+        state
+            .post_term_service
+            .replace(id.into_inner(), body.taxonomy.as_deref(), &body.term_ids)
+            .await,
+        TermInfoTO::from,
+    )
+}
+
+#[delete("/{id}/terms/{term_id}")]
+pub async fn unassign_term(state: Data<AppState>, path: Path<(i32, i32)>) -> impl Responder {
+    let (id, term_id) = path.into_inner();
+    respond_results(
+        state.post_term_service.unassign(id, &[term_id]).await,
+        TermInfoTO::from,
+    )
+}
+
+/// Sets when the post is published and archived by the scheduler; editors only.
+#[put("/{id}/schedule")]
+pub async fn schedule(
+    state: Data<AppState>,
+    req: actix_web::HttpRequest,
+    id: Path<i32>,
+    body: Json<ScheduleTO>,
+) -> impl Responder {
+    let body = body.into_inner();
+    let result = match session_actor(&req) {
+        Ok(actor) => {
+            state
+                .post_service
+                .schedule(id.into_inner(), body.scheduled_at, body.expires_at, &actor)
+                .await
+        }
+        Err(e) => Err(e),
+    };
+    respond_result(result.map(PostTO::from))
+}
+
 #[post("")]
 pub async fn create(
     state: Data<AppState>,
     req: actix_web::HttpRequest,
     post: Json<NewPostTO>,
 ) -> impl Responder {
-    use actix_session::SessionExt as _;
-
-    let Some(user_id) = req
-        .get_session()
-        .get::<UserTO>("user")
-        .ok()
-        .flatten()
-        .map(|u| u.id)
-    else {
-        return respond_result::<PostTO>(Err(CoreError::unauthorized("error.missing_session")));
+    let user_id = match session_user_id(&req) {
+        Ok(user_id) => user_id,
+        Err(e) => return respond_result::<PostTO>(Err(e)),
     };
 
     let create = PostCreate {
         title: post.title.clone(),
+        slug: post.slug.clone(),
         type_id: post.type_id,
         user_id,
     };
@@ -180,13 +533,129 @@ pub async fn update(
     post.id = id.into_inner();
     respond_result(
         state
-            .post_service
-            .update(&Post::from(post.into_inner()))
+            .post_revision_service
+            .update_post(&state.post_service, &Post::from(post.into_inner()))
             .await
             .map(PostTO::from),
     )
 }
 
+/// A post to create, or to update when its type already has the slug.
+/// Without `user_id`, the signed-in user is the author. `status` defaults to
+/// draft; it must be the stored one when updating, and draft when creating.
+#[derive(serde::Deserialize)]
+pub struct PostUpsertTO {
+    pub title: String,
+    pub type_id: i32,
+    pub slug: String,
+    #[serde(default)]
+    pub summary: String,
+    #[serde(default)]
+    pub content: String,
+    pub status: Option<String>,
+    pub user_id: Option<i32>,
+}
+
+impl PostUpsertTO {
+    /// The post to upsert; fails on an unknown `status`. Status, author and
+    /// dates only count when the post is new; existing ones keep theirs.
+    fn into_post(self, default_user_id: i32) -> Result<Post, CoreError> {
+        let status = match self.status {
+            Some(status) => PostStatus::from_str(&status).map_err(|_| {
+                CoreError::UnprocessableEntity(
+                    "error.post.status.invalid",
+                    HashMap::from([("status".to_string(), status)]),
+                )
+            })?,
+            None => PostStatus::DRAFT,
+        };
+        let now = time::OffsetDateTime::now_utc();
+        Ok(Post {
+            id: 0,
+            uid: String::new(),
+            version: 0,
+            created_at: now,
+            updated_at: now,
+            slug: self.slug,
+            title: self.title,
+            summary: self.summary,
+            content: self.content,
+            status,
+            user_id: self.user_id.unwrap_or(default_user_id),
+            type_id: self.type_id,
+            published_at: None,
+            scheduled_at: None,
+            expires_at: None,
+        })
+    }
+}
+
+#[post("/batch")]
+pub async fn create_many(
+    state: Data<AppState>,
+    req: actix_web::HttpRequest,
+    posts: Json<Vec<NewPostTO>>,
+) -> impl Responder {
+    let user_id = match session_user_id(&req) {
+        Ok(user_id) => user_id,
+        Err(e) => return Either::Left(respond_result::<PostTO>(Err(e))),
+    };
+    let creates: Vec<PostCreate> = posts
+        .into_inner()
+        .into_iter()
+        .map(|post| PostCreate {
+            title: post.title,
+            slug: post.slug,
+            type_id: post.type_id,
+            user_id,
+        })
+        .collect();
+    Either::Right(respond_batch(
+        state.post_service.create_many(&creates).await,
+        PostTO::from,
+    ))
+}
+
+#[put("/batch")]
+pub async fn update_many(state: Data<AppState>, posts: Json<Vec<PostTO>>) -> impl Responder {
+    let posts: Vec<Post> = posts.into_inner().into_iter().map(Post::from).collect();
+    respond_batch(
+        state
+            .post_revision_service
+            .update_posts(&state.post_service, &posts)
+            .await,
+        PostTO::from,
+    )
+}
+
+#[put("/upsert")]
+pub async fn upsert(
+    state: Data<AppState>,
+    req: actix_web::HttpRequest,
+    posts: Json<Vec<PostUpsertTO>>,
+) -> impl Responder {
+    let user_id = match session_user_id(&req) {
+        Ok(user_id) => user_id,
+        Err(e) => return Either::Left(respond_result::<PostTO>(Err(e))),
+    };
+    let posts = match posts
+        .into_inner()
+        .into_iter()
+        .map(|post| post.into_post(user_id))
+        .collect::<Result<Vec<Post>, _>>()
+    {
+        Ok(posts) => posts,
+        Err(e) => return Either::Left(respond_result::<PostTO>(Err(e))),
+    };
+    Either::Right(respond_batch(
+        state
+            .post_revision_service
+            .upsert_posts(&state.post_service, &UPSERT_KEY, &posts)
+            .await,
+        PostTO::from,
+    ))
+}
+
 #[delete("/{id}")]
 pub async fn delete_by_id(state: Data<AppState>, id: Path<i32>) -> impl Responder {
     respond_result(state.post_service.delete_by_id(id.into_inner()).await)
@@ -197,25 +666,83 @@ pub async fn delete_by_uid(state: Data<AppState>, uid: Path<String>) -> impl Res
     respond_result(state.post_service.delete_by_uid(uid.into_inner()).await)
 }
 
+#[post("/{id}/restore")]
+pub async fn restore_by_id(state: Data<AppState>, id: Path<i32>) -> impl Responder {
+    respond_result(state.post_service.restore_by_id(id.into_inner()).await)
+}
+
+#[delete("/{id}/purge")]
+pub async fn purge_by_id(state: Data<AppState>, id: Path<i32>) -> impl Responder {
+    respond_result(state.post_service.purge_by_id(id.into_inner()).await)
+}
+
 #[get("/info")]
 pub async fn get_many_info(state: Data<AppState>, query: Query<QueryOptions>) -> impl Responder {
-    respond_results(
-        state
-            .post_info_service
-            .get_many(
-                query.to_sort_criteria(),
-                query.first_result,
-                query.max_results,
-                query.to_filters(),
-            )
-            .await,
-        PostInfoTO::from,
-    )
+    let result = match query.to_filters() {
+        Ok(filters) => {
+            state
+                .post_info_service
+                .get_many(
+                    query.to_sort_criteria(),
+                    query.first_result,
+                    query.max_results,
+                    filters,
+                )
+                .await
+        }
+        Err(e) => Err(e),
+    };
+    respond_results(result, PostInfoTO::from)
+}
+
+#[get("/info/with-attributes")]
+pub async fn get_many_info_with_attributes(
+    state: Data<AppState>,
+    query: Query<QueryOptions>,
+) -> impl Responder {
+    let result = match query.to_filters() {
+        Ok(filters) => {
+            state
+                .post_info_service
+                .get_many_with_attributes(
+                    query.to_sort_criteria(),
+                    query.first_result,
+                    query.max_results,
+                    filters,
+                )
+                .await
+        }
+        Err(e) => Err(e),
+    };
+    respond_results(result, |post| WithAttributesTO::from_entity(post, PostInfoTO::from))
+}
+
+#[get("/info/page")]
+pub async fn get_info_page(state: Data<AppState>, query: Query<QueryOptions>) -> impl Responder {
+    let result = match (query.to_cursor_position(), query.to_filters()) {
+        (Ok(position), Ok(filters)) => {
+            state
+                .post_info_service
+                .get_page(
+                    query.to_sort_criteria(),
+                    position,
+                    query.max_results.unwrap_or(DEFAULT_PAGE_SIZE),
+                    filters,
+                )
+                .await
+        }
+        (Err(e), _) | (_, Err(e)) => Err(e),
+    };
+    respond_result(result.map(|page| page.map(PostInfoTO::from)))
 }
 
 #[get("/info/count")]
 pub async fn count_info(state: Data<AppState>, query: Query<QueryOptions>) -> impl Responder {
-    respond_result(state.post_info_service.count(query.to_filters()).await)
+    let result = match query.to_filters() {
+        Ok(filters) => state.post_info_service.count(filters).await,
+        Err(e) => Err(e),
+    };
+    respond_result(result)
 }
 
 #[get("/{id}/info")]
@@ -246,13 +773,34 @@ pub fn routes(cfg: &mut ServiceConfig) {
     cfg.service(
         scope("/api/posts")
             .service(get_many)
+            .service(get_page)
+            .service(get_info_page)
+            .service(get_many_with_attributes)
+            .service(get_many_info_with_attributes)
             .service(count)
+            .service(facets)
+            .service(aggregate)
+            .service(search)
+            .service(suggest)
             .service(get_by_id)
+            .service(get_html_by_id)
+            .service(get_transitions)
+            .service(transition)
+            .service(schedule)
+            .service(get_terms)
+            .service(assign_terms)
+            .service(replace_terms)
+            .service(unassign_term)
             .service(get_by_uid)
             .service(create)
+            .service(create_many)
+            .service(update_many)
+            .service(upsert)
             .service(update)
             .service(delete_by_id)
             .service(delete_by_uid)
+            .service(restore_by_id)
+            .service(purge_by_id)
             .service(get_many_info)
             .service(count_info)
             .service(get_info_by_id)
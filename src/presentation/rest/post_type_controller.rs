@@ -3,7 +3,7 @@
 use crate::business::post_type_service::{PostType, PostTypeCreate, PostTypeInfo};
 use crate::common::error::CoreError;
 use crate::common::service::{Service, ViewService};
-use crate::presentation::query_options::QueryOptions;
+use crate::presentation::query_options::{AggregateGroupTO, FacetTO, QueryOptions};
 use crate::presentation::rest::response_result::{respond_result, respond_results};
 use crate::state::AppState;
 use crate::{define_readonly_to_with_common_fields_be, define_to_with_common_fields_be};
@@ -65,23 +65,64 @@ impl From<PostTypeInfo> for PostTypeInfoTO {
 // Table endpoints
 #[get("")]
 pub async fn get_many(state: Data<AppState>, query: Query<QueryOptions>) -> impl Responder {
-    respond_results(
-        state
-            .post_type_service
-            .get_many(
-                query.to_sort_criteria(),
-                query.first_result,
-                query.max_results,
-                query.to_filters(),
-            )
-            .await,
-        PostTypeTO::from,
-    )
+    let result = match query.to_filters() {
+        Ok(filters) => {
+            state
+                .post_type_service
+                .get_many(
+                    query.to_sort_criteria(),
+                    query.first_result,
+                    query.max_results,
+                    filters,
+                )
+                .await
+        }
+        Err(e) => Err(e),
+    };
+    respond_results(result, PostTypeTO::from)
 }
 
 #[get("/count")]
 pub async fn count(state: Data<AppState>, query: Query<QueryOptions>) -> impl Responder {
-    respond_result(state.post_type_service.count(query.to_filters()).await)
+    let result = match query.to_filters() {
+        Ok(filters) => state.post_type_service.count(filters).await,
+        Err(e) => Err(e),
+    };
+    respond_result(result)
+}
+
+#[get("/facets")]
+pub async fn facets(state: Data<AppState>, query: Query<QueryOptions>) -> impl Responder {
+    let result = match query.to_filters() {
+        Ok(filters) => {
+            state
+                .post_type_service
+                .get_facets(query.to_facet_fields(), filters)
+                .await
+        }
+        Err(e) => Err(e),
+    };
+    respond_results(result, FacetTO::from)
+}
+
+#[get("/aggregate")]
+pub async fn aggregate(state: Data<AppState>, query: Query<QueryOptions>) -> impl Responder {
+    let result = match (query.to_aggregates(), query.to_filters()) {
+        (Ok(aggregates), Ok(filters)) => {
+            state
+                .post_type_service
+                .aggregate(
+                    query.to_group_by(),
+                    aggregates,
+                    query.first_result,
+                    query.max_results,
+                    filters,
+                )
+                .await
+        }
+        (Err(e), _) | (_, Err(e)) => Err(e),
+    };
+    respond_results(result, AggregateGroupTO::from)
 }
 
 #[get("/{id}")]
@@ -145,26 +186,43 @@ pub async fn delete_by_uid(state: Data<AppState>, uid: Path<String>) -> impl Res
     )
 }
 
+#[post("/{id}/restore")]
+pub async fn restore_by_id(state: Data<AppState>, id: Path<i32>) -> impl Responder {
+    respond_result(state.post_type_service.restore_by_id(id.into_inner()).await)
+}
+
+#[delete("/{id}/purge")]
+pub async fn purge_by_id(state: Data<AppState>, id: Path<i32>) -> impl Responder {
+    respond_result(state.post_type_service.purge_by_id(id.into_inner()).await)
+}
+
 // Info endpoints
 #[get("/info")]
 pub async fn get_many_info(state: Data<AppState>, query: Query<QueryOptions>) -> impl Responder {
-    respond_results(
-        state
-            .post_type_info_service
-            .get_many(
-                query.to_sort_criteria(),
-                query.first_result,
-                query.max_results,
-                query.to_filters(),
-            )
-            .await,
-        PostTypeInfoTO::from,
-    )
+    let result = match query.to_filters() {
+        Ok(filters) => {
+            state
+                .post_type_info_service
+                .get_many(
+                    query.to_sort_criteria(),
+                    query.first_result,
+                    query.max_results,
+                    filters,
+                )
+                .await
+        }
+        Err(e) => Err(e),
+    };
+    respond_results(result, PostTypeInfoTO::from)
 }
 
 #[get("/info/count")]
 pub async fn count_info(state: Data<AppState>, query: Query<QueryOptions>) -> impl Responder {
-    respond_result(state.post_type_info_service.count(query.to_filters()).await)
+    let result = match query.to_filters() {
+        Ok(filters) => state.post_type_info_service.count(filters).await,
+        Err(e) => Err(e),
+    };
+    respond_result(result)
 }
 
 #[get("/{id}/info")]
@@ -196,12 +254,16 @@ pub fn routes(cfg: &mut ServiceConfig) {
         scope("/api/post_types")
             .service(get_many)
             .service(count)
+            .service(facets)
+            .service(aggregate)
             .service(get_by_id)
             .service(get_by_uid)
             .service(create)
             .service(update)
             .service(delete_by_id)
             .service(delete_by_uid)
+            .service(restore_by_id)
+            .service(purge_by_id)
             .service(get_many_info)
             .service(count_info)
             .service(get_info_by_id)
@@ -49,3 +49,49 @@ where
         }
     }
 }
+
+/// Outcome of one item of a batch write, at its position in the request.
+#[derive(serde::Serialize)]
+struct BatchItemTO<U> {
+    index: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    value: Option<U>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<serde_json::Value>,
+}
+
+/// One entry per item of a batch write. The status is 200 when every item
+/// succeeded and 207 Multi-Status when any failed.
+pub fn respond_batch<T, U, F>(results: Vec<Result<T, CoreError>>, mapper: F) -> impl Responder
+where
+    F: Fn(T) -> U,
+    U: serde::Serialize,
+{
+    let mut failed = false;
+    let items = results
+        .into_iter()
+        .enumerate()
+        .map(|(index, result)| match result {
+            Ok(value) => BatchItemTO {
+                index,
+                value: Some(mapper(value)),
+                error: None,
+            },
+            Err(e) => {
+                failed = true;
+                log::warn!("batch item {}: {}", index, e);
+                BatchItemTO {
+                    index,
+                    value: None,
+                    error: serde_json::from_str(&e.to_json()).ok(),
+                }
+            }
+        })
+        .collect::<Vec<_>>();
+    let status = if failed {
+        StatusCode::MULTI_STATUS
+    } else {
+        StatusCode::OK
+    };
+    HttpResponse::build(status).json(items)
+}
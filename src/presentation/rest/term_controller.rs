@@ -3,8 +3,9 @@
 use crate::business::term_service::{Term, TermCreate, TermInfo};
 use crate::common::error::CoreError;
 use crate::common::service::{Service, ViewService};
-use crate::presentation::query_options::QueryOptions;
-use crate::presentation::rest::response_result::{respond_result, respond_results};
+use crate::presentation::query_options::{AggregateGroupTO, FacetTO, QueryOptions};
+use crate::presentation::rest::post_controller::PostTO;
+use crate::presentation::rest::response_result::{respond_batch, respond_result, respond_results};
 use crate::state::AppState;
 use crate::{define_readonly_to_with_common_fields_be, define_to_with_common_fields_be};
 use actix_web::web::{scope, Data, Json, Path, Query, ServiceConfig};
@@ -92,23 +93,108 @@ impl From<TermInfo> for TermInfoTO {
 // Table endpoints
 #[get("")]
 pub async fn get_many(state: Data<AppState>, query: Query<QueryOptions>) -> impl Responder {
-    respond_results(
-        state
-            .term_service
-            .get_many(
-                query.to_sort_criteria(),
-                query.first_result,
-                query.max_results,
-                query.to_filters(),
-            )
-            .await,
-        TermTO::from,
-    )
+    let result = match query.to_filters() {
+        Ok(filters) => {
+            state
+                .term_service
+                .get_many(
+                    query.to_sort_criteria(),
+                    query.first_result,
+                    query.max_results,
+                    filters,
+                )
+                .await
+        }
+        Err(e) => Err(e),
+    };
+    respond_results(result, TermTO::from)
 }
 
 #[get("/count")]
 pub async fn count(state: Data<AppState>, query: Query<QueryOptions>) -> impl Responder {
-    respond_result(state.term_service.count(query.to_filters()).await)
+    let result = match query.to_filters() {
+        Ok(filters) => state.term_service.count(filters).await,
+        Err(e) => Err(e),
+    };
+    respond_result(result)
+}
+
+#[get("/facets")]
+pub async fn facets(state: Data<AppState>, query: Query<QueryOptions>) -> impl Responder {
+    let result = match query.to_filters() {
+        Ok(filters) => {
+            state
+                .term_service
+                .get_facets(query.to_facet_fields(), filters)
+                .await
+        }
+        Err(e) => Err(e),
+    };
+    respond_results(result, FacetTO::from)
+}
+
+#[get("/aggregate")]
+pub async fn aggregate(state: Data<AppState>, query: Query<QueryOptions>) -> impl Responder {
+    let result = match (query.to_aggregates(), query.to_filters()) {
+        (Ok(aggregates), Ok(filters)) => {
+            state
+                .term_service
+                .aggregate(
+                    query.to_group_by(),
+                    aggregates,
+                    query.first_result,
+                    query.max_results,
+                    filters,
+                )
+                .await
+        }
+        (Err(e), _) | (_, Err(e)) => Err(e),
+    };
+    respond_results(result, AggregateGroupTO::from)
+}
+
+#[get("/search")]
+pub async fn search(state: Data<AppState>, query: Query<QueryOptions>) -> impl Responder {
+    let query = query.into_inner();
+    let text = query.search.clone().unwrap_or_default();
+    // The search text is the ranked query itself, not one more filter
+    let filters = QueryOptions {
+        search: None,
+        ..query.clone()
+    }
+    .to_filters();
+    let result = match filters {
+        Ok(filters) => {
+            state
+                .term_service
+                .search(text, query.first_result, query.max_results, filters)
+                .await
+        }
+        Err(e) => Err(e),
+    };
+    respond_results(result, |hit| hit.map(TermTO::from))
+}
+
+#[get("/suggest")]
+pub async fn suggest(state: Data<AppState>, query: Query<QueryOptions>) -> impl Responder {
+    let query = query.into_inner();
+    let text = query.search.clone().unwrap_or_default();
+    // The text is matched fuzzily, not as a full-text filter
+    let filters = QueryOptions {
+        search: None,
+        ..query.clone()
+    }
+    .to_filters();
+    let result = match filters {
+        Ok(filters) => {
+            state
+                .term_service
+                .suggest(text, query.max_results, filters)
+                .await
+        }
+        Err(e) => Err(e),
+    };
+    respond_results(result, |suggestion| suggestion.map(TermTO::from))
 }
 
 #[get("/{id}")]
@@ -157,6 +243,58 @@ pub async fn update(state: Data<AppState>, data: Json<TermTO>) -> impl Responder
     )
 }
 
+/// Terms are unique by slug within their taxonomy.
+const UPSERT_KEY: [&str; 2] = ["taxonomy_id", "slug"];
+
+/// A term to create, or to update when its taxonomy already has the slug.
+#[derive(serde::Deserialize)]
+pub struct TermUpsertTO {
+    pub taxonomy_id: i32,
+    pub slug: String,
+    pub name: String,
+    pub parent_id: Option<i32>,
+    pub description: Option<String>,
+}
+
+impl From<TermUpsertTO> for Term {
+    fn from(to: TermUpsertTO) -> Self {
+        let now = time::OffsetDateTime::now_utc();
+        Self {
+            id: 0,
+            uid: String::new(),
+            version: 0,
+            created_at: now,
+            updated_at: now,
+            taxonomy_id: to.taxonomy_id,
+            slug: to.slug,
+            name: to.name,
+            parent_id: to.parent_id,
+            description: to.description,
+        }
+    }
+}
+
+#[post("/batch")]
+pub async fn create_many(state: Data<AppState>, data: Json<Vec<TermCreateTO>>) -> impl Responder {
+    let creates: Vec<TermCreate> = data.into_inner().into_iter().map(TermCreate::from).collect();
+    respond_batch(state.term_service.create_many(&creates).await, TermTO::from)
+}
+
+#[put("/batch")]
+pub async fn update_many(state: Data<AppState>, data: Json<Vec<TermTO>>) -> impl Responder {
+    let terms: Vec<Term> = data.into_inner().into_iter().map(Term::from).collect();
+    respond_batch(state.term_service.update_many(&terms).await, TermTO::from)
+}
+
+#[put("/upsert")]
+pub async fn upsert(state: Data<AppState>, data: Json<Vec<TermUpsertTO>>) -> impl Responder {
+    let terms: Vec<Term> = data.into_inner().into_iter().map(Term::from).collect();
+    respond_batch(
+        state.term_service.upsert_by(&UPSERT_KEY, &terms).await,
+        TermTO::from,
+    )
+}
+
 #[delete("/{id}")]
 pub async fn delete_by_id(state: Data<AppState>, id: Path<i32>) -> impl Responder {
     respond_result(state.term_service.delete_by_id(id.into_inner()).await)
@@ -167,26 +305,86 @@ pub async fn delete_by_uid(state: Data<AppState>, uid: Path<String>) -> impl Res
     respond_result(state.term_service.delete_by_uid(uid.into_inner()).await)
 }
 
+#[post("/{id}/restore")]
+pub async fn restore_by_id(state: Data<AppState>, id: Path<i32>) -> impl Responder {
+    respond_result(state.term_service.restore_by_id(id.into_inner()).await)
+}
+
+#[delete("/{id}/purge")]
+pub async fn purge_by_id(state: Data<AppState>, id: Path<i32>) -> impl Responder {
+    respond_result(state.term_service.purge_by_id(id.into_inner()).await)
+}
+
+/// Posts having the term, sorted, paged and filtered as usual.
+#[get("/{id}/posts")]
+pub async fn get_posts(
+    state: Data<AppState>,
+    id: Path<i32>,
+    query: Query<QueryOptions>,
+) -> impl Responder {
+    let result = match query.to_filters() {
+        Ok(filters) => {
+            state
+                .post_term_service
+                .get_posts(
+                    id.into_inner(),
+                    query.to_sort_criteria(),
+                    query.first_result,
+                    query.max_results,
+                    filters,
+                )
+                .await
+        }
+        Err(e) => Err(e),
+    };
+    respond_results(result, PostTO::from)
+}
+
+#[get("/{id}/posts/count")]
+pub async fn count_posts(
+    state: Data<AppState>,
+    id: Path<i32>,
+    query: Query<QueryOptions>,
+) -> impl Responder {
+    let result = match query.to_filters() {
+        Ok(filters) => {
+            state
+                .post_term_service
+                .count_posts(id.into_inner(), filters)
+                .await
+        }
+        Err(e) => Err(e),
+    };
+    respond_result(result, )
+}
+
 // Info endpoints
 #[get("/info")]
 pub async fn get_many_info(state: Data<AppState>, query: Query<QueryOptions>) -> impl Responder {
-    respond_results(
-        state
-            .term_info_service
-            .get_many(
-                query.to_sort_criteria(),
-                query.first_result,
-                query.max_results,
-                query.to_filters(),
-            )
-            .await,
-        TermInfoTO::from,
-    )
+    let result = match query.to_filters() {
+        Ok(filters) => {
+            state
+                .term_info_service
+                .get_many(
+                    query.to_sort_criteria(),
+                    query.first_result,
+                    query.max_results,
+                    filters,
+                )
+                .await
+        }
+        Err(e) => Err(e),
+    };
+    respond_results(result, TermInfoTO::from)
 }
 
 #[get("/info/count")]
 pub async fn count_info(state: Data<AppState>, query: Query<QueryOptions>) -> impl Responder {
-    respond_result(state.term_info_service.count(query.to_filters()).await)
+    let result = match query.to_filters() {
+        Ok(filters) => state.term_info_service.count(filters).await,
+        Err(e) => Err(e),
+    };
+    respond_result(result)
 }
 
 #[get("/{id}/info")]
@@ -218,12 +416,23 @@ pub fn routes(cfg: &mut ServiceConfig) {
         scope("/api/terms")
             .service(get_many)
             .service(count)
+            .service(facets)
+            .service(aggregate)
+            .service(search)
+            .service(suggest)
             .service(get_by_id)
             .service(get_by_uid)
+            .service(get_posts)
+            .service(count_posts)
             .service(create)
+            .service(create_many)
+            .service(update_many)
+            .service(upsert)
             .service(update)
             .service(delete_by_id)
             .service(delete_by_uid)
+            .service(restore_by_id)
+            .service(purge_by_id)
             .service(get_many_info)
             .service(count_info)
             .service(get_info_by_id)
@@ -1,8 +1,9 @@
 #![cfg(feature = "ssr")]
 
 use crate::presentation::rest::{
-    attribute_controller, attribute_value_controller, post_collection_controller, post_controller,
-    post_taxonomy_controller, post_type_controller, term_controller, user_controller,
+    attribute_controller, attribute_value_controller, audit_log_controller,
+    post_collection_controller, post_controller, post_taxonomy_controller, post_type_controller,
+    term_controller, user_controller,
 };
 use actix_web::web::{scope, ServiceConfig};
 
@@ -17,5 +18,6 @@ pub fn config(cfg: &mut ServiceConfig) {
         .configure(term_controller::routes)
         .configure(attribute_controller::routes_attributes)
         .configure(attribute_value_controller::routes)
-        .configure(post_collection_controller::routes);
+        .configure(post_collection_controller::routes)
+        .configure(audit_log_controller::routes);
 }
@@ -1,22 +1,33 @@
 #![cfg(feature = "ssr")]
 use crate::business::attribute_service::{AttributeInfoService, AttributeService};
 use crate::business::attribute_value_service::{AttributeValueInfoService, AttributeValueService};
+use crate::business::audit_log_service::AuditLogInfoService;
 use crate::business::auth_service::AuthService;
 use crate::business::post_collection_service::{PostCollectionInfoService, PostCollectionService};
+use crate::business::post_revision_service::{
+    PostRevisionInfoService, PostRevisionService, RevisionRetention,
+};
 use crate::business::post_service::{PostInfoService, PostService};
 use crate::business::post_taxonomy_service::{PostTaxonomyInfoService, PostTaxonomyService};
+use crate::business::post_term_service::PostTermService;
 use crate::business::post_type_service::{PostTypeInfoService, PostTypeService};
+use crate::business::slug_redirect_service::SlugRedirectService;
 use crate::business::term_service::{TermInfoService, TermService};
 use crate::business::user_service::{UserInfoService, UserService};
+use crate::db::DbPools;
 use crate::infras::attribute_sqlx_repository::{
     AttributeInfoSqlxRepository, AttributeSqlxRepository,
 };
 use crate::infras::attribute_value_sqlx_repository::{
     AttributeValueInfoSqlxRepository, AttributeValueSqlxRepository,
 };
+use crate::infras::audit_log_sqlx_repository::AuditLogInfoSqlxRepository;
 use crate::infras::post_collection_sqlx_repository::{
     PostCollectionInfoSqlxRepository, PostCollectionSqlxRepository,
 };
+use crate::infras::post_revision_sqlx_repository::{
+    PostRevisionInfoSqlxRepository, PostRevisionSqlxRepository,
+};
 use crate::infras::post_sqlx_repository::{PostInfoSqlxRepository, PostSqlxRepository};
 use crate::infras::post_taxonomy_sqlx_repository::{
     PostTaxonomyInfoSqlxRepository, PostTaxonomySqlxRepository,
@@ -24,9 +35,9 @@ use crate::infras::post_taxonomy_sqlx_repository::{
 use crate::infras::post_type_sqlx_repository::{
     PostTypeInfoSqlxRepository, PostTypeSqlxRepository,
 };
+use crate::infras::slug_redirect_sqlx_repository::SlugRedirectSqlxRepository;
 use crate::infras::term_sqlx_repository::{TermInfoSqlxRepository, TermSqlxRepository};
 use crate::infras::user_sqlx_repository::{UserInfoSqlxRepository, UserSqlxRepository};
-use sqlx::PgPool;
 use std::sync::Arc;
 
 pub struct AppState {
@@ -34,89 +45,132 @@ pub struct AppState {
     pub auth_service: AuthService<UserSqlxRepository>,
     pub user_service: UserService<UserSqlxRepository>,
     pub post_service: PostService<PostSqlxRepository>,
+    pub post_revision_service: PostRevisionService<PostRevisionSqlxRepository>,
     pub post_type_service: PostTypeService<PostTypeSqlxRepository>,
     pub post_collection_service: PostCollectionService<PostCollectionSqlxRepository>,
     pub post_taxonomy_service: PostTaxonomyService<PostTaxonomySqlxRepository>,
     pub term_service: TermService<TermSqlxRepository>,
-    pub attribute_value_service: AttributeValueService<AttributeValueSqlxRepository>,
+    pub post_term_service: PostTermService<PostSqlxRepository, TermInfoSqlxRepository>,
+    pub slug_redirect_service: SlugRedirectService<SlugRedirectSqlxRepository>,
+    pub attribute_value_service:
+        AttributeValueService<AttributeValueSqlxRepository, AttributeSqlxRepository>,
     pub attribute_service: AttributeService<AttributeSqlxRepository>,
     // View services
     pub user_info_service: UserInfoService<UserInfoSqlxRepository>,
     pub post_info_service: PostInfoService<PostInfoSqlxRepository>,
+    pub post_revision_info_service: PostRevisionInfoService<PostRevisionInfoSqlxRepository>,
     pub post_type_info_service: PostTypeInfoService<PostTypeInfoSqlxRepository>,
     pub post_collection_info_service: PostCollectionInfoService<PostCollectionInfoSqlxRepository>,
     pub post_taxonomy_info_service: PostTaxonomyInfoService<PostTaxonomyInfoSqlxRepository>,
     pub term_info_service: TermInfoService<TermInfoSqlxRepository>,
     pub attribute_info_service: AttributeInfoService<AttributeInfoSqlxRepository>,
     pub attribute_value_info_service: AttributeValueInfoService<AttributeValueInfoSqlxRepository>,
+    pub audit_log_info_service: AuditLogInfoService<AuditLogInfoSqlxRepository>,
 }
 
 #[cfg(feature = "ssr")]
-pub async fn new_app_state(pool: PgPool) -> actix_web::web::Data<AppState> {
-    let user_repository = Arc::new(UserSqlxRepository::new(pool.clone()));
+pub async fn new_app_state(pools: DbPools) -> actix_web::web::Data<AppState> {
+    let user_repository = Arc::new(UserSqlxRepository::new(pools.clone()));
     let user_service = UserService::new(user_repository);
-    let user_info_repository = Arc::new(UserInfoSqlxRepository::new(pool.clone()));
+    let user_info_repository = Arc::new(UserInfoSqlxRepository::new(pools.clone()));
     let user_info_service = UserInfoService::new(user_info_repository);
-    let post_repository = Arc::new(PostSqlxRepository::new(pool.clone()));
-    let post_service = PostService::new(post_repository);
-    let post_info_repository = Arc::new(PostInfoSqlxRepository::new(pool.clone()));
+    let post_repository = Arc::new(PostSqlxRepository::new(pools.clone()));
+    let post_service = PostService::new(post_repository.clone());
+    let post_revision_repository = Arc::new(PostRevisionSqlxRepository::new(pools.clone()));
+    let post_revision_service =
+        PostRevisionService::new(post_revision_repository, revision_retention_from_env());
+    let post_revision_info_repository = Arc::new(PostRevisionInfoSqlxRepository::new(pools.clone()));
+    let post_revision_info_service = PostRevisionInfoService::new(post_revision_info_repository);
+    let post_info_repository = Arc::new(PostInfoSqlxRepository::new(pools.clone()));
     let post_info_service = PostInfoService::new(post_info_repository);
-    let post_type_info_repository = Arc::new(PostTypeInfoSqlxRepository::new(pool.clone()));
+    let post_type_info_repository = Arc::new(PostTypeInfoSqlxRepository::new(pools.clone()));
     let post_type_info_service = PostTypeInfoService::new(post_type_info_repository);
     let post_collection_info_repository =
-        Arc::new(PostCollectionInfoSqlxRepository::new(pool.clone()));
+        Arc::new(PostCollectionInfoSqlxRepository::new(pools.clone()));
     let post_collection_info_service =
         PostCollectionInfoService::new(post_collection_info_repository);
-    let post_taxonomy_info_repository = Arc::new(PostTaxonomyInfoSqlxRepository::new(pool.clone()));
+    let post_taxonomy_info_repository = Arc::new(PostTaxonomyInfoSqlxRepository::new(pools.clone()));
     let post_taxonomy_info_service = PostTaxonomyInfoService::new(post_taxonomy_info_repository);
-    let term_info_repository = Arc::new(TermInfoSqlxRepository::new(pool.clone()));
-    let term_info_service = TermInfoService::new(term_info_repository);
+    let term_info_repository = Arc::new(TermInfoSqlxRepository::new(pools.clone()));
+    let term_info_service = TermInfoService::new(term_info_repository.clone());
+    let post_term_service = PostTermService::new(post_repository, term_info_repository);
 
-    let attribute_repository = Arc::new(AttributeSqlxRepository::new(pool.clone()));
-    let attribute_service = AttributeService::new(attribute_repository);
-    let attribute_info_repository = Arc::new(AttributeInfoSqlxRepository::new(pool.clone()));
+    let attribute_repository = Arc::new(AttributeSqlxRepository::new(pools.clone()));
+    let attribute_service = AttributeService::new(attribute_repository.clone());
+    let attribute_info_repository = Arc::new(AttributeInfoSqlxRepository::new(pools.clone()));
     let attribute_info_service = AttributeInfoService::new(attribute_info_repository);
 
     let attribute_value_info_repository =
-        Arc::new(AttributeValueInfoSqlxRepository::new(pool.clone()));
+        Arc::new(AttributeValueInfoSqlxRepository::new(pools.clone()));
     let attribute_value_info_service =
         AttributeValueInfoService::new(attribute_value_info_repository);
-    let post_type_repository = Arc::new(PostTypeSqlxRepository::new(pool.clone()));
+    let post_type_repository = Arc::new(PostTypeSqlxRepository::new(pools.clone()));
     let post_type_service = PostTypeService::new(post_type_repository);
 
-    let post_collection_repository = Arc::new(PostCollectionSqlxRepository::new(pool.clone()));
+    let post_collection_repository = Arc::new(PostCollectionSqlxRepository::new(pools.clone()));
     let post_collection_service = PostCollectionService::new(post_collection_repository);
 
-    let post_taxonomy_repository = Arc::new(PostTaxonomySqlxRepository::new(pool.clone()));
+    let post_taxonomy_repository = Arc::new(PostTaxonomySqlxRepository::new(pools.clone()));
     let post_taxonomy_service = PostTaxonomyService::new(post_taxonomy_repository);
 
-    let term_repository = Arc::new(TermSqlxRepository::new(pool.clone()));
+    let term_repository = Arc::new(TermSqlxRepository::new(pools.clone()));
     let term_service = TermService::new(term_repository);
 
-    let attribute_value_repository = Arc::new(AttributeValueSqlxRepository::new(pool.clone()));
-    let attribute_value_service = AttributeValueService::new(attribute_value_repository);
+    let slug_redirect_repository = Arc::new(SlugRedirectSqlxRepository::new(pools.clone()));
+    let slug_redirect_service = SlugRedirectService::new(slug_redirect_repository);
+
+    let attribute_value_repository = Arc::new(AttributeValueSqlxRepository::new(pools.clone()));
+    let attribute_value_service =
+        AttributeValueService::new(attribute_value_repository, attribute_repository);
 
     let auth_service = AuthService::new(user_service.clone());
 
+    let audit_log_info_repository = Arc::new(AuditLogInfoSqlxRepository::new(pools.clone()));
+    let audit_log_info_service = AuditLogInfoService::new(audit_log_info_repository);
+
     actix_web::web::Data::new(AppState {
         // CRUD services
         user_service,
         user_info_service,
         post_service,
+        post_revision_service,
         auth_service,
         post_type_service,
         post_collection_service,
         post_taxonomy_service,
         term_service,
+        post_term_service,
+        slug_redirect_service,
         attribute_value_service,
         attribute_service,
         // View services
         post_info_service,
+        post_revision_info_service,
         post_type_info_service,
         post_collection_info_service,
         post_taxonomy_info_service,
         term_info_service,
         attribute_info_service,
         attribute_value_info_service,
+        audit_log_info_service,
     })
 }
+
+/// Revisions kept per post, from `POST_REVISIONS_MAX_AUTOSAVES` and
+/// `POST_REVISIONS_MAX_CHECKPOINTS`.
+fn revision_retention_from_env() -> RevisionRetention {
+    let defaults = RevisionRetention::default();
+    RevisionRetention {
+        max_autosaves: revision_limit("POST_REVISIONS_MAX_AUTOSAVES", defaults.max_autosaves),
+        max_checkpoints: revision_limit("POST_REVISIONS_MAX_CHECKPOINTS", defaults.max_checkpoints),
+    }
+}
+
+/// `0` keeps every revision; unset or invalid keeps the default.
+fn revision_limit(name: &str, default: Option<usize>) -> Option<usize> {
+    match std::env::var(name).ok().and_then(|v| v.parse::<usize>().ok()) {
+        Some(0) => None,
+        Some(n) => Some(n),
+        None => default,
+    }
+}